@@ -3,25 +3,46 @@ use std::env;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
+use std::process::Command;
 
 fn main() {
     let out_dir = env::var("OUT_DIR").unwrap();
-    let dest_path = Path::new(&out_dir).join("git_sha.rs");
+    let dest_path = Path::new(&out_dir).join("build_info.rs");
     let mut f = File::create(&dest_path).unwrap();
 
-    let git_sha = if let Ok(repo) = Repository::open(".") {
-        if let Ok(head) = repo.head() {
-            if let Ok(commit) = head.peel_to_commit() {
-                commit.id().to_string()
-            } else {
-                "unknown".to_string()
-            }
-        } else {
-            "unknown".to_string()
-        }
-    } else {
-        "unknown".to_string()
-    };
+    let git_sha = Repository::open(".")
+        .ok()
+        .and_then(|repo| {
+            let commit = repo.head().ok()?.peel_to_commit().ok()?;
+            Some(commit.id().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
 
-    writeln!(&mut f, "pub const GIT_SHA: &str = \"{git_sha}\";").unwrap();
+    // Cargo points RUSTC at the compiler building this crate
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+        .unwrap_or_else(|| "rustc unknown".to_string());
+
+    let target = env::var("TARGET").unwrap_or_else(|_| "unknown".to_string());
+    let profile = env::var("PROFILE").unwrap_or_else(|_| "unknown".to_string());
+
+    writeln!(&mut f, "pub const GIT_SHA: &str = {git_sha:?};").unwrap();
+    writeln!(&mut f, "pub const RUSTC_VERSION: &str = {rustc_version:?};").unwrap();
+    writeln!(&mut f, "pub const BUILD_TARGET: &str = {target:?};").unwrap();
+    writeln!(&mut f, "pub const BUILD_PROFILE: &str = {profile:?};").unwrap();
+
+    // Rebuild when HEAD moves, whether by checkout or by a new commit on the branch
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    if let Ok(head) = std::fs::read_to_string(".git/HEAD")
+        && let Some(branch_ref) = head.trim().strip_prefix("ref: ")
+    {
+        println!("cargo:rerun-if-changed=.git/{branch_ref}");
+    }
 }
@@ -0,0 +1,111 @@
+use crate::pane::ScrollAction;
+
+/// Something the user can ask grw to do, whether by hotkey or from the command palette
+///
+/// Most are carried out by `App::apply_action`; those that need the config or the
+/// shared git state are run by the main loop instead, see `Action::needs_main_loop`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    ScrollDiff(ScrollAction),
+    NextFile,
+    PrevFile,
+    NextHunk,
+    PrevHunk,
+    FocusNextPane,
+    FocusPrevPane,
+    OpenSearch,
+    OpenCommandPalette,
+    ToggleFolding,
+    SelectDiffLines,
+    CycleViewMode,
+    ToggleStagedView,
+    ToggleStagedAndUnstagedView,
+    StageHunk,
+    DiscardFile,
+    CopyPatch,
+    SavePatch,
+    OpenInEditor,
+    OpenExternalDiff,
+    ShowDiffStats,
+    ShowTokenUsage,
+    DraftCommitMessage,
+    ToggleAdvicePanel,
+    ClearAdviceChat,
+    ToggleHelp,
+    ToggleTheme,
+    SinglePaneDiff,
+    SideBySideDiff,
+    ToggleDiffPanel,
+    ToggleChangedFilesPane,
+    ToggleMonitorPane,
+    OpenCommitPicker,
+    OpenReflog,
+    ShowCommitMessage,
+    ToggleRefreshPaused,
+    ReturnToWorkingDirectory,
+}
+
+impl Action {
+    /// What the action does, as listed in the command palette
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::ScrollDiff(ScrollAction::LineDown) => "Scroll down",
+            Action::ScrollDiff(ScrollAction::LineUp) => "Scroll up",
+            Action::ScrollDiff(ScrollAction::HalfPageDown) => "Half page down",
+            Action::ScrollDiff(ScrollAction::HalfPageUp) => "Half page up",
+            Action::ScrollDiff(ScrollAction::PageDown) => "Page down",
+            Action::ScrollDiff(ScrollAction::PageUp) => "Page up",
+            Action::ScrollDiff(ScrollAction::Top) => "Go to top",
+            Action::ScrollDiff(ScrollAction::Bottom) => "Go to bottom",
+            Action::NextFile => "Next file",
+            Action::PrevFile => "Previous file",
+            Action::NextHunk => "Next hunk",
+            Action::PrevHunk => "Previous hunk",
+            Action::FocusNextPane => "Focus next pane",
+            Action::FocusPrevPane => "Focus previous pane",
+            Action::OpenSearch => "Search all changed files",
+            Action::OpenCommandPalette => "Open the command palette",
+            Action::ToggleFolding => "Fold / show unchanged lines",
+            Action::SelectDiffLines => "Select diff lines",
+            Action::CycleViewMode => "Cycle the view mode",
+            Action::ToggleStagedView => "Toggle the staged-only view",
+            Action::ToggleStagedAndUnstagedView => "Toggle the staged and unstaged view",
+            Action::StageHunk => "Stage the hunk at the top",
+            Action::DiscardFile => "Discard current file's working tree changes",
+            Action::CopyPatch => "Copy current file's patch",
+            Action::SavePatch => "Save current file's patch",
+            Action::OpenInEditor => "Open current file in $EDITOR",
+            Action::OpenExternalDiff => "View current file's diff in external_diff_command",
+            Action::ShowDiffStats => "Show diff stats",
+            Action::ShowTokenUsage => "Show LLM token usage",
+            Action::DraftCommitMessage => "Draft a commit message for staged changes",
+            Action::ToggleAdvicePanel => "Toggle LLM advice panel",
+            Action::ClearAdviceChat => "Clear the advice chat and ask again",
+            Action::ToggleHelp => "Show help",
+            Action::ToggleTheme => "Cycle through themes",
+            Action::SinglePaneDiff => "Switch to inline diff view",
+            Action::SideBySideDiff => "Switch to side-by-side diff view",
+            Action::ToggleDiffPanel => "Toggle diff panel",
+            Action::ToggleChangedFilesPane => "Toggle changed files pane",
+            Action::ToggleMonitorPane => "Toggle monitor pane",
+            Action::OpenCommitPicker => "Enter commit picker mode",
+            Action::OpenReflog => "Browse HEAD's reflog",
+            Action::ShowCommitMessage => "Show full commit message",
+            Action::ToggleRefreshPaused => "Pause/resume git refresh",
+            Action::ReturnToWorkingDirectory => "Return to working directory",
+        }
+    }
+
+    /// Whether the action needs the config or shared git state, which only the main loop has
+    pub fn needs_main_loop(&self) -> bool {
+        matches!(
+            self,
+            Action::OpenCommitPicker
+                | Action::OpenReflog
+                | Action::ShowCommitMessage
+                | Action::ToggleRefreshPaused
+        )
+    }
+}
@@ -1,15 +1,37 @@
 use dirs::config_dir;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+/// Default interval between background git status polls
+pub const DEFAULT_REFRESH_INTERVAL_MS: u64 = 500;
+/// Lower bound for the git polling interval to avoid spinning the CPU
+pub const MIN_REFRESH_INTERVAL_MS: u64 = 50;
+/// Default time a recently changed file stays highlighted in the file tree
+pub const DEFAULT_RECENT_HIGHLIGHT_MS: u64 = 3000;
+/// Default number of improvements the advice panel asks for
+pub const DEFAULT_ADVICE_COUNT: usize = 3;
+/// Largest number of improvements the advice panel may ask for
+pub const MAX_ADVICE_COUNT: usize = 10;
+/// Default size the log file grows to before it's rotated
+pub const DEFAULT_LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+/// Longest commit summary kept, in characters
+/// Lines of monitor command output kept when `monitor_max_lines` isn't set
+pub const DEFAULT_MONITOR_MAX_LINES: usize = 5000;
+pub const DEFAULT_SUMMARY_MAX_CHARS: usize = 1000;
+/// Config file in a repository's root, layered between the global config and `--config`
+pub const REPO_CONFIG_FILE: &str = ".grw.json";
+
 #[derive(Debug, Clone, Serialize, Default, PartialEq, Eq)]
 pub enum Theme {
     #[default]
     Dark,
     Light,
+    HighContrast,
     Custom,
+    /// A theme loaded from `<themes_dir>/<name>.toml`
+    Named(String),
 }
 
 impl<'de> Deserialize<'de> for Theme {
@@ -21,10 +43,12 @@ impl<'de> Deserialize<'de> for Theme {
         match s.to_lowercase().as_str() {
             "dark" => Ok(Theme::Dark),
             "light" => Ok(Theme::Light),
+            "high-contrast" | "highcontrast" => Ok(Theme::HighContrast),
             "custom" => Ok(Theme::Custom),
-            _ => Err(serde::de::Error::custom(format!(
-                "invalid theme: {s}, expected 'dark', 'light', or 'custom'"
-            ))),
+            "" => Err(serde::de::Error::custom(
+                "invalid theme: expected 'dark', 'light', 'custom', or a theme file name",
+            )),
+            _ => Ok(Theme::Named(s)),
         }
     }
 }
@@ -36,8 +60,9 @@ impl FromStr for Theme {
         match s.to_lowercase().as_str() {
             "dark" => Ok(Theme::Dark),
             "light" => Ok(Theme::Light),
+            "high-contrast" | "highcontrast" => Ok(Theme::HighContrast),
             _ => Err(format!(
-                "invalid theme: {s}, expected 'dark' or 'light'. Use config file for custom theme."
+                "invalid theme: {s}, expected 'dark', 'light', or 'high-contrast'. Use config file for custom theme."
             )),
         }
     }
@@ -48,11 +73,185 @@ impl std::fmt::Display for Theme {
         match self {
             Theme::Dark => write!(f, "dark"),
             Theme::Light => write!(f, "light"),
+            Theme::HighContrast => write!(f, "high-contrast"),
             Theme::Custom => write!(f, "custom"),
+            Theme::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// When to emit colored output
+#[derive(Debug, Clone, Copy, Serialize, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Detect from `NO_COLOR`, `COLORTERM`, and `TERM`
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl<'de> Deserialize<'de> for ColorMode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(format!(
+                "invalid color mode: {s}, expected 'auto', 'always', or 'never'"
+            )),
         }
     }
 }
 
+/// How much the status bar says
+#[derive(Debug, Clone, Copy, Serialize, Default, PartialEq, Eq)]
+pub enum StatusBarStyle {
+    /// Compact on narrow terminals, full otherwise
+    #[default]
+    Auto,
+    /// Abbreviated on one line without emoji, like `repo·main·WT·3f+10-2`
+    Compact,
+    Full,
+}
+
+impl<'de> Deserialize<'de> for StatusBarStyle {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for StatusBarStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(StatusBarStyle::Auto),
+            "compact" => Ok(StatusBarStyle::Compact),
+            "full" => Ok(StatusBarStyle::Full),
+            _ => Err(format!(
+                "invalid status bar style: {s}, expected 'auto', 'compact', or 'full'"
+            )),
+        }
+    }
+}
+
+/// How commit summaries are written
+#[derive(Debug, Clone, Copy, Serialize, Default, PartialEq, Eq)]
+pub enum SummaryStyle {
+    /// A brief two-sentence paragraph
+    #[default]
+    Sentences,
+    /// A short list of `- ` bullet points
+    Bullets,
+}
+
+impl<'de> Deserialize<'de> for SummaryStyle {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for SummaryStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sentences" => Ok(SummaryStyle::Sentences),
+            "bullets" => Ok(SummaryStyle::Bullets),
+            _ => Err(format!(
+                "invalid summary style: {s}, expected 'sentences' or 'bullets'"
+            )),
+        }
+    }
+}
+
+/// Which icons mark files and directories in the file tree and commit summary
+#[derive(Debug, Clone, Copy, Serialize, Default, PartialEq, Eq)]
+pub enum IconSet {
+    #[default]
+    Emoji,
+    /// Glyphs from a Nerd Font patched terminal font
+    NerdFont,
+    /// Plain characters, for terminals and fonts that draw emoji at odd widths
+    Ascii,
+}
+
+impl<'de> Deserialize<'de> for IconSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for IconSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "emoji" => Ok(IconSet::Emoji),
+            "nerdfont" | "nerd-font" => Ok(IconSet::NerdFont),
+            "ascii" => Ok(IconSet::Ascii),
+            _ => Err(format!(
+                "invalid icon set: {s}, expected 'emoji', 'nerdfont', or 'ascii'"
+            )),
+        }
+    }
+}
+
+/// Something wrong with the config, found by `Config::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    pub message: String,
+    /// grw refuses to start rather than guess what was meant
+    pub fatal: bool,
+}
+
+impl ConfigProblem {
+    fn error(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            fatal: true,
+        }
+    }
+
+    fn warning(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            fatal: false,
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = if self.fatal { "error" } else { "warning" };
+        write!(f, "{level}: {}", self.message)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CustomTheme {
     pub background: Option<String>,
@@ -68,10 +267,33 @@ pub struct CustomTheme {
     pub unchanged: Option<String>,
 }
 
+impl CustomTheme {
+    /// Every color key with the value set for it, if any
+    fn colors(&self) -> [(&'static str, &Option<String>); 11] {
+        [
+            ("background", &self.background),
+            ("foreground", &self.foreground),
+            ("primary", &self.primary),
+            ("secondary", &self.secondary),
+            ("error", &self.error),
+            ("highlight", &self.highlight),
+            ("border", &self.border),
+            ("directory", &self.directory),
+            ("added", &self.added),
+            ("removed", &self.removed),
+            ("unchanged", &self.unchanged),
+        ]
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub enum LlmProvider {
     #[default]
+    #[serde(alias = "openai")]
     OpenAI,
+    /// Canned offline answers, for tests and trying grw without an API key
+    #[serde(alias = "mock")]
+    Mock,
 }
 
 impl FromStr for LlmProvider {
@@ -80,6 +302,7 @@ impl FromStr for LlmProvider {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "openai" => Ok(LlmProvider::OpenAI),
+            "mock" => Ok(LlmProvider::Mock),
             _ => Err(format!("Invalid LLM provider: {s}")),
         }
     }
@@ -95,6 +318,18 @@ pub struct LlmConfig {
     pub advice_model: Option<String>,
     /// Maximum number of characters/tokens to send to LLM for both summary and advice generation
     pub max_tokens: Option<usize>,
+    /// Diff size limit in characters for summaries and advice, overriding the one derived from `max_tokens`
+    pub max_diff_chars: Option<usize>,
+    /// Number of improvements to ask for when generating advice (1-10)
+    pub advice_count: Option<usize>,
+    /// Tokens the session may use before further LLM calls are refused
+    pub token_budget: Option<u64>,
+    /// Price per 1000 tokens, used to estimate the session's cost
+    pub price_per_1k_tokens: Option<f64>,
+    /// Whether commit summaries are written as sentences or bullet points
+    pub summary_style: Option<SummaryStyle>,
+    /// Longest commit summary kept, in characters; anything past it is cut off
+    pub summary_max_chars: Option<usize>,
 }
 
 impl std::fmt::Debug for LlmConfig {
@@ -107,6 +342,12 @@ impl std::fmt::Debug for LlmConfig {
             .field("base_url", &self.base_url)
             .field("advice_model", &self.advice_model)
             .field("max_tokens", &self.max_tokens)
+            .field("max_diff_chars", &self.max_diff_chars)
+            .field("advice_count", &self.advice_count)
+            .field("token_budget", &self.token_budget)
+            .field("price_per_1k_tokens", &self.price_per_1k_tokens)
+            .field("summary_style", &self.summary_style)
+            .field("summary_max_chars", &self.summary_max_chars)
             .finish()
     }
 }
@@ -132,17 +373,69 @@ impl LlmConfig {
     pub fn get_max_tokens(&self) -> usize {
         self.max_tokens.unwrap_or(16000)
     }
+
+    /// Get the largest diff to send, in characters, at 3 characters per token unless set directly
+    pub fn get_max_diff_chars(&self) -> usize {
+        self.max_diff_chars
+            .unwrap_or_else(|| self.get_max_tokens() * 3)
+    }
+
+    /// Get the number of improvements to ask for, clamped to 1..=MAX_ADVICE_COUNT
+    pub fn get_advice_count(&self) -> usize {
+        self.advice_count
+            .unwrap_or(DEFAULT_ADVICE_COUNT)
+            .clamp(1, MAX_ADVICE_COUNT)
+    }
+
+    pub fn get_summary_style(&self) -> SummaryStyle {
+        self.summary_style.unwrap_or_default()
+    }
+
+    /// Get the longest commit summary to keep, falling back to the default for 0,
+    /// which would leave every summary empty
+    pub fn get_summary_max_chars(&self) -> usize {
+        match self.summary_max_chars {
+            Some(0) | None => DEFAULT_SUMMARY_MAX_CHARS,
+            Some(max_chars) => max_chars,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
     pub debug: Option<bool>,
+    pub log_file: Option<PathBuf>,
+    pub log_level: Option<log::LevelFilter>,
+    pub log_max_bytes: Option<u64>,
     pub no_diff: Option<bool>,
     pub hide_changed_files_pane: Option<bool>,
     pub monitor_command: Option<String>,
     pub monitor_interval: Option<u64>,
+    /// Lines of the monitor command's output kept, dropping the oldest beyond them
+    pub monitor_max_lines: Option<usize>,
+    /// Command the current file's diff is piped through with `x`, such as `delta`
+    pub external_diff_command: Option<String>,
+    pub refresh_interval_ms: Option<u64>,
+    pub recent_highlight_ms: Option<u64>,
+    pub show_stash_count: Option<bool>,
+    pub show_unpushed: Option<bool>,
+    pub dirty_directory_view: Option<bool>,
     pub theme: Option<Theme>,
     pub custom_theme: Option<CustomTheme>,
+    pub themes_dir: Option<PathBuf>,
+    pub color: Option<ColorMode>,
+    pub icons: Option<IconSet>,
+    pub status_bar: Option<StatusBarStyle>,
+    pub status_bar_lines: Option<u16>,
+    /// Directory levels the file tree nests before flattening deeper paths into `a/b/file.rs`
+    pub max_tree_depth: Option<usize>,
+    /// Give added and removed lines a background that warms with their hunk's change density
+    pub diff_heatmap: Option<bool>,
+    /// Rows of context kept between a line jumped or moved to and the diff's edges
+    pub scroll_margin: Option<usize>,
+    /// Count the functions each file's diff touches, for builds with `semantic-stats`
+    pub semantic_stats: Option<bool>,
+    pub diff_base: Option<String>,
     pub llm: Option<LlmConfig>,
     pub commit_history_limit: Option<usize>,
     pub summary_preload_enabled: Option<bool>,
@@ -150,44 +443,382 @@ pub struct Config {
 }
 
 impl Config {
-    pub fn load() -> color_eyre::eyre::Result<Self> {
-        let config_path = Self::get_config_path();
+    /// Load the global config, then the `.grw.json` at `repo_root`, then `explicit`, the
+    /// `--config` file, each layered over the last, along with the files read; missing
+    /// global and repository configs are skipped, a missing explicit one is an error
+    pub fn load(
+        explicit: Option<&Path>,
+        repo_root: Option<&Path>,
+    ) -> color_eyre::eyre::Result<(Self, Vec<PathBuf>)> {
+        let paths = Self::layer_paths(Self::get_config_path(), repo_root, explicit)?;
+        let config = Self::load_files(&paths)?;
+        Ok((config, paths))
+    }
 
-        if !config_path.exists() {
-            return Ok(Self::default());
+    /// The config files to read, lowest precedence first
+    fn layer_paths(
+        global_path: PathBuf,
+        repo_root: Option<&Path>,
+        explicit: Option<&Path>,
+    ) -> color_eyre::eyre::Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        if global_path.exists() {
+            paths.push(global_path);
+        }
+        if let Some(repo_path) = repo_root.map(Self::get_repo_config_path)
+            && repo_path.is_file()
+        {
+            paths.push(repo_path);
+        }
+        if let Some(path) = explicit {
+            if !path.is_file() {
+                return Err(color_eyre::eyre::eyre!(
+                    "Config file {} does not exist",
+                    path.display()
+                ));
+            }
+            paths.push(path.to_path_buf());
         }
+        Ok(paths)
+    }
 
-        let content = fs::read_to_string(&config_path)?;
-        let config: Config = serde_json::from_str(&content)?;
-        Ok(config)
+    /// Parse each file in turn, later files overriding the keys they set in earlier ones
+    /// and merging into nested sections like `llm` key by key
+    fn load_files(paths: &[PathBuf]) -> color_eyre::eyre::Result<Self> {
+        let mut merged = serde_json::Value::Object(serde_json::Map::new());
+        for path in paths {
+            let content = fs::read_to_string(path).map_err(|e| {
+                color_eyre::eyre::eyre!("Failed to read config file {}: {e}", path.display())
+            })?;
+            let layer: serde_json::Value = serde_json::from_str(&content).map_err(|e| {
+                color_eyre::eyre::eyre!("Failed to parse config file {}: {e}", path.display())
+            })?;
+            merge_json(&mut merged, layer);
+        }
+        Ok(serde_json::from_value(merged)?)
     }
 
-    /// Get the commit history limit with a sensible default
+    /// Get the commit history page size with a sensible default
     pub fn get_commit_history_limit(&self) -> usize {
         self.commit_history_limit.unwrap_or(100)
     }
 
+    /// Get the git polling interval, clamped to a sensible minimum
+    pub fn get_refresh_interval_ms(&self) -> u64 {
+        self.refresh_interval_ms
+            .unwrap_or(DEFAULT_REFRESH_INTERVAL_MS)
+            .max(MIN_REFRESH_INTERVAL_MS)
+    }
+
+    /// Get how long recently changed files stay highlighted
+    pub fn get_recent_highlight_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.recent_highlight_ms
+                .unwrap_or(DEFAULT_RECENT_HIGHLIGHT_MS),
+        )
+    }
+
+    /// Whether the status bar shows how many stashes there are
+    pub fn get_show_stash_count(&self) -> bool {
+        self.show_stash_count.unwrap_or(true)
+    }
+
+    /// Whether the status bar marks commits that haven't been pushed
+    pub fn get_show_unpushed(&self) -> bool {
+        self.show_unpushed.unwrap_or(true)
+    }
+
+    /// Whether unstaged changes to tracked files get a dirty directory view of their own
+    pub fn get_dirty_directory_view(&self) -> bool {
+        self.dirty_directory_view.unwrap_or(true)
+    }
+
+    /// Which icons the file tree and commit summary use
+    pub fn get_icons(&self) -> IconSet {
+        self.icons.unwrap_or_default()
+    }
+
+    /// Whether the status bar is compact, full, or picked from the terminal width
+    pub fn get_status_bar_style(&self) -> StatusBarStyle {
+        self.status_bar.unwrap_or_default()
+    }
+
+    /// Rows the full status bar takes, 1 or 2
+    pub fn get_status_bar_lines(&self) -> u16 {
+        self.status_bar_lines
+            .filter(|lines| (1..=2).contains(lines))
+            .unwrap_or(1)
+    }
+
+    /// Directory levels the file tree nests, None for no limit
+    pub fn get_max_tree_depth(&self) -> Option<usize> {
+        self.max_tree_depth.filter(|&depth| depth > 0)
+    }
+
+    /// Whether the diff colors hunks by how dense their changes are
+    pub fn get_diff_heatmap(&self) -> bool {
+        self.diff_heatmap.unwrap_or(false)
+    }
+
+    /// Rows of context the diff keeps around a jump target or the selection cursor, like
+    /// vim's `scrolloff`
+    pub fn get_scroll_margin(&self) -> usize {
+        self.scroll_margin.unwrap_or(0)
+    }
+
+    /// Lines of monitor output kept, so a chatty command can't use up memory
+    pub fn get_monitor_max_lines(&self) -> usize {
+        self.monitor_max_lines
+            .filter(|&lines| lines > 0)
+            .unwrap_or(DEFAULT_MONITOR_MAX_LINES)
+    }
+
+    /// Whether the diff title counts the functions the file's changes touch
+    pub fn get_semantic_stats(&self) -> bool {
+        self.semantic_stats.unwrap_or(false)
+    }
+
+    /// Get the log level, `debug` when debug logging is on and `info` otherwise
+    pub fn get_log_level(&self) -> log::LevelFilter {
+        self.log_level.unwrap_or(if self.debug.unwrap_or(false) {
+            log::LevelFilter::Debug
+        } else {
+            log::LevelFilter::Info
+        })
+    }
+
+    /// Get the size the log file grows to before it's rotated
+    pub fn get_log_max_bytes(&self) -> u64 {
+        self.log_max_bytes.unwrap_or(DEFAULT_LOG_MAX_BYTES)
+    }
+
     /// Get the summary preload configuration
     pub fn get_summary_preload_config(&self) -> crate::git::PreloadConfig {
+        let llm_config = self.llm.clone().unwrap_or_default();
         crate::git::PreloadConfig {
             enabled: self.summary_preload_enabled.unwrap_or(true),
             count: self.summary_preload_count.unwrap_or(5),
+            style: llm_config.get_summary_style(),
+            max_chars: llm_config.get_summary_max_chars(),
+        }
+    }
+}
+
+impl Config {
+    /// Everything wrong with the config, for reporting before the TUI starts; warnings
+    /// say what grw falls back to, errors are settings it can't run with
+    pub fn validate(&self) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+
+        if self.monitor_interval == Some(0) {
+            problems.push(ConfigProblem::error(
+                "monitor_interval must be at least 1 second",
+            ));
+        }
+        if self.monitor_max_lines == Some(0) {
+            problems.push(ConfigProblem::warning(format!(
+                "monitor_max_lines must be at least 1; keeping the last {DEFAULT_MONITOR_MAX_LINES}"
+            )));
+        }
+        if let Some(interval) = self.refresh_interval_ms
+            && interval < MIN_REFRESH_INTERVAL_MS
+        {
+            problems.push(ConfigProblem::warning(format!(
+                "refresh_interval_ms of {interval} is too low; using the minimum of {MIN_REFRESH_INTERVAL_MS}"
+            )));
+        }
+        if let Some(lines) = self.status_bar_lines
+            && !(1..=2).contains(&lines)
+        {
+            problems.push(ConfigProblem::warning(format!(
+                "status_bar_lines can be 1 or 2, not {lines}; using 1"
+            )));
+        }
+        if self.max_tree_depth == Some(0) {
+            problems.push(ConfigProblem::warning(
+                "max_tree_depth must be at least 1; not limiting the tree depth",
+            ));
+        }
+        if cfg!(not(feature = "semantic-stats")) && self.get_semantic_stats() {
+            problems.push(ConfigProblem::warning(
+                "semantic_stats needs grw built with `--features semantic-stats`; leaving it off",
+            ));
+        }
+        if self.commit_history_limit == Some(0) {
+            problems.push(ConfigProblem::error(
+                "commit_history_limit must be at least 1, or the commit picker has nothing to show",
+            ));
+        }
+
+        if let Some(llm) = &self.llm {
+            if let Some(count) = llm.advice_count
+                && !(1..=MAX_ADVICE_COUNT).contains(&count)
+            {
+                problems.push(ConfigProblem::warning(format!(
+                    "llm.advice_count of {count} is out of range; using {}",
+                    count.clamp(1, MAX_ADVICE_COUNT)
+                )));
+            }
+            if llm.summary_max_chars == Some(0) {
+                problems.push(ConfigProblem::warning(format!(
+                    "llm.summary_max_chars of 0 would leave summaries empty; using {DEFAULT_SUMMARY_MAX_CHARS}"
+                )));
+            }
+        }
+
+        self.validate_theme(&mut problems);
+        problems
+    }
+
+    fn validate_theme(&self, problems: &mut Vec<ConfigProblem>) {
+        let mut custom_theme_usable = false;
+        if let Some(custom_theme) = &self.custom_theme {
+            for (key, value) in custom_theme.colors() {
+                let Some(hex) = value else { continue };
+                match crate::ui::parse_hex_color(hex) {
+                    Ok(_) => custom_theme_usable = true,
+                    Err(e) => {
+                        problems.push(ConfigProblem::warning(format!("custom_theme.{key}: {e}")))
+                    }
+                }
+            }
+            if !custom_theme_usable {
+                problems.push(ConfigProblem::warning(
+                    "custom_theme has no valid colors, so the custom theme isn't available",
+                ));
+            }
+        }
+
+        match &self.theme {
+            Some(Theme::Custom) if self.custom_theme.is_none() => {
+                problems.push(ConfigProblem::warning(
+                    "theme is \"custom\" but there's no custom_theme section; using dark",
+                ));
+            }
+            Some(Theme::Named(name)) => {
+                let themes_dir = self.get_themes_dir();
+                let file_name = format!("{name}.toml");
+                let found = fs::read_dir(&themes_dir).is_ok_and(|entries| {
+                    entries.filter_map(|entry| entry.ok()).any(|entry| {
+                        entry
+                            .file_name()
+                            .to_string_lossy()
+                            .eq_ignore_ascii_case(&file_name)
+                    })
+                });
+                if !found {
+                    problems.push(ConfigProblem::warning(format!(
+                        "theme \"{name}\" isn't built in and there's no {file_name} in {}; using dark",
+                        themes_dir.display()
+                    )));
+                }
+            }
+            _ => {}
         }
     }
 }
 
+/// Overlay `layer` onto `base`, merging objects key by key and replacing anything else
+fn merge_json(base: &mut serde_json::Value, layer: serde_json::Value) {
+    match (base, layer) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+/// Settings read once at startup, which a config reload can't change
+const RESTART_ONLY_SETTINGS: &[&str] = &[
+    "debug",
+    "log_file",
+    "log_level",
+    "log_max_bytes",
+    "no_diff",
+    "hide_changed_files_pane",
+    "monitor_command",
+    "monitor_interval",
+    "dirty_directory_view",
+    "diff_base",
+    "llm",
+];
+
 impl Config {
-    fn get_config_path() -> PathBuf {
+    /// Settings that differ in `reloaded` but only take effect when grw restarts
+    pub fn changed_restart_only_settings(&self, reloaded: &Config) -> Vec<&'static str> {
+        let (Ok(current), Ok(reloaded)) =
+            (serde_json::to_value(self), serde_json::to_value(reloaded))
+        else {
+            return Vec::new();
+        };
+        RESTART_ONLY_SETTINGS
+            .iter()
+            .copied()
+            .filter(|key| current.get(key) != reloaded.get(key))
+            .collect()
+    }
+
+    /// `reloaded`, keeping the settings that only take effect on a restart as they are now,
+    /// so a later reload still tells that they need one
+    pub fn with_hot_reloadable_settings_of(&self, reloaded: Config) -> Config {
+        Config {
+            debug: self.debug,
+            log_file: self.log_file.clone(),
+            log_level: self.log_level,
+            log_max_bytes: self.log_max_bytes,
+            no_diff: self.no_diff,
+            hide_changed_files_pane: self.hide_changed_files_pane,
+            monitor_command: self.monitor_command.clone(),
+            monitor_interval: self.monitor_interval,
+            dirty_directory_view: self.dirty_directory_view,
+            diff_base: self.diff_base.clone(),
+            llm: self.llm.clone(),
+            ..reloaded
+        }
+    }
+
+    /// Where the global config file lives, whether or not it exists
+    pub fn get_config_path() -> PathBuf {
         config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("grw")
             .join("config.json")
     }
 
+    /// Where a repository's own config file lives, whether or not it exists
+    pub fn get_repo_config_path(repo_root: &Path) -> PathBuf {
+        repo_root.join(REPO_CONFIG_FILE)
+    }
+
+    /// Get the directory named theme files are loaded from
+    pub fn get_themes_dir(&self) -> PathBuf {
+        self.themes_dir.clone().unwrap_or_else(|| {
+            config_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("grw")
+                .join("themes")
+        })
+    }
+
     pub fn merge_with_args(&self, args: &Args) -> Self {
         let llm_config = self.llm.clone().unwrap_or_default();
         Self {
             debug: if args.debug { Some(true) } else { self.debug },
+            log_file: self.log_file.clone(),
+            // `--debug` predates log levels, so it still means the debug level
+            log_level: if args.debug {
+                Some(log::LevelFilter::Debug)
+            } else {
+                self.log_level
+            },
+            log_max_bytes: self.log_max_bytes,
             no_diff: if args.no_diff {
                 Some(true)
             } else {
@@ -203,8 +834,25 @@ impl Config {
                 .clone()
                 .or_else(|| self.monitor_command.clone()),
             monitor_interval: args.monitor_interval.or(self.monitor_interval),
+            monitor_max_lines: self.monitor_max_lines,
+            external_diff_command: self.external_diff_command.clone(),
+            refresh_interval_ms: args.refresh_interval_ms.or(self.refresh_interval_ms),
+            recent_highlight_ms: self.recent_highlight_ms,
+            show_stash_count: self.show_stash_count,
+            show_unpushed: self.show_unpushed,
+            dirty_directory_view: self.dirty_directory_view,
             theme: args.theme.clone().or_else(|| self.theme.clone()),
             custom_theme: self.custom_theme.clone(),
+            themes_dir: self.themes_dir.clone(),
+            color: args.color.or(self.color),
+            icons: self.icons,
+            status_bar: self.status_bar,
+            status_bar_lines: self.status_bar_lines,
+            max_tree_depth: self.max_tree_depth,
+            diff_heatmap: self.diff_heatmap,
+            scroll_margin: self.scroll_margin,
+            semantic_stats: self.semantic_stats,
+            diff_base: args.diff_base.clone().or_else(|| self.diff_base.clone()),
             llm: Some(LlmConfig {
                 provider: args.llm_provider.clone().or(llm_config.provider),
                 model: args.llm_model.clone().or(llm_config.model),
@@ -213,6 +861,14 @@ impl Config {
                 base_url: args.llm_base_url.clone().or(llm_config.base_url),
                 advice_model: args.llm_advice_model.clone().or(llm_config.advice_model),
                 max_tokens: args.llm_max_tokens.or(llm_config.max_tokens),
+                max_diff_chars: args.llm_max_diff_chars.or(llm_config.max_diff_chars),
+                advice_count: args.llm_advice_count.or(llm_config.advice_count),
+                token_budget: args.llm_token_budget.or(llm_config.token_budget),
+                price_per_1k_tokens: args
+                    .llm_price_per_1k_tokens
+                    .or(llm_config.price_per_1k_tokens),
+                summary_style: llm_config.summary_style,
+                summary_max_chars: llm_config.summary_max_chars,
             }),
             commit_history_limit: args.commit_history_limit.or(self.commit_history_limit),
             summary_preload_enabled: args
@@ -231,6 +887,26 @@ pub struct Args {
     #[arg(short, long, help = "Enable debug logging")]
     pub debug: bool,
 
+    #[arg(
+        long,
+        help = "Check the config, repository, LLM and terminal, print a report and exit"
+    )]
+    pub doctor: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Git repository to watch instead of the one containing the current directory"
+    )]
+    pub repo: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Config file whose settings override ~/.config/grw/config.json"
+    )]
+    pub config: Option<PathBuf>,
+
     #[arg(long, help = "Hide diff panel, show only file tree")]
     pub no_diff: bool,
 
@@ -243,9 +919,30 @@ pub struct Args {
     #[arg(long, help = "Interval in seconds for monitor command refresh")]
     pub monitor_interval: Option<u64>,
 
-    #[arg(long, help = "Theme to use (dark or light)")]
+    #[arg(
+        long = "interval",
+        value_name = "MS",
+        help = "Interval in milliseconds between git status refreshes (default: 500, minimum: 50)"
+    )]
+    pub refresh_interval_ms: Option<u64>,
+
+    #[arg(long, help = "Theme to use (dark, light, or high-contrast)")]
     pub theme: Option<Theme>,
 
+    #[arg(
+        long,
+        value_name = "WHEN",
+        help = "When to use colors: auto, always, or never (default: auto)"
+    )]
+    pub color: Option<ColorMode>,
+
+    #[arg(
+        long = "base",
+        value_name = "REF",
+        help = "Show everything changed since this ref or branch, like a PR diff (e.g. origin/main)"
+    )]
+    pub diff_base: Option<String>,
+
     #[arg(long, help = "LLM provider to use for advice (e.g., openai)")]
     pub llm_provider: Option<LlmProvider>,
 
@@ -267,6 +964,31 @@ pub struct Args {
     )]
     pub llm_max_tokens: Option<usize>,
 
+    #[arg(
+        long,
+        help = "Maximum diff size in characters sent to the LLM for summaries and advice (default: 3 per token of --llm-max-tokens)"
+    )]
+    pub llm_max_diff_chars: Option<usize>,
+
+    #[arg(
+        long,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=10),
+        help = "Number of improvements to ask for when generating advice (1-10, default: 3)"
+    )]
+    pub llm_advice_count: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Tokens the session may use before further LLM calls are refused"
+    )]
+    pub llm_token_budget: Option<u64>,
+
+    #[arg(
+        long = "llm-price-per-1k",
+        help = "Price per 1000 LLM tokens, used to estimate the session's cost"
+    )]
+    pub llm_price_per_1k_tokens: Option<f64>,
+
     #[arg(long, help = "API key for the LLM provider")]
     pub llm_api_key: Option<String>,
 
@@ -275,7 +997,7 @@ pub struct Args {
 
     #[arg(
         long,
-        help = "Maximum number of commits to load in commit picker (default: 100)"
+        help = "Number of commits the commit picker loads at a time (default: 100)"
     )]
     pub commit_history_limit: Option<usize>,
 
@@ -408,6 +1130,10 @@ mod tests {
         assert_eq!(Theme::from_str("light").unwrap(), Theme::Light);
         assert_eq!(Theme::from_str("DARK").unwrap(), Theme::Dark);
         assert_eq!(Theme::from_str("LIGHT").unwrap(), Theme::Light);
+        assert_eq!(
+            Theme::from_str("high-contrast").unwrap(),
+            Theme::HighContrast
+        );
         assert!(Theme::from_str("invalid").is_err());
     }
 
@@ -463,6 +1189,59 @@ mod tests {
         assert_eq!(config_no_theme.theme, None);
     }
 
+    #[test]
+    fn test_config_deserialize_named_theme() {
+        let json = r#"{"theme": "Dracula", "themes_dir": "/tmp/grw-themes"}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.theme, Some(Theme::Named("Dracula".to_string())));
+        assert_eq!(config.theme.as_ref().unwrap().to_string(), "Dracula");
+        assert_eq!(config.get_themes_dir(), PathBuf::from("/tmp/grw-themes"));
+
+        let json = r#"{"theme": ""}"#;
+        assert!(serde_json::from_str::<Config>(json).is_err());
+    }
+
+    #[test]
+    fn test_color_mode_parsing() {
+        let args = Args::parse_from(["grw", "--color", "never"]);
+        assert_eq!(args.color, Some(ColorMode::Never));
+        assert!(Args::try_parse_from(["grw", "--color", "sometimes"]).is_err());
+
+        let config: Config = serde_json::from_str(r#"{"color": "Always"}"#).unwrap();
+        assert_eq!(config.color, Some(ColorMode::Always));
+
+        // CLI takes precedence over config
+        let merged = config.merge_with_args(&args);
+        assert_eq!(merged.color, Some(ColorMode::Never));
+    }
+
+    #[test]
+    fn test_diff_base_config() {
+        let config: Config = serde_json::from_str(r#"{"diff_base": "main"}"#).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert_eq!(merged.diff_base, Some("main".to_string()));
+
+        // CLI takes precedence over config
+        let args = Args::parse_from(["grw", "--base", "origin/main"]);
+        assert_eq!(args.diff_base, Some("origin/main".to_string()));
+        let merged = config.merge_with_args(&args);
+        assert_eq!(merged.diff_base, Some("origin/main".to_string()));
+    }
+
+    #[test]
+    fn test_llm_provider_deserialization() {
+        let lower: Config = serde_json::from_str(r#"{"llm": {"provider": "mock"}}"#).unwrap();
+        assert_eq!(lower.llm.unwrap().provider, Some(LlmProvider::Mock));
+
+        let upper: Config = serde_json::from_str(r#"{"llm": {"provider": "OpenAI"}}"#).unwrap();
+        assert_eq!(upper.llm.unwrap().provider, Some(LlmProvider::OpenAI));
+        let lower: Config = serde_json::from_str(r#"{"llm": {"provider": "openai"}}"#).unwrap();
+        assert_eq!(lower.llm.unwrap().provider, Some(LlmProvider::OpenAI));
+
+        assert_eq!("Mock".parse::<LlmProvider>(), Ok(LlmProvider::Mock));
+    }
+
     #[test]
     fn test_commit_history_limit_config() {
         let config = Config {
@@ -503,6 +1282,395 @@ mod tests {
         assert_eq!(merged.commit_history_limit, Some(75)); // From config
     }
 
+    #[test]
+    fn test_refresh_interval_config() {
+        let config = Config::default();
+        assert_eq!(
+            config.get_refresh_interval_ms(),
+            DEFAULT_REFRESH_INTERVAL_MS
+        );
+
+        let config = Config {
+            refresh_interval_ms: Some(2000),
+            ..Default::default()
+        };
+        assert_eq!(config.get_refresh_interval_ms(), 2000);
+
+        // Values below the minimum are clamped
+        let config = Config {
+            refresh_interval_ms: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(config.get_refresh_interval_ms(), MIN_REFRESH_INTERVAL_MS);
+    }
+
+    #[test]
+    fn test_recent_highlight_duration_config() {
+        let config = Config::default();
+        assert_eq!(
+            config.get_recent_highlight_duration(),
+            std::time::Duration::from_millis(DEFAULT_RECENT_HIGHLIGHT_MS)
+        );
+
+        let json = r#"{"recent_highlight_ms": 1500}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert_eq!(
+            merged.get_recent_highlight_duration(),
+            std::time::Duration::from_millis(1500)
+        );
+    }
+
+    #[test]
+    fn test_status_bar_indicator_config() {
+        let config = Config::default();
+        assert!(config.get_show_stash_count());
+        assert!(config.get_show_unpushed());
+
+        let json = r#"{"show_stash_count": false}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert!(!merged.get_show_stash_count());
+        assert!(merged.get_show_unpushed());
+    }
+
+    #[test]
+    fn test_summary_style_and_length_config() {
+        let preload = Config::default().get_summary_preload_config();
+        assert_eq!(preload.style, SummaryStyle::Sentences);
+        assert_eq!(preload.max_chars, DEFAULT_SUMMARY_MAX_CHARS);
+
+        let json = r#"{"llm": {"summary_style": "bullets", "summary_max_chars": 2500}}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let preload = config
+            .merge_with_args(&Args::parse_from(["grw"]))
+            .get_summary_preload_config();
+        assert_eq!(preload.style, SummaryStyle::Bullets);
+        assert_eq!(preload.max_chars, 2500);
+
+        // A length of 0 would keep nothing, so it falls back to the default
+        let json = r#"{"llm": {"summary_max_chars": 0}}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config.get_summary_preload_config().max_chars,
+            DEFAULT_SUMMARY_MAX_CHARS
+        );
+
+        let json = r#"{"llm": {"summary_style": "haiku"}}"#;
+        assert!(serde_json::from_str::<Config>(json).is_err());
+    }
+
+    #[test]
+    fn test_external_diff_command_config() {
+        assert!(Config::default().external_diff_command.is_none());
+
+        let json = r#"{"external_diff_command": "delta --side-by-side"}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert_eq!(
+            merged.external_diff_command.as_deref(),
+            Some("delta --side-by-side")
+        );
+    }
+
+    #[test]
+    fn test_dirty_directory_view_config() {
+        assert!(Config::default().get_dirty_directory_view());
+
+        let json = r#"{"dirty_directory_view": false}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert!(!merged.get_dirty_directory_view());
+    }
+
+    #[test]
+    fn test_log_config() {
+        let config = Config::default();
+        assert_eq!(config.get_log_level(), log::LevelFilter::Info);
+        assert_eq!(config.get_log_max_bytes(), DEFAULT_LOG_MAX_BYTES);
+        assert!(config.log_file.is_none());
+
+        let json =
+            r#"{"log_file": "/tmp/grw-test.log", "log_level": "TRACE", "log_max_bytes": 1024}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert_eq!(merged.log_file, Some(PathBuf::from("/tmp/grw-test.log")));
+        assert_eq!(merged.get_log_level(), log::LevelFilter::Trace);
+        assert_eq!(merged.get_log_max_bytes(), 1024);
+
+        // `--debug` still means the debug level, over the config's
+        let merged = config.merge_with_args(&Args::parse_from(["grw", "--debug"]));
+        assert_eq!(merged.get_log_level(), log::LevelFilter::Debug);
+
+        let config: Config = serde_json::from_str(r#"{"debug": true}"#).unwrap();
+        assert_eq!(config.get_log_level(), log::LevelFilter::Debug);
+
+        let json = r#"{"log_level": "loud"}"#;
+        assert!(serde_json::from_str::<Config>(json).is_err());
+    }
+
+    #[test]
+    fn test_icons_config() {
+        assert_eq!(Config::default().get_icons(), IconSet::Emoji);
+
+        let json = r#"{"icons": "ascii"}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert_eq!(merged.get_icons(), IconSet::Ascii);
+
+        let json = r#"{"icons": "wingdings"}"#;
+        assert!(serde_json::from_str::<Config>(json).is_err());
+    }
+
+    #[test]
+    fn test_status_bar_style_config() {
+        assert_eq!(
+            Config::default().get_status_bar_style(),
+            StatusBarStyle::Auto
+        );
+
+        let json = r#"{"status_bar": "Compact"}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert_eq!(merged.get_status_bar_style(), StatusBarStyle::Compact);
+    }
+
+    #[test]
+    fn test_status_bar_lines_config() {
+        assert_eq!(Config::default().get_status_bar_lines(), 1);
+
+        let json = r#"{"status_bar_lines": 2}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert_eq!(merged.get_status_bar_lines(), 2);
+
+        let config = Config {
+            status_bar_lines: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(config.get_status_bar_lines(), 1);
+    }
+
+    #[test]
+    fn test_max_tree_depth_config() {
+        assert_eq!(Config::default().get_max_tree_depth(), None);
+
+        let json = r#"{"max_tree_depth": 3}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert_eq!(merged.get_max_tree_depth(), Some(3));
+
+        let config = Config {
+            max_tree_depth: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(config.get_max_tree_depth(), None);
+    }
+
+    #[test]
+    fn test_diff_heatmap_config() {
+        assert!(!Config::default().get_diff_heatmap());
+
+        let json = r#"{"diff_heatmap": true}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert!(merged.get_diff_heatmap());
+    }
+
+    #[test]
+    fn test_scroll_margin_config() {
+        assert_eq!(Config::default().get_scroll_margin(), 0);
+
+        let json = r#"{"scroll_margin": 3}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert_eq!(merged.get_scroll_margin(), 3);
+    }
+
+    #[test]
+    fn test_monitor_max_lines_config() {
+        assert_eq!(
+            Config::default().get_monitor_max_lines(),
+            DEFAULT_MONITOR_MAX_LINES
+        );
+
+        let json = r#"{"monitor_max_lines": 200}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert_eq!(merged.get_monitor_max_lines(), 200);
+
+        let config: Config = serde_json::from_str(r#"{"monitor_max_lines": 0}"#).unwrap();
+        assert_eq!(config.get_monitor_max_lines(), DEFAULT_MONITOR_MAX_LINES);
+        assert_eq!(config.validate().len(), 1);
+    }
+
+    #[test]
+    fn test_semantic_stats_config() {
+        assert!(!Config::default().get_semantic_stats());
+
+        let json = r#"{"semantic_stats": true}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert!(merged.get_semantic_stats());
+        assert_eq!(
+            merged.validate().is_empty(),
+            cfg!(feature = "semantic-stats")
+        );
+    }
+
+    #[test]
+    fn test_load_files_layers_later_files_over_earlier() {
+        let dir = tempfile::tempdir().unwrap();
+        let global = dir.path().join("global.json");
+        let explicit = dir.path().join("explicit.json");
+        fs::write(
+            &global,
+            r#"{"theme": "light", "scroll_margin": 2, "llm": {"provider": "openai", "model": "gpt-4o"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            &explicit,
+            r#"{"theme": "dark", "llm": {"model": "gpt-4o-mini"}}"#,
+        )
+        .unwrap();
+
+        let config = Config::load_files(&[global.clone(), explicit.clone()]).unwrap();
+        assert_eq!(config.theme, Some(Theme::Dark));
+        assert_eq!(config.get_scroll_margin(), 2);
+        let llm = config.llm.unwrap();
+        assert_eq!(llm.provider, Some(LlmProvider::OpenAI));
+        assert_eq!(llm.model.as_deref(), Some("gpt-4o-mini"));
+
+        assert!(Config::load_files(&[]).unwrap().theme.is_none());
+
+        fs::write(&explicit, "{ not json").unwrap();
+        let error = Config::load_files(&[global, explicit]).unwrap_err();
+        assert!(error.to_string().contains("explicit.json"));
+
+        let missing = dir.path().join("missing.json");
+        let error = Config::load(Some(&missing), None).unwrap_err();
+        assert!(error.to_string().contains("does not exist"));
+    }
+
+    #[test]
+    fn test_repo_config_sits_between_global_and_explicit() {
+        let dir = tempfile::tempdir().unwrap();
+        let global = dir.path().join("global.json");
+        let repo = dir.path().join("repo");
+        let explicit = dir.path().join("explicit.json");
+        fs::create_dir(&repo).unwrap();
+        let repo_config = Config::get_repo_config_path(&repo);
+
+        // Missing global and repository configs are skipped
+        let paths = Config::layer_paths(global.clone(), Some(&repo), None).unwrap();
+        assert!(paths.is_empty());
+
+        fs::write(
+            &global,
+            r#"{"theme": "light", "scroll_margin": 2, "max_tree_depth": 8}"#,
+        )
+        .unwrap();
+        fs::write(&repo_config, r#"{"theme": "dark", "scroll_margin": 4}"#).unwrap();
+        fs::write(&explicit, r#"{"scroll_margin": 6}"#).unwrap();
+
+        let paths = Config::layer_paths(global.clone(), Some(&repo), Some(&explicit)).unwrap();
+        assert_eq!(paths, vec![global.clone(), repo_config, explicit]);
+        let config = Config::load_files(&paths).unwrap();
+        assert_eq!(config.get_scroll_margin(), 6);
+        assert_eq!(config.theme, Some(Theme::Dark));
+        assert_eq!(config.max_tree_depth, Some(8));
+
+        // Outside a repository only the global config applies
+        let paths = Config::layer_paths(global.clone(), None, None).unwrap();
+        assert_eq!(paths, vec![global]);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Config::default().validate().is_empty());
+
+        let json = r##"{
+            "monitor_interval": 0,
+            "status_bar_lines": 3,
+            "theme": "custom",
+            "llm": {"advice_count": 20}
+        }"##;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let problems = config.validate();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| problem.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "error: monitor_interval must be at least 1 second",
+                "warning: status_bar_lines can be 1 or 2, not 3; using 1",
+                "warning: llm.advice_count of 20 is out of range; using 10",
+                "warning: theme is \"custom\" but there's no custom_theme section; using dark",
+            ]
+        );
+
+        let json = r##"{"custom_theme": {"added": "#00ff00", "removed": "#12345z"}}"##;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let problems = config.validate();
+        assert_eq!(problems.len(), 1);
+        assert!(!problems[0].fatal);
+        assert!(problems[0].message.starts_with("custom_theme.removed: "));
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Dracula.toml"), "").unwrap();
+        let config = Config {
+            theme: Some(Theme::Named("dracula".to_string())),
+            themes_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_empty());
+        let config = Config {
+            theme: Some(Theme::Named("nord".to_string())),
+            ..config
+        };
+        assert!(config.validate()[0].message.contains("no nord.toml"));
+    }
+
+    #[test]
+    fn test_changed_restart_only_settings() {
+        let current: Config =
+            serde_json::from_str(r#"{"theme": "dark", "llm": {"model": "gpt-4o"}}"#).unwrap();
+        assert!(current.changed_restart_only_settings(&current).is_empty());
+
+        let reloaded: Config = serde_json::from_str(
+            r#"{"theme": "light", "monitor_interval": 3, "llm": {"model": "gpt-4o-mini"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            current.changed_restart_only_settings(&reloaded),
+            ["monitor_interval", "llm"]
+        );
+
+        // Applying the reload keeps them, so the next reload still reports them
+        let applied = current.with_hot_reloadable_settings_of(reloaded.clone());
+        assert_eq!(applied.theme, reloaded.theme);
+        assert_eq!(
+            applied.changed_restart_only_settings(&reloaded),
+            ["monitor_interval", "llm"]
+        );
+    }
+
+    #[test]
+    fn test_merge_with_args_refresh_interval() {
+        let config = Config {
+            refresh_interval_ms: Some(1000),
+            ..Default::default()
+        };
+
+        let merged = config.merge_with_args(&Args::parse_from(["grw"]));
+        assert_eq!(merged.refresh_interval_ms, Some(1000)); // From config
+
+        let args = Args::parse_from(["grw", "--interval", "250"]);
+        let merged = config.merge_with_args(&args);
+        assert_eq!(merged.refresh_interval_ms, Some(250)); // From args
+    }
+
     #[test]
     fn test_llm_config_model_fallback() {
         // Test summary model fallback to general model
@@ -568,6 +1736,67 @@ mod tests {
         assert_eq!(llm_config.summary_model, Some("gpt-4o-mini".to_string())); // From config
     }
 
+    #[test]
+    fn test_max_diff_chars_fallback() {
+        let mut config = LlmConfig {
+            max_tokens: Some(1000),
+            ..Default::default()
+        };
+        assert_eq!(config.get_max_diff_chars(), 3000); // Derived from max_tokens
+
+        config.max_diff_chars = Some(500);
+        assert_eq!(config.get_max_diff_chars(), 500);
+
+        let args = Args::parse_from(["grw", "--llm-max-diff-chars", "200000"]);
+        let merged = Config {
+            llm: Some(config),
+            ..Default::default()
+        }
+        .merge_with_args(&args);
+        assert_eq!(merged.llm.unwrap().max_diff_chars, Some(200000)); // From args
+    }
+
+    #[test]
+    fn test_advice_count_range() {
+        let mut config = LlmConfig::default();
+        assert_eq!(config.get_advice_count(), DEFAULT_ADVICE_COUNT);
+
+        config.advice_count = Some(0);
+        assert_eq!(config.get_advice_count(), 1);
+        config.advice_count = Some(25);
+        assert_eq!(config.get_advice_count(), MAX_ADVICE_COUNT);
+
+        let args = Args::parse_from(["grw", "--llm-advice-count", "5"]);
+        let merged = Config {
+            llm: Some(config),
+            ..Default::default()
+        }
+        .merge_with_args(&args);
+        assert_eq!(merged.llm.unwrap().advice_count, Some(5)); // From args
+
+        assert!(Args::try_parse_from(["grw", "--llm-advice-count", "11"]).is_err());
+    }
+
+    #[test]
+    fn test_merge_with_args_token_budget_and_price() {
+        let config = Config {
+            llm: Some(LlmConfig {
+                token_budget: Some(50000),
+                price_per_1k_tokens: Some(0.01),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let args = Args::parse_from(["grw", "--llm-token-budget", "1000"]);
+
+        let merged = config.merge_with_args(&args);
+        let llm_config = merged.llm.unwrap();
+
+        assert_eq!(llm_config.token_budget, Some(1000)); // From args
+        assert_eq!(llm_config.price_per_1k_tokens, Some(0.01)); // From config
+    }
+
     #[test]
     fn test_llm_config_debug_redaction() {
         let config = LlmConfig {
@@ -0,0 +1,169 @@
+use log::{debug, warn};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+/// What the watcher thread noticed
+enum Seen {
+    /// A watched file or theme changed
+    Change,
+    /// A directory on the way to one that didn't exist yet appeared
+    Directory,
+}
+
+/// Watches config and theme files for changes so they can be reloaded while grw runs
+pub struct ConfigWatcher {
+    watcher: RecommendedWatcher,
+    seen: mpsc::Receiver<Seen>,
+    /// Directories that didn't exist yet, watched through their nearest existing ancestor
+    missing_dirs: Vec<PathBuf>,
+}
+
+impl ConfigWatcher {
+    /// Watch `files` and the `.toml` themes in `theme_dirs`, which needn't exist yet; their
+    /// directories are watched rather than the files themselves, so editors that save by
+    /// replacing the file are noticed too
+    pub fn new(files: Vec<PathBuf>, theme_dirs: Vec<PathBuf>) -> notify::Result<Self> {
+        let mut dirs: Vec<PathBuf> = files
+            .iter()
+            .filter_map(|file| file.parent().map(Path::to_path_buf))
+            .chain(theme_dirs.iter().cloned())
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+
+        let (tx, seen) = mpsc::channel();
+        let wanted_dirs = dirs.clone();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            // Reading the config on reload would otherwise count as a change
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            for path in &event.paths {
+                let is_theme = path.extension().is_some_and(|ext| ext == "toml")
+                    && path
+                        .parent()
+                        .is_some_and(|dir| theme_dirs.iter().any(|theme_dir| theme_dir == dir));
+                if files.contains(path) || is_theme {
+                    let _ = tx.send(Seen::Change);
+                } else if wanted_dirs.iter().any(|dir| dir.starts_with(path)) {
+                    let _ = tx.send(Seen::Directory);
+                }
+            }
+        })?;
+
+        let mut config_watcher = Self {
+            watcher,
+            seen,
+            missing_dirs: Vec::new(),
+        };
+        for dir in dirs {
+            config_watcher.watch_dir(dir)?;
+        }
+        Ok(config_watcher)
+    }
+
+    /// Watch `dir`, or its nearest existing ancestor until it's created
+    fn watch_dir(&mut self, dir: PathBuf) -> notify::Result<()> {
+        if dir.is_dir() {
+            debug!("Watching {} for config changes", dir.display());
+            return self.watcher.watch(&dir, RecursiveMode::NonRecursive);
+        }
+        if let Some(ancestor) = dir.ancestors().find(|ancestor| ancestor.is_dir()) {
+            debug!(
+                "Watching {} until {} is created",
+                ancestor.display(),
+                dir.display()
+            );
+            self.watcher.watch(ancestor, RecursiveMode::NonRecursive)?;
+        }
+        self.missing_dirs.push(dir);
+        Ok(())
+    }
+
+    /// Whether a watched file changed since the last check
+    pub fn take_change(&mut self) -> bool {
+        let mut changed = false;
+        let mut dir_created = false;
+        while let Ok(seen) = self.seen.try_recv() {
+            match seen {
+                Seen::Change => changed = true,
+                Seen::Directory => dir_created = true,
+            }
+        }
+
+        if dir_created {
+            // Move the watch closer; a created directory may already hold a config file
+            for dir in std::mem::take(&mut self.missing_dirs) {
+                changed |= dir.is_dir();
+                if let Err(e) = self.watch_dir(dir) {
+                    warn!("Not watching a new config directory: {e}");
+                }
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_watcher_notices_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.json");
+        let mut watcher = ConfigWatcher::new(vec![config.clone()], Vec::new()).unwrap();
+        assert!(!watcher.take_change());
+
+        // Other files in the directory don't count
+        std::fs::write(dir.path().join("other.json"), "{}").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        assert!(!watcher.take_change());
+
+        std::fs::write(&config, r#"{"theme": "light"}"#).unwrap();
+        wait_for_change(&mut watcher);
+    }
+
+    fn wait_for_change(watcher: &mut ConfigWatcher) {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !watcher.take_change() {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "config change not seen"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_config_watcher_waits_for_missing_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("grw").join("config.json");
+        let themes = dir.path().join("grw").join("themes");
+        let mut watcher = ConfigWatcher::new(vec![config.clone()], vec![themes.clone()]).unwrap();
+
+        std::fs::create_dir_all(&themes).unwrap();
+        // Give the watcher a look at the new directories before files land in them
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !watcher.missing_dirs.is_empty() {
+            assert!(std::time::Instant::now() < deadline, "directories not seen");
+            watcher.take_change();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        watcher.take_change();
+
+        std::fs::write(&config, "{}").unwrap();
+        wait_for_change(&mut watcher);
+
+        // Theme files count, other files next to them don't
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        watcher.take_change();
+        std::fs::write(themes.join("notes.txt"), "").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        assert!(!watcher.take_change());
+        std::fs::write(themes.join("nord.toml"), "").unwrap();
+        wait_for_change(&mut watcher);
+    }
+}
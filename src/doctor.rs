@@ -0,0 +1,317 @@
+use std::io::IsTerminal;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::{Args, Config, LlmProvider};
+use crate::llm::LlmClient;
+use crate::ui::{ColorSupport, MIN_TERMINAL_HEIGHT, MIN_TERMINAL_WIDTH};
+
+/// How long the LLM ping may take before it counts as unreachable
+const LLM_PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How a `--doctor` check came out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    /// grw runs, but something won't work as expected
+    Warn,
+    /// grw can't run until this is fixed
+    Fail,
+}
+
+impl CheckStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+}
+
+/// One line of the `--doctor` report, with a hint at a fix when it didn't pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Check the config, repository, tools, LLM and terminal grw would start with
+pub async fn run_checks(args: &Args) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let explicit_config = args.config.as_deref();
+    let repo_root = crate::git::operations::resolve_repository_path(args.repo.as_deref()).ok();
+    let config = match Config::load(explicit_config, repo_root.as_deref()) {
+        Ok((config, files)) => {
+            checks.push(config_files_check(&files, explicit_config));
+            let config = config.merge_with_args(args);
+            checks.extend(config.validate().into_iter().map(|problem| {
+                let hint = "fix the setting in the config file or on the command line";
+                if problem.fatal {
+                    Check::fail("config", problem.message, hint)
+                } else {
+                    Check::warn("config", problem.message, hint)
+                }
+            }));
+            config
+        }
+        Err(e) => {
+            checks.push(Check::fail(
+                "config",
+                e.to_string(),
+                "fix the file's JSON, or move it aside to start from the defaults",
+            ));
+            Config::default().merge_with_args(args)
+        }
+    };
+
+    checks.push(repository_check(args.repo.as_deref()));
+    checks.push(git_check());
+    checks.push(llm_check(&config).await);
+    checks.extend(terminal_checks(&config));
+    checks.push(editor_check());
+    checks
+}
+
+fn config_files_check(files: &[std::path::PathBuf], explicit: Option<&Path>) -> Check {
+    if files.is_empty() {
+        return Check::pass(
+            "config",
+            format!(
+                "no config file at {}; using the defaults",
+                Config::get_config_path().display()
+            ),
+        );
+    }
+    let files: Vec<String> = files
+        .iter()
+        .map(|file| file.display().to_string())
+        .collect();
+    let detail = format!("loaded {}", files.join(", then "));
+    match explicit {
+        Some(_) => Check::pass("config", format!("{detail} (--config)")),
+        None => Check::pass("config", detail),
+    }
+}
+
+fn repository_check(repo: Option<&Path>) -> Check {
+    match crate::git::operations::resolve_repository_path(repo) {
+        Ok(path) => Check::pass("repository", format!("watching {}", path.display())),
+        Err(e) => Check::fail(
+            "repository",
+            e.to_string(),
+            "run grw inside a git repository with a working tree, or pass --repo <PATH>",
+        ),
+    }
+}
+
+fn git_check() -> Check {
+    match std::process::Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => Check::pass(
+            "git",
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        _ => Check::warn(
+            "git",
+            "git is not on PATH",
+            "grw reads repositories itself, but monitor commands like `git status` need git installed",
+        ),
+    }
+}
+
+async fn llm_check(config: &Config) -> Check {
+    let llm_config = config.llm.clone().unwrap_or_default();
+    let provider = llm_config.provider.clone().unwrap_or_default();
+    if provider == LlmProvider::OpenAI
+        && llm_config.api_key.is_none()
+        && std::env::var("OPENAI_API_KEY").is_err()
+    {
+        return Check::warn(
+            "llm",
+            "no LLM configured, so advice and summaries are off",
+            "set llm.api_key in the config or OPENAI_API_KEY, or use --llm-provider mock to try it offline",
+        );
+    }
+
+    let model = llm_config.get_advice_model();
+    let endpoint = llm_config
+        .base_url
+        .clone()
+        .unwrap_or_else(|| "the OpenAI API".to_string());
+    let client = match LlmClient::new(llm_config) {
+        Ok(client) => client,
+        Err(e) => return Check::fail("llm", e, "check the llm section of the config"),
+    };
+    match tokio::time::timeout(LLM_PING_TIMEOUT, client.ping()).await {
+        Ok(Ok(())) if provider == LlmProvider::Mock => {
+            Check::pass("llm", "mock provider, answering offline")
+        }
+        Ok(Ok(())) => Check::pass("llm", format!("{endpoint} knows model {model}")),
+        Ok(Err(e)) => Check::warn(
+            "llm",
+            format!("{endpoint} didn't accept a request for model {model}: {e}"),
+            "check llm.api_key, llm.base_url and llm.model",
+        ),
+        Err(_) => Check::warn(
+            "llm",
+            format!(
+                "{endpoint} didn't answer within {}s",
+                LLM_PING_TIMEOUT.as_secs()
+            ),
+            "check your network connection and llm.base_url",
+        ),
+    }
+}
+
+fn terminal_checks(config: &Config) -> Vec<Check> {
+    if !std::io::stdout().is_terminal() {
+        return vec![Check::warn(
+            "terminal",
+            "output is not a terminal",
+            "run grw itself in a terminal; only the checks can be piped",
+        )];
+    }
+
+    let mut checks = Vec::new();
+    match crossterm::terminal::size() {
+        Ok((width, height)) if width < MIN_TERMINAL_WIDTH || height < MIN_TERMINAL_HEIGHT => {
+            checks.push(Check::warn(
+                "terminal",
+                format!("{width}x{height} is smaller than grw's {MIN_TERMINAL_WIDTH}x{MIN_TERMINAL_HEIGHT}"),
+                "make the window bigger",
+            ))
+        }
+        Ok((width, height)) => checks.push(Check::pass("terminal", format!("{width}x{height}"))),
+        Err(e) => checks.push(Check::warn(
+            "terminal",
+            format!("size unknown: {e}"),
+            "check TERM is set for your terminal",
+        )),
+    }
+
+    let term = std::env::var("TERM").unwrap_or_default();
+    let colors = match ColorSupport::detect(config.color.unwrap_or_default()) {
+        ColorSupport::TrueColor => Check::pass("colors", format!("truecolor (TERM={term})")),
+        ColorSupport::Ansi256 => Check::pass(
+            "colors",
+            format!("256 colors (TERM={term}); custom themes are mapped to the nearest ones"),
+        ),
+        ColorSupport::Ansi16 => Check::pass(
+            "colors",
+            format!("16 colors (TERM={term}); custom themes are mapped to the nearest ones"),
+        ),
+        ColorSupport::NoColor => Check::warn(
+            "colors",
+            format!("no colors (TERM={term})"),
+            "unset NO_COLOR or pass --color always if your terminal has colors",
+        ),
+    };
+    checks.push(colors);
+    checks
+}
+
+fn editor_check() -> Check {
+    match std::env::var("EDITOR") {
+        Ok(editor) if !editor.trim().is_empty() => Check::pass("editor", editor),
+        _ => Check::warn(
+            "editor",
+            "$EDITOR is not set",
+            "set EDITOR so `e` can open the current file",
+        ),
+    }
+}
+
+/// The report printed by `--doctor`, one check per line with any hint below it
+pub fn format_report(checks: &[Check]) -> String {
+    let mut report = String::new();
+    for check in checks {
+        report.push_str(&format!(
+            "[{}] {:<10} {}\n",
+            check.status.label(),
+            check.name,
+            check.detail
+        ));
+        if let Some(hint) = &check.hint {
+            report.push_str(&format!("       {:<10} hint: {hint}\n", ""));
+        }
+    }
+    report
+}
+
+/// Whether any check failed, so `--doctor` should exit nonzero
+pub fn has_failures(checks: &[Check]) -> bool {
+    checks.iter().any(|check| check.status == CheckStatus::Fail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_report() {
+        let checks = vec![
+            Check::pass("git", "git version 2.45.0"),
+            Check::fail("repository", "not a git repository", "run grw inside one"),
+        ];
+        let hint_indent = " ".repeat(18);
+        assert_eq!(
+            format_report(&checks),
+            format!(
+                "[pass] git        git version 2.45.0\n\
+                 [FAIL] repository not a git repository\n\
+                 {hint_indent}hint: run grw inside one\n"
+            )
+        );
+        assert!(has_failures(&checks));
+        assert!(!has_failures(&checks[..1]));
+    }
+
+    #[test]
+    fn test_repository_check() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(repository_check(Some(dir.path())).status, CheckStatus::Fail);
+
+        git2::Repository::init(dir.path()).unwrap();
+        assert_eq!(repository_check(Some(dir.path())).status, CheckStatus::Pass);
+    }
+
+    #[tokio::test]
+    async fn test_llm_check_with_mock_provider() {
+        let config: Config = serde_json::from_str(r#"{"llm": {"provider": "mock"}}"#).unwrap();
+        let check = llm_check(&config).await;
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert_eq!(check.detail, "mock provider, answering offline");
+    }
+}
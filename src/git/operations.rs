@@ -6,24 +6,63 @@ use git2::{DiffOptions, Repository};
 use log::debug;
 use std::path::{Path, PathBuf};
 
-/// Discover git repository from current working directory using git2
+/// Discover git repository from current working directory using git2, which may be
+/// anywhere inside the worktree
 /// Returns the repository and its workdir path
 pub fn discover_repository() -> Result<(Repository, PathBuf)> {
     debug!("Discovering git repository using git2");
 
     let repo = Repository::open_from_env()
-        .or_else(|_| Repository::open("."))
+        .or_else(|_| Repository::discover("."))
         .map_err(|e| color_eyre::eyre::eyre!("Could not discover git repository: {}", e))?;
 
-    let workdir = repo
-        .workdir()
-        .ok_or_else(|| color_eyre::eyre::eyre!("Repository has no working directory"))?
-        .to_path_buf();
+    let workdir = worktree_root(&repo)?;
 
     debug!("Repository discovered at: {:?}", workdir);
     Ok((repo, workdir))
 }
 
+/// Root of the working tree grw watches, which for a linked worktree is that worktree
+/// rather than the main checkout; a bare repository has none to watch
+pub fn worktree_root(repo: &Repository) -> Result<PathBuf> {
+    match repo.workdir() {
+        Some(workdir) => {
+            if repo.is_worktree() {
+                debug!("Using linked worktree at {:?}", workdir);
+            }
+            Ok(workdir.to_path_buf())
+        }
+        None => Err(color_eyre::eyre::eyre!(
+            "{} is a bare repository with no working tree to watch; run grw in a checkout, e.g. one made with `git worktree add`",
+            repo.path().display()
+        )),
+    }
+}
+
+/// Working tree of the repository grw should watch: the one containing `path` when given,
+/// otherwise the one `GIT_DIR` names or that contains the current directory
+pub fn resolve_repository_path(path: Option<&Path>) -> Result<PathBuf> {
+    let Some(path) = path else {
+        return discover_repository_workdir();
+    };
+
+    let repo = Repository::discover(path).map_err(|e| {
+        color_eyre::eyre::eyre!(
+            "{} is not a git repository: {}",
+            path.display(),
+            e.message()
+        )
+    })?;
+    worktree_root(&repo)
+}
+
+/// Whether the current directory, or `GIT_DIR`, belongs to any repository at all, bare or not
+pub fn is_inside_repository() -> bool {
+    Repository::open_from_env()
+        .or_else(|_| Repository::discover("."))
+        .is_ok()
+}
+
 /// Discover repository and get workdir (convenience function)
 pub fn discover_repository_workdir() -> Result<PathBuf> {
     let (_, workdir) = discover_repository()?;
@@ -77,21 +116,41 @@ pub fn from_repo_relative_path(repo: &Repository, relative_path: &Path) -> PathB
     }
 }
 
+/// Diff options limited to one file, plus its old path so a rename can be paired up
+fn file_diff_options(path: &Path, old_path: Option<&Path>) -> DiffOptions {
+    let mut diff_options = DiffOptions::new();
+    diff_options.pathspec(path);
+    if let Some(old_path) = old_path {
+        diff_options.pathspec(old_path);
+    }
+    diff_options
+}
+
+/// Pair up deleted and added files that are renames of each other
+/// Replaces: git diff -M
+pub fn find_renames(diff: &mut git2::Diff) -> Result<()> {
+    let mut find_options = git2::DiffFindOptions::new();
+    find_options.renames(true).for_untracked(true);
+    diff.find_similar(Some(&mut find_options))?;
+    Ok(())
+}
+
 /// Generate diff for working tree changes
 /// Replaces: git diff --no-color <path>
 pub fn get_working_tree_diff(
     repo: &Repository,
     path: &Path,
+    old_path: Option<&Path>,
 ) -> Result<(Vec<String>, usize, usize)> {
     debug!("Getting working tree diff for: {:?}", path);
 
-    let mut diff_options = DiffOptions::new();
-    diff_options.pathspec(path);
+    let mut diff_options = file_diff_options(path, old_path);
     diff_options.include_untracked(true);
     diff_options.recurse_untracked_dirs(true);
     diff_options.show_untracked_content(true);
 
-    let diff = repo.diff_index_to_workdir(None, Some(&mut diff_options))?;
+    let mut diff = repo.diff_index_to_workdir(None, Some(&mut diff_options))?;
+    find_renames(&mut diff)?;
 
     debug!("Diff deltas found: {}", diff.deltas().count());
 
@@ -103,20 +162,22 @@ pub fn get_working_tree_diff(
         deletions
     );
 
-
     Ok((lines, additions, deletions))
 }
 
-
 /// Generate diff for staged changes
 /// Replaces: git diff --cached --no-color <path>
-pub fn get_staged_diff(repo: &Repository, path: &Path) -> Result<(Vec<String>, usize, usize)> {
+pub fn get_staged_diff(
+    repo: &Repository,
+    path: &Path,
+    old_path: Option<&Path>,
+) -> Result<(Vec<String>, usize, usize)> {
     debug!("Getting staged diff for: {:?}", path);
 
-    let mut diff_options = DiffOptions::new();
-    diff_options.pathspec(path);
+    let mut diff_options = file_diff_options(path, old_path);
 
-    let diff = repo.diff_tree_to_index(None, None, Some(&mut diff_options))?;
+    let mut diff = repo.diff_tree_to_index(None, None, Some(&mut diff_options))?;
+    find_renames(&mut diff)?;
 
     debug!("Staged diff deltas found: {}", diff.deltas().count());
 
@@ -131,71 +192,172 @@ pub fn get_staged_diff(repo: &Repository, path: &Path) -> Result<(Vec<String>, u
     Ok((lines, additions, deletions))
 }
 
-/// Check if file has changes in dirty directory
-/// Replaces: git diff --name-only <path>
-pub fn is_file_in_dirty_directory(repo: &Repository, path: &Path) -> Result<bool> {
-    debug!("Checking if file is in dirty directory: {:?}", path);
+/// Resolve a ref, branch, or commit to the tree it points at
+/// Replaces: git rev-parse <revspec>^{tree}
+pub fn resolve_base_tree<'repo>(
+    repo: &'repo Repository,
+    revspec: &str,
+) -> Result<git2::Tree<'repo>> {
+    let object = repo.revparse_single(revspec)?;
+    Ok(object.peel_to_tree()?)
+}
 
-    let mut diff_options = DiffOptions::new();
-    diff_options.pathspec(path);
+/// Diff from a base tree to the working directory, staged changes and untracked files included
+fn diff_against_base<'repo>(
+    repo: &'repo Repository,
+    revspec: &str,
+    diff_options: &mut DiffOptions,
+) -> Result<git2::Diff<'repo>> {
+    let base_tree = resolve_base_tree(repo, revspec)?;
+    diff_options.include_untracked(true);
+    diff_options.recurse_untracked_dirs(true);
+    diff_options.show_untracked_content(true);
+
+    // Going through the index keeps staged deletions and renames from showing as untracked
+    let mut diff = repo.diff_tree_to_workdir_with_index(Some(&base_tree), Some(diff_options))?;
+    find_renames(&mut diff)?;
+    Ok(diff)
+}
+
+/// A file that differs between a base ref and the working directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaseChange {
+    pub path: PathBuf,
+    /// The path before a rename
+    pub old_path: Option<PathBuf>,
+    pub delta: git2::Delta,
+    pub lines: Vec<String>,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// List files that differ between a base ref and the working directory with their diffs,
+/// all from one diff rather than one per file
+/// Replaces: git diff --no-color -M <revspec>
+pub fn get_base_changes(repo: &Repository, revspec: &str) -> Result<Vec<BaseChange>> {
+    debug!("Getting changes since base: {}", revspec);
+
+    let diff = diff_against_base(repo, revspec, &mut DiffOptions::new())?;
+    let mut changes = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+            continue;
+        };
+        let old_path = delta
+            .old_file()
+            .path()
+            .filter(|old_path| *old_path != path)
+            .map(Path::to_path_buf);
+
+        let mut lines = Vec::new();
+        let mut additions = 0;
+        let mut deletions = 0;
+        if let Some(mut patch) = git2::Patch::from_diff(&diff, index)? {
+            patch.print(&mut |_delta, _hunk, line| {
+                push_diff_line(&line, &mut lines, &mut additions, &mut deletions);
+                true
+            })?;
+        }
 
-    let diff = repo.diff_index_to_workdir(None, Some(&mut diff_options))?;
-    let has_changes = diff.deltas().count() > 0;
+        changes.push(BaseChange {
+            path: path.to_path_buf(),
+            old_path,
+            delta: delta.status(),
+            lines,
+            additions,
+            deletions,
+        });
+    }
 
-    Ok(has_changes)
+    Ok(changes)
 }
 
-/// Get diff content for a specific file in a commit
-/// Replaces: git show --format= --no-color <commit> -- <path>
-pub fn get_commit_file_diff(
+/// Generate diff for a file against a base ref
+/// Replaces: git diff --no-color <revspec> -- <path>
+pub fn get_base_diff(
     repo: &Repository,
-    commit_sha: &str,
+    revspec: &str,
     path: &Path,
-) -> Result<Vec<String>> {
-    debug!("Getting commit diff for: {} {:?}", commit_sha, path);
-
-    let oid = git2::Oid::from_str(commit_sha)?;
-    let commit = repo.find_commit(oid)?;
-    let commit_tree = commit.tree()?;
-
-    // Get parent tree for comparison
-    let parent_tree = if commit.parent_count() > 0 {
-        let parent_commit = commit.parent(0)?;
-        Some(parent_commit.tree()?)
-    } else {
-        None
-    };
+    old_path: Option<&Path>,
+) -> Result<(Vec<String>, usize, usize)> {
+    debug!("Getting diff against base {} for: {:?}", revspec, path);
 
-    let mut diff_options = DiffOptions::new();
-    diff_options.pathspec(path);
+    let mut diff_options = file_diff_options(path, old_path);
 
-    let diff = repo.diff_tree_to_tree(
-        parent_tree.as_ref(),
-        Some(&commit_tree),
-        Some(&mut diff_options),
-    )?;
+    let diff = diff_against_base(repo, revspec, &mut diff_options)?;
 
-    debug!("Commit diff deltas found: {}", diff.deltas().count());
+    debug!("Base diff deltas found: {}", diff.deltas().count());
 
     let (lines, additions, deletions) = extract_diff_lines(&diff)?;
     debug!(
-        "Commit diff lines generated: {}, additions: {}, deletions: {}",
+        "Base diff lines generated: {}, additions: {}, deletions: {}",
         lines.len(),
         additions,
         deletions
     );
 
-    Ok(lines)
+    Ok((lines, additions, deletions))
 }
 
-/// Get file addition/deletion statistics for a commit
-/// Replaces: git diff-tree --numstat --no-merges <commit> -- <path>
-pub fn get_commit_file_stats(
+/// Read a repository-relative path as staged in the index
+pub fn read_index_file(repo: &Repository, path: &Path) -> Result<String> {
+    let index = repo.index()?;
+    let entry = index
+        .get_path(path, 0)
+        .ok_or_else(|| color_eyre::eyre::eyre!("{} is not in the index", path.display()))?;
+    let blob = repo.find_blob(entry.id)?;
+    Ok(String::from_utf8(blob.content().to_vec())?)
+}
+
+/// Read a repository-relative path as committed in HEAD
+pub fn read_head_file(repo: &Repository, path: &Path) -> Result<String> {
+    let tree = repo.head()?.peel_to_tree()?;
+    let blob = tree.get_path(path)?.to_object(repo)?.peel_to_blob()?;
+    Ok(String::from_utf8(blob.content().to_vec())?)
+}
+
+/// Find the top-most directories that contain no tracked files at all
+/// Takes repository-relative paths of untracked files and returns repository-relative
+/// directory paths, so a brand new directory tree is reported once rather than per file
+pub fn get_untracked_directories(repo: &Repository, new_files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let index = repo.index()?;
+    let mut roots: Vec<PathBuf> = Vec::new();
+
+    for file in new_files {
+        let Some(parent) = file.parent() else {
+            continue;
+        };
+
+        let mut prefix = PathBuf::new();
+        for component in parent.components() {
+            prefix.push(component);
+            if roots.contains(&prefix) {
+                break;
+            }
+
+            // Index paths always use forward slashes
+            let dir_prefix = format!("{}/", prefix.to_string_lossy().replace('\\', "/"));
+            if index.find_prefix(dir_prefix.as_str()).is_err() {
+                debug!("Found untracked directory: {:?}", prefix);
+                roots.push(prefix.clone());
+                break;
+            }
+        }
+    }
+
+    roots.sort();
+    Ok(roots)
+}
+
+/// Get diff content for a specific file in a commit
+/// Replaces: git show --format= --no-color <commit> -- <path>
+pub fn get_commit_file_diff(
     repo: &Repository,
     commit_sha: &str,
     path: &Path,
-) -> Result<(usize, usize)> {
-    debug!("Getting commit file stats for: {} {:?}", commit_sha, path);
+    old_path: Option<&Path>,
+) -> Result<Vec<String>> {
+    debug!("Getting commit diff for: {} {:?}", commit_sha, path);
 
     let oid = git2::Oid::from_str(commit_sha)?;
     let commit = repo.find_commit(oid)?;
@@ -209,27 +371,26 @@ pub fn get_commit_file_stats(
         None
     };
 
-    // Create diff options to filter for specific file
-    let mut diff_options = DiffOptions::new();
-    diff_options.pathspec(path);
+    let mut diff_options = file_diff_options(path, old_path);
 
-    // Get diff only for our specific file
-    let diff = repo.diff_tree_to_tree(
+    let mut diff = repo.diff_tree_to_tree(
         parent_tree.as_ref(),
         Some(&commit_tree),
         Some(&mut diff_options),
     )?;
+    find_renames(&mut diff)?;
+
+    debug!("Commit diff deltas found: {}", diff.deltas().count());
 
-    // Extract diff lines and count stats
     let (lines, additions, deletions) = extract_diff_lines(&diff)?;
     debug!(
-        "File diff lines generated: {}, additions: {}, deletions: {}",
+        "Commit diff lines generated: {}, additions: {}, deletions: {}",
         lines.len(),
         additions,
         deletions
     );
 
-    Ok((additions, deletions))
+    Ok(lines)
 }
 
 /// Get full commit diff (for LLM summaries)
@@ -255,7 +416,147 @@ pub fn get_full_commit_diff(repo: &Repository, commit_sha: &str) -> Result<Strin
     Ok(lines.join("\n"))
 }
 
+/// Apply `patch` to the index only, leaving the working tree alone
+/// Replaces: git apply --cached
+pub fn apply_to_index(repo: &Repository, patch: &str) -> Result<()> {
+    let diff = git2::Diff::from_buffer(patch.as_bytes())?;
+    repo.apply(&diff, git2::ApplyLocation::Index, None)?;
+    Ok(())
+}
+
+/// Throw away the working tree changes to `path`, going back to the index's copy, or
+/// delete it when it's untracked; a rename also brings back the file at `old_path`
+/// Replaces: git checkout -- <path>, or rm <path>
+pub fn discard_working_tree_changes(
+    repo: &Repository,
+    path: &Path,
+    old_path: Option<&Path>,
+    untracked: bool,
+) -> Result<()> {
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| color_eyre::eyre::eyre!("Repository has no working tree"))?;
+    if untracked || old_path.is_some() {
+        std::fs::remove_file(workdir.join(path))?;
+    }
+    if untracked {
+        return Ok(());
+    }
+
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.force().path(old_path.unwrap_or(path));
+    repo.checkout_index(None, Some(&mut checkout))?;
+    Ok(())
+}
+
+/// Get the full commit message, including the body
+/// Replaces: git log -1 --format=%B <commit>
+pub fn get_commit_message(repo: &Repository, commit_sha: &str) -> Result<String> {
+    debug!("Getting commit message for: {}", commit_sha);
+
+    let oid = git2::Oid::from_str(commit_sha)?;
+    let commit = repo.find_commit(oid)?;
+    let message = String::from_utf8_lossy(commit.message_bytes());
+
+    Ok(message.trim_end().to_string())
+}
+
+/// Count commits HEAD's branch is ahead of and behind its upstream
+/// Returns None on a detached HEAD, without an upstream, or before the upstream is fetched
+/// Replaces: git rev-list --left-right --count HEAD...@{upstream}
+pub fn get_upstream_ahead_behind(repo: &Repository) -> Result<Option<(usize, usize)>> {
+    let head = match repo.head() {
+        Ok(head) if head.is_branch() => head,
+        _ => return Ok(None),
+    };
+    let (Some(branch), Some(local)) = (head.name(), head.target()) else {
+        return Ok(None);
+    };
+
+    let upstream_name = match repo.branch_upstream_name(branch) {
+        Ok(name) => name,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let Some(upstream_name) = upstream_name.as_str() else {
+        return Ok(None);
+    };
+    let upstream = match repo.refname_to_id(upstream_name) {
+        Ok(oid) => oid,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Some(repo.graph_ahead_behind(local, upstream)?))
+}
+
+/// Count the entries in the stash
+/// Replaces: git stash list | wc -l
+pub fn get_stash_count(repo: &mut Repository) -> Result<usize> {
+    let mut count = 0;
+    repo.stash_foreach(|_, _, _| {
+        count += 1;
+        true
+    })?;
+    Ok(count)
+}
+
+/// Whether HEAD has commits that no remote-tracking branch contains
+/// For a branch without an upstream; one with an upstream is better served by its ahead count
+/// Always false in a repository with no remotes, which has nowhere to push
+/// Replaces: git log HEAD --not --remotes
+pub fn has_commits_missing_from_remotes(repo: &Repository) -> Result<bool> {
+    if repo.remotes()?.is_empty() {
+        return Ok(false);
+    }
+    let Some(head) = repo.head().ok().and_then(|head| head.target()) else {
+        return Ok(false);
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head)?;
+    revwalk.hide_glob("refs/remotes/*")?;
+    Ok(revwalk.next().transpose()?.is_some())
+}
+
 /// Helper function to extract diff lines and statistics from a git2 Diff
+/// Add a printed diff line to `lines` as grw shows it, counting it if it's an addition or
+/// deletion
+fn push_diff_line(
+    line: &git2::DiffLine,
+    lines: &mut Vec<String>,
+    additions: &mut usize,
+    deletions: &mut usize,
+) {
+    let origin = line.origin();
+    let content = std::str::from_utf8(line.content()).unwrap_or("");
+    let trimmed_content = content.trim_end_matches('\n');
+
+    match origin {
+        // Context lines
+        ' ' => {
+            lines.push(format!(" {}", trimmed_content));
+        }
+        // Added lines
+        '+' => {
+            *additions += 1;
+            lines.push(format!("+{}", trimmed_content));
+        }
+        // Deleted lines
+        '-' => {
+            *deletions += 1;
+            lines.push(format!("-{}", trimmed_content));
+        }
+        // Handle other cases (headers, hunks, etc.)
+        _ => {
+            // Split multi-line content (like 'F' origin) into individual lines
+            for l in content.lines() {
+                lines.push(l.to_string());
+            }
+        }
+    }
+}
+
 fn extract_diff_lines(diff: &git2::Diff) -> Result<(Vec<String>, usize, usize)> {
     let mut lines = Vec::new();
     let mut additions = 0;
@@ -263,34 +564,7 @@ fn extract_diff_lines(diff: &git2::Diff) -> Result<(Vec<String>, usize, usize)>
 
     // Generate diff text using proper patch format
     diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
-        let origin = line.origin();
-        let content = std::str::from_utf8(line.content()).unwrap_or("");
-        let trimmed_content = content.trim_end_matches('\n');
-
-        match origin {
-            // Context lines
-            ' ' => {
-                lines.push(format!(" {}", trimmed_content));
-            }
-            // Added lines
-            '+' => {
-                additions += 1;
-                lines.push(format!("+{}", trimmed_content));
-            }
-            // Deleted lines
-            '-' => {
-                deletions += 1;
-                lines.push(format!("-{}", trimmed_content));
-            }
-            // Handle other cases (headers, hunks, etc.)
-            _ => {
-                // Split multi-line content (like 'F' origin) into individual lines
-                for l in content.lines() {
-                    lines.push(l.to_string());
-                }
-            }
-        }
-
+        push_diff_line(&line, &mut lines, &mut additions, &mut deletions);
         true
     })?;
 
@@ -385,7 +659,7 @@ mod tests {
 
         // Test working tree diff
         let relative_path = Path::new("test.txt");
-        let (lines, additions, deletions) = get_working_tree_diff(&repo, relative_path)?;
+        let (lines, additions, deletions) = get_working_tree_diff(&repo, relative_path, None)?;
 
         assert!(!lines.is_empty());
         assert!(additions > 0);
@@ -417,7 +691,7 @@ mod tests {
 
         // Test staged diff
         let relative_path = Path::new("test.txt");
-        let (lines, additions, deletions) = get_staged_diff(&repo, relative_path)?;
+        let (lines, additions, deletions) = get_staged_diff(&repo, relative_path, None)?;
 
         assert!(!lines.is_empty());
         // We expect at least some changes but the exact count might vary
@@ -427,26 +701,101 @@ mod tests {
     }
 
     #[test]
-    fn test_is_file_in_dirty_directory() -> Result<()> {
+    fn test_read_index_and_head_files() -> Result<()> {
         let (_temp_dir, repo, repo_path) = create_test_repo()?;
-
-        // Create initial commit
         create_commit(
             &repo,
             &repo_path,
             "test.txt",
-            "Hello World",
+            "committed\n",
             "Initial commit",
         )?;
 
-        // Modify the file
-        let file_path = repo_path.join("test.txt");
-        fs::write(&file_path, "Hello World Modified")?;
+        fs::write(repo_path.join("test.txt"), "staged\n")?;
+        let mut index = repo.index()?;
+        index.add_path(Path::new("test.txt"))?;
+        index.write()?;
+        fs::write(repo_path.join("test.txt"), "working\n")?;
 
-        // Test dirty directory detection
-        let relative_path = Path::new("test.txt");
-        let is_dirty = is_file_in_dirty_directory(&repo, relative_path)?;
-        assert!(is_dirty);
+        let path = Path::new("test.txt");
+        assert_eq!(read_head_file(&repo, path)?, "committed\n");
+        assert_eq!(read_index_file(&repo, path)?, "staged\n");
+        assert!(read_index_file(&repo, Path::new("missing.txt")).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_base_diff() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+
+        create_commit(&repo, &repo_path, "base.txt", "base", "Initial commit")?;
+        repo.branch("main", &repo.head()?.peel_to_commit()?, false)?;
+
+        // A committed change, a staged change, and an untracked file all count
+        create_commit(&repo, &repo_path, "feature.txt", "feature", "Add feature")?;
+        fs::write(repo_path.join("base.txt"), "base\nstaged")?;
+        let mut index = repo.index()?;
+        index.add_path(Path::new("base.txt"))?;
+        index.write()?;
+        fs::write(repo_path.join("new.txt"), "new")?;
+
+        let mut changes = get_base_changes(&repo, "main")?;
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        let files: Vec<_> = changes
+            .iter()
+            .map(|change| (change.path.clone(), change.old_path.clone(), change.delta))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                (PathBuf::from("base.txt"), None, git2::Delta::Modified),
+                (PathBuf::from("feature.txt"), None, git2::Delta::Added),
+                (PathBuf::from("new.txt"), None, git2::Delta::Untracked),
+            ]
+        );
+
+        // Each file's lines from the one diff match diffing it alone
+        let (lines, additions, deletions) =
+            get_base_diff(&repo, "main", Path::new("base.txt"), None)?;
+        assert!(lines.iter().any(|line| line == "+staged"));
+        assert_eq!((additions, deletions), (2, 1));
+        assert_eq!(changes[0].lines, lines);
+        assert_eq!((changes[0].additions, changes[0].deletions), (2, 1));
+
+        assert!(resolve_base_tree(&repo, "no-such-branch").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_untracked_directories() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+
+        fs::create_dir_all(repo_path.join("src"))?;
+        create_commit(&repo, &repo_path, "src/lib.rs", "// lib", "Initial commit")?;
+
+        // A wholly untracked tree, a new file in a tracked dir, and a new subdir of a tracked dir
+        fs::create_dir_all(repo_path.join("newdir/nested"))?;
+        fs::write(repo_path.join("newdir/a.txt"), "a")?;
+        fs::write(repo_path.join("newdir/nested/b.txt"), "b")?;
+        fs::write(repo_path.join("src/new.rs"), "new")?;
+        fs::create_dir_all(repo_path.join("src/extra"))?;
+        fs::write(repo_path.join("src/extra/c.rs"), "c")?;
+
+        let new_files = vec![
+            PathBuf::from("newdir/a.txt"),
+            PathBuf::from("newdir/nested/b.txt"),
+            PathBuf::from("src/new.rs"),
+            PathBuf::from("src/extra/c.rs"),
+            PathBuf::from("top.txt"),
+        ];
+        let dirs = get_untracked_directories(&repo, &new_files)?;
+
+        assert_eq!(
+            dirs,
+            vec![PathBuf::from("newdir"), PathBuf::from("src/extra")]
+        );
 
         Ok(())
     }
@@ -477,7 +826,7 @@ mod tests {
 
         // Test commit file diff
         let relative_path = Path::new("test.txt");
-        let lines = get_commit_file_diff(&repo, &commit_id.to_string(), relative_path)?;
+        let lines = get_commit_file_diff(&repo, &commit_id.to_string(), relative_path, None)?;
 
         assert!(!lines.is_empty());
 
@@ -485,11 +834,11 @@ mod tests {
     }
 
     #[test]
-    fn test_get_commit_file_stats() -> Result<()> {
+    fn test_get_full_commit_diff() -> Result<()> {
         let (_temp_dir, repo, repo_path) = create_test_repo()?;
 
         // Create initial commit
-        let _commit1_id = create_commit(
+        create_commit(
             &repo,
             &repo_path,
             "test.txt",
@@ -499,56 +848,40 @@ mod tests {
 
         // Create second commit with modification
         let file_path = repo_path.join("test.txt");
-        fs::write(&file_path, "Hello World Modified\nNew line")?;
-        let commit2_id = create_commit(
+        fs::write(&file_path, "Hello World Modified")?;
+        let commit_id = create_commit(
             &repo,
             &repo_path,
             "test.txt",
-            "Hello World Modified\nNew line",
+            "Hello World Modified",
             "Modified file",
         )?;
 
-        // Test commit file stats for second commit
-        let relative_path = repo_path.join("test.txt");
-        let (additions, deletions) =
-            get_commit_file_stats(&repo, &commit2_id.to_string(), &relative_path)?;
+        // Test full commit diff
+        let diff_text = get_full_commit_diff(&repo, &commit_id.to_string())?;
 
-        // Let's be more lenient - just verify the function works and returns some result
-        // The diff detection might be more complex with git2
-        println!("Additions: {}, Deletions: {}", additions, deletions);
+        assert!(!diff_text.is_empty());
+        assert!(diff_text.contains("Hello World"));
 
         Ok(())
     }
 
     #[test]
-    fn test_get_full_commit_diff() -> Result<()> {
+    fn test_get_commit_message() -> Result<()> {
         let (_temp_dir, repo, repo_path) = create_test_repo()?;
 
-        // Create initial commit
-        create_commit(
-            &repo,
-            &repo_path,
-            "test.txt",
-            "Hello World",
-            "Initial commit",
-        )?;
-
-        // Create second commit with modification
-        let file_path = repo_path.join("test.txt");
-        fs::write(&file_path, "Hello World Modified")?;
         let commit_id = create_commit(
             &repo,
             &repo_path,
             "test.txt",
-            "Hello World Modified",
-            "Modified file",
+            "Hello World",
+            "Add greeting\n\nExplain why the greeting matters.\n",
         )?;
 
-        // Test full commit diff
-        let diff_text = get_full_commit_diff(&repo, &commit_id.to_string())?;
+        let message = get_commit_message(&repo, &commit_id.to_string())?;
+        assert_eq!(message, "Add greeting\n\nExplain why the greeting matters.");
 
-        assert!(!diff_text.is_empty());
-        assert!(diff_text.contains("Hello World"));
+        assert!(get_commit_message(&repo, "not-a-sha").is_err());
 
         Ok(())
     }
@@ -572,7 +905,7 @@ mod tests {
 
         // Test working tree diff for untracked file (using relative path)
         let relative_path = Path::new("new_untracked.txt");
-        let (lines, additions, deletions) = get_working_tree_diff(&repo, relative_path)?;
+        let (lines, additions, deletions) = get_working_tree_diff(&repo, relative_path, None)?;
 
         // Verify the diff is generated correctly
         assert!(
@@ -612,6 +945,164 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_get_working_tree_diff_rename() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+
+        let content = "line 1\nline 2\nline 3\nline 4\n";
+        create_commit(&repo, &repo_path, "old.txt", content, "Initial commit")?;
+        fs::rename(repo_path.join("old.txt"), repo_path.join("new.txt"))?;
+
+        // With the old path included, the move shows as a rename rather than a new file
+        let (lines, additions, deletions) =
+            get_working_tree_diff(&repo, Path::new("new.txt"), Some(Path::new("old.txt")))?;
+        assert!(lines.iter().any(|line| line == "rename from old.txt"));
+        assert!(lines.iter().any(|line| line == "rename to new.txt"));
+        assert_eq!((additions, deletions), (0, 0));
+
+        let (_, additions, _) = get_working_tree_diff(&repo, Path::new("new.txt"), None)?;
+        assert_eq!(additions, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_diff_patch_applies() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+
+        create_commit(
+            &repo,
+            &repo_path,
+            "a.txt",
+            "one\ntwo\nthree\n",
+            "Initial commit",
+        )?;
+        fs::write(repo_path.join("a.txt"), "one\n2\nthree\n")?;
+        // No newline at the end, which git marks with a line of its own
+        fs::write(repo_path.join("new.txt"), "first\nsecond")?;
+
+        let patch = |path: &str, status: git2::Status| -> Result<String> {
+            let (line_strings, additions, deletions) =
+                get_working_tree_diff(&repo, Path::new(path), None)?;
+            let file_diff = crate::git::FileDiff {
+                path: repo_path.join(path),
+                old_path: None,
+                submodule: None,
+                status,
+                line_strings,
+                additions,
+                deletions,
+            };
+            Ok(file_diff.patch(&repo_path).unwrap())
+        };
+        let patches = [
+            patch("a.txt", git2::Status::WT_MODIFIED)?,
+            patch("new.txt", git2::Status::WT_NEW)?,
+        ];
+        assert!(patches[1].contains("--- /dev/null\n+++ b/new.txt\n"));
+        assert!(patches[1].ends_with("+second\n\\ No newline at end of file\n"));
+
+        // Undo the changes, then bring them back from the patches alone
+        let head = repo.head()?.peel_to_commit()?;
+        repo.reset(head.as_object(), git2::ResetType::Hard, None)?;
+        fs::remove_file(repo_path.join("new.txt"))?;
+        for patch in &patches {
+            let diff = git2::Diff::from_buffer(patch.as_bytes())?;
+            repo.apply(&diff, git2::ApplyLocation::WorkDir, None)?;
+        }
+        assert_eq!(
+            fs::read_to_string(repo_path.join("a.txt"))?,
+            "one\n2\nthree\n"
+        );
+        assert_eq!(
+            fs::read_to_string(repo_path.join("new.txt"))?,
+            "first\nsecond"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_one_hunk_to_index() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+
+        // A removed `-- comment` shows as `--- comment`, which looks like a header
+        let original: String = (1..=30)
+            .map(|i| match i {
+                25 => "-- comment\n".to_string(),
+                i => format!("line {i}\n"),
+            })
+            .collect();
+        create_commit(&repo, &repo_path, "a.lua", &original, "Initial commit")?;
+        // Two changes far enough apart to be separate hunks
+        let changed = original
+            .replace("line 2\n", "line two\n")
+            .replace("-- comment\n", "");
+        fs::write(repo_path.join("a.lua"), &changed)?;
+        let staged_content = original.replace("-- comment\n", "");
+
+        let (line_strings, additions, deletions) =
+            get_working_tree_diff(&repo, Path::new("a.lua"), None)?;
+        let file_diff = crate::git::FileDiff {
+            path: repo_path.join("a.lua"),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_MODIFIED,
+            line_strings,
+            additions,
+            deletions,
+        };
+        let second_hunk = file_diff
+            .line_strings
+            .iter()
+            .rposition(|line| line.starts_with("@@"))
+            .unwrap();
+        let patch = file_diff.hunk_patch(&repo_path, second_hunk + 1).unwrap();
+        assert!(patch.starts_with("diff --git a/a.lua b/a.lua\n"));
+        assert!(patch.contains("\n--- comment\n line 26\n"));
+        assert!(!patch.contains("+line two"));
+
+        apply_to_index(&repo, &patch)?;
+
+        // Only the second change is staged; the first is still waiting in the working tree
+        let index = repo.index()?;
+        let entry = index.get_path(Path::new("a.lua"), 0).unwrap();
+        let blob = repo.find_blob(entry.id)?;
+        assert_eq!(std::str::from_utf8(blob.content())?, staged_content);
+        let (lines, _, _) = get_working_tree_diff(&repo, Path::new("a.lua"), None)?;
+        assert!(lines.iter().any(|line| line == "+line two"));
+        assert!(!lines.iter().any(|line| line == "--- comment"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_discard_working_tree_changes() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+
+        create_commit(&repo, &repo_path, "a.txt", "committed\n", "Initial commit")?;
+        // Staged changes survive; only the working tree's are thrown away
+        fs::write(repo_path.join("a.txt"), "staged\n")?;
+        let mut index = repo.index()?;
+        index.add_path(Path::new("a.txt"))?;
+        index.write()?;
+        fs::write(repo_path.join("a.txt"), "unstaged\n")?;
+        fs::write(repo_path.join("new.txt"), "untracked\n")?;
+
+        discard_working_tree_changes(&repo, Path::new("a.txt"), None, false)?;
+        assert_eq!(fs::read_to_string(repo_path.join("a.txt"))?, "staged\n");
+
+        discard_working_tree_changes(&repo, Path::new("new.txt"), None, true)?;
+        assert!(!repo_path.join("new.txt").exists());
+
+        // A deleted file comes back
+        fs::remove_file(repo_path.join("a.txt"))?;
+        discard_working_tree_changes(&repo, Path::new("a.txt"), None, false)?;
+        assert_eq!(fs::read_to_string(repo_path.join("a.txt"))?, "staged\n");
+
+        Ok(())
+    }
+
     #[test]
     fn test_discover_repository_workdir() -> Result<()> {
         let (_temp_dir, _repo, repo_path) = create_test_repo()?;
@@ -633,6 +1124,92 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_resolve_repository_path() -> Result<()> {
+        let (_temp_dir, _repo, repo_path) = create_test_repo()?;
+        assert_eq!(resolve_repository_path(Some(&repo_path))?, repo_path);
+
+        let not_a_repo = TempDir::new()?;
+        let error = resolve_repository_path(Some(not_a_repo.path())).unwrap_err();
+        assert!(error.to_string().contains("is not a git repository"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_upstream_ahead_behind() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        let base = create_commit(&repo, &repo_path, "a.txt", "a\n", "Base")?;
+        let branch_name = repo.head()?.shorthand().unwrap().to_string();
+
+        // No upstream configured yet
+        assert_eq!(get_upstream_ahead_behind(&repo)?, None);
+
+        repo.remote("origin", "https://example.invalid/repo.git")?;
+        let remote_ref = format!("refs/remotes/origin/{branch_name}");
+        repo.reference(&remote_ref, base, true, "fetch")?;
+        repo.find_branch(&branch_name, git2::BranchType::Local)?
+            .set_upstream(Some(&format!("origin/{branch_name}")))?;
+        assert_eq!(get_upstream_ahead_behind(&repo)?, Some((0, 0)));
+
+        // Two local commits, and one on the remote that isn't here yet
+        create_commit(&repo, &repo_path, "b.txt", "b\n", "Local 1")?;
+        create_commit(&repo, &repo_path, "c.txt", "c\n", "Local 2")?;
+        let signature = git2::Signature::now("Test User", "test@example.com")?;
+        let base_commit = repo.find_commit(base)?;
+        let remote_commit = repo.commit(
+            None,
+            &signature,
+            &signature,
+            "Remote",
+            &base_commit.tree()?,
+            &[&base_commit],
+        )?;
+        repo.reference(&remote_ref, remote_commit, true, "fetch")?;
+        assert_eq!(get_upstream_ahead_behind(&repo)?, Some((2, 1)));
+
+        // A detached HEAD has no upstream
+        repo.set_head_detached(base)?;
+        assert_eq!(get_upstream_ahead_behind(&repo)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_stash_count() -> Result<()> {
+        let (_temp_dir, mut repo, repo_path) = create_test_repo()?;
+        create_commit(&repo, &repo_path, "a.txt", "a\n", "Base")?;
+        assert_eq!(get_stash_count(&mut repo)?, 0);
+
+        let signature = git2::Signature::now("Test User", "test@example.com")?;
+        for content in ["b\n", "c\n"] {
+            fs::write(repo_path.join("a.txt"), content)?;
+            repo.stash_save(&signature, "wip", None)?;
+        }
+        assert_eq!(get_stash_count(&mut repo)?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_has_commits_missing_from_remotes() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        let base = create_commit(&repo, &repo_path, "a.txt", "a\n", "Base")?;
+
+        // Nowhere to push to
+        assert!(!has_commits_missing_from_remotes(&repo)?);
+
+        repo.remote("origin", "https://example.invalid/repo.git")?;
+        assert!(has_commits_missing_from_remotes(&repo)?);
+
+        repo.reference("refs/remotes/origin/feature", base, true, "push")?;
+        assert!(!has_commits_missing_from_remotes(&repo)?);
+
+        create_commit(&repo, &repo_path, "b.txt", "b\n", "Local")?;
+        assert!(has_commits_missing_from_remotes(&repo)?);
+
+        Ok(())
+    }
 
     #[test]
     fn test_to_repo_relative_path() -> Result<()> {
@@ -1,5 +1,5 @@
+use super::types::{FileDiff, ViewMode, combine_staged_and_unstaged};
 use std::path::PathBuf;
-use super::types::{FileDiff, ViewMode};
 
 #[derive(Debug, Clone)]
 pub struct TreeNode {
@@ -8,6 +8,18 @@ pub struct TreeNode {
     pub is_dir: bool,
     pub children: Vec<TreeNode>,
     pub file_diff: Option<FileDiff>,
+    /// True for a directory that contains no tracked files at all
+    pub untracked: bool,
+}
+
+impl TreeNode {
+    /// Count all files below this node
+    pub fn file_count(&self) -> usize {
+        self.children
+            .iter()
+            .map(|child| if child.is_dir { child.file_count() } else { 1 })
+            .sum()
+    }
 }
 
 #[derive(Debug)]
@@ -16,11 +28,21 @@ pub struct GitRepo {
     pub changed_files: Vec<FileDiff>,
     pub staged_files: Vec<FileDiff>,
     pub dirty_directory_files: Vec<FileDiff>,
+    pub untracked_dirs: Vec<PathBuf>,
     pub last_commit_files: Vec<FileDiff>,
     pub last_commit_id: Option<String>,
+    pub base_files: Vec<FileDiff>,
+    /// Ref the diff is taken against when set with `--base`
+    pub diff_base: Option<String>,
     pub current_view_mode: ViewMode,
     pub repo_name: String,
     pub branch_name: String,
+    /// Commits ahead of and behind the upstream, None when the branch has no upstream
+    pub ahead: Option<usize>,
+    pub behind: Option<usize>,
+    pub stash_count: usize,
+    /// HEAD has commits that aren't on any remote
+    pub has_unpushed: bool,
     pub commit_info: (String, String),
     pub total_stats: (usize, usize, usize),
 }
@@ -32,11 +54,18 @@ impl Clone for GitRepo {
             changed_files: self.changed_files.clone(),
             staged_files: self.staged_files.clone(),
             dirty_directory_files: self.dirty_directory_files.clone(),
+            untracked_dirs: self.untracked_dirs.clone(),
             last_commit_files: self.last_commit_files.clone(),
             last_commit_id: self.last_commit_id.clone(),
+            base_files: self.base_files.clone(),
+            diff_base: self.diff_base.clone(),
             current_view_mode: self.current_view_mode,
             repo_name: self.repo_name.clone(),
             branch_name: self.branch_name.clone(),
+            ahead: self.ahead,
+            behind: self.behind,
+            stash_count: self.stash_count,
+            has_unpushed: self.has_unpushed,
             commit_info: self.commit_info.clone(),
             total_stats: self.total_stats,
         }
@@ -44,12 +73,27 @@ impl Clone for GitRepo {
 }
 
 impl GitRepo {
+    /// Show `view_mode` instead of the one picked automatically, with stats to match
+    pub fn force_view_mode(&mut self, view_mode: ViewMode) {
+        self.current_view_mode = view_mode;
+        let files = self.get_display_files();
+        self.total_stats = (
+            files.len(),
+            files.iter().map(|f| f.additions).sum(),
+            files.iter().map(|f| f.deletions).sum(),
+        );
+    }
+
     pub fn get_display_files(&self) -> Vec<FileDiff> {
         match self.current_view_mode {
             ViewMode::WorkingTree => self.changed_files.clone(),
             ViewMode::Staged => self.staged_files.clone(),
             ViewMode::DirtyDirectory => self.dirty_directory_files.clone(),
             ViewMode::LastCommit => self.get_last_commit_files(),
+            ViewMode::Base => self.base_files.clone(),
+            ViewMode::StagedAndUnstaged => {
+                combine_staged_and_unstaged(&self.staged_files, &self.changed_files)
+            }
         }
     }
 
@@ -64,6 +108,7 @@ impl GitRepo {
             is_dir: true,
             children: Vec::new(),
             file_diff: None,
+            untracked: false,
         };
 
         for file_diff in &self.get_display_files() {
@@ -89,11 +134,12 @@ impl GitRepo {
 
             if i == components.len() - 1 {
                 current_node.children.push(TreeNode {
-                    name: component_str.clone(),
+                    name: file_diff.tree_name(&self.path),
                     path: file_diff.path.clone(),
                     is_dir: false,
                     children: Vec::new(),
                     file_diff: Some(file_diff.clone()),
+                    untracked: false,
                 });
             } else {
                 let child_index = current_node
@@ -104,12 +150,16 @@ impl GitRepo {
                 if let Some(index) = child_index {
                     current_node = &mut current_node.children[index];
                 } else {
+                    let dir_path = current_node.path.join(&component_str);
+                    let untracked = self.current_view_mode == ViewMode::WorkingTree
+                        && self.untracked_dirs.contains(&dir_path);
                     let new_child = TreeNode {
                         name: component_str.clone(),
-                        path: current_node.path.join(&component_str),
+                        path: dir_path,
                         is_dir: true,
                         children: Vec::new(),
                         file_diff: None,
+                        untracked,
                     };
                     current_node.children.push(new_child);
                     let new_len = current_node.children.len();
@@ -1,3 +1,4 @@
+use crate::config::{DEFAULT_SUMMARY_MAX_CHARS, SummaryStyle};
 use crate::shared_state::LlmSharedState;
 use super::types::CommitInfo;
 use log::debug;
@@ -7,6 +8,10 @@ use std::sync::Arc;
 pub struct PreloadConfig {
     pub enabled: bool,
     pub count: usize, // Default: 5
+    /// Whether summaries are asked for as sentences or bullet points
+    pub style: SummaryStyle,
+    /// Longest summary kept, in characters
+    pub max_chars: usize,
 }
 
 impl Default for PreloadConfig {
@@ -14,10 +19,29 @@ impl Default for PreloadConfig {
         Self {
             enabled: true,
             count: 5,
+            style: SummaryStyle::default(),
+            max_chars: DEFAULT_SUMMARY_MAX_CHARS,
         }
     }
 }
 
+/// Opening line of the summary prompt, asking for the summary in `style`
+fn summary_request(style: SummaryStyle) -> &'static str {
+    match style {
+        SummaryStyle::Sentences => {
+            "Please provide a brief, 2-sentence summary of what this commit changes.\n"
+        }
+        SummaryStyle::Bullets => {
+            "Please summarize what this commit changes as a short list of bullet points, one per line starting with \"- \".\n"
+        }
+    }
+}
+
+/// Key of a commit's summary error in the LLM shared state
+pub fn summary_error_key(commit_sha: &str) -> String {
+    format!("summary_{}", commit_sha)
+}
+
 pub struct SummaryPreloader {
     llm_client: Option<crate::llm::LlmClient>,
     config: PreloadConfig,
@@ -81,6 +105,11 @@ impl SummaryPreloader {
             return;
         }
 
+        // A failed summary stays failed until retried, so its error stays on screen
+        if self.llm_state.get_error(&summary_error_key(commit_sha)).is_some() {
+            return;
+        }
+
         // Check if summary is already cached
         if self.llm_state.get_cached_summary(commit_sha).is_some() {
             debug!(
@@ -93,21 +122,32 @@ impl SummaryPreloader {
         let sha = commit_sha.to_string();
         let llm_client = self.llm_client.clone();
         let llm_state = Arc::clone(&self.llm_state);
+        let style = self.config.style;
+        let max_chars = self.config.max_chars;
 
         // Mark as active in shared state
         self.llm_state.start_summary_task(sha.clone());
 
         // Spawn background task to generate summary
         tokio::spawn(async move {
-            Self::generate_summary_with_shared_state(sha, llm_client, llm_state).await;
+            Self::generate_summary_with_shared_state(sha, llm_client, llm_state, style, max_chars)
+                .await;
         });
     }
 
-    /// Generate summary using shared state
+    /// Clear a failed summary's error and generate it again
+    pub fn retry_summary(&mut self, commit_sha: &str) {
+        self.llm_state.clear_error(&summary_error_key(commit_sha));
+        self.preload_single_summary(commit_sha);
+    }
+
+    /// Generate summary using shared state, written in `style` and cut to `max_chars`
     async fn generate_summary_with_shared_state(
         commit_sha: String,
         llm_client: Option<crate::llm::LlmClient>,
         llm_state: Arc<LlmSharedState>,
+        style: SummaryStyle,
+        max_chars: usize,
     ) {
         if let Some(client) = llm_client {
             // Get the full diff using git2 instead of subprocess
@@ -129,7 +169,7 @@ impl SummaryPreloader {
                     // Git operation failed, log error but continue
                     debug!("Git diff failed for commit {}: {}", commit_sha, e);
                     llm_state.set_error(
-                        format!("summary_{}", commit_sha),
+                        summary_error_key(&commit_sha),
                         format!("Git diff failed: {}", e),
                     );
                     llm_state.complete_summary_task(&commit_sha);
@@ -139,7 +179,7 @@ impl SummaryPreloader {
                     // Task execution failed
                     debug!("Task execution failed for commit {}: {}", commit_sha, e);
                     llm_state.set_error(
-                        format!("summary_{}", commit_sha),
+                        summary_error_key(&commit_sha),
                         format!("Task execution failed: {}", e),
                     );
                     llm_state.complete_summary_task(&commit_sha);
@@ -147,18 +187,11 @@ impl SummaryPreloader {
                 }
             };
 
-            // Create a prompt with the full diff content
-            let mut prompt =
-                "Please provide a brief, 2-sentence summary of what this commit changes:\n\n"
-                    .to_string();
+            // The diff is sent separately so LlmClient can truncate it by file and hunk
+            let mut prompt = summary_request(style).to_string();
 
             if full_diff.trim().is_empty() {
                 prompt.push_str("No diff content available (this might be a merge commit or have parsing issues).\n");
-            } else {
-                // Diff content will be truncated in LlmClient based on max_tokens config
-                prompt.push_str("Full diff:\n```diff\n");
-                prompt.push_str(&full_diff);
-                prompt.push_str("\n```\n");
             }
 
             prompt.push_str("\nFocus on the functional impact and purpose of the changes. Keep it concise and technical.");
@@ -171,7 +204,7 @@ impl SummaryPreloader {
                         let sanitized_summary = summary_result
                             .content
                             .chars()
-                            .take(1000)
+                            .take(max_chars)
                             .collect::<String>();
                         llm_state.cache_summary(commit_sha.clone(), sanitized_summary);
                         debug!("Successfully pre-loaded summary for commit {}", commit_sha);
@@ -182,7 +215,7 @@ impl SummaryPreloader {
                             commit_sha, summary_result.content
                         );
                         llm_state.set_error(
-                            format!("summary_{}", commit_sha),
+                            summary_error_key(&commit_sha),
                             format!("Failed to generate summary: {}", summary_result.content),
                         );
                     }
@@ -194,7 +227,7 @@ impl SummaryPreloader {
                         commit_sha, e
                     );
                     llm_state.set_error(
-                        format!("summary_{}", commit_sha),
+                        summary_error_key(&commit_sha),
                         format!("Failed to generate summary: {}", e),
                     );
                 }
@@ -205,7 +238,7 @@ impl SummaryPreloader {
         } else {
             // No LLM client available
             llm_state.set_error(
-                format!("summary_{}", commit_sha),
+                summary_error_key(&commit_sha),
                 "No LLM client available".to_string(),
             );
             llm_state.complete_summary_task(&commit_sha);
@@ -232,6 +265,14 @@ mod tests {
         let config = PreloadConfig::default();
         assert!(config.enabled);
         assert_eq!(config.count, 5);
+        assert_eq!(config.style, SummaryStyle::Sentences);
+        assert_eq!(config.max_chars, DEFAULT_SUMMARY_MAX_CHARS);
+    }
+
+    #[test]
+    fn test_summary_request_follows_style() {
+        assert!(summary_request(SummaryStyle::Sentences).contains("2-sentence summary"));
+        assert!(summary_request(SummaryStyle::Bullets).contains("bullet points"));
     }
 
     #[test]
@@ -247,6 +288,7 @@ mod tests {
         let config = PreloadConfig {
             enabled: false,
             count: 10,
+            ..Default::default()
         };
         let mut preloader = SummaryPreloader::new(None, create_test_llm_state());
         preloader.set_config(config.clone());
@@ -259,6 +301,7 @@ mod tests {
         let config = PreloadConfig {
             enabled: false,
             count: 5,
+            ..Default::default()
         };
         let mut preloader = SummaryPreloader::new(None, create_test_llm_state());
         preloader.set_config(config);
@@ -267,6 +310,7 @@ mod tests {
             sha: "abc123".to_string(),
             short_sha: "abc123".to_string(),
             message: "Test commit".to_string(),
+            time: 0,
             files_changed: vec![],
         }];
 
@@ -282,6 +326,7 @@ mod tests {
             sha: "abc123".to_string(),
             short_sha: "abc123".to_string(),
             message: "Test commit".to_string(),
+            time: 0,
             files_changed: vec![],
         }];
 
@@ -298,12 +343,14 @@ mod tests {
                 sha: "abc123".to_string(),
                 short_sha: "abc123".to_string(),
                 message: "Test commit 1".to_string(),
+                time: 0,
                 files_changed: vec![],
             },
             CommitInfo {
                 sha: "def456".to_string(),
                 short_sha: "def456".to_string(),
                 message: "Test commit 2".to_string(),
+                time: 0,
                 files_changed: vec![],
             },
         ];
@@ -1,26 +1,412 @@
 use git2::Status;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub struct FileDiff {
     pub path: PathBuf,
+    /// Path before the file was renamed, if it was
+    pub old_path: Option<PathBuf>,
+    /// Set for a submodule, which is shown by commit rather than as a text diff
+    pub submodule: Option<SubmoduleChange>,
     pub status: Status,
     pub line_strings: Vec<String>,
     pub additions: usize,
     pub deletions: usize,
 }
 
+impl FileDiff {
+    /// Number of `@@` hunk headers in the diff
+    pub fn hunk_count(&self) -> usize {
+        self.line_strings
+            .iter()
+            .filter(|line| line.starts_with("@@"))
+            .count()
+    }
+
+    /// Line number in the new version of the file for `line_strings[index]`
+    /// None for removed lines and headers, which have no place in the new file
+    pub fn new_file_line(&self, index: usize) -> Option<usize> {
+        let mut next_line = None;
+        for line in self.line_strings.iter().take(index) {
+            if line.starts_with("@@") {
+                next_line = hunk_new_start(line);
+            } else if line.starts_with('+') || line.starts_with(' ') {
+                next_line = next_line.map(|n| n + 1);
+            }
+        }
+
+        let line = self.line_strings.get(index)?;
+        if line.starts_with('+') || line.starts_with(' ') {
+            next_line
+        } else {
+            None
+        }
+    }
+
+    /// Lines `start..=end` of the diff, led by the header of the hunk they start in
+    /// when the range doesn't include it, so the LLM still knows where they are
+    pub fn excerpt(&self, start: usize, end: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        let starts_in_hunk = self
+            .line_strings
+            .get(start)
+            .is_some_and(|line| !line.starts_with("@@"));
+        if starts_in_hunk
+            && let Some(header) = self.line_strings[..start]
+                .iter()
+                .rev()
+                .find(|line| line.starts_with("@@"))
+        {
+            lines.push(header.clone());
+        }
+        lines.extend(
+            self.line_strings
+                .iter()
+                .skip(start)
+                .take(end.saturating_sub(start) + 1)
+                .cloned(),
+        );
+        lines
+    }
+
+    /// The file's diff as git would print it, for sending to the LLM
+    /// Text diffs already carry git's own headers; anything else, like a submodule,
+    /// gets a `diff --git` line relative to `root` and, for a rename, `rename from`/`rename to`
+    pub fn unified_diff(&self, root: &Path) -> String {
+        let mut diff = String::new();
+        // Checked past the first line, which may be a staged or unstaged section label
+        let has_git_header = self
+            .line_strings
+            .iter()
+            .any(|line| line.starts_with("diff --git "));
+        if !has_git_header {
+            let path = self.path.strip_prefix(root).unwrap_or(&self.path);
+            let old_path = self
+                .old_path
+                .as_deref()
+                .map(|old_path| old_path.strip_prefix(root).unwrap_or(old_path))
+                .unwrap_or(path);
+            diff.push_str(&format!(
+                "diff --git a/{} b/{}\n",
+                old_path.display(),
+                path.display()
+            ));
+            if old_path != path {
+                diff.push_str(&format!("rename from {}\n", old_path.display()));
+                diff.push_str(&format!("rename to {}\n", path.display()));
+            }
+        }
+        for line in &self.line_strings {
+            diff.push_str(line);
+            diff.push('\n');
+        }
+        diff
+    }
+
+    /// The file's changes as a patch `git apply` accepts
+    /// git's own headers are kept; a diff without them gets `diff --git`, `---` and `+++`
+    /// lines written from the status, with `/dev/null` on the missing side of a new or
+    /// deleted file
+    pub fn patch(&self, root: &Path) -> Result<String, String> {
+        let first_hunk = self
+            .line_strings
+            .iter()
+            .position(|line| line.starts_with("@@"))
+            .unwrap_or(self.line_strings.len());
+        let (headers, hunks) = self.line_strings.split_at(first_hunk);
+        self.build_patch(root, headers, hunks)
+    }
+
+    /// Just the hunk `line_strings[index]` is in as a patch, with the headers of the diff
+    /// it belongs to; from a header, the hunk after it
+    pub fn hunk_patch(&self, root: &Path, index: usize) -> Result<String, String> {
+        let mut hunk = None;
+        for (start, line) in self.line_strings.iter().enumerate() {
+            if !line.starts_with("@@") {
+                continue;
+            }
+            let end = self.hunk_end(start);
+            if index < end {
+                hunk = Some((start, end));
+                break;
+            }
+        }
+        let (start, end) = hunk.ok_or_else(|| "No hunk here".to_string())?;
+
+        // Headers of the diff holding the hunk, which in the staged and unstaged view
+        // isn't always the first
+        let header_start = self.line_strings[..start]
+            .iter()
+            .rposition(|line| line.starts_with("diff --git "))
+            .unwrap_or(0);
+        let header_end = self.line_strings[header_start..start]
+            .iter()
+            .position(|line| line.starts_with("@@"))
+            .map_or(start, |offset| header_start + offset);
+        let headers: Vec<String> = self.line_strings[header_start..header_end]
+            .iter()
+            .filter(|line| {
+                line.as_str() != STAGED_SECTION_LABEL && line.as_str() != UNSTAGED_SECTION_LABEL
+            })
+            .cloned()
+            .collect();
+        self.build_patch(root, &headers, &self.line_strings[start..end])
+    }
+
+    /// Index just past the hunk whose header is at `start`, found by counting off the
+    /// lines its header says each side has, since a removed `-- comment` looks like a header
+    fn hunk_end(&self, start: usize) -> usize {
+        let (mut old_left, mut new_left) =
+            hunk_line_counts(&self.line_strings[start]).unwrap_or((0, 0));
+        let mut end = start + 1;
+        while let Some(line) = self.line_strings.get(end) {
+            if line.starts_with(' ') && old_left > 0 && new_left > 0 {
+                old_left -= 1;
+                new_left -= 1;
+            } else if line.starts_with('-') && old_left > 0 {
+                old_left -= 1;
+            } else if line.starts_with('+') && new_left > 0 {
+                new_left -= 1;
+            } else if !(line.is_empty() || line.starts_with('\\')) {
+                // Anything else past the last counted line belongs to what comes next
+                break;
+            }
+            end += 1;
+        }
+        end
+    }
+
+    /// A patch of `hunks` under `headers`, filling in the headers git would have written
+    fn build_patch(
+        &self,
+        root: &Path,
+        headers: &[String],
+        hunks: &[String],
+    ) -> Result<String, String> {
+        if self.submodule.is_some() {
+            return Err("Submodules can't be exported as a patch".to_string());
+        }
+        if self
+            .line_strings
+            .iter()
+            .any(|line| line.starts_with("Binary files "))
+        {
+            return Err("Binary files can't be exported as a patch".to_string());
+        }
+
+        let has_git_header = headers.iter().any(|line| line.starts_with("diff --git "));
+        // A rename with no content change is all headers
+        if hunks.is_empty() && !has_git_header {
+            return Err("No changes to export".to_string());
+        }
+
+        let path = self.path.strip_prefix(root).unwrap_or(&self.path);
+        let old_path = self
+            .old_path
+            .as_deref()
+            .map(|old_path| old_path.strip_prefix(root).unwrap_or(old_path))
+            .unwrap_or(path);
+        let is_new = self.status.intersects(Status::WT_NEW | Status::INDEX_NEW);
+        let is_deleted = self
+            .status
+            .intersects(Status::WT_DELETED | Status::INDEX_DELETED);
+
+        let mut patch = String::new();
+        if !has_git_header {
+            patch.push_str(&format!(
+                "diff --git a/{} b/{}\n",
+                old_path.display(),
+                path.display()
+            ));
+            if is_new {
+                patch.push_str("new file mode 100644\n");
+            } else if is_deleted {
+                patch.push_str("deleted file mode 100644\n");
+            }
+        }
+        for line in headers {
+            patch.push_str(line);
+            patch.push('\n');
+        }
+        if !hunks.is_empty() && !headers.iter().any(|line| line.starts_with("--- ")) {
+            let old_side = if is_new {
+                "/dev/null".to_string()
+            } else {
+                format!("a/{}", old_path.display())
+            };
+            let new_side = if is_deleted {
+                "/dev/null".to_string()
+            } else {
+                format!("b/{}", path.display())
+            };
+            patch.push_str(&format!("--- {old_side}\n+++ {new_side}\n"));
+        }
+        // Every line of a hunk has a prefix; the only empty ones are left over from the
+        // newline git prints ahead of `\ No newline at end of file`
+        for line in hunks.iter().filter(|line| !line.is_empty()) {
+            patch.push_str(line);
+            patch.push('\n');
+        }
+        Ok(patch)
+    }
+
+    /// Name shown in the file tree: the file name, or `old -> new` for a rename
+    /// The old path is only given in full when the file moved to another directory
+    pub fn tree_name(&self, root: &Path) -> String {
+        let name = rename_label(&self.path, self.old_path.as_deref(), root);
+        match &self.submodule {
+            Some(submodule) => format!("{name} @ {submodule}"),
+            None => name,
+        }
+    }
+}
+
+#[cfg(test)]
+impl FileDiff {
+    /// A working tree modification of `path` whose diff is `lines`, for tests
+    pub fn for_test(path: &str, lines: &[&str]) -> Self {
+        Self {
+            path: PathBuf::from(path),
+            old_path: None,
+            submodule: None,
+            status: Status::WT_MODIFIED,
+            line_strings: lines.iter().map(|line| line.to_string()).collect(),
+            additions: 0,
+            deletions: 0,
+        }
+    }
+
+    /// Set the added and removed line counts, which `for_test` leaves at zero
+    pub fn with_counts(mut self, additions: usize, deletions: usize) -> Self {
+        self.additions = additions;
+        self.deletions = deletions;
+        self
+    }
+}
+
+/// Commits a submodule moved between, as full SHAs
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmoduleChange {
+    pub old_sha: Option<String>,
+    pub new_sha: Option<String>,
+}
+
+impl std::fmt::Display for SubmoduleChange {
+    /// `old → new` with short SHAs, or a single SHA when only the contents changed
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let short = |sha: &Option<String>| match sha {
+            Some(sha) => sha.chars().take(7).collect(),
+            None => "none".to_string(),
+        };
+
+        if self.old_sha == self.new_sha {
+            write!(f, "{} (modified content)", short(&self.new_sha))
+        } else {
+            write!(f, "{} → {}", short(&self.old_sha), short(&self.new_sha))
+        }
+    }
+}
+
+/// Line leading a file's staged changes in the staged and unstaged view
+pub const STAGED_SECTION_LABEL: &str = "▶ Staged (HEAD → index)";
+/// Line leading a file's unstaged changes in the staged and unstaged view
+pub const UNSTAGED_SECTION_LABEL: &str = "▶ Unstaged (index → working tree)";
+
+/// Every file with staged or unstaged changes, in path order, with its staged diff and
+/// then its unstaged one, each led by its section label
+pub fn combine_staged_and_unstaged(staged: &[FileDiff], unstaged: &[FileDiff]) -> Vec<FileDiff> {
+    let labelled = |label: &str, file: &FileDiff| {
+        std::iter::once(label.to_string())
+            .chain(file.line_strings.iter().cloned())
+            .collect::<Vec<_>>()
+    };
+
+    let mut files: Vec<FileDiff> = staged
+        .iter()
+        .map(|file| FileDiff {
+            line_strings: labelled(STAGED_SECTION_LABEL, file),
+            ..file.clone()
+        })
+        .collect();
+    for file in unstaged {
+        let lines = labelled(UNSTAGED_SECTION_LABEL, file);
+        match files.iter_mut().find(|combined| combined.path == file.path) {
+            Some(combined) => {
+                combined.status |= file.status;
+                combined.old_path = combined.old_path.take().or_else(|| file.old_path.clone());
+                combined.submodule = combined.submodule.take().or_else(|| file.submodule.clone());
+                combined.line_strings.extend(lines);
+                combined.additions += file.additions;
+                combined.deletions += file.deletions;
+            }
+            None => files.push(FileDiff {
+                line_strings: lines,
+                ..file.clone()
+            }),
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// Start line of the new side from a `@@ -a,b +c,d @@` header
+pub(crate) fn hunk_new_start(header: &str) -> Option<usize> {
+    let new_range = header
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('+'))?;
+    new_range.split(',').next()?.parse().ok()
+}
+
+/// Lines on the old and new side from a `@@ -a,b +c,d @@` header, where a missing
+/// count means one line
+fn hunk_line_counts(header: &str) -> Option<(usize, usize)> {
+    let count = |prefix: char| {
+        let range = header
+            .split_whitespace()
+            .find_map(|part| part.strip_prefix(prefix))?;
+        match range.split_once(',') {
+            Some((_, count)) => count.parse().ok(),
+            None => Some(1),
+        }
+    };
+    Some((count('-')?, count('+')?))
+}
+
+/// `new`'s file name, prefixed with `old -> ` when `old` is set
+fn rename_label(path: &Path, old_path: Option<&Path>, root: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    match old_path {
+        Some(old_path) if old_path.parent() == path.parent() => {
+            let old_name = old_path.file_name().unwrap_or_default().to_string_lossy();
+            format!("{old_name} -> {name}")
+        }
+        Some(old_path) => {
+            let old_path = old_path.strip_prefix(root).unwrap_or(old_path);
+            format!("{} -> {name}", old_path.display())
+        }
+        None => name,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CommitInfo {
     pub sha: String,
     pub short_sha: String,
     pub message: String,
+    /// When the commit was made, or for a reflog entry when HEAD moved, in seconds since the Unix epoch
+    pub time: i64,
     pub files_changed: Vec<CommitFileChange>,
 }
 
 #[derive(Debug, Clone)]
 pub struct CommitFileChange {
     pub path: PathBuf,
+    /// Path before the file was renamed, if it was
+    pub old_path: Option<PathBuf>,
     pub status: FileChangeStatus,
     pub additions: usize,
     pub deletions: usize,
@@ -40,4 +426,8 @@ pub enum ViewMode {
     Staged,
     DirtyDirectory,
     LastCommit,
+    /// Everything changed since the ref passed as `--base`
+    Base,
+    /// Staged and unstaged changes together, each file's under its own label
+    StagedAndUnstaged,
 }
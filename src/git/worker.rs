@@ -1,18 +1,29 @@
 use super::operations as git_operations;
-use super::{CommitFileChange, CommitInfo, FileChangeStatus, FileDiff, GitRepo, ViewMode};
+use super::{
+    CommitFileChange, CommitInfo, FileChangeStatus, FileDiff, GitRepo, SubmoduleChange, ViewMode,
+};
 use crate::shared_state::GitSharedState;
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{Result, eyre};
 use git2::{DiffOptions, Repository, Status, StatusOptions};
 use log::debug;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+/// Shared state error key for a last commit that can't be read
+pub const LAST_COMMIT_ERROR_KEY: &str = "last_commit";
+/// Shared state error key for a diff base that can't be read
+pub const BASE_ERROR_KEY: &str = "diff_base";
+
+/// Status given to every file in the dirty directory view
+const DIRTY_DIRECTORY_STATUS: Status = Status::INDEX_MODIFIED;
+
 /// Types of diffs that can be generated
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum DiffType {
     WorkingTree,
     Staged,
     DirtyDirectory,
+    Base,
 }
 
 pub struct GitWorker {
@@ -21,17 +32,35 @@ pub struct GitWorker {
     changed_files: Vec<FileDiff>,
     staged_files: Vec<FileDiff>,
     dirty_directory_files: Vec<FileDiff>,
+    untracked_dirs: Vec<PathBuf>,
+    /// Repository-relative paths of submodules, refreshed on every update
+    submodule_paths: Vec<PathBuf>,
     last_commit_files: Vec<FileDiff>,
     last_commit_id: Option<String>,
+    base_files: Vec<FileDiff>,
+    diff_base: Option<String>,
     current_view_mode: ViewMode,
+    /// Whether the dirty directory view is offered at all
+    dirty_directory_view: bool,
     shared_state: Arc<GitSharedState>,
     last_head_commit_id: Option<String>, // Track HEAD commit to detect branch changes
+    /// Commits the branch is ahead of its upstream, None without an upstream
+    ahead: Option<usize>,
+    /// Commits the branch is behind its upstream, None without an upstream
+    behind: Option<usize>,
+    stash_count: usize,
+    /// HEAD has commits not on any remote
+    has_unpushed: bool,
 }
 
 impl GitWorker {
     /// Create a new GitWorker with shared state
+    /// `path` may be anywhere inside the repository; the worker watches its worktree root
     pub fn new(path: PathBuf, shared_state: Arc<GitSharedState>) -> Result<Self> {
-        let repo = Repository::open(&path)?;
+        let repo = Repository::discover(&path)?;
+        // Status and diff paths are relative to the root, so every join must start there
+        let path = git_operations::worktree_root(&repo)?;
+        debug!("Watching repository at {path:?}");
 
         let last_commit_id = repo
             .head()
@@ -47,24 +76,59 @@ impl GitWorker {
             changed_files: Vec::new(),
             staged_files: Vec::new(),
             dirty_directory_files: Vec::new(),
+            untracked_dirs: Vec::new(),
+            submodule_paths: Vec::new(),
             last_commit_files: Vec::new(),
             last_commit_id,
+            base_files: Vec::new(),
+            diff_base: None,
             current_view_mode: ViewMode::WorkingTree,
+            dirty_directory_view: true,
             shared_state,
             last_head_commit_id,
+            ahead: None,
+            behind: None,
+            stash_count: 0,
+            has_unpushed: false,
         })
     }
 
-    /// Continuous run loop for shared state mode
+    /// Show everything changed since `revspec` (e.g. `origin/main`) instead of the
+    /// usual working tree, staged, and last commit views
+    pub fn set_diff_base(&mut self, revspec: &str) -> Result<()> {
+        // Fail early on a bad ref; it is resolved again on every update so it can move
+        git_operations::resolve_base_tree(&self.repo, revspec)?;
+        debug!("Diffing against base: {revspec}");
+        self.diff_base = Some(revspec.to_string());
+        Ok(())
+    }
+
+    /// Offer the dirty directory view, or leave unstaged changes to the working tree view alone
+    pub fn set_dirty_directory_view(&mut self, enabled: bool) {
+        self.dirty_directory_view = enabled;
+    }
+
+    /// Continuous run loop for shared state mode, waiting `update_interval_ms` between
+    /// updates until the shared state's refresh interval is changed
     pub async fn run_continuous(&mut self, update_interval_ms: u64) -> Result<()> {
         debug!(
             "Starting GitWorker continuous run loop with {}ms interval",
             update_interval_ms
         );
-
-        let update_interval = tokio::time::Duration::from_millis(update_interval_ms);
+        self.shared_state
+            .set_refresh_interval_ms(update_interval_ms);
 
         loop {
+            let update_interval =
+                tokio::time::Duration::from_millis(self.shared_state.refresh_interval_ms());
+            // Check the pause flag more often than we refresh so resuming feels immediate
+            let pause_poll_interval = update_interval.min(tokio::time::Duration::from_millis(50));
+
+            if self.shared_state.is_paused() {
+                tokio::time::sleep(pause_poll_interval).await;
+                continue;
+            }
+
             // Perform git status update
             // Use block_in_place to prevent blocking the async runtime
             if let Err(e) = tokio::task::block_in_place(|| self.update_shared_state()) {
@@ -73,8 +137,13 @@ impl GitWorker {
                 // Continue running despite errors
             }
 
-            // Sleep for the configured interval
-            tokio::time::sleep(update_interval).await;
+            // Sleep for the configured interval, waking early if a refresh is requested
+            let sleep_start = tokio::time::Instant::now();
+            while sleep_start.elapsed() < update_interval
+                && !self.shared_state.take_refresh_request()
+            {
+                tokio::time::sleep(pause_poll_interval).await;
+            }
         }
     }
 
@@ -138,37 +207,58 @@ impl GitWorker {
     fn update_internal_direct(&mut self) -> Result<()> {
         // Check for HEAD/branch changes first
         let head_changed = self.detect_head_change();
+        self.submodule_paths = self.get_submodule_paths();
 
         // Get all statuses including staged files
         let statuses = self.repo.statuses(Some(
             StatusOptions::new()
                 .include_ignored(false)
                 .include_untracked(true)
-                .recurse_untracked_dirs(true),
+                .recurse_untracked_dirs(true)
+                .renames_head_to_index(true)
+                .renames_index_to_workdir(true),
         ))?;
         let mut new_changed_files = Vec::new();
         let mut new_staged_files = Vec::new();
         let mut new_dirty_directory_files = Vec::new();
+        let mut new_untracked_files = Vec::new();
         let status_count = statuses.len();
         debug!("Found {status_count} total status entries");
 
         for status in statuses.iter() {
+            // For a rename this is the old path; the new one comes from the deltas below
             let path = status.path().unwrap_or("");
-            // Use git2-based path handling for consistent relative/absolute path conversion
-            let file_path = super::operations::from_repo_relative_path(&self.repo, Path::new(path));
+            let (staged_path, staged_old_path) = self.delta_paths(status.head_to_index(), path);
+            let (file_path, old_path) = self.delta_paths(status.index_to_workdir(), path);
+
+            // Each file is classified once from its status flags; the dirty directory view
+            // holds the tracked files with unstaged changes, like `git diff --name-only`
+            let unstaged = status.status().intersects(
+                Status::WT_MODIFIED
+                    | Status::WT_DELETED
+                    | Status::WT_RENAMED
+                    | Status::WT_TYPECHANGE,
+            );
+            let mut working_tree_diff = None;
 
             // Working tree changes (unstaged)
             if status.status().is_wt_new()
                 || status.status().is_wt_modified()
                 || status.status().is_wt_deleted()
+                || status.status().is_wt_renamed()
             {
-                let diff = self.get_file_diff(&file_path, status.status());
+                let diff = self.get_file_diff(&file_path, old_path.as_deref(), status.status());
                 debug!(
                     "Processing working tree file: {} (status: {:?})",
                     path,
                     status.status()
                 );
+                working_tree_diff = Some(diff.clone());
                 new_changed_files.push(diff);
+
+                if status.status().is_wt_new() {
+                    new_untracked_files.push(PathBuf::from(path));
+                }
             }
 
             // Staged files
@@ -178,7 +268,11 @@ impl GitWorker {
                 || status.status().is_index_renamed()
                 || status.status().is_index_typechange()
             {
-                let diff = self.get_staged_file_diff(&file_path, status.status());
+                let diff = self.get_staged_file_diff(
+                    &staged_path,
+                    staged_old_path.as_deref(),
+                    status.status(),
+                );
                 debug!(
                     "Processing staged file: {} (status: {:?})",
                     path,
@@ -187,17 +281,30 @@ impl GitWorker {
                 new_staged_files.push(diff);
             }
 
-            // Dirty directory detection (files that would be shown by git diff --name-only)
-            if self.is_file_in_dirty_directory(&file_path) {
-                let diff = self.get_dirty_directory_diff(&file_path);
+            // The same unstaged diff, computed again only for a type change the working
+            // tree view leaves out
+            if self.dirty_directory_view && unstaged {
+                let diff = match working_tree_diff {
+                    Some(diff) => FileDiff {
+                        status: DIRTY_DIRECTORY_STATUS,
+                        ..diff
+                    },
+                    None => self.get_dirty_directory_diff(&file_path, old_path.as_deref()),
+                };
                 debug!("Processing dirty directory file: {path}");
                 new_dirty_directory_files.push(diff);
             }
         }
 
+        // Release the borrow of the repository, which counting stashes needs mutably
+        drop(statuses);
+
         // Determine view mode based on priority
         let old_view_mode = self.current_view_mode;
-        if !new_changed_files.is_empty() {
+        if let Some(base_files) = self.read_base_files() {
+            self.base_files = base_files;
+            self.current_view_mode = ViewMode::Base;
+        } else if !new_changed_files.is_empty() {
             self.current_view_mode = ViewMode::WorkingTree;
         } else if !new_dirty_directory_files.is_empty() {
             self.current_view_mode = ViewMode::DirtyDirectory;
@@ -211,13 +318,61 @@ impl GitWorker {
         // or if HEAD changed, to ensure we have fresh data for the UI
         if (head_changed || self.last_commit_files.is_empty()) && self.last_commit_id.is_some() {
             debug!("Refreshing last commit files due to HEAD change or empty cache");
-            self.last_commit_files = self.get_last_commit_files();
+            self.last_commit_files = match self.get_last_commit_files() {
+                Ok(files) => files,
+                Err(e) => {
+                    self.record_last_commit_error(&e);
+                    Vec::new()
+                }
+            };
+        }
+
+        // Group wholly untracked directories so the tree can show them as a unit
+        self.untracked_dirs =
+            super::operations::get_untracked_directories(&self.repo, &new_untracked_files)
+                .unwrap_or_else(|e| {
+                    debug!("Failed to detect untracked directories: {e}");
+                    Vec::new()
+                })
+                .iter()
+                .map(|dir| super::operations::from_repo_relative_path(&self.repo, dir))
+                .collect();
+
+        // Status order isn't guaranteed, and a reshuffled tree would move the cursor
+        for files in [
+            &mut new_changed_files,
+            &mut new_staged_files,
+            &mut new_dirty_directory_files,
+        ] {
+            files.sort_by(|a, b| a.path.cmp(&b.path));
         }
 
         self.changed_files = new_changed_files;
         self.staged_files = new_staged_files;
         self.dirty_directory_files = new_dirty_directory_files;
 
+        // Recounted every refresh since a fetch moves the upstream without touching HEAD
+        let ahead_behind =
+            git_operations::get_upstream_ahead_behind(&self.repo).unwrap_or_else(|e| {
+                debug!("Failed to count commits against upstream: {e}");
+                None
+            });
+        self.ahead = ahead_behind.map(|(ahead, _)| ahead);
+        self.behind = ahead_behind.map(|(_, behind)| behind);
+        self.has_unpushed = match self.ahead {
+            Some(ahead) => ahead > 0,
+            None => {
+                git_operations::has_commits_missing_from_remotes(&self.repo).unwrap_or_else(|e| {
+                    debug!("Failed to look for unpushed commits: {e}");
+                    false
+                })
+            }
+        };
+        self.stash_count = git_operations::get_stash_count(&mut self.repo).unwrap_or_else(|e| {
+            debug!("Failed to count stashes: {e}");
+            0
+        });
+
         if old_view_mode != self.current_view_mode {
             debug!(
                 "View mode changed: {:?} -> {:?}",
@@ -237,7 +392,13 @@ impl GitWorker {
     }
 
     /// Unified diff generation method that handles all diff types
-    fn generate_diff(&self, path: &Path, status: Status, diff_type: DiffType) -> FileDiff {
+    fn generate_diff(
+        &self,
+        path: &Path,
+        old_path: Option<&Path>,
+        status: Status,
+        diff_type: DiffType,
+    ) -> FileDiff {
         debug!("Computing diff for file: {path:?} (status: {status:?}, type: {diff_type:?})");
 
         let mut line_strings = Vec::new();
@@ -246,12 +407,34 @@ impl GitWorker {
 
         // Convert absolute path to relative path for git_operations using unified helper
         let relative_path = super::operations::to_repo_relative_path(&self.repo, path);
+        let relative_old_path =
+            old_path.map(|old_path| super::operations::to_repo_relative_path(&self.repo, old_path));
+        let relative_old_path = relative_old_path.as_deref();
+
+        // Submodules are shown by the commit they point at, never as a text diff
+        if self.submodule_paths.contains(&relative_path) {
+            let submodule = self.get_submodule_change(&relative_path, diff_type);
+            debug!("Submodule {relative_path:?}: {submodule}");
+            return FileDiff {
+                path: path.to_path_buf(),
+                old_path: old_path.map(Path::to_path_buf),
+                line_strings: vec![format!("Submodule {} {submodule}", relative_path.display())],
+                submodule: Some(submodule),
+                status,
+                additions: 0,
+                deletions: 0,
+            };
+        }
 
         match diff_type {
             DiffType::WorkingTree => {
                 if status.is_wt_new() {
                     // For new files, use the same git_operations function
-                    match git_operations::get_working_tree_diff(&self.repo, &relative_path) {
+                    match git_operations::get_working_tree_diff(
+                        &self.repo,
+                        &relative_path,
+                        relative_old_path,
+                    ) {
                         Ok((lines, added, deleted)) => {
                             line_strings = lines;
                             additions = added;
@@ -265,8 +448,15 @@ impl GitWorker {
                             );
                         }
                     }
-                } else if status.is_wt_modified() || status.is_wt_deleted() {
-                    match git_operations::get_working_tree_diff(&self.repo, &relative_path) {
+                } else if status.is_wt_modified()
+                    || status.is_wt_deleted()
+                    || status.is_wt_renamed()
+                {
+                    match git_operations::get_working_tree_diff(
+                        &self.repo,
+                        &relative_path,
+                        relative_old_path,
+                    ) {
                         Ok((lines, added, deleted)) => {
                             line_strings = lines;
                             additions = added;
@@ -282,19 +472,46 @@ impl GitWorker {
                     }
                 }
             }
-            DiffType::Staged => match git_operations::get_staged_diff(&self.repo, &relative_path) {
-                Ok((lines, added, deleted)) => {
-                    line_strings = lines;
-                    additions = added;
-                    deletions = deleted;
-                    debug!("Staged file: +{additions} -{deletions}");
+            DiffType::Staged => {
+                match git_operations::get_staged_diff(&self.repo, &relative_path, relative_old_path)
+                {
+                    Ok((lines, added, deleted)) => {
+                        line_strings = lines;
+                        additions = added;
+                        deletions = deleted;
+                        debug!("Staged file: +{additions} -{deletions}");
+                    }
+                    Err(e) => {
+                        debug!("Failed to get staged diff for {:?}: {}", relative_path, e);
+                    }
                 }
-                Err(e) => {
-                    debug!("Failed to get staged diff for {:?}: {}", relative_path, e);
+            }
+            DiffType::Base => {
+                if let Some(revspec) = &self.diff_base {
+                    match git_operations::get_base_diff(
+                        &self.repo,
+                        revspec,
+                        &relative_path,
+                        relative_old_path,
+                    ) {
+                        Ok((lines, added, deleted)) => {
+                            line_strings = lines;
+                            additions = added;
+                            deletions = deleted;
+                            debug!("Base file: +{additions} -{deletions}");
+                        }
+                        Err(e) => {
+                            debug!("Failed to get base diff for {:?}: {}", relative_path, e);
+                        }
+                    }
                 }
-            },
+            }
             DiffType::DirtyDirectory => {
-                match git_operations::get_working_tree_diff(&self.repo, &relative_path) {
+                match git_operations::get_working_tree_diff(
+                    &self.repo,
+                    &relative_path,
+                    relative_old_path,
+                ) {
                     Ok((lines, added, deleted)) => {
                         line_strings = lines;
                         additions = added;
@@ -313,6 +530,8 @@ impl GitWorker {
 
         FileDiff {
             path: path.to_path_buf(),
+            old_path: old_path.map(Path::to_path_buf),
+            submodule: None,
             status,
             line_strings,
             additions,
@@ -321,27 +540,152 @@ impl GitWorker {
     }
 
     /// Get file diff for working tree (maintains backward compatibility)
-    fn get_file_diff(&self, path: &Path, status: Status) -> FileDiff {
-        self.generate_diff(path, status, DiffType::WorkingTree)
+    fn get_file_diff(&self, path: &Path, old_path: Option<&Path>, status: Status) -> FileDiff {
+        self.generate_diff(path, old_path, status, DiffType::WorkingTree)
     }
 
     /// Get file diff for staged files (maintains backward compatibility)
-    fn get_staged_file_diff(&self, path: &Path, status: Status) -> FileDiff {
-        self.generate_diff(path, status, DiffType::Staged)
+    fn get_staged_file_diff(
+        &self,
+        path: &Path,
+        old_path: Option<&Path>,
+        status: Status,
+    ) -> FileDiff {
+        self.generate_diff(path, old_path, status, DiffType::Staged)
     }
 
     /// Get file diff for dirty directory files (maintains backward compatibility)
-    fn get_dirty_directory_diff(&self, path: &Path) -> FileDiff {
+    fn get_dirty_directory_diff(&self, path: &Path, old_path: Option<&Path>) -> FileDiff {
         self.generate_diff(
             path,
-            Status::from_bits_truncate(2),
+            old_path,
+            DIRTY_DIRECTORY_STATUS,
             DiffType::DirtyDirectory,
         )
     }
 
-    fn is_file_in_dirty_directory(&self, path: &Path) -> bool {
-        // Check if the file has unstaged changes that would be committed
-        git_operations::is_file_in_dirty_directory(&self.repo, path).unwrap_or(false)
+    fn get_submodule_paths(&self) -> Vec<PathBuf> {
+        match self.repo.submodules() {
+            Ok(submodules) => submodules
+                .iter()
+                .map(|submodule| submodule.path().to_path_buf())
+                .collect(),
+            Err(e) => {
+                debug!("Failed to list submodules: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Commits a submodule moved between for the given kind of diff
+    fn get_submodule_change(&self, relative_path: &Path, diff_type: DiffType) -> SubmoduleChange {
+        let submodule = self
+            .repo
+            .find_submodule(&relative_path.to_string_lossy())
+            .ok();
+
+        let (old_id, new_id) = match (&submodule, diff_type) {
+            (None, _) => (None, None),
+            (Some(submodule), DiffType::Staged) => (submodule.head_id(), submodule.index_id()),
+            (Some(submodule), DiffType::Base) => {
+                let base_id = self.diff_base.as_ref().and_then(|revspec| {
+                    let tree = git_operations::resolve_base_tree(&self.repo, revspec).ok()?;
+                    tree.get_path(relative_path).ok().map(|entry| entry.id())
+                });
+                (base_id, submodule.workdir_id())
+            }
+            (Some(submodule), DiffType::WorkingTree | DiffType::DirtyDirectory) => {
+                (submodule.index_id(), submodule.workdir_id())
+            }
+        };
+
+        SubmoduleChange {
+            old_sha: old_id.map(|id| id.to_string()),
+            new_sha: new_id.map(|id| id.to_string()),
+        }
+    }
+
+    /// Absolute new path of a status delta, plus the old path if it was renamed
+    fn delta_paths(
+        &self,
+        delta: Option<git2::DiffDelta>,
+        fallback: &str,
+    ) -> (PathBuf, Option<PathBuf>) {
+        let new_path = delta
+            .as_ref()
+            .and_then(|delta| delta.new_file().path())
+            .unwrap_or(Path::new(fallback));
+        let old_path = delta
+            .as_ref()
+            .filter(|delta| delta.status() == git2::Delta::Renamed)
+            .and_then(|delta| delta.old_file().path())
+            .map(|old_path| super::operations::from_repo_relative_path(&self.repo, old_path));
+
+        // Use git2-based path handling for consistent relative/absolute path conversion
+        (
+            super::operations::from_repo_relative_path(&self.repo, new_path),
+            old_path,
+        )
+    }
+
+    /// Files changed since the diff base, None without one; a base that can't be read, such
+    /// as a branch deleted since, is reported rather than failing the whole refresh
+    fn read_base_files(&self) -> Option<Vec<FileDiff>> {
+        let revspec = self.diff_base.as_ref()?;
+        match self.get_base_files(revspec) {
+            Ok(files) => {
+                self.shared_state.clear_error(BASE_ERROR_KEY);
+                Some(files)
+            }
+            Err(e) => {
+                debug!("Failed to read the diff base {revspec}: {e}");
+                self.shared_state.set_error(
+                    BASE_ERROR_KEY.to_string(),
+                    format!("Diff base {revspec} not read: {e}"),
+                );
+                None
+            }
+        }
+    }
+
+    /// Get every file changed since `revspec`, with its diff against it, from one diff of the
+    /// working directory
+    fn get_base_files(&self, revspec: &str) -> Result<Vec<FileDiff>> {
+        let files = git_operations::get_base_changes(&self.repo, revspec)?
+            .into_iter()
+            .map(|change| {
+                let status = match change.delta {
+                    git2::Delta::Added | git2::Delta::Untracked => Status::WT_NEW,
+                    git2::Delta::Deleted => Status::WT_DELETED,
+                    _ => Status::WT_MODIFIED,
+                };
+                let file_path =
+                    super::operations::from_repo_relative_path(&self.repo, &change.path);
+                let old_path = change.old_path.as_ref().map(|old_path| {
+                    super::operations::from_repo_relative_path(&self.repo, old_path)
+                });
+                // Submodules are shown by the commit they point at
+                if self.submodule_paths.contains(&change.path) {
+                    return self.generate_diff(
+                        &file_path,
+                        old_path.as_deref(),
+                        status,
+                        DiffType::Base,
+                    );
+                }
+                FileDiff {
+                    path: file_path,
+                    old_path,
+                    submodule: None,
+                    status,
+                    line_strings: change.lines,
+                    additions: change.additions,
+                    deletions: change.deletions,
+                }
+            })
+            .collect();
+
+        Ok(files)
     }
 
     /// Detect HEAD/branch changes and force refresh of git state
@@ -409,20 +753,44 @@ impl GitWorker {
         }
     }
 
+    /// The commit `last_commit_id` names, or None before the first commit
+    /// A malformed or missing id is an error rather than a lookup of the zero OID
+    fn find_last_commit(&self) -> Result<Option<git2::Commit<'_>>> {
+        let Some(commit_id) = &self.last_commit_id else {
+            return Ok(None);
+        };
+        let oid = git2::Oid::from_str(commit_id)
+            .map_err(|e| eyre!("Invalid last commit id {commit_id:?}: {}", e.message()))?;
+        let commit = self
+            .repo
+            .find_commit(oid)
+            .map_err(|e| eyre!("Last commit {commit_id} not found: {}", e.message()))?;
+        Ok(Some(commit))
+    }
+
+    /// Report a last commit that can't be read, so the UI can say so rather than show nothing
+    fn record_last_commit_error(&self, error: &color_eyre::eyre::Report) {
+        debug!("Failed to read the last commit: {error}");
+        self.shared_state
+            .set_error(LAST_COMMIT_ERROR_KEY.to_string(), error.to_string());
+    }
+
     fn get_last_commit_info(&self) -> (String, String) {
-        if let Some(commit_id) = &self.last_commit_id {
-            if let Ok(commit) = self
-                .repo
-                .find_commit(git2::Oid::from_str(commit_id).unwrap_or(git2::Oid::zero()))
-            {
-                let short_id = commit_id.chars().take(7).collect::<String>();
+        match self.find_last_commit() {
+            Ok(Some(commit)) => {
+                self.shared_state.clear_error(LAST_COMMIT_ERROR_KEY);
+                let short_id = commit.id().to_string().chars().take(7).collect::<String>();
                 let summary = commit.summary().unwrap_or("no summary").to_string();
                 (short_id, summary)
-            } else {
-                ("unknown".to_string(), "unknown commit".to_string())
             }
-        } else {
-            ("no commits".to_string(), "no commits".to_string())
+            Ok(None) => {
+                self.shared_state.clear_error(LAST_COMMIT_ERROR_KEY);
+                ("no commits".to_string(), "no commits".to_string())
+            }
+            Err(e) => {
+                self.record_last_commit_error(&e);
+                ("unknown".to_string(), e.to_string())
+            }
         }
     }
 
@@ -431,7 +799,11 @@ impl GitWorker {
             ViewMode::WorkingTree => self.changed_files.clone(),
             ViewMode::Staged => self.staged_files.clone(),
             ViewMode::DirtyDirectory => self.dirty_directory_files.clone(),
-            ViewMode::LastCommit => self.get_last_commit_files(),
+            ViewMode::LastCommit => self.last_commit_files.clone(),
+            ViewMode::Base => self.base_files.clone(),
+            ViewMode::StagedAndUnstaged => {
+                super::combine_staged_and_unstaged(&self.staged_files, &self.changed_files)
+            }
         };
         let total_files = display_files.len();
         let total_additions: usize = display_files.iter().map(|f| f.additions).sum();
@@ -439,21 +811,28 @@ impl GitWorker {
         (total_files, total_additions, total_deletions)
     }
 
-    fn get_last_commit_files(&self) -> Vec<FileDiff> {
+    fn get_last_commit_files(&self) -> Result<Vec<FileDiff>> {
         let mut files = Vec::new();
 
-        if let Some(commit_id) = &self.last_commit_id
-            && let Ok(commit) = self
-                .repo
-                .find_commit(git2::Oid::from_str(commit_id).unwrap_or(git2::Oid::zero()))
+        if let Some(commit) = self.find_last_commit()?
             && let Ok(tree) = commit.tree()
-            && let Ok(parent_tree) = commit.parent(0).and_then(|parent| parent.tree())
         {
+            // The first commit has no parent, so everything in it is diffed against an empty tree
+            let parent_tree = if commit.parent_count() > 0 {
+                Some(commit.parent(0)?.tree()?)
+            } else {
+                None
+            };
+
             // Get the diff between the commit and its parent
-            if let Ok(diff) = self
-                .repo
-                .diff_tree_to_tree(Some(&parent_tree), Some(&tree), None)
+            if let Ok(mut diff) =
+                self.repo
+                    .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
             {
+                if let Err(e) = git_operations::find_renames(&mut diff) {
+                    debug!("Failed to detect renames in last commit: {e}");
+                }
+
                 for delta in diff.deltas() {
                     if let Some(old_file) = delta.old_file().path()
                         && let Some(new_file) = delta.new_file().path()
@@ -473,8 +852,14 @@ impl GitWorker {
                             }
                         }
 
+                        let old_path = (old_file != new_file).then(|| {
+                            super::operations::from_repo_relative_path(&self.repo, old_file)
+                        });
+
                         files.push(FileDiff {
                             path: file_path,
+                            old_path,
+                            submodule: None,
                             status: Status::from_bits_truncate(4), // INDEX_MODIFIED
                             line_strings: diff_content,
                             additions,
@@ -485,12 +870,13 @@ impl GitWorker {
             }
         }
 
-        files
+        Ok(files)
     }
 
-    fn get_commit_diff_content(&self, _old_path: &Path, new_path: &Path) -> Vec<String> {
+    fn get_commit_diff_content(&self, old_path: &Path, new_path: &Path) -> Vec<String> {
         if let Some(commit_id) = &self.last_commit_id {
-            git_operations::get_commit_file_diff(&self.repo, commit_id, new_path)
+            let old_path = (old_path != new_path).then_some(old_path);
+            git_operations::get_commit_file_diff(&self.repo, commit_id, new_path, old_path)
                 .unwrap_or_default()
         } else {
             Vec::new()
@@ -503,21 +889,38 @@ impl GitWorker {
             changed_files: self.changed_files.clone(),
             staged_files: self.staged_files.clone(),
             dirty_directory_files: self.dirty_directory_files.clone(),
+            untracked_dirs: self.untracked_dirs.clone(),
             last_commit_files: self.last_commit_files.clone(),
             last_commit_id: self.last_commit_id.clone(),
+            base_files: self.base_files.clone(),
+            diff_base: self.diff_base.clone(),
             current_view_mode: self.current_view_mode,
             repo_name: self.get_repo_name(),
             branch_name: self.get_current_branch(),
+            ahead: self.ahead,
+            behind: self.behind,
+            stash_count: self.stash_count,
+            has_unpushed: self.has_unpushed,
             commit_info: self.get_last_commit_info(),
             total_stats: self.get_total_stats(),
         }
     }
 
     /// Get commit history with SHA and message
-    /// Returns a list of commits ordered from most recent to oldest
+    /// Returns up to `limit` commits ordered from most recent to oldest, resuming after
+    /// the commit `after` when loading a later page
     /// Uses caching to improve performance for repeated requests
-    pub fn get_commit_history(&mut self, limit: usize) -> Result<Vec<CommitInfo>> {
-        debug!("Fetching commit history with limit: {}", limit);
+    /// Skipping OIDs is cheap compared to loading file changes, so a page's walk restarts from HEAD
+    pub fn get_commit_history(
+        &mut self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        debug!(
+            "Fetching commit history with limit: {} after: {:?}",
+            limit, after
+        );
+        let after = after.map(git2::Oid::from_str).transpose()?;
 
         let mut commits = Vec::new();
 
@@ -559,6 +962,8 @@ impl GitWorker {
         let mut count = 0;
         let mut errors_encountered = 0;
         const MAX_ERRORS: usize = 5; // Allow some errors but not too many
+        let mut skipping = after.is_some();
+        self.shared_state.set_commit_history_progress(0);
 
         for oid_result in revwalk {
             if count >= limit {
@@ -578,6 +983,22 @@ impl GitWorker {
                 }
             };
 
+            // Everything up to and including `after` was on earlier pages
+            if skipping {
+                skipping = Some(oid) != after;
+                continue;
+            }
+
+            let sha = oid.to_string();
+
+            // Commits never change, so a cached one skips the object lookup and diff entirely
+            if let Some(cached_commit) = self.shared_state.get_cached_commit(&sha) {
+                commits.push(cached_commit);
+                count += 1;
+                self.shared_state.set_commit_history_progress(count);
+                continue;
+            }
+
             let commit = match self.repo.find_commit(oid) {
                 Ok(commit) => commit,
                 Err(e) => {
@@ -591,15 +1012,6 @@ impl GitWorker {
                 }
             };
 
-            let sha = oid.to_string();
-
-            // Check shared state cache first for this commit
-            if let Some(cached_commit) = self.shared_state.get_cached_commit(&sha) {
-                commits.push(cached_commit);
-                count += 1;
-                continue;
-            }
-
             let short_sha = sha.chars().take(7).collect::<String>();
             let message = commit.summary().unwrap_or("<no message>").to_string();
 
@@ -618,6 +1030,7 @@ impl GitWorker {
                 sha: sha.clone(),
                 short_sha,
                 message,
+                time: commit.time().seconds(),
                 files_changed,
             };
 
@@ -626,6 +1039,7 @@ impl GitWorker {
             commits.push(commit_info);
 
             count += 1;
+            self.shared_state.set_commit_history_progress(count);
         }
 
         if errors_encountered > 0 {
@@ -643,6 +1057,46 @@ impl GitWorker {
         Ok(commits)
     }
 
+    /// Up to `limit` entries of HEAD's reflog, newest first, as commits for the picker
+    /// Each message leads with its `HEAD@{n}` selector, so a commit lost to a reset or
+    /// rebase can be found by what moved HEAD away from it
+    pub fn get_reflog(&mut self, limit: usize) -> Result<Vec<CommitInfo>> {
+        debug!("Getting up to {limit} reflog entries");
+        let reflog = self.repo.reflog("HEAD")?;
+        self.shared_state.set_commit_history_progress(0);
+
+        let mut entries = Vec::new();
+        for (index, entry) in reflog.iter().take(limit).enumerate() {
+            let sha = entry.id_new().to_string();
+            // Unreachable commits are eventually pruned, leaving reflog entries behind
+            let files_changed = match self.shared_state.get_cached_commit(&sha) {
+                Some(cached_commit) => cached_commit.files_changed,
+                None => match Self::get_commit_file_changes_static(&self.repo, &self.path, &sha) {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        debug!("Skipping reflog entry HEAD@{{{index}}} for {sha}: {e}");
+                        continue;
+                    }
+                },
+            };
+
+            entries.push(CommitInfo {
+                short_sha: sha.chars().take(7).collect(),
+                message: format!(
+                    "HEAD@{{{index}}}: {}",
+                    entry.message().unwrap_or("<no message>")
+                ),
+                sha,
+                time: entry.committer().when().seconds(),
+                files_changed,
+            });
+            self.shared_state.set_commit_history_progress(index + 1);
+        }
+
+        debug!("Retrieved {} reflog entries", entries.len());
+        Ok(entries)
+    }
+
     /// Static method to get file changes without requiring mutable self
     /// Used internally by get_commit_history to avoid borrowing issues
     fn get_commit_file_changes_static(
@@ -701,7 +1155,7 @@ impl GitWorker {
         };
 
         // Create diff between parent and current commit with error handling
-        let diff = match repo.diff_tree_to_tree(
+        let mut diff = match repo.diff_tree_to_tree(
             parent_tree.as_ref(),
             Some(&commit_tree),
             Some(&mut DiffOptions::new()),
@@ -712,6 +1166,9 @@ impl GitWorker {
                 return Err(e.into());
             }
         };
+        if let Err(e) = git_operations::find_renames(&mut diff) {
+            debug!("Failed to detect renames for commit {}: {}", commit_sha, e);
+        }
 
         // Process each delta (file change) in the diff
         let mut errors_encountered = 0;
@@ -758,38 +1215,41 @@ impl GitWorker {
             // Convert to absolute path for the CommitFileChange struct
             let absolute_file_path = repo_path.join(&relative_file_path);
 
-            // Get line count statistics using git diff-tree with error handling
-            // OPTIMIZATION: Use the patch from the existing diff instead of recomputing it
-            let (additions, deletions) = if let Ok(Some(patch)) = git2::Patch::from_diff(&diff, i) {
-                let stats = patch.line_stats().unwrap_or((0, 0, 0));
-                (stats.1, stats.2)
-            } else {
-                match Self::get_commit_file_stats_static_relative(
-                    repo,
-                    commit_sha,
-                    &relative_file_path,
-                ) {
-                    Ok(stats) => stats,
-                    Err(e) => {
-                        debug!(
-                            "Failed to get file stats for {} in commit {}: {}",
-                            relative_file_path.display(),
-                            commit_sha,
-                            e
-                        );
-                        errors_encountered += 1;
-                        if errors_encountered >= MAX_FILE_ERRORS {
-                            debug!("Too many file processing errors, stopping");
-                            break;
-                        }
-                        // Continue with zero stats rather than failing completely
-                        (0, 0)
+            // Line counts come from the patch of the diff already computed for this commit
+            // Binary files have no patch and count as zero lines
+            let (additions, deletions) = match git2::Patch::from_diff(&diff, i) {
+                Ok(Some(patch)) => {
+                    let (_, additions, deletions) = patch.line_stats().unwrap_or((0, 0, 0));
+                    (additions, deletions)
+                }
+                Ok(None) => (0, 0),
+                Err(e) => {
+                    debug!(
+                        "Failed to get line stats for {} in commit {}: {}",
+                        relative_file_path.display(),
+                        commit_sha,
+                        e
+                    );
+                    errors_encountered += 1;
+                    if errors_encountered >= MAX_FILE_ERRORS {
+                        debug!("Too many file processing errors, stopping");
+                        break;
                     }
+                    (0, 0)
+                }
+            };
+
+            // Only a rename keeps its old path; for other changes it matches the new one
+            let old_path = match status {
+                FileChangeStatus::Renamed => {
+                    delta.old_file().path().map(|path| repo_path.join(path))
                 }
+                _ => None,
             };
 
             file_changes.push(CommitFileChange {
                 path: absolute_file_path,
+                old_path,
                 status,
                 additions,
                 deletions,
@@ -813,28 +1273,6 @@ impl GitWorker {
 
         Ok(file_changes)
     }
-
-    /// Static helper method to get addition/deletion counts for a specific file in a commit (using relative paths)
-    fn get_commit_file_stats_static_relative(
-        repo: &Repository,
-        commit_sha: &str,
-        relative_file_path: &Path,
-    ) -> Result<(usize, usize)> {
-        // Validate inputs
-        if commit_sha.is_empty() {
-            return Err(color_eyre::eyre::eyre!(
-                "Empty commit SHA provided to get_commit_file_stats"
-            ));
-        }
-
-        if relative_file_path.as_os_str().is_empty() {
-            return Err(color_eyre::eyre::eyre!(
-                "Empty file path provided to get_commit_file_stats"
-            ));
-        }
-
-        git_operations::get_commit_file_stats(repo, commit_sha, relative_file_path)
-    }
 }
 
 #[cfg(test)]
@@ -865,13 +1303,27 @@ mod tests {
         content: &str,
         message: &str,
     ) -> Result<git2::Oid> {
-        // Create file
-        let file_path = repo_path.join(filename);
-        fs::write(&file_path, content)?;
+        commit_fixtures(repo, repo_path, &[(filename, content)], message)
+    }
 
-        // Add to index
+    /// Write each `(path, content)` fixture, creating directories as needed, and commit
+    /// them all on top of HEAD
+    fn commit_fixtures(
+        repo: &Repository,
+        repo_path: &Path,
+        fixtures: &[(&str, &str)],
+        message: &str,
+    ) -> Result<git2::Oid> {
+        // Create files and add them to the index
         let mut index = repo.index()?;
-        index.add_path(Path::new(filename))?;
+        for (filename, content) in fixtures {
+            let file_path = repo_path.join(filename);
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&file_path, content)?;
+            index.add_path(Path::new(filename))?;
+        }
         index.write()?;
 
         // Create commit
@@ -901,6 +1353,107 @@ mod tests {
         Ok(commit_id)
     }
 
+    /// A worker on a fresh temporary repository with one committed file, `committed.txt`,
+    /// and no changes yet
+    fn create_test_worker() -> Result<(TempDir, Repository, PathBuf, GitWorker)> {
+        create_test_worker_with_fixtures(&[("committed.txt", "one\n")])
+    }
+
+    /// A worker on a fresh temporary repository whose only commit holds `fixtures`
+    fn create_test_worker_with_fixtures(
+        fixtures: &[(&str, &str)],
+    ) -> Result<(TempDir, Repository, PathBuf, GitWorker)> {
+        let (temp_dir, repo, repo_path) = create_test_repo()?;
+        commit_fixtures(&repo, &repo_path, fixtures, "Initial commit")?;
+        let git_worker = GitWorker::new(repo_path.clone(), Arc::new(GitSharedState::new()))?;
+        Ok((temp_dir, repo, repo_path, git_worker))
+    }
+
+    fn stage(repo: &Repository, repo_path: &Path, filename: &str, content: &str) -> Result<()> {
+        fs::write(repo_path.join(filename), content)?;
+        let mut index = repo.index()?;
+        index.add_path(Path::new(filename))?;
+        index.write()?;
+        Ok(())
+    }
+
+    /// The view mode chosen by a refresh of the worker's repository as it is now
+    fn refreshed_view_mode(git_worker: &mut GitWorker) -> Result<ViewMode> {
+        git_worker.update_shared_state()?;
+        Ok(git_worker.current_view_mode)
+    }
+
+    #[test]
+    fn test_view_mode_clean_shows_last_commit() -> Result<()> {
+        let (_temp_dir, _repo, _repo_path, mut git_worker) = create_test_worker()?;
+        assert_eq!(refreshed_view_mode(&mut git_worker)?, ViewMode::LastCommit);
+        assert_eq!(git_worker.last_commit_files.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_view_mode_only_staged_shows_staged() -> Result<()> {
+        let (_temp_dir, repo, repo_path, mut git_worker) = create_test_worker()?;
+        stage(&repo, &repo_path, "committed.txt", "one\ntwo\n")?;
+        stage(&repo, &repo_path, "added.txt", "new\n")?;
+
+        // Staged changes must not be masked by the working tree or dirty directory views
+        assert_eq!(refreshed_view_mode(&mut git_worker)?, ViewMode::Staged);
+        assert_eq!(git_worker.staged_files.len(), 2);
+        assert!(git_worker.changed_files.is_empty());
+        assert!(git_worker.dirty_directory_files.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_view_mode_only_unstaged_shows_working_tree() -> Result<()> {
+        let (_temp_dir, _repo, repo_path, mut git_worker) = create_test_worker()?;
+        fs::write(repo_path.join("committed.txt"), "one\ntwo\n")?;
+        fs::write(repo_path.join("untracked.txt"), "new\n")?;
+
+        assert_eq!(refreshed_view_mode(&mut git_worker)?, ViewMode::WorkingTree);
+        assert_eq!(git_worker.changed_files.len(), 2);
+        assert!(git_worker.staged_files.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_view_mode_mixed_prefers_working_tree() -> Result<()> {
+        let (_temp_dir, repo, repo_path, mut git_worker) = create_test_worker()?;
+        // Partly staged: one version in the index, another in the working tree
+        stage(&repo, &repo_path, "committed.txt", "one\ntwo\n")?;
+        fs::write(repo_path.join("committed.txt"), "one\ntwo\nthree\n")?;
+        stage(&repo, &repo_path, "added.txt", "new\n")?;
+
+        assert_eq!(refreshed_view_mode(&mut git_worker)?, ViewMode::WorkingTree);
+        assert_eq!(git_worker.changed_files.len(), 1);
+        assert_eq!(git_worker.staged_files.len(), 2);
+
+        // Once the working tree matches the index again, the staged view takes over
+        fs::write(repo_path.join("committed.txt"), "one\ntwo\n")?;
+        assert_eq!(refreshed_view_mode(&mut git_worker)?, ViewMode::Staged);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_view_mode_type_change_shows_dirty_directory() -> Result<()> {
+        let (_temp_dir, _repo, repo_path, mut git_worker) = create_test_worker()?;
+        // A file turned into a symlink is unstaged, but not a working tree change
+        fs::remove_file(repo_path.join("committed.txt"))?;
+        std::os::unix::fs::symlink("elsewhere", repo_path.join("committed.txt"))?;
+
+        assert_eq!(
+            refreshed_view_mode(&mut git_worker)?,
+            ViewMode::DirtyDirectory
+        );
+        assert_eq!(git_worker.dirty_directory_files.len(), 1);
+
+        git_worker.set_dirty_directory_view(false);
+        assert_eq!(refreshed_view_mode(&mut git_worker)?, ViewMode::LastCommit);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_commit_history() -> Result<()> {
         let (_temp_dir, repo, repo_path) = create_test_repo()?;
@@ -933,7 +1486,7 @@ mod tests {
         let mut git_worker = GitWorker::new(repo_path.clone(), shared_state)?;
 
         // Test get_commit_history
-        let commits = git_worker.get_commit_history(10)?;
+        let commits = git_worker.get_commit_history(None, 10)?;
 
         // Should have 3 commits
         assert_eq!(commits.len(), 3);
@@ -973,7 +1526,7 @@ mod tests {
         let mut git_worker = GitWorker::new(repo_path.clone(), shared_state)?;
 
         // Test with limit
-        let commits = git_worker.get_commit_history(3)?;
+        let commits = git_worker.get_commit_history(None, 3)?;
 
         // Should only return 3 commits
         assert_eq!(commits.len(), 3);
@@ -987,6 +1540,95 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_commit_file_changes_line_stats() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        create_commit(&repo, &repo_path, "text.txt", "a\nb\n", "Initial commit")?;
+
+        fs::write(repo_path.join("text.txt"), "a\nc\nd\n")?;
+        fs::write(repo_path.join("image.bin"), [0u8, 159, 146, 150, 0, 1])?;
+        let mut index = repo.index()?;
+        index.add_path(Path::new("text.txt"))?;
+        index.add_path(Path::new("image.bin"))?;
+        index.write()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let signature = git2::Signature::now("Test User", "test@example.com")?;
+        let parent_commit = repo.head()?.peel_to_commit()?;
+        let commit_id = repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Edit text and add binary",
+            &tree,
+            &[&parent_commit],
+        )?;
+
+        let changes =
+            GitWorker::get_commit_file_changes_static(&repo, &repo_path, &commit_id.to_string())?;
+        let stats = |name: &str| {
+            changes
+                .iter()
+                .find(|change| change.path.ends_with(name))
+                .map(|change| (change.additions, change.deletions))
+        };
+        assert_eq!(stats("text.txt"), Some((2, 1)));
+        // Binary files have no lines to count
+        assert_eq!(stats("image.bin"), Some((0, 0)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_commit_file_stats_match_file_diff() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        create_commit(&repo, &repo_path, "a.txt", "1\n2\n3\n4\n", "Initial commit")?;
+        create_commit(&repo, &repo_path, "b.txt", "x\n", "Add b")?;
+
+        fs::write(repo_path.join("a.txt"), "1\ntwo\n3\n4\n5\n6\n")?;
+        fs::write(repo_path.join("b.txt"), "")?;
+        fs::write(repo_path.join("c.txt"), "new\nfile\n")?;
+        let mut index = repo.index()?;
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            index.add_path(Path::new(name))?;
+        }
+        index.write()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let signature = git2::Signature::now("Test User", "test@example.com")?;
+        let parent_commit = repo.head()?.peel_to_commit()?;
+        let sha = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "Touch every file",
+                &tree,
+                &[&parent_commit],
+            )?
+            .to_string();
+
+        // The picker's counts must agree with the +/- lines of the diff it shows
+        let changes = GitWorker::get_commit_file_changes_static(&repo, &repo_path, &sha)?;
+        assert_eq!(changes.len(), 3);
+        for change in changes {
+            let relative_path = change.path.strip_prefix(&repo_path)?;
+            let lines = git_operations::get_commit_file_diff(&repo, &sha, relative_path, None)?;
+            let count = |prefix: char, header: &str| {
+                lines
+                    .iter()
+                    .filter(|line| line.starts_with(prefix) && !line.starts_with(header))
+                    .count()
+            };
+            assert_eq!(
+                (change.additions, change.deletions),
+                (count('+', "+++"), count('-', "---")),
+                "{}",
+                relative_path.display()
+            );
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_commit_file_changes() -> Result<()> {
         let (_temp_dir, repo, repo_path) = create_test_repo()?;
@@ -1053,6 +1695,108 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_renames_are_followed() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        let content = "line 1\nline 2\nline 3\nline 4\n";
+        create_commit(&repo, &repo_path, "old.txt", content, "Initial commit")?;
+
+        // An uncommitted move shows once, as a rename
+        fs::create_dir(repo_path.join("src"))?;
+        fs::rename(repo_path.join("old.txt"), repo_path.join("src/new.txt"))?;
+        let shared_state = Arc::new(GitSharedState::new());
+        let mut git_worker = GitWorker::new(repo_path.clone(), shared_state.clone())?;
+        git_worker.update_shared_state()?;
+
+        let repo_data = shared_state.get_repo().unwrap();
+        assert_eq!(repo_data.changed_files.len(), 1);
+        let renamed = &repo_data.changed_files[0];
+        assert_eq!(renamed.path, repo_path.join("src/new.txt"));
+        assert_eq!(renamed.old_path, Some(repo_path.join("old.txt")));
+        assert_eq!(renamed.tree_name(&repo_path), "old.txt -> new.txt");
+
+        // Once committed, the commit's file list pairs the paths too
+        let mut index = repo.index()?;
+        index.remove_path(Path::new("old.txt"))?;
+        index.add_path(Path::new("src/new.txt"))?;
+        index.write()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let signature = git2::Signature::now("Test User", "test@example.com")?;
+        let parent_commit = repo.head()?.peel_to_commit()?;
+        let commit_id = repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Move file",
+            &tree,
+            &[&parent_commit],
+        )?;
+
+        let changes =
+            GitWorker::get_commit_file_changes_static(&repo, &repo_path, &commit_id.to_string())?;
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(changes[0].status, FileChangeStatus::Renamed));
+        assert_eq!(changes[0].path, repo_path.join("src/new.txt"));
+        assert_eq!(changes[0].old_path, Some(repo_path.join("old.txt")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submodule_changes_are_not_text_diffed() -> Result<()> {
+        let (_sub_dir, sub_repo, sub_path) = create_test_repo()?;
+        let first_sha = create_commit(&sub_repo, &sub_path, "lib.txt", "v1", "First")?;
+
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        create_commit(&repo, &repo_path, "README.md", "readme", "Initial commit")?;
+        let mut submodule = repo.submodule(sub_path.to_str().unwrap(), Path::new("lib"), true)?;
+        submodule.clone(None)?;
+        submodule.add_finalize()?;
+        let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+        let signature = git2::Signature::now("Test User", "test@example.com")?;
+        let parent_commit = repo.head()?.peel_to_commit()?;
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Add submodule",
+            &tree,
+            &[&parent_commit],
+        )?;
+
+        // Move the checked out submodule to a new commit
+        let checkout = submodule.open()?;
+        let second_sha =
+            create_commit(&checkout, &repo_path.join("lib"), "lib.txt", "v2", "Second")?;
+
+        let shared_state = Arc::new(GitSharedState::new());
+        let mut git_worker = GitWorker::new(repo_path.clone(), shared_state.clone())?;
+        git_worker.update_shared_state()?;
+
+        let repo_data = shared_state.get_repo().unwrap();
+        assert_eq!(repo_data.changed_files.len(), 1);
+        let file = &repo_data.changed_files[0];
+        assert_eq!(
+            file.submodule,
+            Some(SubmoduleChange {
+                old_sha: Some(first_sha.to_string()),
+                new_sha: Some(second_sha.to_string()),
+            })
+        );
+        assert_eq!(file.line_strings.len(), 1);
+        assert_eq!((file.additions, file.deletions), (0, 0));
+        assert_eq!(
+            file.tree_name(&repo_path),
+            format!(
+                "lib @ {:.7} → {:.7}",
+                first_sha.to_string(),
+                second_sha.to_string()
+            )
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_commit_file_changes_with_deletion() -> Result<()> {
         let (_temp_dir, repo, repo_path) = create_test_repo()?;
@@ -1113,7 +1857,7 @@ mod tests {
         let mut git_worker = GitWorker::new(repo_path.clone(), shared_state)?;
 
         // Test get_commit_history on empty repo
-        let commits = git_worker.get_commit_history(10)?;
+        let commits = git_worker.get_commit_history(None, 10)?;
         assert_eq!(commits.len(), 0);
 
         Ok(())
@@ -1156,6 +1900,43 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_commit_history_pages() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+
+        for i in 1..=5 {
+            let name = format!("file{i}.txt");
+            create_commit(&repo, &repo_path, &name, "content", &format!("Commit {i}"))?;
+        }
+
+        let shared_state = Arc::new(GitSharedState::new());
+        let mut git_worker = GitWorker::new(repo_path.clone(), Arc::clone(&shared_state))?;
+        let all = git_worker.get_commit_history(None, 10)?;
+        assert_eq!(shared_state.get_commit_history_progress(), 5);
+        assert_eq!(all.len(), 5);
+
+        let first = git_worker.get_commit_history(None, 2)?;
+        assert_eq!(shared_state.get_commit_history_progress(), 2);
+        let second = git_worker.get_commit_history(Some(&first[1].sha), 2)?;
+        let third = git_worker.get_commit_history(Some(&second[1].sha), 2)?;
+        let paged: Vec<_> = first
+            .iter()
+            .chain(&second)
+            .chain(&third)
+            .map(|c| c.sha.clone())
+            .collect();
+        assert_eq!(paged, all.iter().map(|c| c.sha.clone()).collect::<Vec<_>>());
+
+        // Past the root commit there is nothing left to load
+        assert!(
+            git_worker
+                .get_commit_history(Some(&third[0].sha), 2)?
+                .is_empty()
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_commit_caching() -> Result<()> {
         let (_temp_dir, repo, repo_path) = create_test_repo()?;
@@ -1169,16 +1950,16 @@ mod tests {
         let mut git_worker = GitWorker::new(repo_path.clone(), shared_state)?;
 
         // First call should populate cache
-        let commits1 = git_worker.get_commit_history(10)?;
+        let commits1 = git_worker.get_commit_history(None, 10)?;
         assert_eq!(commits1.len(), 3);
 
         // Second call should return the same results
-        let commits2 = git_worker.get_commit_history(10)?;
+        let commits2 = git_worker.get_commit_history(None, 10)?;
         assert_eq!(commits2.len(), 3);
         assert_eq!(commits1[0].sha, commits2[0].sha);
 
         // Cache size management is now handled by shared state
-        let commits3 = git_worker.get_commit_history(10)?;
+        let commits3 = git_worker.get_commit_history(None, 10)?;
         assert_eq!(commits3.len(), 3);
 
         // Cache clearing is now handled by shared state
@@ -1198,7 +1979,7 @@ mod tests {
         let mut git_worker = GitWorker::new(repo_path.clone(), shared_state)?;
 
         // Test that we can still get commits even if some operations fail
-        let commits = git_worker.get_commit_history(10)?;
+        let commits = git_worker.get_commit_history(None, 10)?;
         assert!(commits.len() >= 2);
 
         // Verify commit data is valid
@@ -1253,7 +2034,7 @@ mod tests {
         // Note: LlmSharedState doesn't have a clear_all method, so we verify the cache works as expected
 
         // Verify git worker still works for commit data
-        let commits = git_worker.get_commit_history(10)?;
+        let commits = git_worker.get_commit_history(None, 10)?;
         assert!(!commits.is_empty()); // Should still have commit data
 
         Ok(())
@@ -1289,6 +2070,174 @@ mod tests {
         Ok(())
     }
 
+    /// Files a commit and a working tree of changes to it, used by the harness tests
+    const FIXTURES: &[(&str, &str)] = &[
+        ("README.md", "# Fixture\n"),
+        ("src/lib.rs", "pub fn one() -> u32 {\n    1\n}\n"),
+        ("src/main.rs", "fn main() {}\n"),
+    ];
+
+    #[test]
+    fn test_update_shared_state_publishes_changes() -> Result<()> {
+        let (_temp_dir, repo, repo_path, mut git_worker) =
+            create_test_worker_with_fixtures(FIXTURES)?;
+        fs::write(
+            repo_path.join("src/lib.rs"),
+            "pub fn one() -> u32 {\n    2\n}\n",
+        )?;
+        stage(&repo, &repo_path, "src/new.rs", "pub fn new() {}\n")?;
+        fs::write(repo_path.join("notes.txt"), "todo\n")?;
+
+        git_worker.update_shared_state()?;
+        let snapshot = git_worker.shared_state.get_repo().unwrap();
+        let paths = |files: &[FileDiff]| {
+            let mut paths: Vec<_> = files
+                .iter()
+                .map(|f| f.path.strip_prefix(&repo_path).unwrap().to_path_buf())
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(snapshot.path, repo_path);
+        assert_eq!(snapshot.current_view_mode, ViewMode::WorkingTree);
+        assert_eq!(
+            paths(&snapshot.changed_files),
+            [PathBuf::from("notes.txt"), PathBuf::from("src/lib.rs")]
+        );
+        assert_eq!(paths(&snapshot.staged_files), [PathBuf::from("src/new.rs")]);
+        assert_eq!(
+            paths(&snapshot.dirty_directory_files),
+            [PathBuf::from("src/lib.rs")]
+        );
+        assert_eq!(paths(&snapshot.last_commit_files).len(), FIXTURES.len());
+
+        let lib = snapshot
+            .changed_files
+            .iter()
+            .find(|f| f.path == repo_path.join("src/lib.rs"))
+            .unwrap();
+        assert_eq!((lib.additions, lib.deletions), (1, 1));
+        assert!(lib.line_strings.contains(&"+    2".to_string()));
+        assert!(git_worker.shared_state.get_error("git_status").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_changed_files_keep_path_order_across_updates() -> Result<()> {
+        let (_temp_dir, repo, repo_path, mut git_worker) =
+            create_test_worker_with_fixtures(FIXTURES)?;
+        for (path, content) in FIXTURES {
+            fs::write(repo_path.join(path), format!("{content}changed\n"))?;
+        }
+        for path in ["zeta.txt", "alpha.txt", "src/mid.rs"] {
+            stage(&repo, &repo_path, path, "new\n")?;
+        }
+
+        let paths = |files: &[FileDiff]| files.iter().map(|f| f.path.clone()).collect::<Vec<_>>();
+        git_worker.update_shared_state()?;
+        let first = (
+            paths(&git_worker.changed_files),
+            paths(&git_worker.staged_files),
+            paths(&git_worker.dirty_directory_files),
+        );
+        git_worker.update_shared_state()?;
+        let second = (
+            paths(&git_worker.changed_files),
+            paths(&git_worker.staged_files),
+            paths(&git_worker.dirty_directory_files),
+        );
+
+        assert_eq!(first, second);
+        for files in [&first.0, &first.1, &first.2] {
+            assert!(files.is_sorted(), "{files:?} is not sorted");
+        }
+        assert_eq!(first.1.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_history_lists_fixture_commits() -> Result<()> {
+        let (_temp_dir, repo, repo_path, mut git_worker) =
+            create_test_worker_with_fixtures(FIXTURES)?;
+        commit_fixtures(
+            &repo,
+            &repo_path,
+            &[(
+                "src/lib.rs",
+                "pub fn one() -> u32 {\n    1\n}\n\npub fn two() {}\n",
+            )],
+            "Add two",
+        )?;
+
+        let commits = git_worker.get_commit_history(None, 10)?;
+        let messages: Vec<_> = commits.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(messages, ["Add two", "Initial commit"]);
+
+        let initial_files = &commits[1].files_changed;
+        assert!(
+            initial_files
+                .iter()
+                .all(|change| matches!(change.status, FileChangeStatus::Added))
+        );
+        let mut added: Vec<_> = initial_files
+            .iter()
+            .map(|change| {
+                let path = change.path.strip_prefix(&repo_path).unwrap();
+                (path.to_path_buf(), change.additions)
+            })
+            .collect();
+        added.sort();
+        assert_eq!(
+            added,
+            [
+                (PathBuf::from("README.md"), 1),
+                (PathBuf::from("src/lib.rs"), 3),
+                (PathBuf::from("src/main.rs"), 1),
+            ]
+        );
+
+        let change = &commits[0].files_changed;
+        assert_eq!(change.len(), 1);
+        assert!(matches!(change[0].status, FileChangeStatus::Modified));
+        assert_eq!((change[0].additions, change[0].deletions), (2, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_full_commit_diff_of_fixture_commits() -> Result<()> {
+        let (_temp_dir, repo, repo_path, git_worker) = create_test_worker_with_fixtures(FIXTURES)?;
+        let initial = git_worker.last_commit_id.clone().unwrap();
+        let update = commit_fixtures(
+            &repo,
+            &repo_path,
+            &[("src/main.rs", "fn main() {\n    lib::one();\n}\n")],
+            "Call one",
+        )?;
+
+        // The root commit is diffed against the empty tree
+        let diff = git_operations::get_full_commit_diff(&git_worker.repo, &initial)?;
+        for (path, _) in FIXTURES {
+            assert!(
+                diff.contains(&format!("+++ b/{path}")),
+                "missing {path} in {diff}"
+            );
+        }
+
+        let diff = git_operations::get_full_commit_diff(&git_worker.repo, &update.to_string())?;
+        assert!(diff.contains("+++ b/src/main.rs"));
+        assert!(diff.contains("-fn main() {}"));
+        assert!(diff.contains("+    lib::one();"));
+        assert!(!diff.contains("src/lib.rs"));
+
+        assert!(git_operations::get_full_commit_diff(&git_worker.repo, "not-a-sha").is_err());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_git_worker_shared_state_integration() -> Result<()> {
         let (_temp_dir, _repo, repo_path) = create_test_repo()?;
@@ -1312,7 +2261,7 @@ mod tests {
         assert!(!repo_data.repo_name.is_empty());
 
         // Test commit history caching in shared state
-        let commits = git_worker.get_commit_history(10)?;
+        let commits = git_worker.get_commit_history(None, 10)?;
         assert_eq!(commits.len(), 2);
 
         // Verify commits are cached in shared state
@@ -1333,6 +2282,189 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_git_worker_discovers_root_from_subdirectory() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        create_commit(&repo, &repo_path, "file1.txt", "Hello", "Initial commit")?;
+        let nested = repo_path.join("src").join("nested");
+        fs::create_dir_all(&nested)?;
+
+        let git_worker = GitWorker::new(nested, Arc::new(GitSharedState::new()))?;
+        assert_eq!(git_worker.path, repo_path);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_changes_from_subdirectory_use_worktree_root() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        fs::create_dir_all(repo_path.join("src/nested"))?;
+        create_commit(&repo, &repo_path, "src/lib.rs", "one\n", "Initial commit")?;
+        fs::write(repo_path.join("src/lib.rs"), "one\ntwo\n")?;
+
+        let shared_state = Arc::new(GitSharedState::new());
+        let mut git_worker =
+            GitWorker::new(repo_path.join("src/nested"), Arc::clone(&shared_state))?;
+        git_worker.update_shared_state()?;
+
+        let repo_data = shared_state.get_repo().unwrap();
+        assert_eq!(repo_data.path, repo_path);
+        assert_eq!(repo_data.changed_files.len(), 1);
+        let file = &repo_data.changed_files[0];
+        assert_eq!(file.path, repo_path.join("src/lib.rs"));
+        assert_eq!(file.additions, 1);
+        assert!(file.line_strings.iter().any(|line| line == "+two"));
+        assert_eq!(repo_data.dirty_directory_files.len(), 1);
+
+        // One `src` directory holding the file, not a tree rebuilt from absolute paths
+        let tree = repo_data.get_file_tree();
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].name, "src");
+        assert_eq!(tree.children[0].children.len(), 1);
+        assert_eq!(tree.children[0].children[0].name, "lib.rs");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dirty_directory_view_classifies_files_once() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        create_commit(&repo, &repo_path, "tracked.txt", "one\n", "Initial commit")?;
+        fs::write(repo_path.join("tracked.txt"), "one\ntwo\n")?;
+        fs::write(repo_path.join("new.txt"), "new\n")?;
+        fs::write(repo_path.join("staged.txt"), "staged\n")?;
+        let mut index = repo.index()?;
+        index.add_path(Path::new("staged.txt"))?;
+        index.write()?;
+
+        let mut git_worker = GitWorker::new(repo_path.clone(), Arc::new(GitSharedState::new()))?;
+        git_worker.update_shared_state()?;
+        assert_eq!(git_worker.changed_files.len(), 2);
+        assert_eq!(git_worker.staged_files.len(), 1);
+        // Only the tracked file with unstaged changes, with the same diff as the working tree
+        assert_eq!(git_worker.dirty_directory_files.len(), 1);
+        let dirty = &git_worker.dirty_directory_files[0];
+        assert_eq!(dirty.path, repo_path.join("tracked.txt"));
+        assert_eq!(dirty.status, DIRTY_DIRECTORY_STATUS);
+        assert!(dirty.line_strings.iter().any(|line| line == "+two"));
+
+        // Turned off, the view never fills and can't be chosen
+        git_worker.set_dirty_directory_view(false);
+        git_worker.update_shared_state()?;
+        assert!(git_worker.dirty_directory_files.is_empty());
+        assert_eq!(git_worker.changed_files.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_linked_worktree_and_bare_repository() -> Result<()> {
+        let (temp_dir, repo, repo_path) = create_test_repo()?;
+        create_commit(&repo, &repo_path, "file1.txt", "one\n", "Initial commit")?;
+
+        // A linked worktree is watched on its own, not as the main checkout
+        let worktree_path = temp_dir.path().join("linked");
+        repo.worktree("linked", &worktree_path, None)?;
+        fs::write(worktree_path.join("file1.txt"), "one\ntwo\n")?;
+        let shared_state = Arc::new(GitSharedState::new());
+        let mut git_worker = GitWorker::new(worktree_path.clone(), Arc::clone(&shared_state))?;
+        git_worker.update_shared_state()?;
+        let repo_data = shared_state.get_repo().unwrap();
+        assert_eq!(repo_data.path, worktree_path);
+        assert_eq!(repo_data.changed_files.len(), 1);
+        assert_eq!(
+            repo_data.changed_files[0].path,
+            worktree_path.join("file1.txt")
+        );
+
+        // A bare repository has nothing to watch, and says so
+        let bare_dir = TempDir::new()?;
+        Repository::init_bare(bare_dir.path())?;
+        let error = GitWorker::new(bare_dir.path().to_path_buf(), shared_state)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("bare repository"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_reflog_finds_commits_lost_to_a_reset() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        let first = create_commit(&repo, &repo_path, "file1.txt", "one", "Initial commit")?;
+        let lost = create_commit(&repo, &repo_path, "file1.txt", "two", "Soon to be lost")?;
+
+        // Reset the branch back, leaving the second commit reachable only from the reflog
+        let first_commit = repo.find_object(first, None)?;
+        repo.reset(&first_commit, git2::ResetType::Hard, None)?;
+
+        let mut git_worker = GitWorker::new(repo_path, Arc::new(GitSharedState::new()))?;
+        let entries = git_worker.get_reflog(10)?;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].sha, first.to_string());
+        assert!(entries[0].message.starts_with("HEAD@{0}: "));
+        assert_eq!(entries[1].sha, lost.to_string());
+        assert!(entries[1].message.starts_with("HEAD@{1}: "));
+        assert_eq!(entries[1].files_changed.len(), 1);
+
+        assert_eq!(git_worker.get_reflog(1)?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_last_commit_files_for_root_commit() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        create_commit(
+            &repo,
+            &repo_path,
+            "file1.txt",
+            "one\ntwo\n",
+            "Initial commit",
+        )?;
+
+        let mut git_worker = GitWorker::new(repo_path.clone(), Arc::new(GitSharedState::new()))?;
+        git_worker.update_shared_state()?;
+
+        assert_eq!(git_worker.current_view_mode, ViewMode::LastCommit);
+        assert_eq!(git_worker.last_commit_files.len(), 1);
+        let file = &git_worker.last_commit_files[0];
+        assert_eq!(file.path, repo_path.join("file1.txt"));
+        assert_eq!((file.additions, file.deletions), (2, 0));
+        assert_eq!(git_worker.get_total_stats(), (1, 2, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_last_commit_id_is_reported() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        create_commit(&repo, &repo_path, "file1.txt", "one", "Initial commit")?;
+
+        let shared_state = Arc::new(GitSharedState::new());
+        let mut git_worker = GitWorker::new(repo_path, Arc::clone(&shared_state))?;
+        git_worker.last_commit_id = Some("not-a-commit".to_string());
+
+        assert!(git_worker.get_last_commit_files().is_err());
+        let (short_id, summary) = git_worker.get_last_commit_info();
+        assert_eq!(short_id, "unknown");
+        assert!(summary.contains("Invalid last commit id \"not-a-commit\""));
+        let error = shared_state.get_error(LAST_COMMIT_ERROR_KEY).unwrap();
+        assert!(error.contains("not-a-commit"));
+
+        // A well-formed id for a commit that doesn't exist is reported too
+        git_worker.last_commit_id = Some("1".repeat(40));
+        let (_, summary) = git_worker.get_last_commit_info();
+        assert!(summary.contains("not found"));
+
+        // Reading a real commit again clears the error
+        git_worker.last_commit_id = git_worker.last_head_commit_id.clone();
+        assert_eq!(git_worker.get_last_commit_info().1, "Initial commit");
+        assert!(shared_state.get_error(LAST_COMMIT_ERROR_KEY).is_none());
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_git_worker_continuous_run() -> Result<()> {
         let (_temp_dir, _repo, repo_path) = create_test_repo()?;
@@ -1376,6 +2508,36 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_git_worker_continuous_run_paused() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+        create_commit(&repo, &repo_path, "file1.txt", "content", "Initial commit")?;
+
+        let shared_state = Arc::new(GitSharedState::new());
+        let mut git_worker = GitWorker::new(repo_path.clone(), shared_state.clone())?;
+
+        // Start paused: no snapshot should be published
+        shared_state.toggle_paused();
+        let run_task = tokio::spawn(async move {
+            tokio::time::timeout(
+                tokio::time::Duration::from_millis(300),
+                git_worker.run_continuous(50),
+            )
+            .await
+        });
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        assert!(shared_state.get_repo().is_none());
+
+        // Resuming refreshes straight away
+        shared_state.toggle_paused();
+        tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
+        assert!(shared_state.get_repo().is_some());
+
+        let _ = run_task.await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_git_worker_error_handling_in_shared_state() -> Result<()> {
         let (_temp_dir, _repo, repo_path) = create_test_repo()?;
@@ -1424,7 +2586,7 @@ mod tests {
         let mut git_worker = GitWorker::new(repo_path.clone(), shared_state.clone())?;
 
         // Test commit history retrieval and caching
-        let commits1 = git_worker.get_commit_history(5)?;
+        let commits1 = git_worker.get_commit_history(None, 5)?;
         assert_eq!(commits1.len(), 3);
 
         // Verify all commits are cached
@@ -1435,7 +2597,7 @@ mod tests {
         }
 
         // Test second retrieval uses cache (should be same results)
-        let commits2 = git_worker.get_commit_history(5)?;
+        let commits2 = git_worker.get_commit_history(None, 5)?;
         assert_eq!(commits2.len(), 3);
         assert_eq!(commits1[0].sha, commits2[0].sha);
 
@@ -1450,6 +2612,50 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_git_worker_diff_base() -> Result<()> {
+        let (_temp_dir, repo, repo_path) = create_test_repo()?;
+
+        create_commit(&repo, &repo_path, "file1.txt", "content1", "First commit")?;
+        repo.branch("main", &repo.head()?.peel_to_commit()?, false)?;
+        create_commit(&repo, &repo_path, "file2.txt", "content2", "Second commit")?;
+        fs::write(repo_path.join("file1.txt"), "changed")?;
+
+        let shared_state = Arc::new(GitSharedState::new());
+        let mut git_worker = GitWorker::new(repo_path.clone(), shared_state.clone())?;
+        assert!(git_worker.set_diff_base("no-such-branch").is_err());
+        git_worker.set_diff_base("main")?;
+        git_worker.update_shared_state()?;
+
+        // Both the committed and the uncommitted change show, as in a PR diff
+        let repo_data = shared_state.get_repo().unwrap();
+        assert_eq!(repo_data.current_view_mode, ViewMode::Base);
+        assert_eq!(repo_data.diff_base.as_deref(), Some("main"));
+        let mut paths: Vec<PathBuf> = repo_data
+            .get_display_files()
+            .iter()
+            .map(|file| file.path.strip_prefix(&repo_path).unwrap().to_path_buf())
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("file1.txt"), PathBuf::from("file2.txt")]
+        );
+        assert_eq!(repo_data.total_stats, (2, 2, 1));
+        assert!(shared_state.get_error(BASE_ERROR_KEY).is_none());
+
+        // A base deleted since is reported, and the other views still refresh
+        repo.find_branch("main", git2::BranchType::Local)?
+            .delete()?;
+        git_worker.update_shared_state()?;
+        let repo_data = shared_state.get_repo().unwrap();
+        assert_eq!(repo_data.current_view_mode, ViewMode::WorkingTree);
+        let error = shared_state.get_error(BASE_ERROR_KEY).unwrap();
+        assert!(error.contains("Diff base main not read"));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_git_worker_detects_head_changes() -> Result<()> {
         let (_temp_dir, repo, repo_path) = create_test_repo()?;
@@ -1540,10 +2746,8 @@ mod tests {
                 let delay = actual_interval.saturating_sub(interval);
 
                 // Ignore small scheduling jitter < 5ms
-                if delay > std::time::Duration::from_millis(5) {
-                    if delay > max_delay {
-                        max_delay = delay;
-                    }
+                if delay > std::time::Duration::from_millis(5) && delay > max_delay {
+                    max_delay = delay;
                 }
                 last_tick = now;
             }
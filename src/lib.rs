@@ -1,14 +1,20 @@
 // Library interface for grw (Git Repository Watcher)
 // This exposes modules for integration testing
 
+pub mod action;
+pub mod clipboard;
 pub mod config;
+pub mod config_watcher;
+pub mod doctor;
 pub mod git;
 pub mod llm;
 pub mod logging;
 pub mod monitor;
 pub mod pane;
+pub mod semantic_stats;
 pub mod shared_state;
 pub mod ui;
+pub mod util;
 
 // Re-export commonly used types for easier testing
 pub use git::worker::GitWorker;
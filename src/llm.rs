@@ -1,10 +1,15 @@
-use crate::config::LlmConfig;
+use crate::config::{LlmConfig, LlmProvider};
 use log::debug;
 use openai_api_rs::v1::api::OpenAIClient;
 use openai_api_rs::v1::chat_completion::{self, ChatCompletionMessage, ChatCompletionRequest};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::env;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 use tokio::sync::Mutex;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,10 +20,603 @@ pub struct LlmAdviceResult {
     pub has_error: bool,
 }
 
+/// Tokens the provider billed for one request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// A completion and what it cost
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmResponse {
+    pub content: String,
+    pub usage: TokenUsage,
+}
+
+/// Boxed future returned by `LlmBackend` methods, which keeps the trait object safe
+pub type LlmFuture<'a> = Pin<Box<dyn Future<Output = Result<LlmResponse, String>> + Send + 'a>>;
+
+/// Boxed future returned by `LlmBackend::ping`
+pub type PingFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// Tokens used by every request this session, shared by all clones of an `LlmClient`
+#[derive(Debug, Default)]
+pub struct SessionUsage {
+    prompt_tokens: AtomicU64,
+    completion_tokens: AtomicU64,
+    requests: AtomicU64,
+    budget: Option<u64>,
+    price_per_1k_tokens: Option<f64>,
+}
+
+/// A point-in-time copy of `SessionUsage` for display
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UsageSummary {
+    pub usage: TokenUsage,
+    pub requests: u64,
+    pub budget: Option<u64>,
+    /// Total tokens priced at the configured rate, if one is set
+    pub estimated_cost: Option<f64>,
+}
+
+impl SessionUsage {
+    pub fn new(budget: Option<u64>, price_per_1k_tokens: Option<f64>) -> Self {
+        Self {
+            budget,
+            price_per_1k_tokens,
+            ..Default::default()
+        }
+    }
+
+    pub fn record(&self, usage: TokenUsage) {
+        self.prompt_tokens
+            .fetch_add(usage.prompt_tokens, Ordering::Relaxed);
+        self.completion_tokens
+            .fetch_add(usage.completion_tokens, Ordering::Relaxed);
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn summary(&self) -> UsageSummary {
+        let usage = TokenUsage {
+            prompt_tokens: self.prompt_tokens.load(Ordering::Relaxed),
+            completion_tokens: self.completion_tokens.load(Ordering::Relaxed),
+        };
+        UsageSummary {
+            usage,
+            requests: self.requests.load(Ordering::Relaxed),
+            budget: self.budget,
+            estimated_cost: self
+                .price_per_1k_tokens
+                .map(|price| usage.total() as f64 / 1000.0 * price),
+        }
+    }
+
+    /// Err once the session has used up its token budget
+    pub fn check_budget(&self) -> Result<(), String> {
+        match self.budget {
+            Some(budget) if self.summary().usage.total() >= budget => {
+                Err(format!("Session token budget of {budget} tokens used up"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A provider that answers grw's five kinds of request
+/// Backends build their own prompts; diffs arrive already truncated to the token budget
+pub trait LlmBackend: Send + Sync + std::fmt::Debug {
+    /// Summarize a commit from its message and diff
+    fn summarize(&self, model: String, commit_message: String, diff: String) -> LlmFuture<'_>;
+
+    /// Suggest `count` improvements for a diff
+    fn advise(&self, model: String, diff: String, count: usize) -> LlmFuture<'_>;
+
+    /// Explain a few selected lines of the diff to `file_path`
+    fn explain(&self, model: String, file_path: String, excerpt: String) -> LlmFuture<'_>;
+
+    /// Draft a commit message for a staged diff
+    fn commit_message(&self, model: String, diff: String) -> LlmFuture<'_>;
+
+    /// Answer a follow-up question given the conversation so far
+    fn chat(
+        &self,
+        model: String,
+        history: Vec<crate::pane::ChatMessageData>,
+        question: String,
+    ) -> LlmFuture<'_>;
+
+    /// Check the provider can be reached and knows `model`, without spending tokens
+    fn ping(&self, model: String) -> PingFuture<'_>;
+}
+
+/// Shown in place of an LLM reply when no LLM is configured
+pub const NO_LLM_HINT: &str =
+    "No LLM configured: set llm.api_key or OPENAI_API_KEY, or try --llm-provider mock";
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You are an expert at summarizing code changes. Generate a concise, clear summary of the following commit changes. Focus on the key changes and their impact.";
+
+const CHAT_SYSTEM_PROMPT: &str = "You are an expert software engineer helping with code improvements. \
+    The user is asking about specific code changes and improvements. \
+    Be helpful, specific, and provide practical advice. \
+    Keep your responses concise but thorough.";
+
+const ADVICE_PROMPT_SUFFIX: &str = "for the following code changes:";
+
+const EXPLAIN_SYSTEM_PROMPT: &str = "You are an expert software engineer reviewing a diff. \
+    Explain what the selected lines change and why it matters, in a few short paragraphs. \
+    Only describe what the lines show; say so when more context would be needed.";
+
+const COMMIT_MESSAGE_SYSTEM_PROMPT: &str = "You write git commit messages in the Conventional Commits format. \
+    Reply with only the message: a `type(scope): summary` subject of at most 72 characters, \
+    then a blank line and a short body explaining what changed and why, wrapped at 72 characters. \
+    Leave out the body for trivial changes.";
+
+/// The request for an explanation of `excerpt`, a selection from the diff to `file_path`
+fn explain_prompt(file_path: &str, excerpt: &str) -> String {
+    format!("Explain this change to {file_path}:\n\n```diff\n{excerpt}\n```")
+}
+
+/// The request for `count` improvements to `diff`, also shown as the first message of the advice chat
+pub fn advice_prompt(diff: &str, count: usize) -> String {
+    let improvements = if count == 1 {
+        "1 actionable improvement".to_string()
+    } else {
+        format!("{count} actionable improvements")
+    };
+    format!(
+        "Please provide {improvements} {ADVICE_PROMPT_SUFFIX}\n\n```diff\n{}\n```\n\nFocus on practical, specific suggestions that would improve code quality, performance, or maintainability. \
+        Number each improvement (\"1. Title\"), then give a \"Priority: Low/Medium/High/Critical\" line, a \"Category:\" line, a short explanation and any code examples in fenced blocks.",
+        diff
+    )
+}
+
+/// Whether a chat message is the initial advice request, which the chat view hides
+pub fn is_advice_prompt(content: &str) -> bool {
+    content.starts_with("Please provide ") && content.contains(ADVICE_PROMPT_SUFFIX)
+}
+
+/// One file of a unified diff: the lines before its first hunk, then each hunk
+#[derive(Default)]
+struct DiffSection {
+    header: String,
+    hunks: Vec<String>,
+    changed_lines: usize,
+}
+
+/// Split a diff into whatever precedes the first `diff --git` line and one section per file
+fn split_diff_sections(diff: &str) -> (String, Vec<DiffSection>) {
+    let mut preamble = String::new();
+    let mut sections: Vec<DiffSection> = Vec::new();
+
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("diff --git ") {
+            sections.push(DiffSection::default());
+        }
+        let Some(section) = sections.last_mut() else {
+            preamble.push_str(line);
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            section.hunks.push(String::new());
+        }
+        match section.hunks.last_mut() {
+            Some(hunk) => {
+                if line.starts_with('+') || line.starts_with('-') {
+                    section.changed_lines += 1;
+                }
+                hunk.push_str(line);
+            }
+            None => section.header.push_str(line),
+        }
+    }
+
+    (preamble, sections)
+}
+
+/// Fit `diff` into `max_chars` by dropping whole files and trailing hunks instead of cutting mid-line
+/// The most-changed files are kept first, and a note says how much was left out
+pub fn truncate_diff(diff: &str, max_chars: usize) -> String {
+    if diff.len() <= max_chars {
+        return diff.to_string();
+    }
+
+    let (preamble, sections) = split_diff_sections(diff);
+    if sections.is_empty() {
+        // Not a git diff, so the best we can do is keep whole lines
+        let mut kept = String::new();
+        for line in diff.split_inclusive('\n') {
+            if kept.len() + line.len() > max_chars {
+                break;
+            }
+            kept.push_str(line);
+        }
+        return format!(
+            "{}\n\n[... diff truncated for brevity ...]",
+            kept.trim_end()
+        );
+    }
+
+    let mut by_size: Vec<usize> = (0..sections.len()).collect();
+    by_size.sort_by_key(|&i| std::cmp::Reverse(sections[i].changed_lines));
+
+    // Whole hunks kept per file and the start of a first hunk too big to keep whole, None for
+    // files left out entirely
+    let mut kept_hunks: Vec<Option<(usize, Option<&str>)>> = vec![None; sections.len()];
+    let mut remaining = max_chars.saturating_sub(preamble.len());
+    for i in by_size {
+        let section = &sections[i];
+        let mut used = section.header.len();
+        let mut hunks = 0;
+        for hunk in &section.hunks {
+            if used + hunk.len() > remaining {
+                break;
+            }
+            used += hunk.len();
+            hunks += 1;
+        }
+        if hunks == 0 && !section.hunks.is_empty() {
+            // Keep as many of the first hunk's lines as fit rather than none of the file
+            let budget = remaining.saturating_sub(used + HUNK_TRUNCATED.len());
+            if let Some(prefix) = hunk_prefix(&section.hunks[0], budget) {
+                remaining -= used + prefix.len() + HUNK_TRUNCATED.len();
+                kept_hunks[i] = Some((0, Some(prefix)));
+            }
+            continue;
+        }
+        if used > remaining {
+            continue;
+        }
+        remaining -= used;
+        kept_hunks[i] = Some((hunks, None));
+    }
+
+    let mut result = preamble;
+    let mut omitted_files = 0;
+    let mut omitted_hunks = 0;
+    for (section, kept) in sections.iter().zip(&kept_hunks) {
+        match kept {
+            Some((hunks, partial)) => {
+                result.push_str(&section.header);
+                for hunk in &section.hunks[..*hunks] {
+                    result.push_str(hunk);
+                }
+                let mut shown = *hunks;
+                if let Some(prefix) = partial {
+                    result.push_str(prefix);
+                    result.push_str(HUNK_TRUNCATED);
+                    shown += 1;
+                }
+                omitted_hunks += section.hunks.len() - shown;
+            }
+            None => omitted_files += 1,
+        }
+    }
+
+    let mut note = Vec::new();
+    if omitted_files > 0 {
+        note.push(format!(
+            "{omitted_files} of {} files omitted",
+            sections.len()
+        ));
+    }
+    if omitted_hunks > 0 {
+        note.push(format!(
+            "{omitted_hunks} hunks omitted from the files shown"
+        ));
+    }
+    if note.is_empty() {
+        return format!(
+            "{}\n\n[... diff truncated for brevity ...]",
+            result.trim_end()
+        );
+    }
+    format!(
+        "{}\n\n[... diff truncated for brevity: {} ...]",
+        result.trim_end(),
+        note.join(", ")
+    )
+}
+
+/// Marks where a hunk too big for the budget was cut
+const HUNK_TRUNCATED: &str = "[... hunk truncated ...]\n";
+
+/// Whole lines from the start of `hunk` that fit in `budget`, None when that's no more than its
+/// `@@` line
+fn hunk_prefix(hunk: &str, budget: usize) -> Option<&str> {
+    let header_len = hunk.split_inclusive('\n').next()?.len();
+    let mut end = 0;
+    for line in hunk.split_inclusive('\n') {
+        if end + line.len() > budget {
+            break;
+        }
+        end += line.len();
+    }
+    (end > header_len).then(|| &hunk[..end])
+}
+
+fn text_message(role: chat_completion::MessageRole, content: String) -> ChatCompletionMessage {
+    ChatCompletionMessage {
+        role,
+        content: chat_completion::Content::Text(content),
+        name: None,
+        tool_calls: None,
+        tool_call_id: None,
+    }
+}
+
+/// Any OpenAI-compatible chat completions API
+#[derive(Debug)]
+pub struct OpenAiBackend {
+    client: Mutex<OpenAIClient>,
+}
+
+impl OpenAiBackend {
+    pub fn new(config: &LlmConfig) -> Result<Self, String> {
+        let api_key = config
+            .api_key
+            .clone()
+            .or_else(|| env::var("OPENAI_API_KEY").ok())
+            .ok_or_else(|| {
+                "OpenAI API key not found. Please set it in the config file or as an environment variable."
+                    .to_string()
+            })?;
+
+        let mut builder = OpenAIClient::builder().with_api_key(api_key);
+
+        if let Some(base_url) = &config.base_url {
+            builder = builder.with_endpoint(base_url);
+        }
+
+        let client = Mutex::new(builder.build().map_err(|e| e.to_string())?);
+        Ok(Self { client })
+    }
+
+    async fn make_llm_request(
+        &self,
+        model: String,
+        messages: Vec<ChatCompletionMessage>,
+    ) -> Result<LlmResponse, String> {
+        debug!(
+            "🤖 LLM_CLIENT: Making request to model: {} ({} messages)",
+            model,
+            messages.len()
+        );
+
+        let req = ChatCompletionRequest::new(model, messages);
+
+        let mut client = self.client.lock().await;
+
+        match client.chat_completion(req).await {
+            Ok(response) => {
+                if let Some(choice) = response.choices.first() {
+                    let content = choice.message.content.clone().unwrap_or_default();
+                    let usage = TokenUsage {
+                        prompt_tokens: response.usage.prompt_tokens.max(0) as u64,
+                        completion_tokens: response.usage.completion_tokens.max(0) as u64,
+                    };
+                    Ok(LlmResponse { content, usage })
+                } else {
+                    Err("No response from LLM".to_string())
+                }
+            }
+            Err(e) => Err(format!("LLM command execution failed: {e}")),
+        }
+    }
+}
+
+impl LlmBackend for OpenAiBackend {
+    fn summarize(&self, model: String, commit_message: String, diff: String) -> LlmFuture<'_> {
+        let messages = vec![
+            text_message(
+                chat_completion::MessageRole::system,
+                SUMMARY_SYSTEM_PROMPT.to_string(),
+            ),
+            text_message(
+                chat_completion::MessageRole::user,
+                format!(
+                    "Commit message: {}\n\nPlease summarize these changes:\n\n{}",
+                    commit_message, diff
+                ),
+            ),
+        ];
+        Box::pin(self.make_llm_request(model, messages))
+    }
+
+    fn advise(&self, model: String, diff: String, count: usize) -> LlmFuture<'_> {
+        let messages = vec![
+            text_message(
+                chat_completion::MessageRole::system,
+                CHAT_SYSTEM_PROMPT.to_string(),
+            ),
+            text_message(
+                chat_completion::MessageRole::user,
+                advice_prompt(&diff, count),
+            ),
+        ];
+        Box::pin(self.make_llm_request(model, messages))
+    }
+
+    fn explain(&self, model: String, file_path: String, excerpt: String) -> LlmFuture<'_> {
+        let messages = vec![
+            text_message(
+                chat_completion::MessageRole::system,
+                EXPLAIN_SYSTEM_PROMPT.to_string(),
+            ),
+            text_message(
+                chat_completion::MessageRole::user,
+                explain_prompt(&file_path, &excerpt),
+            ),
+        ];
+        Box::pin(self.make_llm_request(model, messages))
+    }
+
+    fn commit_message(&self, model: String, diff: String) -> LlmFuture<'_> {
+        let messages = vec![
+            text_message(
+                chat_completion::MessageRole::system,
+                COMMIT_MESSAGE_SYSTEM_PROMPT.to_string(),
+            ),
+            text_message(
+                chat_completion::MessageRole::user,
+                format!("Write a commit message for these staged changes:\n\n```diff\n{diff}\n```"),
+            ),
+        ];
+        Box::pin(self.make_llm_request(model, messages))
+    }
+
+    fn chat(
+        &self,
+        model: String,
+        history: Vec<crate::pane::ChatMessageData>,
+        question: String,
+    ) -> LlmFuture<'_> {
+        let mut messages = vec![text_message(
+            chat_completion::MessageRole::system,
+            CHAT_SYSTEM_PROMPT.to_string(),
+        )];
+
+        // Add conversation history (which already contains the initial message with diff context)
+        for msg in history {
+            let role = match msg.role {
+                crate::pane::MessageRole::User => chat_completion::MessageRole::user,
+                crate::pane::MessageRole::Assistant => chat_completion::MessageRole::assistant,
+                crate::pane::MessageRole::System => chat_completion::MessageRole::system,
+            };
+            messages.push(text_message(role, msg.content));
+        }
+
+        // Add the current question
+        messages.push(text_message(chat_completion::MessageRole::user, question));
+        Box::pin(self.make_llm_request(model, messages))
+    }
+
+    fn ping(&self, model: String) -> PingFuture<'_> {
+        Box::pin(async move {
+            let mut client = self.client.lock().await;
+            client
+                .retrieve_model(model)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+    }
+}
+
+/// Offline answers for tests and demos without network access
+/// Queued replies, which may be errors, answer calls in order; after that every call
+/// gets a canned answer describing its input
+#[derive(Debug)]
+pub struct MockLlmBackend {
+    replies: std::sync::Mutex<VecDeque<Result<String, String>>>,
+    delay: Duration,
+}
+
+impl MockLlmBackend {
+    /// `delay` is waited out before every answer, to exercise loading states
+    pub fn new(replies: Vec<Result<String, String>>, delay: Duration) -> Self {
+        Self {
+            replies: std::sync::Mutex::new(replies.into()),
+            delay,
+        }
+    }
+
+    /// Usage is estimated at 3 characters per token, like the diff truncation
+    fn respond(&self, canned: String, prompt_chars: usize) -> LlmFuture<'_> {
+        let reply = self
+            .replies
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .pop_front()
+            .unwrap_or(Ok(canned));
+        let delay = self.delay;
+        Box::pin(async move {
+            tokio::time::sleep(delay).await;
+            reply.map(|content| LlmResponse {
+                usage: TokenUsage {
+                    prompt_tokens: prompt_chars.div_ceil(3) as u64,
+                    completion_tokens: content.len().div_ceil(3) as u64,
+                },
+                content,
+            })
+        })
+    }
+}
+
+impl LlmBackend for MockLlmBackend {
+    fn summarize(&self, model: String, commit_message: String, diff: String) -> LlmFuture<'_> {
+        self.respond(
+            format!(
+                "Mock summary from {model} of \"{commit_message}\" ({} diff lines)",
+                diff.lines().count()
+            ),
+            commit_message.len() + diff.len(),
+        )
+    }
+
+    fn advise(&self, model: String, diff: String, _count: usize) -> LlmFuture<'_> {
+        self.respond(
+            format!(
+                "Mock advice from {model} for {} diff lines",
+                diff.lines().count()
+            ),
+            diff.len(),
+        )
+    }
+
+    fn explain(&self, model: String, file_path: String, excerpt: String) -> LlmFuture<'_> {
+        self.respond(
+            format!(
+                "Mock explanation from {model} of {} lines in {file_path}",
+                excerpt.lines().count()
+            ),
+            file_path.len() + excerpt.len(),
+        )
+    }
+
+    fn commit_message(&self, model: String, diff: String) -> LlmFuture<'_> {
+        self.respond(
+            format!(
+                "chore: mock commit message from {model}\n\nCovers {} staged diff lines.",
+                diff.lines().count()
+            ),
+            diff.len(),
+        )
+    }
+
+    fn chat(
+        &self,
+        model: String,
+        history: Vec<crate::pane::ChatMessageData>,
+        question: String,
+    ) -> LlmFuture<'_> {
+        let prompt_chars = history.iter().map(|m| m.content.len()).sum::<usize>() + question.len();
+        self.respond(
+            format!(
+                "Mock reply from {model} to \"{question}\" after {} messages",
+                history.len()
+            ),
+            prompt_chars,
+        )
+    }
+
+    fn ping(&self, _model: String) -> PingFuture<'_> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LlmClient {
-    client: Arc<Mutex<OpenAIClient>>,
+    backend: Arc<dyn LlmBackend>,
     config: LlmConfig,
+    usage: Arc<SessionUsage>,
 }
 
 impl LlmClient {
@@ -35,24 +633,38 @@ impl LlmClient {
             log_config
         );
 
-        let api_key = config
-            .api_key
-            .clone()
-            .or_else(|| env::var("OPENAI_API_KEY").ok())
-            .ok_or_else(|| {
-                "OpenAI API key not found. Please set it in the config file or as an environment variable."
-                    .to_string()
-            })?;
+        let backend: Arc<dyn LlmBackend> = match config.provider.clone().unwrap_or_default() {
+            LlmProvider::OpenAI => Arc::new(OpenAiBackend::new(&config)?),
+            LlmProvider::Mock => Arc::new(MockLlmBackend::new(Vec::new(), Duration::ZERO)),
+        };
 
-        let mut builder = OpenAIClient::builder().with_api_key(api_key);
+        Ok(Self::with_backend(config, backend))
+    }
 
-        if let Some(base_url) = &config.base_url {
-            builder = builder.with_endpoint(base_url);
+    /// Use `backend` regardless of the configured provider, e.g. a scripted mock in tests
+    pub fn with_backend(config: LlmConfig, backend: Arc<dyn LlmBackend>) -> Self {
+        let usage = Arc::new(SessionUsage::new(
+            config.token_budget,
+            config.price_per_1k_tokens,
+        ));
+        Self {
+            backend,
+            config,
+            usage,
         }
+    }
 
-        let client = Arc::new(Mutex::new(builder.build().map_err(|e| e.to_string())?));
+    /// Tokens used so far by this client and every clone of it
+    pub fn session_usage(&self) -> Arc<SessionUsage> {
+        Arc::clone(&self.usage)
+    }
 
-        Ok(Self { client, config })
+    /// Run a backend request unless the token budget is used up, recording what it cost
+    async fn request(&self, request: LlmFuture<'_>) -> Result<String, String> {
+        self.usage.check_budget()?;
+        let response = request.await?;
+        self.usage.record(response.usage);
+        Ok(response.content)
     }
 
     pub async fn get_llm_summary(
@@ -62,44 +674,14 @@ impl LlmClient {
     ) -> Result<LlmAdviceResult, String> {
         let start_time = tokio::time::Instant::now();
 
-        // Truncate diff content if needed based on config
-        // Convert tokens to characters using 3 chars per token ratio
-        let max_tokens = self.config.get_max_tokens();
-        let max_chars = max_tokens * 3;
-        let truncated_diff = if diff_content.len() > max_chars {
-            let truncated = diff_content.chars().take(max_chars).collect::<String>();
-            format!("{}\n\n[... diff truncated for brevity ...]", truncated)
-        } else {
-            diff_content.clone()
-        };
-
-        // Build the prompt for commit summary
-        let messages = vec![
-            ChatCompletionMessage {
-                role: chat_completion::MessageRole::system,
-                content: chat_completion::Content::Text(
-                    "You are an expert at summarizing code changes. Generate a concise, clear summary of the following commit changes. Focus on the key changes and their impact.".to_string(),
-                ),
-                name: None,
-                tool_calls: None,
-                tool_call_id: None,
-            },
-            ChatCompletionMessage {
-                role: chat_completion::MessageRole::user,
-                content: chat_completion::Content::Text(
-                    format!(
-                        "Commit message: {}\n\nPlease summarize these changes:\n\n{}",
-                        commit_message, truncated_diff
-                    ),
-                ),
-                name: None,
-                tool_calls: None,
-                tool_call_id: None,
-            },
-        ];
+        let truncated_diff = truncate_diff(&diff_content, self.get_max_diff_chars());
 
         let result = self
-            .make_llm_request(self.config.get_summary_model(), messages)
+            .request(self.backend.summarize(
+                self.config.get_summary_model(),
+                commit_message,
+                truncated_diff,
+            ))
             .await;
         let execution_time = start_time.elapsed();
 
@@ -119,37 +701,88 @@ impl LlmClient {
         }
     }
 
-    /// Get the maximum number of tokens to send to LLM, with a sensible default
-    pub fn get_max_tokens(&self) -> usize {
-        self.config.get_max_tokens()
+    /// Model the advice chat uses
+    pub fn get_advice_model(&self) -> String {
+        self.config.get_advice_model()
     }
 
-    async fn make_llm_request(
+    /// Number of improvements the advice chat asks for
+    pub fn get_advice_count(&self) -> usize {
+        self.config.get_advice_count()
+    }
+
+    /// Largest diff, in characters, that summaries and the advice chat may send
+    pub fn get_max_diff_chars(&self) -> usize {
+        self.config.get_max_diff_chars()
+    }
+
+    /// Ask for improvements to an already truncated diff, starting the advice chat
+    pub async fn get_llm_advice(
         &self,
-        model: String,
-        messages: Vec<ChatCompletionMessage>,
+        diff: String,
+    ) -> Result<crate::pane::ChatMessageData, String> {
+        debug!("🤖 LLM_CLIENT: Requesting advice");
+        let result = self
+            .request(self.backend.advise(
+                self.config.get_advice_model(),
+                diff,
+                self.config.get_advice_count(),
+            ))
+            .await;
+
+        match result {
+            Ok(content) => Ok(assistant_message(content)),
+            Err(error) => {
+                debug!("🤖 LLM_CLIENT: Failed to get advice: {}", error);
+                Err(format!("Failed to get advice: {}", error))
+            }
+        }
+    }
+
+    /// Explain lines selected from the diff to `file_path`, truncated like any other diff
+    pub async fn explain_diff_lines(
+        &self,
+        file_path: String,
+        lines: Vec<String>,
     ) -> Result<String, String> {
         debug!(
-            "🤖 LLM_CLIENT: Making request to model: {} ({} messages)",
-            model,
-            messages.len()
+            "🤖 LLM_CLIENT: Requesting explanation of {} lines in {}",
+            lines.len(),
+            file_path
         );
+        let excerpt = truncate_diff(&lines.join("\n"), self.config.get_max_diff_chars());
 
-        let req = ChatCompletionRequest::new(model, messages);
+        self.request(
+            self.backend
+                .explain(self.config.get_advice_model(), file_path, excerpt),
+        )
+        .await
+        .map_err(|error| {
+            debug!("🤖 LLM_CLIENT: Failed to explain lines: {}", error);
+            format!("Failed to explain lines: {}", error)
+        })
+    }
 
-        let mut client = self.client.lock().await;
+    /// Check the provider answers and knows the advice model, which costs no tokens
+    pub async fn ping(&self) -> Result<(), String> {
+        self.backend.ping(self.get_advice_model()).await
+    }
 
-        match client.chat_completion(req).await {
-            Ok(response) => {
-                if let Some(choice) = response.choices.first() {
-                    let content = choice.message.content.clone().unwrap_or_default();
-                    Ok(content)
-                } else {
-                    Err("No response from LLM".to_string())
-                }
-            }
-            Err(e) => Err(format!("LLM command execution failed: {e}")),
-        }
+    /// Draft a Conventional Commits message for the staged diff, truncated like any other diff
+    pub async fn draft_commit_message(&self, diff: String) -> Result<String, String> {
+        debug!("🤖 LLM_CLIENT: Requesting a commit message");
+        let truncated_diff = truncate_diff(&diff, self.config.get_max_diff_chars());
+
+        self.request(
+            self.backend
+                .commit_message(self.config.get_summary_model(), truncated_diff),
+        )
+        .await
+        .map(|message| message.trim().to_string())
+        .map_err(|error| {
+            debug!("🤖 LLM_CLIENT: Failed to draft a commit message: {}", error);
+            format!("Failed to draft a commit message: {}", error)
+        })
     }
 
     /// Send a chat follow-up message for advice context
@@ -159,54 +792,17 @@ impl LlmClient {
         conversation_history: Vec<crate::pane::ChatMessageData>,
     ) -> Result<crate::pane::ChatMessageData, String> {
         let start_time = tokio::time::Instant::now();
-        debug!("🤖 LLM_CLIENT: Processing chat follow-up");
-
-        // Build conversation context
-        let mut context_messages = vec![ChatCompletionMessage {
-            role: chat_completion::MessageRole::system,
-            content: chat_completion::Content::Text(
-                "You are an expert software engineer helping with code improvements. \
-                    The user is asking about specific code changes and improvements. \
-                    Be helpful, specific, and provide practical advice. \
-                    Keep your responses concise but thorough."
-                    .to_string(),
-            ),
-            name: None,
-            tool_calls: None,
-            tool_call_id: None,
-        }];
-
-        // Add conversation history (which already contains the initial message with diff context)
-        for msg in conversation_history {
-            let role = match msg.role {
-                crate::pane::MessageRole::User => chat_completion::MessageRole::user,
-                crate::pane::MessageRole::Assistant => chat_completion::MessageRole::assistant,
-                crate::pane::MessageRole::System => chat_completion::MessageRole::system,
-            };
-            context_messages.push(ChatCompletionMessage {
-                role,
-                content: chat_completion::Content::Text(msg.content),
-                name: None,
-                tool_calls: None,
-                tool_call_id: None,
-            });
-        }
-
-        // Add the current question
-        context_messages.push(ChatCompletionMessage {
-            role: chat_completion::MessageRole::user,
-            content: chat_completion::Content::Text(question),
-            name: None,
-            tool_calls: None,
-            tool_call_id: None,
-        });
-
         debug!(
-            "🤖 LLM_CLIENT: About to make HTTP request to LLM API with {} messages",
-            context_messages.len()
+            "🤖 LLM_CLIENT: Processing chat follow-up with {} earlier messages",
+            conversation_history.len()
         );
+
         let result = self
-            .make_llm_request(self.config.get_advice_model(), context_messages)
+            .request(self.backend.chat(
+                self.config.get_advice_model(),
+                conversation_history,
+                question,
+            ))
             .await;
 
         let execution_time = start_time.elapsed();
@@ -216,12 +812,7 @@ impl LlmClient {
         );
 
         match result {
-            Ok(content) => Ok(crate::pane::ChatMessageData {
-                id: uuid::Uuid::new_v4().to_string(),
-                role: crate::pane::MessageRole::Assistant,
-                content,
-                timestamp: std::time::SystemTime::now(),
-            }),
+            Ok(content) => Ok(assistant_message(content)),
             Err(error) => {
                 debug!("🤖 LLM_CLIENT: Failed to process chat: {}", error);
                 Err(format!("Failed to process chat: {}", error))
@@ -230,6 +821,15 @@ impl LlmClient {
     }
 }
 
+fn assistant_message(content: String) -> crate::pane::ChatMessageData {
+    crate::pane::ChatMessageData {
+        id: uuid::Uuid::new_v4().to_string(),
+        role: crate::pane::MessageRole::Assistant,
+        content,
+        timestamp: std::time::SystemTime::now(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,4 +866,230 @@ mod tests {
         // This might fail due to network issues, but should at least get past the API key check
         // In a real test, you'd mock the HTTP client
     }
+
+    fn file_diff(path: &str, hunks: &[&[&str]]) -> String {
+        let mut diff = format!("diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n");
+        for hunk in hunks {
+            diff.push_str("@@ -1 +1 @@\n");
+            for line in *hunk {
+                diff.push_str(line);
+                diff.push('\n');
+            }
+        }
+        diff
+    }
+
+    #[test]
+    fn test_truncate_diff_keeps_whole_hunks_of_most_changed_files() {
+        let small = file_diff("small.rs", &[&["-a", "+b"]]);
+        let big = file_diff(
+            "big.rs",
+            &[&["-1", "+2", "+3", "+4"], &["+5", "+6", "+7", "+8", "+9"]],
+        );
+        let diff = format!("{small}{big}");
+
+        // Short diffs pass through untouched
+        assert_eq!(truncate_diff(&diff, diff.len()), diff);
+
+        // Room for big.rs without its last hunk: small.rs is dropped, the hunk is not cut
+        let budget = big.len() - 10;
+        let truncated = truncate_diff(&diff, budget);
+        assert!(!truncated.contains("small.rs"));
+        assert!(truncated.contains("+4\n\n[... diff truncated"));
+        assert!(!truncated.contains("+5"));
+        assert!(
+            truncated.ends_with("1 of 2 files omitted, 1 hunks omitted from the files shown ...]")
+        );
+
+        // Files that still fit after the biggest one are kept, in their original order
+        let truncated = truncate_diff(&format!("{small}{big}{small}"), big.len() + small.len());
+        assert!(truncated.starts_with(format!("{small}{big}").trim_end()));
+        assert!(truncated.ends_with("1 of 3 files omitted ...]"));
+    }
+
+    #[test]
+    fn test_truncate_diff_cuts_an_oversized_first_hunk() {
+        let lines: Vec<String> = (0..20).map(|i| format!("+line {i}")).collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let big = file_diff("big.rs", &[&lines]);
+
+        // The header and the hunk's first lines are kept, not the whole file dropped
+        let budget = big.len() / 2;
+        let truncated = truncate_diff(&big, budget);
+        assert!(truncated.contains("big.rs"));
+        assert!(truncated.contains("+line 0\n"));
+        assert!(!truncated.contains("+line 19"));
+        assert!(truncated.contains("[... hunk truncated ...]"));
+        let cut = truncated
+            .split("[... hunk truncated ...]")
+            .next()
+            .unwrap_or_default();
+        assert!(cut.ends_with('\n'));
+        assert!(cut.len() + "[... hunk truncated ...]\n".len() <= budget);
+        assert!(truncated.ends_with("[... diff truncated for brevity ...]"));
+    }
+
+    #[test]
+    fn test_truncate_diff_without_file_headers_keeps_whole_lines() {
+        let truncated = truncate_diff("+first line\n+second line\n", 15);
+        assert_eq!(
+            truncated,
+            "+first line\n\n[... diff truncated for brevity ...]"
+        );
+    }
+
+    fn mock_client() -> LlmClient {
+        LlmClient::new(LlmConfig {
+            provider: Some(LlmProvider::Mock),
+            model: Some("test-model".to_string()),
+            ..Default::default()
+        })
+        .expect("the mock backend needs no API key")
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_summary() {
+        let result = mock_client()
+            .get_llm_summary("Fix bug".to_string(), "+a\n-b".to_string())
+            .await
+            .unwrap();
+        assert!(!result.has_error);
+        assert_eq!(
+            result.content,
+            "Mock summary from test-model of \"Fix bug\" (2 diff lines)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_advice_and_chat() {
+        let client = mock_client();
+
+        let advice = client.get_llm_advice("+a".to_string()).await.unwrap();
+        assert_eq!(advice.role, crate::pane::MessageRole::Assistant);
+        assert_eq!(
+            advice.content,
+            "Mock advice from test-model for 1 diff lines"
+        );
+
+        let reply = client
+            .send_chat_followup("Why?".to_string(), vec![advice])
+            .await
+            .unwrap();
+        assert_eq!(
+            reply.content,
+            "Mock reply from test-model to \"Why?\" after 1 messages"
+        );
+    }
+
+    #[test]
+    fn test_advice_prompt_count() {
+        let single = advice_prompt("+a", 1);
+        assert!(single.starts_with("Please provide 1 actionable improvement for"));
+        assert!(is_advice_prompt(&single));
+
+        let five = advice_prompt("+a", 5);
+        assert!(five.starts_with("Please provide 5 actionable improvements for"));
+        assert!(is_advice_prompt(&five));
+
+        assert!(!is_advice_prompt("Please provide more detail"));
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_explain() {
+        let client = mock_client();
+
+        let explanation = client
+            .explain_diff_lines(
+                "src/lib.rs".to_string(),
+                vec![
+                    "@@ -1 +1 @@".to_string(),
+                    "-a".to_string(),
+                    "+b".to_string(),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            explanation,
+            "Mock explanation from test-model of 3 lines in src/lib.rs"
+        );
+        assert!(explain_prompt("src/lib.rs", "+b").contains("```diff\n+b\n```"));
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_commit_message() {
+        let client = mock_client();
+
+        let message = client
+            .draft_commit_message("+a\n+b\n".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            message,
+            "chore: mock commit message from test-model\n\nCovers 2 staged diff lines."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_scripted_replies() {
+        let backend = MockLlmBackend::new(
+            vec![Ok("First".to_string()), Err("rate limited".to_string())],
+            Duration::from_millis(20),
+        );
+        let client = LlmClient::with_backend(LlmConfig::default(), Arc::new(backend));
+
+        let start = tokio::time::Instant::now();
+        let first = client.get_llm_advice("+a".to_string()).await.unwrap();
+        assert_eq!(first.content, "First");
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        let error = client
+            .send_chat_followup("Why?".to_string(), Vec::new())
+            .await
+            .unwrap_err();
+        assert_eq!(error, "Failed to process chat: rate limited");
+
+        // Once the script runs out, calls get canned answers
+        let summary = client
+            .get_llm_summary("Msg".to_string(), String::new())
+            .await
+            .unwrap();
+        assert!(!summary.has_error);
+        assert_eq!(
+            summary.content,
+            "Mock summary from gpt-4o-mini of \"Msg\" (0 diff lines)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_usage_cost_and_budget() {
+        let client = LlmClient::new(LlmConfig {
+            provider: Some(LlmProvider::Mock),
+            token_budget: Some(20),
+            price_per_1k_tokens: Some(0.5),
+            ..Default::default()
+        })
+        .unwrap();
+        let usage = client.session_usage();
+
+        // 6 prompt characters and a 45 character reply, at 3 characters per token
+        client.get_llm_advice("+a\n-bc".to_string()).await.unwrap();
+        let summary = usage.summary();
+        assert_eq!(
+            summary.usage,
+            TokenUsage {
+                prompt_tokens: 2,
+                completion_tokens: 15,
+            }
+        );
+        assert_eq!(summary.requests, 1);
+        let cost = summary.estimated_cost.unwrap();
+        assert!((cost - 0.0085).abs() < 1e-9);
+
+        // Clones share the session's count, so the second call spends the rest of the budget
+        client.clone().get_llm_advice(String::new()).await.unwrap();
+        let error = client.get_llm_advice(String::new()).await.unwrap_err();
+        assert!(error.contains("Session token budget of 20 tokens used up"));
+        assert_eq!(usage.summary().requests, 2);
+    }
 }
@@ -1,31 +1,32 @@
+use crate::config::Config;
 use color_eyre::eyre::Result;
-use log::LevelFilter;
 use std::env;
-use std::fs;
-use std::path::PathBuf;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-pub fn init_logging(debug: bool) -> Result<()> {
-    let log_level = if debug {
-        LevelFilter::Debug
-    } else {
-        LevelFilter::Info
-    };
+/// Rotated logs kept next to the current one, as `grw.log.1` (newest) to `grw.log.3`
+const ROTATED_LOG_FILES: usize = 3;
 
-    let log_dir = get_default_log_dir()?;
-    fs::create_dir_all(&log_dir)?;
+/// Start logging as `config` says, returning the path of the log file
+pub fn init_logging(config: &Config) -> Result<PathBuf> {
+    let log_level = config.get_log_level();
 
-    let log_file = log_dir.join("grw.log");
+    let log_file = match &config.log_file {
+        Some(path) => path.clone(),
+        None => get_default_log_dir()?.join("grw.log"),
+    };
+    if let Some(log_dir) = log_file.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(log_dir)?;
+    }
 
     env_logger::Builder::new()
         .filter_level(log_level)
-        .target(env_logger::Target::Pipe(Box::new(
-            std::fs::OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(log_file)?,
-        )))
+        .target(env_logger::Target::Pipe(Box::new(RotatingFile::open(
+            log_file.clone(),
+            config.get_log_max_bytes(),
+        )?)))
         .format(|buf, record| {
-            use std::io::Write;
             writeln!(
                 buf,
                 "{} [{}] - {}: {}",
@@ -37,8 +38,8 @@ pub fn init_logging(debug: bool) -> Result<()> {
         })
         .init();
 
-    log::info!("Logging initialized with level: {log_level}");
-    Ok(())
+    log::info!("Logging initialized with level: {log_level}, writing to {log_file:?}");
+    Ok(log_file)
 }
 
 fn get_default_log_dir() -> Result<PathBuf> {
@@ -52,3 +53,96 @@ fn get_default_log_dir() -> Result<PathBuf> {
         Ok(PathBuf::from("/tmp/grw"))
     }
 }
+
+/// Log file that moves aside to `<path>.1` once it grows past `max_bytes`, so long
+/// debug sessions don't fill the disk
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, max_bytes: u64) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_bytes,
+        })
+    }
+
+    /// `<path>.<index>`, where rotated logs are kept
+    fn rotated_path(path: &Path, index: usize) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+
+    /// Shift each rotated log one place older, dropping the oldest, and start a new file
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        for index in (1..ROTATED_LOG_FILES).rev() {
+            let from = Self::rotated_path(&self.path, index);
+            if from.exists() {
+                fs::rename(&from, Self::rotated_path(&self.path, index + 1))?;
+            }
+        }
+        fs::rename(&self.path, Self::rotated_path(&self.path, 1))?;
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // A record never straddles two files; one larger than the limit gets a file of its own
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_file_rotates_past_max_bytes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("grw.log");
+        let mut log = RotatingFile::open(path.clone(), 10).unwrap();
+
+        for line in ["first\n", "second\n", "third\n", "fourth\n", "fifth\n"] {
+            log.write_all(line.as_bytes()).unwrap();
+        }
+        log.flush().unwrap();
+
+        let read = |path: &Path| fs::read_to_string(path).unwrap();
+        assert_eq!(read(&path), "fifth\n");
+        assert_eq!(read(&RotatingFile::rotated_path(&path, 1)), "fourth\n");
+        assert_eq!(read(&RotatingFile::rotated_path(&path, 2)), "third\n");
+        assert_eq!(read(&RotatingFile::rotated_path(&path, 3)), "second\n");
+        // Only three rotated logs are kept
+        assert!(!RotatingFile::rotated_path(&path, 4).exists());
+
+        // Reopening carries on from the current file's size
+        let log = RotatingFile::open(path, 10).unwrap();
+        assert_eq!(log.size, 6);
+    }
+}
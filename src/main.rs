@@ -12,26 +12,33 @@ use ratatui::{
 use std::io;
 use std::time::Duration;
 
+mod action;
+mod clipboard;
 mod config;
+mod config_watcher;
+mod doctor;
 mod git;
 mod llm;
 mod logging;
 mod monitor;
 mod pane;
+mod semantic_stats;
 mod shared_state;
 mod ui;
+mod util;
 
 use std::env;
 use std::sync::Arc;
 
+use action::Action;
 use config::{Args, Config};
 use llm::LlmClient;
 use log::{debug, error, info};
 use monitor::AsyncMonitorCommand;
 use shared_state::SharedStateManager;
-use ui::App;
+use ui::{App, CommitHistoryTask};
 
-pub const GIT_SHA: &str = "unknown";
+include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
 const ERROR_CLEANUP_INTERVAL_SECS: u64 = 30;
 
 #[tokio::main]
@@ -39,18 +46,73 @@ async fn main() -> Result<()> {
     let args = Args::parse();
 
     if args.version {
-        println!("grw version 0.1.0 (git: {GIT_SHA})");
+        println!("{}", version_info());
         return Ok(());
     }
 
-    let config = Config::load()?;
-    let final_config = config.merge_with_args(&args);
+    if args.doctor {
+        println!("{}\n", version_info());
+        let checks = doctor::run_checks(&args).await;
+        print!("{}", doctor::format_report(&checks));
+        if doctor::has_failures(&checks) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Made absolute so the config can still be reloaded once grw moves into the repository
+    let explicit_config = args
+        .config
+        .as_deref()
+        .map(std::path::absolute)
+        .transpose()?;
+    // The repository's own config is layered in when there is one; a missing repository
+    // is reported once logging is set up
+    let repo_root = crate::git::operations::resolve_repository_path(args.repo.as_deref()).ok();
+    let (config, config_files) = Config::load(explicit_config.as_deref(), repo_root.as_deref())?;
+    let mut final_config = config.merge_with_args(&args);
+
+    let log_file = logging::init_logging(&final_config)?;
+    install_panic_hook(log_file)?;
+    if config_files.is_empty() {
+        debug!("No config file found; using defaults");
+    } else {
+        debug!("Loaded config from {config_files:?}");
+    }
 
-    logging::init_logging(final_config.debug.unwrap_or(false))?;
-    color_eyre::install()?;
+    // Reported on stderr, which shows once grw exits, as well as in the log and status bar
+    let config_problems = final_config.validate();
+    for problem in &config_problems {
+        eprintln!("grw: config {problem}");
+        if problem.fatal {
+            error!("Config {problem}");
+        } else {
+            log::warn!("Config {problem}");
+        }
+    }
+    if config_problems.iter().any(|problem| problem.fatal) {
+        return Err(color_eyre::eyre::eyre!(
+            "Invalid config; fix the errors above and start grw again"
+        ));
+    }
 
-    let repo_path = std::env::current_dir()?;
-    log::info!("Starting grw in directory: {repo_path:?}");
+    let repo_path = match crate::git::operations::resolve_repository_path(args.repo.as_deref()) {
+        Ok(path) => path,
+        // An explicit --repo is usually scripted, so it fails plainly; a bare `grw` explains itself
+        Err(e) if args.repo.is_none() => {
+            error!("No repository to watch: {e}");
+            let headline = if crate::git::operations::is_inside_repository() {
+                "This repository has no working tree to watch"
+            } else {
+                "Not a git repository — open grw inside one"
+            };
+            return show_startup_error(headline, &e.to_string());
+        }
+        Err(e) => return Err(e),
+    };
+    // Like `git -C`, the monitor command, $EDITOR and repository lookups then all run in the repo
+    std::env::set_current_dir(&repo_path)?;
+    log::info!("Starting grw in repository: {repo_path:?}");
     log::debug!("Debug mode enabled");
 
     // Initialize shared state manager
@@ -69,16 +131,23 @@ async fn main() -> Result<()> {
         repo_path.clone(),
         Arc::clone(shared_state_manager.git_state()),
     )?;
+    if let Some(diff_base) = &final_config.diff_base {
+        git_worker.set_diff_base(diff_base)?;
+    }
+    git_worker.set_dirty_directory_view(final_config.get_dirty_directory_view());
 
     // Start the GitWorker in a background task
+    let refresh_interval_ms = final_config.get_refresh_interval_ms();
+    debug!("Git refresh interval: {refresh_interval_ms}ms");
     tokio::spawn(async move {
-        if let Err(e) = git_worker.run_continuous(500).await {
+        if let Err(e) = git_worker.run_continuous(refresh_interval_ms).await {
             error!("GitWorker continuous run failed: {}", e);
         }
     });
 
     let llm_client = if let Some(llm_config) = &final_config.llm {
-        if llm_config.api_key.is_some() || env::var("OPENAI_API_KEY").is_ok() {
+        let needs_api_key = llm_config.provider != Some(config::LlmProvider::Mock);
+        if !needs_api_key || llm_config.api_key.is_some() || env::var("OPENAI_API_KEY").is_ok() {
             match LlmClient::new(llm_config.clone()) {
                 Ok(client) => Some(client),
                 Err(e) => {
@@ -93,67 +162,10 @@ async fn main() -> Result<()> {
         None
     };
 
-    // Theme setup
-    let mut themes = vec![ui::Theme::Dark, ui::Theme::Light];
-    if let Some(custom_theme_config) = &final_config.custom_theme {
-        let mut custom_palette = ui::ColorPalette::dark(); // Base
-        let mut any_color_parsed = false;
-
-        macro_rules! apply_color {
-            ($field:ident) => {
-                if let Some(hex) = &custom_theme_config.$field {
-                    match ui::parse_hex_color(hex) {
-                        Ok(color) => {
-                            custom_palette.$field = color;
-                            any_color_parsed = true;
-                        }
-                        Err(e) => log::warn!(
-                            "Failed to parse custom theme color for '{}': {}",
-                            stringify!($field),
-                            e
-                        ),
-                    }
-                }
-            };
-        }
-
-        apply_color!(background);
-        apply_color!(foreground);
-        apply_color!(primary);
-        apply_color!(secondary);
-        apply_color!(error);
-        apply_color!(highlight);
-        apply_color!(border);
-        apply_color!(directory);
-        apply_color!(added);
-        apply_color!(removed);
-        apply_color!(unchanged);
-
-        if any_color_parsed {
-            themes.push(ui::Theme::Custom(Arc::new(custom_palette)));
-        } else {
-            log::warn!(
-                "Custom theme section found in config, but no valid colors were parsed. Custom theme will not be available."
-            );
-        }
-    }
-
-    let initial_theme_config = final_config.theme.clone().unwrap_or(config::Theme::Dark);
-
-    let initial_theme_index = match initial_theme_config {
-        config::Theme::Dark => 0,
-        config::Theme::Light => 1,
-        config::Theme::Custom => {
-            if themes.iter().any(|t| matches!(t, ui::Theme::Custom(_))) {
-                themes.len() - 1 // The last one is custom
-            } else {
-                log::warn!(
-                    "Configured theme is 'custom', but no valid custom theme was loaded. Falling back to dark theme."
-                );
-                0 // Fallback to dark
-            }
-        }
-    };
+    let color_support = ui::ColorSupport::detect(final_config.color.unwrap_or_default());
+    info!("Terminal color support: {color_support:?}");
+    let themes = load_themes(&final_config, color_support);
+    let initial_theme_index = configured_theme_index(&final_config, &themes);
 
     let mut app = App::new_with_config(
         !final_config.no_diff.unwrap_or(false),
@@ -169,6 +181,14 @@ async fn main() -> Result<()> {
     // Configure summary preloader from config
     let preload_config = final_config.get_summary_preload_config();
     app.set_preload_config(preload_config);
+    apply_live_settings(&mut app, &final_config, color_support);
+    if let Some(problem) = config_problems.first() {
+        let others = match config_problems.len() {
+            1 => String::new(),
+            count => format!(" (and {} more, see the log)", count - 1),
+        };
+        app.set_status_message(format!("Config {problem}{others}"));
+    }
 
     let (monitor_command, mut monitor_rx) = if let Some(cmd) = &final_config.monitor_command {
         let (cmd, rx) =
@@ -188,16 +208,36 @@ async fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
     let _ = terminal.clear();
 
+    let mut watched_config_files = vec![
+        Config::get_config_path(),
+        Config::get_repo_config_path(&repo_path),
+    ];
+    watched_config_files.extend(explicit_config.clone());
+    let theme_dirs = vec![final_config.get_themes_dir()];
+    let mut config_watcher =
+        match config_watcher::ConfigWatcher::new(watched_config_files, theme_dirs) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                log::warn!("Not watching the config for changes: {e}");
+                None
+            }
+        };
+
     enable_raw_mode()?;
     execute!(io::stdout(), EnterAlternateScreen)?;
 
+    // Worker errors still standing, already shown in the status bar
+    let mut posted_last_commit_error = None;
+    let mut posted_base_error = None;
+
     loop {
         // Read git updates from shared state
-        if let Some(repo) = shared_state_manager.git_state().get_repo() {
+        if let Some(repo) = displayed_repo(&app, &shared_state_manager) {
             use crate::git::ViewMode;
 
             // Detect branch changes and clear selected commit if needed
             app.detect_branch_change(&repo.branch_name);
+            app.set_view_mode(repo.current_view_mode);
 
             // Always update files and tree based on current view mode
             let changed_files = repo.get_display_files();
@@ -213,7 +253,11 @@ async fn main() -> Result<()> {
 
             // Handle view mode changes automatically, but preserve user-selected commits
             match repo.current_view_mode {
-                ViewMode::WorkingTree | ViewMode::Staged | ViewMode::DirtyDirectory => {
+                ViewMode::WorkingTree
+                | ViewMode::Staged
+                | ViewMode::DirtyDirectory
+                | ViewMode::Base
+                | ViewMode::StagedAndUnstaged => {
                     // Working directory has changes
                     if app.get_selected_commit().is_some() {
                         // User has explicitly selected a commit, preserve it
@@ -248,6 +292,24 @@ async fn main() -> Result<()> {
             }
         }
 
+        // A last commit that can't be read would otherwise just leave the view empty
+        post_new_error(
+            &mut app,
+            &mut posted_last_commit_error,
+            shared_state_manager
+                .git_state()
+                .get_error(crate::git::worker::LAST_COMMIT_ERROR_KEY),
+        );
+
+        // So would a diff base deleted since grw started
+        post_new_error(
+            &mut app,
+            &mut posted_base_error,
+            shared_state_manager
+                .git_state()
+                .get_error(crate::git::worker::BASE_ERROR_KEY),
+        );
+
         // Check for git errors in shared state
         if let Some(error) = shared_state_manager.git_state().get_error("git_status") {
             error!("Git shared state error: {error}");
@@ -255,12 +317,25 @@ async fn main() -> Result<()> {
             shared_state_manager.git_state().clear_error("git_status");
         }
 
-        // Update monitor command if it exists
+        // Pick up edits to the config file and theme files
+        if config_watcher
+            .as_mut()
+            .is_some_and(|watcher| watcher.take_change())
+        {
+            reload_config(
+                &mut app,
+                &mut final_config,
+                &args,
+                explicit_config.as_deref(),
+                &repo_path,
+                &shared_state_manager,
+            );
+        }
+
         if let Some(ref mut rx) = monitor_rx {
             // Poll for new monitor output
             while let Ok(monitor_output) = rx.try_recv() {
-                app.update_monitor_output(monitor_output.output.clone());
-                app.update_monitor_timing(Some(monitor_output.timestamp.elapsed()), true);
+                app.update_monitor_output(monitor_output);
             }
         }
 
@@ -319,88 +394,24 @@ async fn main() -> Result<()> {
             LAST_ERROR_CLEANUP.store(current_time, std::sync::atomic::Ordering::Relaxed);
         }
 
-        // Calculate monitor visible height before rendering
-        let terminal_size = terminal.size()?;
-        let terminal_rect =
-            ratatui::layout::Rect::new(0, 0, terminal_size.width, terminal_size.height);
-        if app.is_showing_monitor_pane() {
-            let chunks = if app.is_showing_diff_panel() {
-                // When both diff panel and monitor pane are shown
-                let main_chunks = ratatui::layout::Layout::default()
-                    .direction(ratatui::layout::Direction::Vertical)
-                    .constraints([
-                        ratatui::layout::Constraint::Length(1),
-                        ratatui::layout::Constraint::Min(0),
-                    ])
-                    .split(terminal_rect);
-
-                let bottom_chunks = ratatui::layout::Layout::default()
-                    .direction(ratatui::layout::Direction::Horizontal)
-                    .constraints([
-                        ratatui::layout::Constraint::Percentage(30),
-                        ratatui::layout::Constraint::Percentage(70),
-                    ])
-                    .split(main_chunks[1]);
-
-                ratatui::layout::Layout::default()
-                    .direction(ratatui::layout::Direction::Vertical)
-                    .constraints([
-                        ratatui::layout::Constraint::Percentage(50),
-                        ratatui::layout::Constraint::Percentage(50),
-                    ])
-                    .split(bottom_chunks[0])
-            } else {
-                // When only monitor pane is shown (no diff panel)
-                let main_chunks = ratatui::layout::Layout::default()
-                    .direction(ratatui::layout::Direction::Vertical)
-                    .constraints([
-                        ratatui::layout::Constraint::Length(1),
-                        ratatui::layout::Constraint::Min(0),
-                    ])
-                    .split(terminal_rect);
-
-                ratatui::layout::Layout::default()
-                    .direction(ratatui::layout::Direction::Vertical)
-                    .constraints([
-                        ratatui::layout::Constraint::Percentage(50),
-                        ratatui::layout::Constraint::Percentage(50),
-                    ])
-                    .split(main_chunks[1])
-            };
+        // Parse the file on screen, unless it's been parsed since its diff last changed
+        app.update_semantic_stats();
 
-            app.set_monitor_visible_height(chunks[1].height.saturating_sub(2) as usize);
-        }
+        let terminal_size = terminal.size()?;
+        update_layout_sizes(
+            &mut app,
+            ratatui::layout::Rect::new(0, 0, terminal_size.width, terminal_size.height),
+        );
 
         let render_start = std::time::Instant::now();
         terminal.draw(|f| {
-            let size = f.area();
-
-            // Calculate diff height only if diff panel is visible
-            if app.is_showing_diff_panel() {
-                let chunks = ratatui::layout::Layout::default()
-                    .direction(ratatui::layout::Direction::Vertical)
-                    .constraints([
-                        ratatui::layout::Constraint::Length(1),
-                        ratatui::layout::Constraint::Min(0),
-                    ])
-                    .split(size);
-
-                let bottom_chunks = ratatui::layout::Layout::default()
-                    .direction(ratatui::layout::Direction::Horizontal)
-                    .constraints([
-                        ratatui::layout::Constraint::Percentage(30),
-                        ratatui::layout::Constraint::Percentage(70),
-                    ])
-                    .split(chunks[1]);
-
-                let diff_height = bottom_chunks[1].height.saturating_sub(2) as usize;
-                app.current_diff_height = diff_height;
-            } else {
-                // When diff panel is hidden, set a reasonable default height
-                app.current_diff_height = 20;
+            // Splitting a terminal this small leaves panes with no room inside their borders
+            if ui::is_terminal_too_small(f.area()) {
+                ui::render_terminal_too_small(f);
+                return;
             }
 
-            if let Some(repo) = &shared_state_manager.git_state().get_repo() {
+            if let Some(repo) = &displayed_repo(&app, &shared_state_manager) {
                 ui::render::<CrosstermBackend<std::io::Stdout>>(f, &app, repo);
             }
         })?;
@@ -428,16 +439,54 @@ async fn main() -> Result<()> {
         // Check for async advice panel task completion
         app.check_advice_panel_tasks();
 
+        // Pick up the explanation of selected diff lines
+        app.check_explanation_task();
+
+        // Pick up the drafted commit message
+        app.check_commit_draft_task();
+
         // Poll for LLM summary updates from shared state
         // Summary updates are now handled through shared state cache
 
-        if crossterm::event::poll(Duration::from_millis(10))?
-            && let Event::Key(key) = crossterm::event::read()?
-            && handle_key_event(key, &mut app, &final_config, &shared_state_manager)
-        {
-            break;
+        if crossterm::event::poll(Duration::from_millis(10))? {
+            match crossterm::event::read()? {
+                Event::Key(key) if handle_key_event(key, &mut app) => {
+                    break;
+                }
+                Event::Resize(width, height) => {
+                    // Reflow now instead of drawing one more frame at the old sizes
+                    let area = ratatui::layout::Rect::new(0, 0, width, height);
+                    terminal.resize(area)?;
+                    update_layout_sizes(&mut app, area);
+                    app.clamp_scroll_offsets();
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        // Commands picked in the palette that need the config or shared git state end up here
+        if let Some(action) = app.take_main_loop_action() {
+            run_main_loop_action(action, &mut app, &final_config, &shared_state_manager);
+        }
+
+        // Opening an editor needs the terminal, so the key handler only records the request
+        if let Some((path, line)) = app.take_editor_request() {
+            open_in_editor(&mut terminal, &mut app, &path, line)?;
+            shared_state_manager.git_state().request_refresh();
+        }
+        if let Some(patch) = app.take_hunk_stage_request() {
+            stage_hunk(&mut app, &shared_state_manager, &patch);
+        }
+        if let Some(request) = app.take_discard_request() {
+            discard_file(&mut app, &shared_state_manager, &request);
+        }
+        if let Some(diff) = app.take_external_diff_request() {
+            open_in_external_diff(&mut terminal, &mut app, &final_config, &diff)?;
         }
 
+        poll_commit_history(&mut app, &final_config, &shared_state_manager);
+
         // Handle commit selection from commit picker
         if app.is_in_commit_picker_mode() && app.is_commit_picker_enter_pressed() {
             if let Some(selected_commit) = app.get_current_selected_commit_from_picker() {
@@ -485,95 +534,577 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-fn handle_key_event(
-    key: KeyEvent,
+/// Install color_eyre's hooks, with a panic hook that first puts the terminal back to normal
+/// Without it a panic leaves raw mode on and the report drawn over the alternate screen
+fn install_panic_hook(log_file: std::path::PathBuf) -> Result<()> {
+    let (panic_hook, eyre_hook) = color_eyre::config::HookBuilder::default().into_hooks();
+    eyre_hook.install()?;
+
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, crossterm::cursor::Show);
+
+        error!("grw panicked: {panic_info}");
+        eprintln!("{}", panic_hook.panic_report(panic_info));
+        eprintln!("{}", crash_message(&log_file));
+        std::process::exit(1);
+    }));
+    Ok(())
+}
+
+/// What to tell the user after a panic, below the report
+fn crash_message(log_file: &std::path::Path) -> String {
+    format!(
+        "grw crashed. Please report this with the panic above and the log at {}",
+        log_file.display()
+    )
+}
+
+/// The built-in themes, then the config's custom theme and the theme files, adapted to
+/// what the terminal can show
+fn load_themes(config: &Config, color_support: ui::ColorSupport) -> Vec<ui::Theme> {
+    let mut themes = vec![ui::Theme::Dark, ui::Theme::Light, ui::Theme::HighContrast];
+    // Problems with the section were reported by `Config::validate`
+    if let Some(custom_theme_config) = &config.custom_theme
+        && let Some(custom_palette) = ui::ColorPalette::from_custom_theme(custom_theme_config)
+    {
+        themes.push(ui::Theme::Custom(
+            "custom".to_string(),
+            Arc::new(custom_palette),
+        ));
+    }
+    themes.extend(ui::load_themes_dir(&config.get_themes_dir()));
+
+    themes
+        .into_iter()
+        .map(|theme| theme.for_color_support(color_support))
+        .collect()
+}
+
+/// Where the configured theme is in `themes`, or the dark theme's index when it wasn't loaded
+fn configured_theme_index(config: &Config, themes: &[ui::Theme]) -> usize {
+    let theme_config = config.theme.clone().unwrap_or(config::Theme::Dark);
+    match theme_config {
+        config::Theme::Dark => 0,
+        config::Theme::Light => 1,
+        config::Theme::HighContrast => 2,
+        config::Theme::Custom | config::Theme::Named(_) => {
+            let name = theme_config.to_string();
+            if let Some(index) = themes
+                .iter()
+                .position(|t| t.name().eq_ignore_ascii_case(&name))
+            {
+                index
+            } else {
+                log::warn!(
+                    "Configured theme is '{name}', but no such theme was loaded. Falling back to dark theme."
+                );
+                0 // Fallback to dark
+            }
+        }
+    }
+}
+
+/// Apply the settings that can change while grw runs, at startup and on a config reload
+fn apply_live_settings(app: &mut App, config: &Config, color_support: ui::ColorSupport) {
+    app.set_recent_highlight_duration(config.get_recent_highlight_duration());
+    app.set_icons(config.get_icons());
+    app.set_status_bar_style(config.get_status_bar_style());
+    app.set_status_bar_lines(config.get_status_bar_lines());
+    app.set_max_tree_depth(config.get_max_tree_depth());
+    // The gradient needs RGB backgrounds, which 16-color terminals would round to garish ones
+    let diff_heatmap = config.get_diff_heatmap();
+    let truecolor = color_support == ui::ColorSupport::TrueColor;
+    if diff_heatmap && !truecolor {
+        log::warn!("diff_heatmap needs a truecolor terminal; leaving it off");
+    }
+    app.set_diff_heatmap(diff_heatmap && truecolor);
+    app.set_scroll_margin(config.get_scroll_margin());
+    app.set_monitor_max_lines(config.get_monitor_max_lines());
+    app.set_semantic_stats(cfg!(feature = "semantic-stats") && config.get_semantic_stats());
+    app.set_status_bar_indicators(config.get_show_stash_count(), config.get_show_unpushed());
+}
+
+/// Read the config files again after one changed and apply what can change live: themes,
+/// the refresh interval, icons, the status bar and the diff settings. A config that
+/// doesn't parse or has errors is left unapplied, keeping the current one
+fn reload_config(
     app: &mut App,
-    config: &Config,
+    config: &mut Config,
+    args: &Args,
+    explicit_config: Option<&std::path::Path>,
+    repo_path: &std::path::Path,
     shared_state_manager: &SharedStateManager,
-) -> bool {
-    // Handle Ctrl+P commit picker activation separately as it needs access to config and shared_state_manager
-    if key.code == KeyCode::Char('p') && key.modifiers.contains(KeyModifiers::CONTROL) {
-        debug!("User pressed Ctrl+P - activating commit picker");
-        // Only activate commit picker when in appropriate diff mode
-        if app.is_showing_diff_panel() && !app.is_in_commit_picker_mode() {
-            if let Some(repo) = shared_state_manager.git_state().get_repo() {
-                // Enter commit picker mode first and show loading state
-                app.enter_commit_picker_mode();
-                app.set_commit_picker_loading();
-                // Create a temporary GitWorker to load commit history using shared state
-                match crate::git::GitWorker::new(
-                    repo.path.clone(),
-                    Arc::clone(shared_state_manager.git_state()),
-                ) {
-                    Ok(mut git_worker) => {
-                        // Use configurable commit history limit
-                        let commit_limit = config.get_commit_history_limit();
-                        match git_worker.get_commit_history(commit_limit) {
-                            Ok(commits) => {
-                                debug!("Successfully loaded {} commits", commits.len());
-                                app.update_commit_picker_commits(commits.clone());
-                                // Configure and trigger summary pre-loading
-                                let preload_config = config.get_summary_preload_config();
-                                app.set_preload_config(preload_config);
-                                // Start pre-loading summaries for the first few commits
-                                debug!(
-                                    "Starting summary pre-loading for {} commits",
-                                    commits.len()
-                                );
-                                app.preload_summaries(&commits);
-                            }
-                            Err(e) => {
-                                error!("Failed to load commit history: {}", e);
-                                let error_msg = if e.to_string().contains("not a git repository") {
-                                    "This directory is not a Git repository".to_string()
-                                } else if e.to_string().contains("no commits")
-                                    || e.to_string().contains("HEAD")
-                                {
-                                    "No commits found in this repository".to_string()
-                                } else if e.to_string().contains("permission") {
-                                    "Permission denied accessing Git repository".to_string()
-                                } else {
-                                    format!("Git error: {}", e)
-                                };
-                                app.set_commit_picker_error(error_msg);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to create GitWorker: {}", e);
-                        app.set_commit_picker_error(format!(
-                            "Failed to access Git repository: {}",
-                            e
-                        ));
-                    }
+) {
+    let reloaded = match Config::load(explicit_config, Some(repo_path)) {
+        Ok((reloaded, files)) => {
+            info!("Reloading config from {files:?}");
+            reloaded.merge_with_args(args)
+        }
+        Err(e) => {
+            log::warn!("Config not reloaded: {e}");
+            app.set_status_message(format!("Config not reloaded: {e}"));
+            return;
+        }
+    };
+
+    let problems = reloaded.validate();
+    for problem in &problems {
+        log::warn!("Config {problem}");
+    }
+    if let Some(problem) = problems.iter().find(|problem| problem.fatal) {
+        app.set_status_message(format!("Config not reloaded: {}", problem.message));
+        return;
+    }
+
+    let color_support = ui::ColorSupport::detect(reloaded.color.unwrap_or_default());
+    let themes = load_themes(&reloaded, color_support);
+    // Stay on a theme picked with Ctrl+t unless the configured theme changed
+    let current_theme = app.get_theme().name().to_string();
+    let theme_index = match themes
+        .iter()
+        .position(|theme| theme.name() == current_theme)
+    {
+        Some(index) if reloaded.theme == config.theme => index,
+        _ => configured_theme_index(&reloaded, &themes),
+    };
+    app.set_themes(themes, theme_index);
+    apply_live_settings(app, &reloaded, color_support);
+    shared_state_manager
+        .git_state()
+        .set_refresh_interval_ms(reloaded.get_refresh_interval_ms());
+
+    let needs_restart = config.changed_restart_only_settings(&reloaded);
+    let message = if needs_restart.is_empty() {
+        "Config reloaded".to_string()
+    } else {
+        let settings = needs_restart.join(", ");
+        info!("Changed settings that need a restart: {settings}");
+        format!("Config reloaded; restart grw to apply {settings}")
+    };
+    app.set_status_message(message);
+    *config = config.with_hot_reloadable_settings_of(reloaded);
+}
+
+/// Show `headline` and `detail` full screen until the user quits
+fn show_startup_error(headline: &str, detail: &str) -> Result<()> {
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+
+    let result = (|| -> Result<()> {
+        loop {
+            terminal.draw(|f| ui::render_startup_error(f, headline, detail))?;
+            if let Event::Key(key) = crossterm::event::read()? {
+                let ctrl_c =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    return Ok(());
                 }
-            } else {
-                app.set_commit_picker_error("No Git repository available".to_string());
             }
         }
-        return false;
+    })();
+
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+    result
+}
+
+/// Suspend the TUI, edit `path` in $EDITOR, then restore the TUI
+fn open_in_editor(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    path: &std::path::Path,
+    line: Option<usize>,
+) -> Result<()> {
+    let Some(editor) = env::var("EDITOR").ok().filter(|e| !e.trim().is_empty()) else {
+        app.set_status_message("$EDITOR is not set".to_string());
+        return Ok(());
+    };
+
+    // EDITOR may carry arguments, e.g. "code --wait"
+    let mut editor_args = editor.split_whitespace();
+    let program = editor_args.next().unwrap_or_default();
+    info!("Opening {path:?} at line {line:?} with {editor}");
+
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+    let status = std::process::Command::new(program)
+        .args(editor_args)
+        .args(editor_file_args(program, path, line))
+        .status();
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    terminal.clear()?;
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => app.set_status_message(format!("{editor} exited with {status}")),
+        Err(e) => {
+            error!("Failed to launch {editor}: {e}");
+            app.set_status_message(format!("Failed to launch {editor}: {e}"));
+        }
     }
+    Ok(())
+}
 
-    // Handle Ctrl+O monitor pane toggle separately as it needs special handling
-    if key.code == KeyCode::Char('o') && key.modifiers.contains(KeyModifiers::CONTROL) {
-        debug!("User pressed Ctrl+O - toggling monitor pane");
-        app.toggle_monitor_pane();
-        debug!("Monitor pane is now: {}", app.is_showing_monitor_pane());
-        return false;
+/// Apply a single hunk's patch to the index and refresh to show it staged
+fn stage_hunk(app: &mut App, shared_state_manager: &SharedStateManager, patch: &str) {
+    let Some(repo) = shared_state_manager.git_state().get_repo() else {
+        app.set_status_message("No Git repository available".to_string());
+        return;
+    };
+
+    let result = git2::Repository::open(&repo.path)
+        .map_err(color_eyre::eyre::Report::from)
+        .and_then(|repo| crate::git::operations::apply_to_index(&repo, patch));
+    match result {
+        Ok(()) => {
+            info!("Staged a hunk");
+            app.set_status_message("Staged the hunk".to_string());
+            shared_state_manager.git_state().request_refresh();
+        }
+        Err(e) => {
+            error!("Failed to stage the hunk: {e}");
+            app.set_status_message(format!("Failed to stage the hunk: {e}"));
+        }
+    }
+}
+
+/// Throw away a file's working tree changes, confirmed in the popup, and refresh
+fn discard_file(
+    app: &mut App,
+    shared_state_manager: &SharedStateManager,
+    request: &ui::DiscardRequest,
+) {
+    let Some(repo) = shared_state_manager.git_state().get_repo() else {
+        app.set_status_message("No Git repository available".to_string());
+        return;
+    };
+
+    let result = git2::Repository::open(&repo.path)
+        .map_err(color_eyre::eyre::Report::from)
+        .and_then(|repo| {
+            let path = crate::git::operations::to_repo_relative_path(&repo, &request.path);
+            let old_path = request
+                .old_path
+                .as_deref()
+                .map(|old_path| crate::git::operations::to_repo_relative_path(&repo, old_path));
+            crate::git::operations::discard_working_tree_changes(
+                &repo,
+                &path,
+                old_path.as_deref(),
+                request.untracked,
+            )
+        });
+    match result {
+        Ok(()) => {
+            info!("Discarded the changes to {:?}", request.path);
+            let done = if request.untracked {
+                "Deleted"
+            } else {
+                "Discarded the changes to"
+            };
+            app.set_status_message(format!("{done} {}", request.path.display()));
+            shared_state_manager.git_state().request_refresh();
+        }
+        Err(e) => {
+            error!("Failed to discard the changes to {:?}: {e}", request.path);
+            app.set_status_message(format!("Failed to discard the changes: {e}"));
+        }
+    }
+}
+
+/// Suspend the TUI and pipe `diff` through `external_diff_command`, such as delta,
+/// waiting for Enter before coming back so output that doesn't page stays readable
+fn open_in_external_diff(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    config: &Config,
+    diff: &str,
+) -> Result<()> {
+    let Some(command) = config
+        .external_diff_command
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+    else {
+        app.set_status_message(
+            "Set external_diff_command in the config to view diffs in another tool".to_string(),
+        );
+        return Ok(());
+    };
+    info!("Piping the current diff through {command}");
+
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+    let status = pipe_through_command(command, diff);
+    if status.is_ok() {
+        println!("\nPress Enter to return to grw");
+        let mut line = String::new();
+        let _ = io::stdin().read_line(&mut line);
     }
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    terminal.clear()?;
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => app.set_status_message(format!("{command} exited with {status}")),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            error!("External diff command not found: {command}");
+            app.set_status_message(format!(
+                "{command} not found; check external_diff_command in the config"
+            ));
+        }
+        Err(e) => {
+            error!("Failed to run {command}: {e}");
+            app.set_status_message(format!("Failed to run {command}: {e}"));
+        }
+    }
+    Ok(())
+}
+
+/// Run `command`, split on whitespace like $EDITOR, with `input` on its stdin
+fn pipe_through_command(command: &str, input: &str) -> io::Result<std::process::ExitStatus> {
+    let mut args = command.split_whitespace();
+    let program = args.next().unwrap_or_default();
+    let mut child = std::process::Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A viewer that quits before reading everything closes the pipe, which is fine
+        let _ = io::Write::write_all(&mut stdin, input.as_bytes());
+    }
+    child.wait()
+}
+
+/// Load a page of commit history off the UI thread, after `after` or from HEAD
+fn start_commit_history_load(
+    app: &mut App,
+    config: &Config,
+    shared_state_manager: &SharedStateManager,
+    after: Option<String>,
+) {
+    let Some(repo) = shared_state_manager.git_state().get_repo() else {
+        app.set_commit_picker_error("No Git repository available".to_string());
+        return;
+    };
+
+    let git_state = Arc::clone(shared_state_manager.git_state());
+    let commit_limit = config.get_commit_history_limit();
+    let resume_after = after.clone();
+    let handle = tokio::task::spawn_blocking(move || {
+        // A temporary GitWorker loads commit history using shared state
+        let result = crate::git::GitWorker::new(repo.path, Arc::clone(&git_state))
+            .and_then(|mut git_worker| {
+                git_worker.get_commit_history(resume_after.as_deref(), commit_limit)
+            })
+            .map_err(|e| e.to_string());
+        git_state.store_commit_history_result(resume_after.as_deref(), result);
+    });
+    app.start_commit_history_task(CommitHistoryTask::new(handle, after));
+}
+
+/// Size the monitor and diff panes for a terminal filling `terminal_rect`, laid out the way
+/// `ui::render` will draw them
+fn update_layout_sizes(app: &mut App, terminal_rect: ratatui::layout::Rect) {
+    // Nothing is laid out below the minimum size, so keep the last sizes for when it grows
+    if ui::is_terminal_too_small(terminal_rect) {
+        return;
+    }
+
+    if app.is_showing_monitor_pane() {
+        let chunks = if app.is_showing_diff_panel() {
+            // When both diff panel and monitor pane are shown
+            let main_chunks = ratatui::layout::Layout::default()
+                .direction(ratatui::layout::Direction::Vertical)
+                .constraints([
+                    ui::header_constraint(app, terminal_rect.width),
+                    ratatui::layout::Constraint::Min(0),
+                ])
+                .split(terminal_rect);
+
+            let bottom_chunks = ratatui::layout::Layout::default()
+                .direction(ratatui::layout::Direction::Horizontal)
+                .constraints([
+                    ratatui::layout::Constraint::Percentage(30),
+                    ratatui::layout::Constraint::Percentage(70),
+                ])
+                .split(main_chunks[1]);
+
+            ratatui::layout::Layout::default()
+                .direction(ratatui::layout::Direction::Vertical)
+                .constraints([
+                    ratatui::layout::Constraint::Percentage(50),
+                    ratatui::layout::Constraint::Percentage(50),
+                ])
+                .split(bottom_chunks[0])
+        } else {
+            // When only monitor pane is shown (no diff panel)
+            let main_chunks = ratatui::layout::Layout::default()
+                .direction(ratatui::layout::Direction::Vertical)
+                .constraints([
+                    ui::header_constraint(app, terminal_rect.width),
+                    ratatui::layout::Constraint::Min(0),
+                ])
+                .split(terminal_rect);
+
+            ratatui::layout::Layout::default()
+                .direction(ratatui::layout::Direction::Vertical)
+                .constraints([
+                    ratatui::layout::Constraint::Percentage(50),
+                    ratatui::layout::Constraint::Percentage(50),
+                ])
+                .split(main_chunks[1])
+        };
+
+        app.set_monitor_visible_height(chunks[1].height.saturating_sub(2) as usize);
+    }
+
+    // Calculate diff height only if diff panel is visible
+    if app.is_showing_diff_panel() {
+        let chunks = ratatui::layout::Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([
+                ui::header_constraint(app, terminal_rect.width),
+                ratatui::layout::Constraint::Min(0),
+            ])
+            .split(terminal_rect);
+
+        let bottom_chunks = ratatui::layout::Layout::default()
+            .direction(ratatui::layout::Direction::Horizontal)
+            .constraints([
+                ratatui::layout::Constraint::Percentage(30),
+                ratatui::layout::Constraint::Percentage(70),
+            ])
+            .split(chunks[1]);
+
+        let diff_height = bottom_chunks[1].height.saturating_sub(2) as usize;
+        app.current_diff_height = diff_height;
+    } else {
+        // When diff panel is hidden, set a reasonable default height
+        app.current_diff_height = 20;
+    }
+}
+
+/// The latest repository snapshot, showing the view mode the user forced if there is one
+fn displayed_repo(
+    app: &App,
+    shared_state_manager: &SharedStateManager,
+) -> Option<crate::git::GitRepo> {
+    let mut repo = shared_state_manager.git_state().get_repo()?;
+    if let Some(view_mode) = app.get_forced_view_mode() {
+        repo.force_view_mode(view_mode);
+    }
+    Some(repo)
+}
 
-    // Handle Ctrl+W returning to working directory view separately
-    // Only handle if advice panel chat input is NOT active
-    if key.code == KeyCode::Char('w')
-        && key.modifiers.contains(KeyModifiers::CONTROL)
-        && !app.is_advice_panel_chat_input_active()
+/// Load HEAD's reflog off the UI thread, for the commit picker to list instead of history
+fn start_reflog_load(app: &mut App, config: &Config, shared_state_manager: &SharedStateManager) {
+    let Some(repo) = shared_state_manager.git_state().get_repo() else {
+        app.set_commit_picker_error("No Git repository available".to_string());
+        return;
+    };
+
+    let git_state = Arc::clone(shared_state_manager.git_state());
+    let limit = config.get_commit_history_limit();
+    let handle = tokio::task::spawn_blocking(move || {
+        let result = crate::git::GitWorker::new(repo.path, Arc::clone(&git_state))
+            .and_then(|mut git_worker| git_worker.get_reflog(limit))
+            .map_err(|e| e.to_string());
+        git_state.store_reflog_result(result);
+    });
+    app.start_commit_history_task(CommitHistoryTask::reflog(handle));
+}
+
+/// Show progress of a commit history load, hand finished pages to the picker, and
+/// start the next page once the picker nears the end of what's loaded
+fn poll_commit_history(app: &mut App, config: &Config, shared_state_manager: &SharedStateManager) {
+    // The configurable limit is the page size; older pages load while scrolling
+    let commit_limit = config.get_commit_history_limit();
+
+    if app.is_loading_commit_history() {
+        let loaded = shared_state_manager
+            .git_state()
+            .get_commit_history_progress();
+        app.set_commit_picker_progress(loaded, commit_limit);
+    }
+
+    match app.take_finished_commit_history(shared_state_manager.git_state()) {
+        Some((None, Ok(commits))) => {
+            debug!("Successfully loaded {} commits", commits.len());
+            // The reflog is listed in one go, never paged
+            let has_more = !app.is_commit_picker_showing_reflog() && commits.len() == commit_limit;
+            app.update_commit_picker_commits(commits.clone());
+            app.set_commit_picker_has_more(has_more);
+            // Start pre-loading summaries for the first few commits
+            debug!("Starting summary pre-loading for {} commits", commits.len());
+            app.preload_summaries(&commits);
+        }
+        Some((None, Err(e))) => {
+            error!("Failed to load commit history: {}", e);
+            let error_msg = if e.contains("not a git repository") {
+                "This directory is not a Git repository".to_string()
+            } else if e.contains("no commits") || e.contains("HEAD") {
+                "No commits found in this repository".to_string()
+            } else if e.contains("permission") {
+                "Permission denied accessing Git repository".to_string()
+            } else {
+                format!("Git error: {}", e)
+            };
+            app.set_commit_picker_error(error_msg);
+        }
+        Some((Some(after), Ok(commits))) => {
+            debug!("Loaded {} more commits after {after}", commits.len());
+            let has_more = commits.len() == commit_limit;
+            app.append_commit_picker_commits(commits, has_more);
+        }
+        Some((Some(_), Err(e))) => {
+            // Stop paging rather than retrying on every frame
+            error!("Failed to load more commit history: {e}");
+            app.append_commit_picker_commits(Vec::new(), false);
+        }
+        None => {}
+    }
+
+    if !app.is_loading_commit_history()
+        && let Some(after) = app.take_commit_picker_load_more_request()
     {
-        debug!("User pressed Ctrl+W - returning to working directory view");
-        app.clear_selected_commit();
-        return false;
+        start_commit_history_load(app, config, shared_state_manager, Some(after));
     }
+}
 
-    // Use the new keys module for all other key handling
+/// Arguments that open `path` at `line` in the given editor program
+/// GUI editors take `--goto file:N`, `file:N` or `--line N`; everything else
+/// gets the `+N file` form understood by vi, nano, emacs and friends
+fn editor_file_args(
+    program: &str,
+    path: &std::path::Path,
+    line: Option<usize>,
+) -> Vec<std::ffi::OsString> {
+    let Some(line) = line else {
+        return vec![path.into()];
+    };
+
+    let name = std::path::Path::new(program)
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let path_at_line = || format!("{}:{line}", path.display()).into();
+
+    match name.as_str() {
+        "code" | "code-insiders" | "codium" | "cursor" => vec!["--goto".into(), path_at_line()],
+        "subl" | "zed" | "hx" | "helix" => vec![path_at_line()],
+        "idea" | "clion" | "goland" | "pycharm" | "rustrover" | "webstorm" => {
+            vec!["--line".into(), line.to_string().into(), path.into()]
+        }
+        _ => vec![format!("+{line}").into(), path.into()],
+    }
+}
+
+fn handle_key_event(key: KeyEvent, app: &mut App) -> bool {
+    // Shortcuts that need the config or shared git state are queued for the main loop
     match pane::GlobalKeyHandler::handle_global_key(app, &key) {
         pane::KeyResult::Quit => true,
         pane::KeyResult::Handled => false,
@@ -581,4 +1112,172 @@ fn handle_key_event(
     }
 }
 
+/// Run one of the actions `App::apply_action` leaves to the main loop, as it needs the config
+/// or shared git state
+/// Show a worker's standing `error` in the status bar when it's new, rather than every
+/// frame, which would bury any message the user just caused; `posted` is the last one shown
+fn post_new_error(app: &mut App, posted: &mut Option<String>, error: Option<String>) {
+    if let Some(message) = &error
+        && posted.as_ref() != Some(message)
+    {
+        app.set_status_message(message.clone());
+    }
+    *posted = error;
+}
+
+fn run_main_loop_action(
+    action: Action,
+    app: &mut App,
+    config: &Config,
+    shared_state_manager: &SharedStateManager,
+) {
+    match action {
+        Action::OpenCommitPicker => {
+            debug!("Activating commit picker");
+            // Only activate commit picker when in appropriate diff mode
+            if app.is_showing_diff_panel() && !app.is_in_commit_picker_mode() && !app.is_searching()
+            {
+                // Enter commit picker mode first and show loading state
+                app.enter_commit_picker_mode();
+                app.set_commit_picker_reflog(false);
+                app.set_commit_picker_loading();
+                // Summaries start pre-loading once the first page of commits arrives
+                app.set_preload_config(config.get_summary_preload_config());
+                start_commit_history_load(app, config, shared_state_manager, None);
+            }
+        }
+        Action::OpenReflog => {
+            // Lists HEAD's reflog in the commit picker, to recover a lost commit
+            debug!("Showing the reflog");
+            if app.is_showing_diff_panel() && !app.is_in_commit_picker_mode() && !app.is_searching()
+            {
+                app.enter_commit_picker_mode();
+                app.set_commit_picker_reflog(true);
+                app.set_commit_picker_loading();
+                app.set_preload_config(config.get_summary_preload_config());
+                start_reflog_load(app, config, shared_state_manager);
+            }
+        }
+        Action::ShowCommitMessage => {
+            // Needs the HEAD commit from shared state when no commit is selected
+            let commit_sha = app
+                .get_selected_commit()
+                .map(|c| c.sha.clone())
+                .or_else(|| {
+                    shared_state_manager
+                        .git_state()
+                        .get_repo()
+                        .and_then(|repo| repo.last_commit_id)
+                });
+            match commit_sha {
+                Some(sha) => {
+                    debug!("Showing commit message for {sha}");
+                    app.show_commit_message(&sha);
+                }
+                None => debug!("No commit message to show"),
+            }
+        }
+        Action::ToggleRefreshPaused => {
+            // The background git refresh lives in shared state
+            let paused = shared_state_manager.git_state().toggle_paused();
+            info!("Git refresh {}", if paused { "paused" } else { "resumed" });
+            app.set_git_refresh_paused(paused);
+        }
+        _ => {
+            app.apply_action(action);
+        }
+    }
+}
+
+/// `--version` output: the grw release and commit, plus what it was built with, for bug reports
+fn version_info() -> String {
+    let (major, minor, rev) = git2::Version::get().libgit2_version();
+    format!(
+        "grw version {} (git: {GIT_SHA})\nlibgit2 {major}.{minor}.{rev}\n{RUSTC_VERSION}\ntarget: {BUILD_TARGET} ({BUILD_PROFILE})",
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
 // Note: Tests removed during shared state migration
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_version_info() {
+        let version = version_info();
+        assert!(version.starts_with(&format!("grw version {} (git: ", env!("CARGO_PKG_VERSION"))));
+        assert!(version.contains("\nlibgit2 "));
+        assert!(version.contains(BUILD_TARGET));
+    }
+
+    #[test]
+    fn test_crash_message_points_at_log() {
+        let message = crash_message(Path::new("/state/grw/grw.log"));
+        assert!(message.starts_with("grw crashed. Please report this"));
+        assert!(message.ends_with("/state/grw/grw.log"));
+    }
+
+    #[test]
+    fn test_post_new_error_only_posts_changes() {
+        let llm_state = std::sync::Arc::new(shared_state::LlmSharedState::new());
+        let mut app = App::new_with_config(true, true, 0, vec![ui::Theme::Dark], None, llm_state);
+        let mut posted = None;
+        let base_gone = || Some("Diff base main no longer exists".to_string());
+
+        post_new_error(&mut app, &mut posted, base_gone());
+        assert_eq!(
+            app.get_status_message(),
+            Some("Diff base main no longer exists")
+        );
+
+        // A message the user caused isn't overwritten by the same error next frame
+        app.set_status_message("Staged hunk".to_string());
+        post_new_error(&mut app, &mut posted, base_gone());
+        assert_eq!(app.get_status_message(), Some("Staged hunk"));
+
+        // Once cleared, the error is shown again if it comes back
+        post_new_error(&mut app, &mut posted, None);
+        post_new_error(&mut app, &mut posted, base_gone());
+        assert_eq!(
+            app.get_status_message(),
+            Some("Diff base main no longer exists")
+        );
+    }
+
+    #[test]
+    fn test_pipe_through_command() {
+        let status = pipe_through_command("grep -q +added", "+added\n").unwrap();
+        assert!(status.success());
+        let status = pipe_through_command("grep -q +added", "-removed\n").unwrap();
+        assert!(!status.success());
+
+        let error = pipe_through_command("grw-no-such-diff-viewer", "").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_editor_file_args() {
+        let path = Path::new("/repo/src/main.rs");
+        let args = |program, line| {
+            editor_file_args(program, path, line)
+                .into_iter()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(args("vim", None), ["/repo/src/main.rs"]);
+        assert_eq!(
+            args("/usr/bin/nvim", Some(12)),
+            ["+12", "/repo/src/main.rs"]
+        );
+        assert_eq!(args("code", Some(12)), ["--goto", "/repo/src/main.rs:12"]);
+        assert_eq!(args("hx", Some(12)), ["/repo/src/main.rs:12"]);
+        assert_eq!(
+            args("idea", Some(12)),
+            ["--line", "12", "/repo/src/main.rs"]
+        );
+    }
+}
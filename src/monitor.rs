@@ -1,12 +1,79 @@
 use log::debug;
+use std::collections::VecDeque;
+use std::process::Stdio;
 use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command as AsyncCommand;
 use tokio::sync::mpsc;
 
-#[derive(Debug, Clone)]
-pub struct MonitorOutput {
-    pub output: String,
-    pub timestamp: Instant,
+/// What a monitor command run reports, streamed as it happens
+#[derive(Debug, Clone, PartialEq)]
+pub enum MonitorOutput {
+    /// A run began; its lines replace the last run's once it prints or finishes
+    Started,
+    /// A line the command printed, on stdout or stderr
+    Line(String),
+    /// The run ended
+    Finished(Instant),
+}
+
+/// The monitor command's latest output, keeping only its last `max_lines` lines so a
+/// chatty command can't grow it without bound
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorBuffer {
+    lines: VecDeque<String>,
+    max_lines: usize,
+    /// The lines are from the last run, kept on screen until the new one prints or finishes
+    stale: bool,
+}
+
+impl MonitorBuffer {
+    pub fn new(max_lines: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            max_lines: max_lines.max(1),
+            stale: false,
+        }
+    }
+
+    /// A new run started: its first line or finishing replaces what's shown
+    pub fn start_run(&mut self) {
+        self.stale = true;
+    }
+
+    /// The run finished, so lines left from the last one go even if it printed nothing
+    pub fn finish_run(&mut self) {
+        if self.stale {
+            self.clear();
+        }
+    }
+
+    /// Append a line, dropping the oldest once the buffer is full
+    pub fn push_line(&mut self, line: String) {
+        if self.stale {
+            self.clear();
+        }
+        if self.lines.len() == self.max_lines {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Change the limit, dropping the oldest lines that no longer fit
+    pub fn set_max_lines(&mut self, max_lines: usize) {
+        self.max_lines = max_lines.max(1);
+        let excess = self.lines.len().saturating_sub(self.max_lines);
+        self.lines.drain(..excess);
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.stale = false;
+    }
+
+    pub fn lines(&self) -> impl ExactSizeIterator<Item = &str> {
+        self.lines.iter().map(String::as_str)
+    }
 }
 
 #[derive(Debug)]
@@ -16,7 +83,7 @@ pub struct AsyncMonitorCommand {
 
 impl AsyncMonitorCommand {
     pub fn new(command: String, interval: u64) -> (Self, mpsc::Receiver<MonitorOutput>) {
-        let (output_tx, output_rx) = mpsc::channel(32);
+        let (output_tx, output_rx) = mpsc::channel(256);
         let last_run = std::sync::Arc::new(std::sync::RwLock::new(None));
 
         let command_clone = command.clone();
@@ -35,61 +102,7 @@ impl AsyncMonitorCommand {
 
                 if should_run {
                     debug!("Running async monitor command: {command_clone}");
-                    let _start_time = Instant::now();
-
-                    let result = if cfg!(target_os = "windows") {
-                        AsyncCommand::new("cmd")
-                            .args(["/C", &command_clone])
-                            .output()
-                            .await
-                    } else {
-                        AsyncCommand::new("sh")
-                            .args(["-c", &command_clone])
-                            .output()
-                            .await
-                    };
-
-                    let monitor_output = match result {
-                        Ok(output) => {
-                            let stdout = String::from_utf8_lossy(&output.stdout);
-                            let stderr = String::from_utf8_lossy(&output.stderr);
-
-                            if output.status.success() {
-                                let output_str = if stderr.is_empty() {
-                                    format!("$ {command_clone}\n{stdout}")
-                                } else {
-                                    format!("$ {command_clone}\n{stdout}\n{stderr}")
-                                };
-
-                                debug!("Async monitor command completed successfully");
-                                MonitorOutput {
-                                    output: output_str,
-                                    timestamp: Instant::now(),
-                                }
-                            } else {
-                                let error_str = format!(
-                                    "$ {command_clone}\nCommand failed: {stderr}\n{stdout}"
-                                );
-                                debug!("Async monitor command failed: {stderr}");
-                                MonitorOutput {
-                                    output: error_str,
-                                    timestamp: Instant::now(),
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            let error_str =
-                                format!("$ {command_clone}\nCommand execution failed: {e}");
-                            debug!("Async monitor command execution error: {e}");
-                            MonitorOutput {
-                                output: error_str,
-                                timestamp: Instant::now(),
-                            }
-                        }
-                    };
-
-                    // Send output through channel
-                    if let Err(e) = tx_clone.send(monitor_output.clone()).await {
+                    if let Err(e) = Self::run_once(&command_clone, &tx_clone).await {
                         debug!("Failed to send monitor output: {e}");
                     }
 
@@ -108,6 +121,93 @@ impl AsyncMonitorCommand {
         (Self { last_run }, output_rx)
     }
 
+    /// Run the command once, sending each line of its stdout and stderr as it's printed
+    /// rather than waiting for it to finish
+    async fn run_once(
+        command: &str,
+        tx: &mpsc::Sender<MonitorOutput>,
+    ) -> Result<(), mpsc::error::SendError<MonitorOutput>> {
+        tx.send(MonitorOutput::Started).await?;
+        tx.send(MonitorOutput::Line(format!("$ {command}"))).await?;
+
+        let mut process = if cfg!(target_os = "windows") {
+            let mut process = AsyncCommand::new("cmd");
+            process.args(["/C", command]);
+            process
+        } else {
+            let mut process = AsyncCommand::new("sh");
+            process.args(["-c", command]);
+            process
+        };
+        process
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = match process.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                debug!("Async monitor command execution error: {e}");
+                tx.send(MonitorOutput::Line(format!(
+                    "Command execution failed: {e}"
+                )))
+                .await?;
+                return tx.send(MonitorOutput::Finished(Instant::now())).await;
+            }
+        };
+
+        let mut stdout = child.stdout.take().map(Self::split_lines);
+        let mut stderr = child.stderr.take().map(Self::split_lines);
+        while stdout.is_some() || stderr.is_some() {
+            let line = tokio::select! {
+                line = Self::next_line(&mut stdout), if stdout.is_some() => line,
+                line = Self::next_line(&mut stderr), if stderr.is_some() => line,
+            };
+            if let Some(line) = line {
+                tx.send(MonitorOutput::Line(line)).await?;
+            }
+        }
+
+        match child.wait().await {
+            Ok(status) if status.success() => {
+                debug!("Async monitor command completed successfully");
+            }
+            Ok(status) => {
+                debug!("Async monitor command failed: {status}");
+                tx.send(MonitorOutput::Line(format!("Command failed: {status}")))
+                    .await?;
+            }
+            Err(e) => {
+                debug!("Async monitor command execution error: {e}");
+                tx.send(MonitorOutput::Line(format!(
+                    "Command execution failed: {e}"
+                )))
+                .await?;
+            }
+        }
+        tx.send(MonitorOutput::Finished(Instant::now())).await
+    }
+
+    fn split_lines<R: AsyncRead + Unpin>(stream: R) -> tokio::io::Split<BufReader<R>> {
+        BufReader::new(stream).split(b'\n')
+    }
+
+    /// The next line from `stream`, which is set to None once it's closed
+    async fn next_line<R: AsyncRead + Unpin>(
+        stream: &mut Option<tokio::io::Split<BufReader<R>>>,
+    ) -> Option<String> {
+        let segment = match stream.as_mut()?.next_segment().await {
+            Ok(Some(segment)) => segment,
+            _ => {
+                *stream = None;
+                return None;
+            }
+        };
+        let line = String::from_utf8_lossy(&segment);
+        Some(line.strip_suffix('\r').unwrap_or(&line).to_string())
+    }
+
     pub fn get_elapsed_since_last_run(&self) -> Option<Duration> {
         if let Ok(last_run) = self.last_run.read() {
             last_run.map(|instant| instant.elapsed())
@@ -130,6 +230,58 @@ mod tests {
     use super::*;
     use std::time::Duration;
 
+    /// Output of the next whole run waiting in `rx`, and when it finished
+    fn next_run(rx: &mut mpsc::Receiver<MonitorOutput>) -> Option<(String, Instant)> {
+        let mut lines = Vec::new();
+        loop {
+            match rx.try_recv().ok()? {
+                MonitorOutput::Started => lines.clear(),
+                MonitorOutput::Line(line) => lines.push(line),
+                MonitorOutput::Finished(timestamp) => return Some((lines.join("\n"), timestamp)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_monitor_buffer_keeps_last_lines() {
+        let mut buffer = MonitorBuffer::new(3);
+        for i in 1..=5 {
+            buffer.push_line(format!("line {i}"));
+        }
+        assert_eq!(buffer.lines().len(), 3);
+        assert_eq!(
+            buffer.lines().collect::<Vec<_>>(),
+            ["line 3", "line 4", "line 5"]
+        );
+
+        buffer.set_max_lines(2);
+        assert_eq!(buffer.lines().collect::<Vec<_>>(), ["line 4", "line 5"]);
+
+        buffer.clear();
+        assert_eq!(buffer.lines().len(), 0);
+    }
+
+    #[test]
+    fn test_monitor_buffer_keeps_last_run_until_replaced() {
+        let mut buffer = MonitorBuffer::new(3);
+        buffer.push_line("old".to_string());
+
+        // The last run stays on screen until the new one prints
+        buffer.start_run();
+        assert_eq!(buffer.lines().collect::<Vec<_>>(), ["old"]);
+        buffer.push_line("new".to_string());
+        buffer.push_line("newer".to_string());
+        assert_eq!(buffer.lines().collect::<Vec<_>>(), ["new", "newer"]);
+        buffer.finish_run();
+        assert_eq!(buffer.lines().collect::<Vec<_>>(), ["new", "newer"]);
+
+        // A run printing nothing clears it when it finishes
+        buffer.start_run();
+        assert_eq!(buffer.lines().len(), 2);
+        buffer.finish_run();
+        assert_eq!(buffer.lines().len(), 0);
+    }
+
     #[tokio::test]
     async fn test_monitor_command_creation() {
         let (monitor, mut rx) = AsyncMonitorCommand::new("echo test".to_string(), 1);
@@ -150,18 +302,39 @@ mod tests {
         tokio::time::sleep(Duration::from_secs(2)).await;
 
         // Check that we received output
-        let output = rx.try_recv();
-        assert!(output.is_ok());
+        let output = next_run(&mut rx);
+        assert!(output.is_some());
 
         let monitor_output = output.unwrap();
-        assert!(monitor_output.output.contains("hello world"));
-        assert!(monitor_output.output.contains("echo hello world"));
+        assert!(monitor_output.0.contains("hello world"));
+        assert!(monitor_output.0.contains("echo hello world"));
 
         // Check that monitor state was updated
         assert!(monitor.has_run_yet());
         assert!(monitor.get_elapsed_since_last_run().is_some());
     }
 
+    #[tokio::test]
+    async fn test_monitor_command_streams_lines() {
+        let (_monitor, mut rx) =
+            AsyncMonitorCommand::new("echo first; sleep 5; echo second".to_string(), 10);
+
+        // The first line arrives while the command is still running
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert_eq!(
+            events,
+            [
+                MonitorOutput::Started,
+                MonitorOutput::Line("$ echo first; sleep 5; echo second".to_string()),
+                MonitorOutput::Line("first".to_string()),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_monitor_command_error_handling() {
         let (_monitor, mut rx) =
@@ -171,11 +344,11 @@ mod tests {
         tokio::time::sleep(Duration::from_secs(2)).await;
 
         // Check that we received error output
-        let output = rx.try_recv();
-        assert!(output.is_ok());
+        let output = next_run(&mut rx);
+        assert!(output.is_some());
 
         let monitor_output = output.unwrap();
-        assert!(monitor_output.output.contains("nonexistent_command_12345"));
+        assert!(monitor_output.0.contains("nonexistent_command_12345"));
     }
 
     #[tokio::test]
@@ -184,21 +357,21 @@ mod tests {
 
         // First execution should happen immediately
         tokio::time::sleep(Duration::from_millis(100)).await;
-        let first_output = rx.try_recv();
-        assert!(first_output.is_ok());
+        let first_output = next_run(&mut rx);
+        assert!(first_output.is_some());
 
         // Clear the first output
         let _ = first_output;
 
         // Should not receive another output within 2 seconds
         tokio::time::sleep(Duration::from_secs(1)).await;
-        let second_output = rx.try_recv();
-        assert!(second_output.is_err());
+        let second_output = next_run(&mut rx);
+        assert!(second_output.is_none());
 
         // Should receive another output after 2 seconds
         tokio::time::sleep(Duration::from_secs(2)).await;
-        let third_output = rx.try_recv();
-        assert!(third_output.is_ok());
+        let third_output = next_run(&mut rx);
+        assert!(third_output.is_some());
     }
 
     #[tokio::test]
@@ -227,12 +400,12 @@ mod tests {
         // Wait for execution
         tokio::time::sleep(Duration::from_secs(2)).await;
 
-        let output = rx.try_recv().unwrap();
+        let output = next_run(&mut rx).unwrap();
 
         // Verify the output structure
-        assert!(output.output.contains("echo structured_output"));
-        assert!(output.output.starts_with("$ echo structured_output"));
-        assert!(!output.output.is_empty());
+        assert!(output.0.contains("echo structured_output"));
+        assert!(output.0.starts_with("$ echo structured_output"));
+        assert!(!output.0.is_empty());
     }
 
     #[tokio::test]
@@ -243,11 +416,11 @@ mod tests {
         // Wait for execution
         tokio::time::sleep(Duration::from_secs(2)).await;
 
-        let output = rx.try_recv().unwrap();
+        let output = next_run(&mut rx).unwrap();
 
         // Should contain both stdout and stderr
-        assert!(output.output.contains("stdout"));
-        assert!(output.output.contains("stderr"));
+        assert!(output.0.contains("stdout"));
+        assert!(output.0.contains("stderr"));
     }
 
     #[tokio::test]
@@ -258,8 +431,8 @@ mod tests {
         tokio::time::sleep(Duration::from_secs(2)).await;
 
         // Should have first output
-        let first_output = rx.try_recv();
-        assert!(first_output.is_ok());
+        let first_output = next_run(&mut rx);
+        assert!(first_output.is_some());
 
         // Clear the first output
         let _ = first_output;
@@ -268,13 +441,13 @@ mod tests {
         tokio::time::sleep(Duration::from_secs(2)).await;
 
         // Should have second output
-        let second_output = rx.try_recv();
-        assert!(second_output.is_ok());
+        let second_output = next_run(&mut rx);
+        assert!(second_output.is_some());
 
         // Verify outputs are different (different timestamps)
         let first = first_output.unwrap();
         let second = second_output.unwrap();
-        assert!(first.timestamp != second.timestamp);
+        assert!(first.1 != second.1);
     }
 
     #[tokio::test]
@@ -287,7 +460,7 @@ mod tests {
 
         // Should have multiple outputs in the buffer
         let mut count = 0;
-        while rx.try_recv().is_ok() {
+        while next_run(&mut rx).is_some() {
             count += 1;
             if count > 10 {
                 break; // Safety check
@@ -1,14 +1,12 @@
-use std::cell::RefCell;
 use std::sync::Arc;
 
-use crossterm::event::{KeyCode, KeyModifiers};
 use log::debug;
 use ratatui::{
     Frame,
     layout::Rect,
     prelude::Stylize,
-    style::Style,
-    text::Line,
+    style::{Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 use unicode_segmentation::UnicodeSegmentation;
@@ -23,6 +21,8 @@ use crate::ui::App;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AdviceMode {
     Chatting,
+    /// The advice reply as a prioritized list of improvements
+    Improvements,
     Help,
 }
 
@@ -42,6 +42,30 @@ pub enum ImprovementPriority {
     Unknown,
 }
 
+impl ImprovementPriority {
+    /// Read a priority word such as "high" or "Critical"
+    fn parse(text: &str) -> Self {
+        match text.trim().to_lowercase().as_str() {
+            "low" => ImprovementPriority::Low,
+            "medium" => ImprovementPriority::Medium,
+            "high" => ImprovementPriority::High,
+            "critical" => ImprovementPriority::Critical,
+            _ => ImprovementPriority::Unknown,
+        }
+    }
+
+    /// Position in the improvements view, most urgent first
+    fn rank(&self) -> u8 {
+        match self {
+            ImprovementPriority::Critical => 0,
+            ImprovementPriority::High => 1,
+            ImprovementPriority::Medium => 2,
+            ImprovementPriority::Low => 3,
+            ImprovementPriority::Unknown => 4,
+        }
+    }
+}
+
 impl std::fmt::Display for ImprovementPriority {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -64,6 +88,90 @@ pub struct AdviceImprovement {
     pub code_examples: Vec<String>,
 }
 
+impl AdviceImprovement {
+    /// Split an advice reply into its numbered items, most urgent first
+    /// `Priority:` and `Category:` lines become metadata and fenced blocks become code examples
+    pub fn parse_list(text: &str) -> Vec<AdviceImprovement> {
+        let mut improvements: Vec<AdviceImprovement> = Vec::new();
+        let mut code_block: Option<String> = None;
+
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") {
+                match code_block.take() {
+                    Some(code) => {
+                        if let Some(improvement) = improvements.last_mut() {
+                            improvement.code_examples.push(code);
+                        }
+                    }
+                    None => code_block = Some(String::new()),
+                }
+                continue;
+            }
+            if let Some(code) = code_block.as_mut() {
+                code.push_str(line);
+                code.push('\n');
+                continue;
+            }
+
+            if let Some(title) = numbered_item_title(trimmed) {
+                improvements.push(AdviceImprovement {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    title,
+                    description: String::new(),
+                    priority: ImprovementPriority::Unknown,
+                    category: "General".to_string(),
+                    code_examples: Vec::new(),
+                });
+                continue;
+            }
+            let Some(improvement) = improvements.last_mut() else {
+                continue;
+            };
+
+            let plain = trimmed.replace("**", "");
+            let plain = plain.trim_start_matches(['-', '*', ' ']);
+            if let Some(priority) = strip_label(plain, "priority:") {
+                improvement.priority = ImprovementPriority::parse(priority);
+            } else if let Some(category) = strip_label(plain, "category:") {
+                improvement.category = category.trim().to_string();
+            } else if !trimmed.is_empty() {
+                if !improvement.description.is_empty() {
+                    improvement.description.push('\n');
+                }
+                improvement.description.push_str(trimmed);
+            }
+        }
+
+        improvements.sort_by_key(|improvement| improvement.priority.rank());
+        improvements
+    }
+}
+
+/// The title of a `1. Title`, `2) Title` or `### 3. **Title**` line
+fn numbered_item_title(line: &str) -> Option<String> {
+    let line = line.trim_start_matches(['#', ' ']).trim_start_matches("**");
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits == 0 {
+        return None;
+    }
+    let rest = line[digits..].strip_prefix(['.', ')'])?;
+    if !rest.starts_with(' ') {
+        return None;
+    }
+    let title = rest.replace("**", "");
+    let title = title.trim().trim_end_matches(':');
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// The text after a case-insensitive `label`, if the line starts with it
+fn strip_label<'a>(line: &'a str, label: &str) -> Option<&'a str> {
+    let prefix = line.get(..label.len())?;
+    prefix
+        .eq_ignore_ascii_case(label)
+        .then(|| &line[label.len()..])
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ChatMessageData {
     pub id: String,
@@ -72,6 +180,15 @@ pub struct ChatMessageData {
     pub timestamp: std::time::SystemTime,
 }
 
+/// A chat request, kept until its reply arrives so a failed one can be sent again
+#[derive(Debug, Clone)]
+pub struct ChatRequest {
+    pub message_id: String,
+    pub message: String,
+    /// The diff when the request is the opening one asking for advice
+    pub advice_diff: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub enum AdviceContent {
     Improvements(Vec<AdviceImprovement>),
@@ -102,12 +219,25 @@ pub struct AdvicePanel {
     pub pending_advice_task: Option<tokio::task::JoinHandle<()>>,
     pub pending_chat_task: Option<tokio::task::JoinHandle<()>>,
     pub pending_chat_message_id: Option<String>,
-    pub current_diff_content: RefCell<Option<String>>,
-    pub max_tokens: usize, // Cache the max_tokens from config
+    /// The request awaiting a reply
+    pub pending_request: Option<ChatRequest>,
+    /// The last request if it failed, sent again with `r`
+    pub failed_request: Option<ChatRequest>,
+    pub max_diff_chars: usize, // Cache the diff size limit from config
+    pub advice_model: String,  // Part of the advice cache key, so switching models regenerates
+    pub advice_count: usize, // Improvements the initial prompt asks for, also part of the cache key
     pub initial_message_sent: bool,
     pub first_visit: bool,
     pub chat_content_backup: Option<AdviceContent>,
     pub needs_initialization: bool,
+    /// Highlighted row of the improvements view
+    pub selected_improvement: usize,
+    /// Improvements whose code examples are shown
+    pub expanded_improvements: std::collections::HashSet<usize>,
+    /// Improvement and code example last copied, so `y` moves on to the next example
+    pub copied_example: Option<(usize, usize)>,
+    /// Result of the last copy, shown in the improvements view's title
+    pub copy_notice: Option<String>,
 }
 
 impl AdvicePanel {
@@ -126,12 +256,19 @@ impl AdvicePanel {
             pending_advice_task: None,
             pending_chat_task: None,
             pending_chat_message_id: None,
-            current_diff_content: RefCell::new(None),
-            max_tokens: 16000, // Default value, will be updated when config is available
+            pending_request: None,
+            failed_request: None,
+            max_diff_chars: crate::config::LlmConfig::default().get_max_diff_chars(), // Updated when config is available
+            advice_model: crate::config::LlmConfig::default().get_advice_model(),
+            advice_count: crate::config::DEFAULT_ADVICE_COUNT,
             initial_message_sent: false,
             first_visit: true,
             chat_content_backup: None,
             needs_initialization: false,
+            selected_improvement: 0,
+            expanded_improvements: std::collections::HashSet::new(),
+            copied_example: None,
+            copy_notice: None,
         })
     }
 
@@ -146,12 +283,38 @@ impl AdvicePanel {
         self.llm_client = Some(llm_client);
     }
 
-    /// Set max_tokens directly (for testing or when config is available separately)
-    pub fn set_max_tokens(&mut self, max_tokens: usize) {
-        self.max_tokens = max_tokens;
+    /// Set the diff size limit directly (for testing or when config is available separately)
+    pub fn set_max_diff_chars(&mut self, max_diff_chars: usize) {
+        self.max_diff_chars = max_diff_chars;
+    }
+
+    /// Set the advice model directly (for testing or when config is available separately)
+    pub fn set_advice_model(&mut self, advice_model: String) {
+        self.advice_model = advice_model;
+    }
+
+    /// Set the number of improvements to ask for (for testing or when config is available separately)
+    pub fn set_advice_count(&mut self, advice_count: usize) {
+        self.advice_count = advice_count;
+    }
+
+    /// Key for the advice cache: the same diff asked of the same model gets the same chat back
+    fn advice_cache_key(&self, diff_content: &str) -> String {
+        format!(
+            "{}:{}:{}",
+            self.advice_model,
+            self.advice_count,
+            diff_hash(diff_content)
+        )
     }
 
     pub fn send_chat_message(&mut self, message: &str) -> Result<(), String> {
+        self.send_message(message, None)
+    }
+
+    /// Add `message` to the chat and request a reply, as advice on `advice_diff` when it's
+    /// the opening request or as a follow-up otherwise
+    fn send_message(&mut self, message: &str, advice_diff: Option<String>) -> Result<(), String> {
         debug!("🎯 ADVICE_PANEL: Sending chat message");
 
         // Add user message to chat history immediately (preserves content)
@@ -182,6 +345,16 @@ impl AdvicePanel {
 
         // Store the message ID for tracking the response
         self.pending_chat_message_id = Some(user_message_id.clone());
+        self.pending_request = Some(ChatRequest {
+            message_id: user_message_id.clone(),
+            message: message.to_string(),
+            advice_diff: advice_diff.clone(),
+        });
+        // A new request supersedes a failed one, whose error is no longer shown
+        if let (Some(failed), Some(shared_state)) = (self.failed_request.take(), &self.shared_state)
+        {
+            shared_state.clear_advice_error(&format!("chat_{}", failed.message_id));
+        }
 
         // Clone necessary data for the async task
         let shared_state_clone = self.shared_state.clone();
@@ -199,10 +372,15 @@ impl AdvicePanel {
                     let client = llm_client.lock().await;
                     debug!("🎯 ADVICE_PANEL: About to call LLM send_chat_followup");
 
-                    match client
-                        .send_chat_followup(message_content, conversation_history)
-                        .await
-                    {
+                    let response = match advice_diff {
+                        Some(diff) => client.get_llm_advice(diff).await,
+                        None => {
+                            client
+                                .send_chat_followup(message_content, conversation_history)
+                                .await
+                        }
+                    };
+                    match response {
                         Ok(ai_message) => {
                             debug!("🎯 ADVICE_PANEL: Successfully generated AI chat response");
                             Ok(ai_message)
@@ -318,6 +496,11 @@ impl AdvicePanel {
                     // Add the AI response to the chat
                     if let AdviceContent::Chat(messages) = &mut self.content {
                         messages.push(response);
+
+                        // Cache the chat so far for this diff, to restore on the next visit or run
+                        if let Some(diff_hash) = &self.current_diff_hash {
+                            shared_state.cache_advice_session(diff_hash.clone(), messages.clone());
+                        }
                     }
 
                     // Reset loading state
@@ -333,9 +516,9 @@ impl AdvicePanel {
                         error
                     );
 
-                    // Add error message to chat
+                    // Add error message to chat, with an ID that lets a retry remove it
                     let error_message = ChatMessageData {
-                        id: uuid::Uuid::new_v4().to_string(),
+                        id: format!("error_{}", message_id),
                         role: MessageRole::Assistant,
                         content: format!("Sorry, I encountered an error: {}", error),
                         timestamp: std::time::SystemTime::now(),
@@ -348,8 +531,8 @@ impl AdvicePanel {
                     // Reset loading state
                     self.update_advice_status(LoadingState::Idle);
 
-                    // Clean up the shared state
-                    shared_state.clear_advice_error(&format!("chat_{}", message_id));
+                    // The error stays in shared state until the request is retried
+                    self.failed_request = self.pending_request.take();
                 } else {
                     // If no response or error found, put the message ID back
                     self.pending_chat_message_id = Some(message_id);
@@ -369,23 +552,13 @@ impl AdvicePanel {
         self.mode = AdviceMode::Chatting;
         self.content = AdviceContent::Chat(Vec::new());
 
-        // Apply truncation to diff content using config max_tokens
-        // Convert tokens to characters using 3 chars per token ratio
-        let max_chars = self.max_tokens * 3;
-        let truncated_diff = if diff_content.len() > max_chars {
-            let truncated = diff_content.chars().take(max_chars).collect::<String>();
-            format!("{}\n\n[... diff truncated for brevity ...]", truncated)
-        } else {
-            diff_content.to_string()
-        };
+        // Follow-up chat sends this message as history, so the diff is only truncated here
+        let truncated_diff = crate::llm::truncate_diff(diff_content, self.max_diff_chars);
 
-        let initial_message = format!(
-            "Please provide 3 actionable improvements for the following code changes:\n\n```diff\n{}\n```\n\nFocus on practical, specific suggestions that would improve code quality, performance, or maintainability.",
-            truncated_diff
-        );
+        let initial_message = crate::llm::advice_prompt(&truncated_diff, self.advice_count);
 
         // Send the initial message automatically
-        if let Err(e) = self.send_chat_message(&initial_message) {
+        if let Err(e) = self.send_message(&initial_message, Some(truncated_diff)) {
             // If sending fails, add an error message
             let error_message = ChatMessageData {
                 id: uuid::Uuid::new_v4().to_string(),
@@ -423,12 +596,46 @@ impl AdvicePanel {
         }
     }
 
+    /// Whether the last request failed and can be sent again
+    pub fn can_retry(&self) -> bool {
+        self.failed_request.is_some()
+    }
+
+    /// Send the last failed request again, replacing it and its error in the chat
+    pub fn retry_failed_request(&mut self) {
+        let Some(request) = self.failed_request.take() else {
+            return;
+        };
+        debug!("🎯 ADVICE_PANEL: Retrying failed chat request");
+
+        if let Some(shared_state) = &self.shared_state {
+            shared_state.clear_advice_error(&format!("chat_{}", request.message_id));
+        }
+        if let AdviceContent::Chat(messages) = &mut self.content {
+            let error_id = format!("error_{}", request.message_id);
+            messages.retain(|message| message.id != request.message_id && message.id != error_id);
+        }
+
+        if let Err(e) = self.send_message(&request.message, request.advice_diff) {
+            debug!("🎯 ADVICE_PANEL: Retry failed to send: {}", e);
+        }
+    }
+
     pub fn refresh_chat_with_new_diff(&mut self) {
         debug!("🎯 ADVICE_PANEL: Refreshing chat with new diff");
 
+        // Asking again means the cached chat is not wanted
+        if let (Some(shared_state), Some(diff_hash)) = (&self.shared_state, &self.current_diff_hash)
+        {
+            shared_state.remove_advice_session(diff_hash);
+        }
+
         // Clear existing chat content
         self.content = AdviceContent::Chat(Vec::new());
+        self.failed_request = None;
         self.scroll_offset = 0;
+        self.selected_improvement = 0;
+        self.expanded_improvements.clear();
 
         // Reset first visit flag so it will send a new initial message
         self.first_visit = true;
@@ -438,6 +645,144 @@ impl AdvicePanel {
         // The new message will be sent on the next initialization cycle
     }
 
+    /// Improvements from the first advice reply in the chat, most urgent first
+    pub fn improvements(&self) -> Vec<AdviceImprovement> {
+        self.get_chat_history()
+            .iter()
+            .find(|message| message.role == MessageRole::Assistant)
+            .map(|message| AdviceImprovement::parse_list(&message.content))
+            .unwrap_or_default()
+    }
+
+    /// Switch between the chat and the improvements view
+    pub fn toggle_improvements_view(&mut self) {
+        self.mode = match self.mode {
+            AdviceMode::Improvements => AdviceMode::Chatting,
+            _ => AdviceMode::Improvements,
+        };
+        self.scroll_offset = 0;
+    }
+
+    /// Move the improvements view's highlight by `delta` rows
+    pub fn move_improvement_selection(&mut self, delta: isize) {
+        let last = self.improvements().len().saturating_sub(1);
+        self.selected_improvement = self
+            .selected_improvement
+            .saturating_add_signed(delta)
+            .min(last);
+        self.copy_notice = None;
+    }
+
+    /// The highlighted improvement's next code example, cycling on repeated calls
+    /// Returns the code with its 1-based position and the number of examples
+    pub fn next_code_example(&mut self) -> Option<(String, usize, usize)> {
+        let selected = self.selected_improvement;
+        let examples = self.improvements().into_iter().nth(selected)?.code_examples;
+        if examples.is_empty() {
+            return None;
+        }
+
+        let index = match self.copied_example {
+            Some((improvement, example)) if improvement == selected => {
+                (example + 1) % examples.len()
+            }
+            _ => 0,
+        };
+        self.copied_example = Some((selected, index));
+        Some((examples[index].clone(), index + 1, examples.len()))
+    }
+
+    /// Copy the highlighted improvement's next code example to the clipboard
+    pub fn copy_selected_example(&mut self) {
+        self.copy_notice = Some(match self.next_code_example() {
+            Some((code, position, count)) => match crate::clipboard::copy(&code) {
+                Ok(()) => format!("Copied code example {position}/{count}"),
+                Err(e) => format!("Copy failed: {e}"),
+            },
+            None => "No code example to copy".to_string(),
+        });
+    }
+
+    /// Show or hide the highlighted improvement's code examples
+    pub fn toggle_selected_improvement(&mut self) {
+        let selected = self.selected_improvement;
+        if !self.expanded_improvements.remove(&selected) {
+            self.expanded_improvements.insert(selected);
+        }
+    }
+
+    /// Lines of the improvements view, and the line the highlighted item starts on
+    fn improvement_lines(&self, theme: &crate::ui::Theme) -> (Vec<Line<'static>>, usize) {
+        let improvements = self.improvements();
+        if improvements.is_empty() {
+            let message = if self.loading_state == LoadingState::SendingChat {
+                "Waiting for advice..."
+            } else {
+                "No numbered improvements in the advice yet. Press Tab to return to the chat."
+            };
+            return (vec![Line::from(message)], 0);
+        }
+
+        let mut lines = Vec::new();
+        let mut selected_line = 0;
+        for (index, improvement) in improvements.iter().enumerate() {
+            let selected = index == self.selected_improvement;
+            let expanded = self.expanded_improvements.contains(&index);
+            if selected {
+                selected_line = lines.len();
+            }
+
+            let marker = match (improvement.code_examples.is_empty(), expanded) {
+                (true, _) => " ",
+                (false, false) => "▸",
+                (false, true) => "▾",
+            };
+            let header_style = if selected {
+                Style::default()
+                    .fg(theme.highlight_color())
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+            } else {
+                Style::default()
+                    .fg(theme.primary_color())
+                    .add_modifier(Modifier::BOLD)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{marker} {}. {}", index + 1, improvement.title),
+                    header_style,
+                ),
+                Span::styled(
+                    format!("  {} · {}", improvement.priority, improvement.category),
+                    Style::default().fg(theme.secondary_color()),
+                ),
+            ]));
+            for description_line in improvement.description.lines() {
+                lines.push(Line::from(format!("    {description_line}")));
+            }
+
+            if expanded {
+                for code in &improvement.code_examples {
+                    for code_line in code.lines() {
+                        lines.push(
+                            Line::from(format!("    │ {code_line}")).fg(theme.highlight_color()),
+                        );
+                    }
+                    lines.push(Line::from(""));
+                }
+            } else if !improvement.code_examples.is_empty() {
+                lines.push(
+                    Line::from(format!(
+                        "    Enter shows {} code example(s)",
+                        improvement.code_examples.len()
+                    ))
+                    .fg(theme.secondary_color()),
+                );
+            }
+            lines.push(Line::from(""));
+        }
+        (lines, selected_line)
+    }
+
     /// Format chat content to preserve markdown, code blocks, and spacing
     fn format_chat_content(&self, content: &str, theme: &crate::ui::Theme) -> Vec<Line<'_>> {
         use ratatui::style::Style;
@@ -615,60 +960,52 @@ impl AdvicePanel {
         }
     }
 
+    /// Every changed file's diff, one after another, as the context for advice
+    fn build_diff_content(files: &[crate::git::FileDiff], root: &std::path::Path) -> String {
+        files
+            .iter()
+            .map(|file_diff| file_diff.unified_diff(root))
+            .collect()
+    }
+
     /// Initialize the panel with current diff content when it becomes visible
-    pub fn initialize_with_current_diff(&mut self, files: &[crate::git::FileDiff]) {
+    pub fn initialize_with_current_diff(
+        &mut self,
+        files: &[crate::git::FileDiff],
+        root: &std::path::Path,
+    ) {
         if !self.needs_initialization {
             return;
         }
 
-        // Build diff content from files
-        let mut diff_content = String::new();
-        if !files.is_empty() {
-            for file_diff in files {
-                // Add file header
-                diff_content.push_str(&format!(
-                    "diff --git a/{} b/{}\n",
-                    file_diff.path.to_string_lossy(),
-                    file_diff.path.to_string_lossy()
-                ));
-
-                // Add git index line based on status
-                if file_diff.status.contains(git2::Status::WT_NEW) {
-                    diff_content.push_str("new file mode 100644\n");
-                } else if file_diff.status.contains(git2::Status::WT_DELETED) {
-                    diff_content.push_str("deleted file mode 100644\n");
-                } else if file_diff.status.contains(git2::Status::WT_RENAMED) {
-                    diff_content.push_str("similarity index 100%\n");
-                    diff_content.push_str("rename from old_name\n");
-                    diff_content.push_str("rename to new_name\n");
-                } else {
-                    diff_content.push_str("index 0000000..1111111 100644\n");
-                }
-
-                // Add file change markers
-                diff_content.push_str(&format!("--- a/{}\n", file_diff.path.to_string_lossy()));
-                diff_content.push_str(&format!("+++ b/{}\n", file_diff.path.to_string_lossy()));
-
-                // Add the actual diff content
-                for line in &file_diff.line_strings {
-                    diff_content.push_str(line);
-                    diff_content.push('\n');
-                }
-
-                diff_content.push('\n'); // Add separator between files
+        // Without an LLM there's nothing to ask, so the panel only says how to set one up
+        if self.llm_client.is_none() {
+            if matches!(self.content, AdviceContent::Loading) {
+                self.content = AdviceContent::Chat(Vec::new());
             }
+            self.needs_initialization = false;
+            return;
         }
 
-        // Store the diff content
-        *self.current_diff_content.borrow_mut() = if diff_content.trim().is_empty() {
-            None
-        } else {
-            Some(diff_content.clone())
-        };
+        let diff_content = Self::build_diff_content(files, root);
 
         // Send initial message if we have content and haven't sent it yet
         if !self.initial_message_sent && self.first_visit {
-            if !diff_content.trim().is_empty() {
+            // The chat started here is the one cached under this diff
+            self.current_diff_hash =
+                (!diff_content.trim().is_empty()).then(|| self.advice_cache_key(&diff_content));
+            let cached_session = match (&self.shared_state, &self.current_diff_hash) {
+                (Some(shared_state), Some(diff_hash)) => {
+                    shared_state.get_cached_advice_session(diff_hash)
+                }
+                _ => None,
+            };
+
+            if let Some(messages) = cached_session {
+                debug!("🎯 ADVICE_PANEL: Restoring cached advice chat");
+                self.mode = AdviceMode::Chatting;
+                self.content = AdviceContent::Chat(messages);
+            } else if !diff_content.trim().is_empty() {
                 self.send_initial_message_with_diff(&diff_content);
             } else {
                 self.send_no_changes_message();
@@ -681,10 +1018,22 @@ impl AdvicePanel {
     }
 }
 
+/// Digest of a diff for cache keys
+/// md5 rather than `DefaultHasher`, whose output may change between Rust releases and would
+/// orphan the advice chats cached on disk
+pub fn diff_hash(diff_content: &str) -> String {
+    format!("{:x}", md5::compute(diff_content))
+}
+
 impl Pane for AdvicePanel {
     fn title(&self) -> String {
         match self.mode {
-            AdviceMode::Chatting => "Chat".to_string(),
+            AdviceMode::Chatting if self.llm_client.is_none() => "Chat (AI disabled)".to_string(),
+            AdviceMode::Chatting => "Chat (Tab: improvements)".to_string(),
+            AdviceMode::Improvements => match &self.copy_notice {
+                Some(notice) => format!("Improvements - {notice}"),
+                None => "Improvements (j/k select, Enter code, y copy, Tab: chat)".to_string(),
+            },
             AdviceMode::Help => "Help".to_string(),
         }
     }
@@ -698,60 +1047,20 @@ impl Pane for AdvicePanel {
     ) -> Result<(), Box<dyn std::error::Error>> {
         let theme = app.get_theme();
 
-        // Update the current diff content from the app's files (read-only operation)
-        let files = app.get_files();
-        if !files.is_empty() {
-            let mut diff_content = String::new();
-
-            for file_diff in files {
-                // Add file header
-                diff_content.push_str(&format!(
-                    "diff --git a/{} b/{}\n",
-                    file_diff.path.to_string_lossy(),
-                    file_diff.path.to_string_lossy()
-                ));
-
-                // Add git index line based on status
-                if file_diff.status.contains(git2::Status::WT_NEW) {
-                    diff_content.push_str("new file mode 100644\n");
-                } else if file_diff.status.contains(git2::Status::WT_DELETED) {
-                    diff_content.push_str("deleted file mode 100644\n");
-                } else if file_diff.status.contains(git2::Status::WT_RENAMED) {
-                    diff_content.push_str("similarity index 100%\n");
-                    diff_content.push_str("rename from old_name\n");
-                    diff_content.push_str("rename to new_name\n");
-                } else {
-                    diff_content.push_str("index 0000000..1111111 100644\n");
-                }
-
-                // Add file change markers
-                diff_content.push_str(&format!("--- a/{}\n", file_diff.path.to_string_lossy()));
-                diff_content.push_str(&format!("+++ b/{}\n", file_diff.path.to_string_lossy()));
-
-                // Add the actual diff content
-                for line in &file_diff.line_strings {
-                    diff_content.push_str(line);
-                    diff_content.push('\n');
-                }
-
-                diff_content.push('\n'); // Add separator between files
-            }
-
-            *self.current_diff_content.borrow_mut() = if diff_content.trim().is_empty() {
-                None
-            } else {
-                Some(diff_content)
-            };
-        } else {
-            *self.current_diff_content.borrow_mut() = None;
-        }
-
         let block = Block::default()
             .title(self.title())
             .borders(Borders::ALL)
             .border_style(Style::default().fg(theme.border_color()));
 
+        let mut scroll_offset = self.scroll_offset;
         let content = match &self.content {
+            _ if self.mode == AdviceMode::Improvements => {
+                let (lines, selected_line) = self.improvement_lines(theme);
+                // Keep the highlighted improvement near the middle of the panel
+                let visible_lines = area.height.saturating_sub(2) as usize;
+                scroll_offset = selected_line.saturating_sub(visible_lines / 2);
+                lines
+            }
             AdviceContent::Loading => {
                 vec![Line::from("Loading advice...".to_string())]
             }
@@ -792,11 +1101,14 @@ impl Pane for AdvicePanel {
                 }
                 lines
             }
+            AdviceContent::Chat(messages) if messages.is_empty() && self.llm_client.is_none() => {
+                vec![Line::from(crate::llm::NO_LLM_HINT).fg(theme.highlight_color())]
+            }
             AdviceContent::Chat(messages) => {
                 let mut lines = Vec::new();
                 for msg in messages {
                     // Skip user messages that contain the diff pattern (initial automated message)
-                    if msg.role == MessageRole::User && msg.content.contains("Please provide 3 actionable improvements for the following code changes:") {
+                    if msg.role == MessageRole::User && crate::llm::is_advice_prompt(&msg.content) {
                         continue;
                     }
                     let (prefix, color) = match msg.role {
@@ -806,14 +1118,7 @@ impl Pane for AdvicePanel {
                     };
 
                     // Add message header with timestamp
-                    let time_str = msg.timestamp.elapsed().unwrap_or_default().as_secs();
-                    let time_display = if time_str < 60 {
-                        format!("{}s ago", time_str)
-                    } else if time_str < 3600 {
-                        format!("{}m ago", time_str / 60)
-                    } else {
-                        format!("{}h ago", time_str / 3600)
-                    };
+                    let time_display = crate::util::humanize_timestamp(msg.timestamp);
 
                     lines.push(Line::from(format!("[{}] {}:", time_display, prefix)).fg(color));
 
@@ -831,9 +1136,13 @@ impl Pane for AdvicePanel {
                 {
                     lines.push(Line::from("[now] AI:").fg(theme.secondary_color()));
                     lines.push(
-                        Line::from("  🤔 Thinking...".to_string()).fg(theme.highlight_color()),
+                        Line::from(format!("  {} Thinking...", app.spinner_frame()))
+                            .fg(theme.highlight_color()),
                     );
                     lines.push(Line::from(""));
+                } else if self.can_retry() {
+                    lines.push(Line::from("  Press r to retry").fg(theme.highlight_color()));
+                    lines.push(Line::from(""));
                 }
 
                 lines
@@ -874,7 +1183,7 @@ impl Pane for AdvicePanel {
         let paragraph = Paragraph::new(content)
             .block(block)
             .wrap(Wrap { trim: false })
-            .scroll((self.scroll_offset as u16, 0));
+            .scroll((scroll_offset as u16, 0));
 
         f.render_widget(paragraph, content_area);
 
@@ -938,16 +1247,7 @@ impl Pane for AdvicePanel {
     fn handle_event(&mut self, event: &AppEvent) -> bool {
         match event {
             AppEvent::Key(key_event) => {
-                // Handle Ctrl+R refresh separately (needs access to self method)
-                if key_event.code == KeyCode::Char('r')
-                    && key_event.modifiers.contains(KeyModifiers::CONTROL)
-                {
-                    // Ctrl+R: Refresh diff and clear chat
-                    self.refresh_chat_with_new_diff();
-                    return true;
-                }
-
-                // Use the keys module for all other advice panel key handling
+                // Ctrl+R clearing the chat goes through App, which asks for confirmation first
                 super::keys::AdvicePanelKeyHandler::handle_advice_panel_keys(self, key_event)
             }
             _ => false,
@@ -978,3 +1278,305 @@ impl Pane for AdvicePanel {
         Some(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmBackend;
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use std::time::Duration;
+
+    async fn wait_for_reply(panel: &mut AdvicePanel) {
+        while panel.pending_chat_task.is_some() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            panel.check_pending_tasks();
+        }
+    }
+
+    /// A one-line change to src/main.rs under /repo
+    fn sample_diff() -> crate::git::FileDiff {
+        crate::git::FileDiff {
+            path: std::path::PathBuf::from("/repo/src/main.rs"),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_MODIFIED,
+            line_strings: vec!["@@ -1 +1 @@".to_string(), "+fn main() {}".to_string()],
+            additions: 1,
+            deletions: 0,
+        }
+    }
+
+    /// A panel answered by `backend` that asks for advice when next initialized
+    fn panel_with_mock(backend: MockLlmBackend, shared_state: Arc<LlmSharedState>) -> AdvicePanel {
+        let client =
+            LlmClient::with_backend(crate::config::LlmConfig::default(), Arc::new(backend));
+        let mut panel = AdvicePanel::new().unwrap();
+        panel.set_shared_state(shared_state);
+        panel.set_llm_client(Arc::new(tokio::sync::Mutex::new(client)));
+        panel.needs_initialization = true;
+        panel
+    }
+
+    const ADVICE_REPLY: &str = "Here are some ideas:
+
+1. **Handle the error**
+Priority: Medium
+Category: Reliability
+`unwrap` panics on bad input.
+```rust
+let value = parse(input)?;
+```
+
+2) Avoid the clone
+**Priority:** High
+- **Category:** Performance
+The vector is copied on every call.
+
+### 3. Name the constant
+The magic number is unclear.
+";
+
+    #[test]
+    fn test_parse_improvements() {
+        let improvements = AdviceImprovement::parse_list(ADVICE_REPLY);
+        let titles: Vec<&str> = improvements.iter().map(|i| i.title.as_str()).collect();
+        // Most urgent first, with unlabelled items last
+        assert_eq!(
+            titles,
+            ["Avoid the clone", "Handle the error", "Name the constant"]
+        );
+
+        assert_eq!(improvements[0].priority, ImprovementPriority::High);
+        assert_eq!(improvements[0].category, "Performance");
+        assert_eq!(
+            improvements[0].description,
+            "The vector is copied on every call."
+        );
+        assert_eq!(
+            improvements[1].code_examples,
+            ["let value = parse(input)?;\n"]
+        );
+        assert_eq!(improvements[2].priority, ImprovementPriority::Unknown);
+        assert_eq!(improvements[2].category, "General");
+    }
+
+    #[test]
+    fn test_improvements_view_keys() {
+        let mut panel = AdvicePanel::new().unwrap();
+        panel.content = AdviceContent::Chat(vec![ChatMessageData {
+            id: "1".to_string(),
+            role: MessageRole::Assistant,
+            content: ADVICE_REPLY.to_string(),
+            timestamp: std::time::SystemTime::now(),
+        }]);
+        let key = |code| AppEvent::Key(crossterm::event::KeyEvent::new(code, KeyModifiers::NONE));
+
+        assert!(panel.handle_event(&key(KeyCode::Tab)));
+        assert_eq!(panel.mode, AdviceMode::Improvements);
+
+        // Selection stops at the last improvement
+        for _ in 0..5 {
+            panel.handle_event(&key(KeyCode::Char('j')));
+        }
+        assert_eq!(panel.selected_improvement, 2);
+        panel.handle_event(&key(KeyCode::Char('k')));
+        panel.handle_event(&key(KeyCode::Enter));
+        assert!(panel.expanded_improvements.contains(&1));
+        panel.handle_event(&key(KeyCode::Enter));
+        assert!(panel.expanded_improvements.is_empty());
+
+        assert!(panel.handle_event(&key(KeyCode::Tab)));
+        assert_eq!(panel.mode, AdviceMode::Chatting);
+    }
+
+    #[test]
+    fn test_code_examples_copied_in_turn() {
+        let mut panel = AdvicePanel::new().unwrap();
+        panel.content = AdviceContent::Chat(vec![ChatMessageData {
+            id: "1".to_string(),
+            role: MessageRole::Assistant,
+            content: "1. Split it\nPriority: High\n```\nfn a() {}\n```\n```\nfn b() {}\n```\n\n2. Leave it\n"
+                .to_string(),
+            timestamp: std::time::SystemTime::now(),
+        }]);
+
+        assert_eq!(
+            panel.next_code_example(),
+            Some(("fn a() {}\n".to_string(), 1, 2))
+        );
+        assert_eq!(
+            panel.next_code_example(),
+            Some(("fn b() {}\n".to_string(), 2, 2))
+        );
+        assert_eq!(
+            panel.next_code_example(),
+            Some(("fn a() {}\n".to_string(), 1, 2))
+        );
+
+        panel.move_improvement_selection(1);
+        assert_eq!(panel.next_code_example(), None);
+    }
+
+    #[test]
+    fn test_advice_diff_uses_real_headers() {
+        let text = crate::git::FileDiff {
+            path: std::path::PathBuf::from("/repo/src/lib.rs"),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_MODIFIED,
+            line_strings: [
+                "diff --git a/src/lib.rs b/src/lib.rs",
+                "index 3b18e51..a2c2c3a 100644",
+                "--- a/src/lib.rs",
+                "+++ b/src/lib.rs",
+                "@@ -1 +1 @@",
+                "-old",
+                "+new",
+            ]
+            .iter()
+            .map(|line| line.to_string())
+            .collect(),
+            additions: 1,
+            deletions: 1,
+        };
+        let submodule = crate::git::FileDiff {
+            path: std::path::PathBuf::from("/repo/vendor/lib"),
+            old_path: Some(std::path::PathBuf::from("/repo/lib")),
+            submodule: None,
+            status: git2::Status::WT_RENAMED,
+            line_strings: vec!["Submodule vendor/lib 1234567".to_string()],
+            additions: 0,
+            deletions: 0,
+        };
+
+        let diff = AdvicePanel::build_diff_content(
+            &[text.clone(), submodule],
+            std::path::Path::new("/repo"),
+        );
+        let expected = format!(
+            "{}\n{}",
+            text.line_strings.join("\n"),
+            "diff --git a/lib b/vendor/lib\n\
+             rename from lib\n\
+             rename to vendor/lib\n\
+             Submodule vendor/lib 1234567\n"
+        );
+        assert_eq!(diff, expected);
+    }
+
+    #[tokio::test]
+    async fn test_advice_chat_flow_with_mock_backend() {
+        let backend = MockLlmBackend::new(
+            vec![
+                Ok("1. Handle the error".to_string()),
+                Err("rate limited".to_string()),
+            ],
+            Duration::ZERO,
+        );
+        let mut panel = panel_with_mock(backend, Arc::new(LlmSharedState::new()));
+
+        panel.initialize_with_current_diff(&[sample_diff()], std::path::Path::new("/repo"));
+        assert_eq!(panel.loading_state, LoadingState::SendingChat);
+        wait_for_reply(&mut panel).await;
+
+        // The opening request asks for advice on the diff
+        let history = panel.get_chat_history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].role, MessageRole::User);
+        assert!(history[0].content.contains("+fn main() {}"));
+        assert_eq!(history[1].content, "1. Handle the error");
+        assert_eq!(panel.loading_state, LoadingState::Idle);
+
+        // A failed follow-up shows up in the chat
+        panel.send_chat_message("Why?").unwrap();
+        wait_for_reply(&mut panel).await;
+        let history = panel.get_chat_history();
+        assert_eq!(
+            history[3].content,
+            "Sorry, I encountered an error: LLM chat request failed: Failed to process chat: rate limited"
+        );
+        assert!(panel.can_retry());
+
+        // Retrying replaces the failed question and its error; with the script used up
+        // the mock falls back to its canned reply
+        panel.retry_failed_request();
+        wait_for_reply(&mut panel).await;
+        let history = panel.get_chat_history();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[2].content, "Why?");
+        assert!(
+            history[3]
+                .content
+                .starts_with("Mock reply from gpt-4o-mini")
+        );
+        assert!(!panel.can_retry());
+    }
+
+    #[test]
+    fn test_no_llm_skips_initial_message() {
+        let mut panel = AdvicePanel::new().unwrap();
+        panel.set_shared_state(Arc::new(LlmSharedState::new()));
+        panel.set_visible(true);
+
+        panel.initialize_with_current_diff(&[sample_diff()], std::path::Path::new("/repo"));
+        assert!(panel.get_chat_history().is_empty());
+        assert!(matches!(&panel.content, AdviceContent::Chat(messages) if messages.is_empty()));
+        assert!(panel.pending_chat_task.is_none());
+        assert_eq!(panel.loading_state, LoadingState::Idle);
+        assert_eq!(panel.title(), "Chat (AI disabled)");
+    }
+
+    #[tokio::test]
+    async fn test_advice_chat_restored_from_cache() {
+        let shared_state = Arc::new(LlmSharedState::new());
+        let new_panel = |model: &str| {
+            let backend = MockLlmBackend::new(Vec::new(), Duration::ZERO);
+            let mut panel = panel_with_mock(backend, Arc::clone(&shared_state));
+            panel.set_advice_model(model.to_string());
+            panel
+        };
+        let files = [sample_diff()];
+        let root = std::path::Path::new("/repo");
+
+        let mut panel = new_panel("model-a");
+        panel.initialize_with_current_diff(&files, root);
+        wait_for_reply(&mut panel).await;
+        assert_eq!(panel.get_chat_history().len(), 2);
+
+        // Same diff and model: the chat comes back without asking again
+        let mut panel = new_panel("model-a");
+        panel.initialize_with_current_diff(&files, root);
+        assert_eq!(panel.loading_state, LoadingState::Idle);
+        assert_eq!(panel.get_chat_history().len(), 2);
+
+        // Another model gets its own advice
+        let mut panel = new_panel("model-b");
+        panel.initialize_with_current_diff(&files, root);
+        assert_eq!(panel.loading_state, LoadingState::SendingChat);
+        wait_for_reply(&mut panel).await;
+    }
+
+    #[tokio::test]
+    async fn test_advice_cache_key_is_stable() {
+        // A fixed digest, so keys written to disk by one build are found by the next
+        assert_eq!(diff_hash(""), "d41d8cd98f00b204e9800998ecf8427e");
+
+        let mut panel = panel_with_mock(
+            MockLlmBackend::new(Vec::new(), Duration::ZERO),
+            Arc::new(LlmSharedState::new()),
+        );
+        let files = [sample_diff()];
+        let root = std::path::Path::new("/repo");
+
+        // Opening the panel and asking again key the same diff the same way
+        panel.initialize_with_current_diff(&files, root);
+        let first_key = panel.current_diff_hash.clone().unwrap();
+        wait_for_reply(&mut panel).await;
+        panel.refresh_chat_with_new_diff();
+        panel.initialize_with_current_diff(&files, root);
+        assert_eq!(panel.current_diff_hash, Some(first_key.clone()));
+
+        let diff = AdvicePanel::build_diff_content(&files, root);
+        assert!(first_key.ends_with(&diff_hash(&diff)));
+    }
+}
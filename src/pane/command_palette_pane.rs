@@ -0,0 +1,338 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+};
+
+use super::{AppEvent, Pane, ScrollAction};
+use crate::action::Action;
+use crate::git::GitRepo;
+use crate::ui::App;
+
+/// An action listed in the command palette, with the hotkey that also runs it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaletteCommand {
+    pub action: Action,
+    /// The hotkey as shown in the help page
+    pub keys: &'static str,
+}
+
+impl PaletteCommand {
+    const fn new(action: Action, keys: &'static str) -> Self {
+        Self { action, keys }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.action.description()
+    }
+}
+
+/// Every action the palette offers, in the order listed before any filtering
+pub const PALETTE_COMMANDS: &[PaletteCommand] = &[
+    PaletteCommand::new(Action::NextFile, "Right"),
+    PaletteCommand::new(Action::PrevFile, "Left"),
+    PaletteCommand::new(Action::NextHunk, "n"),
+    PaletteCommand::new(Action::PrevHunk, "N"),
+    PaletteCommand::new(Action::ScrollDiff(ScrollAction::Top), "g g"),
+    PaletteCommand::new(Action::ScrollDiff(ScrollAction::Bottom), "Shift+G"),
+    PaletteCommand::new(Action::OpenSearch, "/"),
+    PaletteCommand::new(Action::ToggleFolding, "z"),
+    PaletteCommand::new(Action::SelectDiffLines, "V"),
+    PaletteCommand::new(Action::StageHunk, "s"),
+    PaletteCommand::new(Action::DiscardFile, "X"),
+    PaletteCommand::new(Action::CycleViewMode, "v"),
+    PaletteCommand::new(Action::ToggleStagedView, "S"),
+    PaletteCommand::new(Action::ToggleStagedAndUnstagedView, "B"),
+    PaletteCommand::new(Action::DraftCommitMessage, "C"),
+    PaletteCommand::new(Action::CopyPatch, "p"),
+    PaletteCommand::new(Action::SavePatch, "P"),
+    PaletteCommand::new(Action::OpenInEditor, "e"),
+    PaletteCommand::new(Action::OpenExternalDiff, "x"),
+    PaletteCommand::new(Action::ShowDiffStats, "i"),
+    PaletteCommand::new(Action::ShowTokenUsage, "$"),
+    PaletteCommand::new(Action::ToggleAdvicePanel, "Ctrl+l"),
+    PaletteCommand::new(Action::ClearAdviceChat, "Ctrl+R in the advice panel"),
+    PaletteCommand::new(Action::OpenCommitPicker, "Ctrl+P"),
+    PaletteCommand::new(Action::OpenReflog, "Ctrl+R"),
+    PaletteCommand::new(Action::ShowCommitMessage, "Ctrl+g"),
+    PaletteCommand::new(Action::ReturnToWorkingDirectory, "Ctrl+W"),
+    PaletteCommand::new(Action::ToggleRefreshPaused, "Ctrl+Space"),
+    PaletteCommand::new(Action::SinglePaneDiff, "Ctrl+d"),
+    PaletteCommand::new(Action::SideBySideDiff, "Ctrl+s"),
+    PaletteCommand::new(Action::ToggleDiffPanel, "Ctrl+h"),
+    PaletteCommand::new(Action::ToggleChangedFilesPane, "Ctrl+b"),
+    PaletteCommand::new(Action::ToggleMonitorPane, "Ctrl+o"),
+    PaletteCommand::new(Action::FocusNextPane, "Tab"),
+    PaletteCommand::new(Action::FocusPrevPane, "Shift+Tab"),
+    PaletteCommand::new(Action::ToggleTheme, "Ctrl+t"),
+    PaletteCommand::new(Action::ToggleHelp, "?"),
+    PaletteCommand::new(Action::Quit, "q"),
+];
+
+/// Added to the score of a match whose characters are scattered, so it ranks below any
+/// match where the query appears whole
+const SCATTERED_MATCH: usize = 1000;
+
+/// How well `query` fuzzily matches `text`, lower being better, or None when its characters
+/// don't all appear in order; the query appearing whole ranks first, earliest first, then
+/// scattered matches by how spread out they are
+pub fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let query = query.to_lowercase();
+    let text = text.to_lowercase();
+    if let Some(index) = text.find(query.as_str()) {
+        return Some(text[..index].chars().count());
+    }
+
+    let text: Vec<char> = text.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous = None;
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|&c| c == wanted)?;
+        score += match previous {
+            Some(previous) => found - previous - 1,
+            None => found,
+        };
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(SCATTERED_MATCH + score)
+}
+
+/// Fuzzy launcher over every action, which closes once one is picked
+pub struct CommandPalettePane {
+    visible: bool,
+    query: String,
+    /// Index into the filtered commands
+    selected: usize,
+}
+
+impl Default for CommandPalettePane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandPalettePane {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Clear the query so the palette opens on the full list
+    pub fn reset(&mut self) {
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    /// Commands matching the query, best match first
+    pub fn filtered_commands(&self) -> Vec<&'static PaletteCommand> {
+        let mut matches: Vec<(usize, usize, &'static PaletteCommand)> = PALETTE_COMMANDS
+            .iter()
+            .enumerate()
+            .filter_map(|(index, command)| {
+                fuzzy_score(&self.query, command.name()).map(|score| (score, index, command))
+            })
+            .collect();
+        matches.sort_by_key(|&(score, index, _)| (score, index));
+        matches.into_iter().map(|(_, _, command)| command).collect()
+    }
+
+    pub fn get_selected_command(&self) -> Option<&'static PaletteCommand> {
+        self.filtered_commands().get(self.selected).copied()
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let count = self.filtered_commands().len();
+        if count == 0 {
+            return;
+        }
+        self.selected = if down {
+            (self.selected + 1) % count
+        } else {
+            (self.selected + count - 1) % count
+        };
+    }
+}
+
+impl Pane for CommandPalettePane {
+    fn title(&self) -> String {
+        "Command Palette (Enter runs, Esc closes)".to_string()
+    }
+
+    fn render(
+        &self,
+        f: &mut Frame,
+        app: &App,
+        area: Rect,
+        _git_repo: &GitRepo,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = app.get_theme();
+        let popup = super::centered_area(area, 70, 20);
+        f.render_widget(Clear, popup);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(popup);
+        let block = |title: String| {
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.primary_color()))
+                .style(
+                    Style::default()
+                        .fg(theme.foreground_color())
+                        .bg(theme.background_color()),
+                )
+        };
+
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.secondary_color())),
+            Span::raw(self.query.as_str()),
+            Span::styled("_", Style::default().fg(theme.secondary_color())),
+        ]))
+        .block(block(self.title()));
+        f.render_widget(input, chunks[0]);
+
+        let commands = self.filtered_commands();
+        if commands.is_empty() {
+            f.render_widget(
+                Paragraph::new("No commands match").block(block("Commands".to_string())),
+                chunks[1],
+            );
+            return Ok(());
+        }
+
+        // Scroll just far enough to keep the selected command on screen
+        let visible_height = chunks[1].height.saturating_sub(2) as usize;
+        let start_index = (self.selected + 1).saturating_sub(visible_height);
+        let items: Vec<ListItem> = commands
+            .iter()
+            .enumerate()
+            .skip(start_index)
+            .take(visible_height)
+            .map(|(index, command)| {
+                let line = Line::from(vec![
+                    Span::raw(format!("{:<52}", command.name())),
+                    Span::styled(command.keys, Style::default().fg(theme.secondary_color())),
+                ]);
+                if index == self.selected {
+                    ListItem::new(
+                        line.style(
+                            Style::default()
+                                .bg(theme.highlight_color())
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                    )
+                } else {
+                    ListItem::new(line)
+                }
+            })
+            .collect();
+        f.render_widget(
+            List::new(items).block(block(format!("Commands ({})", commands.len()))),
+            chunks[1],
+        );
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &AppEvent) -> bool {
+        match event {
+            AppEvent::Key(key) => match key.code {
+                KeyCode::Down => {
+                    self.move_selection(true);
+                    true
+                }
+                KeyCode::Up => {
+                    self.move_selection(false);
+                    true
+                }
+                KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.move_selection(true);
+                    true
+                }
+                KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.move_selection(false);
+                    true
+                }
+                KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.reset();
+                    true
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.selected = 0;
+                    true
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.query.push(c);
+                    self.selected = 0;
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn as_command_palette_pane(&self) -> Option<&CommandPalettePane> {
+        Some(self)
+    }
+
+    fn as_command_palette_pane_mut(&mut self) -> Option<&mut CommandPalettePane> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Next file"), Some(0));
+        assert_eq!(fuzzy_score("next", "Next file"), Some(0));
+        assert_eq!(fuzzy_score("file", "Next file"), Some(5));
+        assert_eq!(fuzzy_score("nf", "Next file"), Some(SCATTERED_MATCH + 4));
+        assert_eq!(fuzzy_score("fn", "Next file"), None);
+    }
+
+    #[test]
+    fn test_filtered_commands_best_match_first() {
+        let mut palette = CommandPalettePane::new();
+        assert_eq!(palette.filtered_commands().len(), PALETTE_COMMANDS.len());
+
+        for c in "theme".chars() {
+            palette.handle_event(&AppEvent::Key(KeyEvent::new(
+                KeyCode::Char(c),
+                KeyModifiers::NONE,
+            )));
+        }
+        assert_eq!(
+            palette.get_selected_command().map(|command| command.name()),
+            Some("Cycle through themes")
+        );
+
+        palette.handle_event(&AppEvent::Key(KeyEvent::new(
+            KeyCode::Char('!'),
+            KeyModifiers::NONE,
+        )));
+        assert!(palette.get_selected_command().is_none());
+    }
+}
@@ -0,0 +1,333 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use std::path::PathBuf;
+use tokio::sync::oneshot;
+
+use super::{AppEvent, Pane};
+use crate::git::GitRepo;
+use crate::llm::LlmClient;
+use crate::ui::App;
+
+/// Popup with an LLM-drafted commit message for the staged changes, editable before use
+pub struct CommitDraftPane {
+    visible: bool,
+    /// None when no LLM is configured, so drafting only says how to set one up
+    llm_client: Option<LlmClient>,
+    /// The draft being edited, empty while the LLM is still writing it
+    draft: String,
+    /// Cursor position in `draft`, in characters
+    cursor: usize,
+    error: Option<String>,
+    pending_reply: Option<oneshot::Receiver<Result<String, String>>>,
+    /// Where `Ctrl+S` saves the draft, `COMMIT_EDITMSG` in the repository's git directory
+    git_dir: Option<PathBuf>,
+    /// Result of the last copy or save, shown in the title
+    notice: Option<String>,
+}
+
+impl CommitDraftPane {
+    pub fn new(llm_client: Option<LlmClient>) -> Self {
+        Self {
+            visible: false,
+            llm_client,
+            draft: String::new(),
+            cursor: 0,
+            error: None,
+            pending_reply: None,
+            git_dir: None,
+            notice: None,
+        }
+    }
+
+    /// Ask the LLM for a commit message for `diff`, showing the popup while it writes
+    /// Any draft still in flight is dropped
+    pub fn draft(&mut self, diff: String, git_dir: Option<PathBuf>) {
+        self.draft.clear();
+        self.cursor = 0;
+        self.notice = None;
+        self.git_dir = git_dir;
+        self.visible = true;
+        let Some(client) = self.llm_client.clone() else {
+            self.error = Some(crate::llm::NO_LLM_HINT.to_string());
+            self.pending_reply = None;
+            return;
+        };
+
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let result = client.draft_commit_message(diff).await;
+            // The popup may have been closed, in which case nobody is waiting
+            let _ = sender.send(result);
+        });
+        self.error = None;
+        self.pending_reply = Some(receiver);
+    }
+
+    /// Pick up the draft once it arrives, with the cursor at its end
+    pub fn check_pending_reply(&mut self) {
+        let Some(receiver) = self.pending_reply.as_mut() else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(Ok(message)) => {
+                self.cursor = message.chars().count();
+                self.draft = message;
+            }
+            Ok(Err(error)) => self.error = Some(error),
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.error = Some("The commit message request was dropped".to_string())
+            }
+        }
+        self.pending_reply = None;
+    }
+
+    fn is_loading(&self) -> bool {
+        self.pending_reply.is_some()
+    }
+
+    /// Byte offset in `draft` of the character at `cursor`
+    fn cursor_byte(&self) -> usize {
+        self.draft
+            .char_indices()
+            .nth(self.cursor)
+            .map_or(self.draft.len(), |(index, _)| index)
+    }
+
+    fn insert(&mut self, c: char) {
+        let at = self.cursor_byte();
+        self.draft.insert(at, c);
+        self.cursor += 1;
+    }
+
+    fn delete_before_cursor(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let at = self.cursor_byte();
+            self.draft.remove(at);
+        }
+    }
+
+    /// Line and column of the cursor, counted in characters
+    fn cursor_line_and_column(&self) -> (usize, usize) {
+        let before = &self.draft[..self.cursor_byte()];
+        let line = before.matches('\n').count();
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count();
+        (line, column)
+    }
+
+    fn copy_to_clipboard(&mut self) {
+        self.notice = Some(match crate::clipboard::copy(&self.draft) {
+            Ok(()) => "Copied to clipboard".to_string(),
+            Err(e) => format!("Copy failed: {e}"),
+        });
+    }
+
+    /// Save the draft as `COMMIT_EDITMSG` in the git directory
+    ///
+    /// A plain `git commit` overwrites that file before opening the editor, so the notice
+    /// gives the `git commit -e -F <file>` that starts from the draft instead.
+    fn write_commit_editmsg(&mut self) {
+        self.notice = Some(match &self.git_dir {
+            Some(git_dir) => {
+                let path = git_dir.join("COMMIT_EDITMSG");
+                match std::fs::write(&path, format!("{}\n", self.draft)) {
+                    Ok(()) => format!("Saved; commit with git commit -e -F {}", path.display()),
+                    Err(e) => format!("Save failed: {e}"),
+                }
+            }
+            None => "No git directory to save to".to_string(),
+        });
+    }
+}
+
+impl Pane for CommitDraftPane {
+    fn title(&self) -> String {
+        match &self.notice {
+            Some(notice) => format!("Commit message draft - {notice}"),
+            None => "Commit message draft (Ctrl+Y copy, Ctrl+S save, Esc close)".to_string(),
+        }
+    }
+
+    fn render(
+        &self,
+        f: &mut Frame,
+        app: &App,
+        area: Rect,
+        _git_repo: &GitRepo,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = app.get_theme();
+        let popup = super::large_popup_area(area);
+
+        let text: Vec<Line> = if self.is_loading() {
+            vec![Line::from(Span::styled(
+                format!(
+                    "{} Drafting a commit message for the staged changes...",
+                    app.spinner_frame()
+                ),
+                Style::default()
+                    .fg(theme.secondary_color())
+                    .add_modifier(Modifier::ITALIC),
+            ))]
+        } else if let Some(error) = &self.error {
+            vec![Line::from(Span::styled(
+                format!("❌ {error}"),
+                Style::default().fg(theme.error_color()),
+            ))]
+        } else {
+            self.draft
+                .split('\n')
+                .enumerate()
+                .map(|(i, line)| {
+                    // The subject line is what shows up in `git log --oneline`
+                    if i == 0 {
+                        Line::from(Span::styled(
+                            line.to_string(),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ))
+                    } else {
+                        Line::from(line.to_string())
+                    }
+                })
+                .collect()
+        };
+
+        // Keep the cursor in view; lines aren't wrapped so the cursor maps straight to the screen
+        let visible_height = popup.height.saturating_sub(2) as usize;
+        let visible_width = popup.width.saturating_sub(2) as usize;
+        let (cursor_line, cursor_column) = self.cursor_line_and_column();
+        let scroll_y = (cursor_line + 1).saturating_sub(visible_height);
+        let scroll_x = (cursor_column + 1).saturating_sub(visible_width);
+
+        let paragraph = Paragraph::new(text)
+            .style(
+                Style::default()
+                    .fg(theme.foreground_color())
+                    .bg(theme.background_color()),
+            )
+            .block(
+                Block::default()
+                    .title(self.title())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary_color())),
+            )
+            .scroll((scroll_y as u16, scroll_x as u16));
+
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+
+        if !self.is_loading() && self.error.is_none() {
+            f.set_cursor_position(Position {
+                x: popup.x + 1 + (cursor_column - scroll_x) as u16,
+                y: popup.y + 1 + (cursor_line - scroll_y) as u16,
+            });
+        }
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &AppEvent) -> bool {
+        let AppEvent::Key(key) = event else {
+            return false;
+        };
+        if key.code == KeyCode::Esc {
+            self.pending_reply = None;
+            self.set_visible(false);
+            return true;
+        }
+        // Nothing to edit until the draft arrives
+        if self.is_loading() || self.error.is_some() {
+            return true;
+        }
+
+        self.notice = None;
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('y') if control => self.copy_to_clipboard(),
+            KeyCode::Char('s') if control => self.write_commit_editmsg(),
+            KeyCode::Char(c) if !control => self.insert(c),
+            KeyCode::Enter => self.insert('\n'),
+            KeyCode::Backspace => self.delete_before_cursor(),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.draft.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.draft.chars().count(),
+            _ => {}
+        }
+        true
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn as_commit_draft_pane_mut(&mut self) -> Option<&mut CommitDraftPane> {
+        Some(self)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmBackend;
+    use crossterm::event::KeyEvent;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> AppEvent {
+        AppEvent::Key(KeyEvent::new(code, modifiers))
+    }
+
+    #[tokio::test]
+    async fn test_commit_draft_editing_and_saving() {
+        let backend = MockLlmBackend::new(vec![Ok("feat: add x\n".to_string())], Duration::ZERO);
+        let client =
+            LlmClient::with_backend(crate::config::LlmConfig::default(), Arc::new(backend));
+        let mut pane = CommitDraftPane::new(Some(client));
+        let git_dir = tempfile::TempDir::new().unwrap();
+
+        pane.draft("+x".to_string(), Some(git_dir.path().to_path_buf()));
+        assert!(pane.visible());
+        assert!(pane.is_loading());
+
+        for _ in 0..100 {
+            pane.check_pending_reply();
+            if !pane.is_loading() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(pane.draft, "feat: add x");
+        assert_eq!(pane.cursor_line_and_column(), (0, 11));
+
+        // Edit before the last character
+        pane.handle_event(&key(KeyCode::Left, KeyModifiers::NONE));
+        pane.handle_event(&key(KeyCode::Backspace, KeyModifiers::NONE));
+        pane.handle_event(&key(KeyCode::Char('é'), KeyModifiers::NONE));
+        pane.handle_event(&key(KeyCode::End, KeyModifiers::NONE));
+        pane.handle_event(&key(KeyCode::Enter, KeyModifiers::NONE));
+        pane.handle_event(&key(KeyCode::Enter, KeyModifiers::NONE));
+        pane.handle_event(&key(KeyCode::Char('B'), KeyModifiers::SHIFT));
+        assert_eq!(pane.draft, "feat: addéx\n\nB");
+        assert_eq!(pane.cursor_line_and_column(), (2, 1));
+
+        pane.handle_event(&key(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        let saved = std::fs::read_to_string(git_dir.path().join("COMMIT_EDITMSG")).unwrap();
+        assert_eq!(saved, "feat: addéx\n\nB\n");
+        assert!(pane.title().contains(&format!(
+            "git commit -e -F {}",
+            git_dir.path().join("COMMIT_EDITMSG").display()
+        )));
+
+        assert!(pane.handle_event(&key(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(!pane.visible());
+    }
+}
@@ -0,0 +1,154 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use super::{AppEvent, Pane, PaneKeyUtils};
+use crate::git::GitRepo;
+use crate::ui::App;
+
+/// Popup showing the full message of the commit named in the status bar
+pub struct CommitMessagePane {
+    visible: bool,
+    commit_sha: String,
+    message: String,
+    scroll_offset: usize,
+}
+
+impl Default for CommitMessagePane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommitMessagePane {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            commit_sha: String::new(),
+            message: String::new(),
+            scroll_offset: 0,
+        }
+    }
+
+    pub fn set_message(&mut self, commit_sha: &str, message: String) {
+        self.commit_sha = commit_sha.chars().take(7).collect();
+        self.message = message;
+        self.scroll_offset = 0;
+    }
+}
+
+impl Pane for CommitMessagePane {
+    fn title(&self) -> String {
+        format!("Commit {} (Esc to close)", self.commit_sha)
+    }
+
+    fn render(
+        &self,
+        f: &mut Frame,
+        app: &App,
+        area: Rect,
+        _git_repo: &GitRepo,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = app.get_theme();
+        let popup = super::large_popup_area(area);
+
+        let mut lines = self.message.lines();
+        let mut text = Vec::new();
+        if let Some(subject) = lines.next() {
+            text.push(Line::from(Span::styled(
+                subject.to_string(),
+                Style::default()
+                    .fg(theme.secondary_color())
+                    .add_modifier(Modifier::BOLD),
+            )));
+        }
+        text.extend(lines.map(|line| Line::from(line.to_string())));
+
+        // Don't scroll past the last line of the message
+        let visible_height = popup.height.saturating_sub(2) as usize;
+        let max_scroll = text.len().saturating_sub(visible_height);
+        let scroll = self.scroll_offset.min(max_scroll) as u16;
+
+        let paragraph = Paragraph::new(text)
+            .style(
+                Style::default()
+                    .fg(theme.foreground_color())
+                    .bg(theme.background_color()),
+            )
+            .block(
+                Block::default()
+                    .title(self.title())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary_color())),
+            )
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0));
+
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &AppEvent) -> bool {
+        match event {
+            AppEvent::Key(key) => match key.code {
+                KeyCode::Esc => {
+                    self.set_visible(false);
+                    true
+                }
+                _ => {
+                    let line_count = self.message.lines().count();
+                    PaneKeyUtils::handle_scroll_keys(&mut self.scroll_offset, key, line_count);
+                    self.scroll_offset = self.scroll_offset.min(line_count.saturating_sub(1));
+                    true
+                }
+            },
+            _ => false,
+        }
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn as_commit_message_pane_mut(&mut self) -> Option<&mut CommitMessagePane> {
+        Some(self)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    #[test]
+    fn test_commit_message_scrolling_and_dismissal() {
+        let mut pane = CommitMessagePane::new();
+        pane.set_message("0123456789abcdef", "Subject\n\nBody line".to_string());
+        pane.set_visible(true);
+        assert_eq!(pane.title(), "Commit 0123456 (Esc to close)");
+
+        let j = AppEvent::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        for _ in 0..5 {
+            assert!(pane.handle_event(&j));
+        }
+        assert_eq!(pane.scroll_offset, 2);
+
+        let esc = AppEvent::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(pane.handle_event(&esc));
+        assert!(!pane.visible());
+
+        // A new message starts at the top
+        pane.set_message("fedcba", "Other".to_string());
+        assert_eq!(pane.scroll_offset, 0);
+        assert_eq!(pane.message, "Other");
+    }
+}
@@ -11,6 +11,12 @@ use super::{AppEvent, Pane};
 use crate::git::GitRepo;
 use crate::ui::App;
 
+/// Frames of the spinner shown while commit history loads
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// How close to the end of the loaded commits the selection gets before more are requested
+const LOAD_MORE_THRESHOLD: usize = 10;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum CommitPickerLoadingState {
     NotLoaded,
@@ -28,6 +34,14 @@ pub struct CommitPickerPane {
     enter_pressed: bool,
     loading_state: CommitPickerLoadingState,
     error_message: Option<String>,
+    // Whether older commits remain to be loaded from the revwalk
+    has_more: bool,
+    // Listing HEAD's reflog rather than commit history
+    showing_reflog: bool,
+    load_more_requested: bool,
+    // Commits processed and expected while the first page loads
+    progress: Option<(usize, usize)>,
+    loading_started: std::time::Instant,
     // Performance optimization fields
     last_visible_height: usize,
     render_cache_valid: bool,
@@ -50,6 +64,11 @@ impl CommitPickerPane {
             enter_pressed: false,
             loading_state: CommitPickerLoadingState::NotLoaded,
             error_message: None,
+            has_more: false,
+            showing_reflog: false,
+            load_more_requested: false,
+            progress: None,
+            loading_started: std::time::Instant::now(),
             last_visible_height: 0,
             render_cache_valid: false,
         }
@@ -58,10 +77,44 @@ impl CommitPickerPane {
     pub fn set_loading(&mut self) {
         self.loading_state = CommitPickerLoadingState::Loading;
         self.error_message = None;
+        self.progress = None;
+        self.loading_started = std::time::Instant::now();
+    }
+
+    /// List HEAD's reflog instead of commit history, from the next load on
+    pub fn set_showing_reflog(&mut self, showing_reflog: bool) {
+        self.showing_reflog = showing_reflog;
+    }
+
+    pub fn is_showing_reflog(&self) -> bool {
+        self.showing_reflog
+    }
+
+    pub fn set_progress(&mut self, loaded: usize, total: usize) {
+        self.progress = Some((loaded.min(total), total));
+    }
+
+    fn loading_text(&self) -> String {
+        let frame = self.loading_started.elapsed().as_millis() / 100;
+        let spinner = SPINNER_FRAMES[frame as usize % SPINNER_FRAMES.len()];
+        let what = if self.showing_reflog {
+            "reflog entries"
+        } else {
+            "commits"
+        };
+        match self.progress {
+            Some((loaded, total)) if loaded > 0 => {
+                format!("{spinner} Loaded {loaded}/{total} {what}...")
+            }
+            _ if self.showing_reflog => format!("{spinner} Loading reflog..."),
+            _ => format!("{spinner} Loading commit history..."),
+        }
     }
 
     pub fn update_commits(&mut self, commits: Vec<crate::git::CommitInfo>) {
         self.commits = commits;
+        self.has_more = false;
+        self.load_more_requested = false;
         if self.current_index >= self.commits.len() {
             self.current_index = 0;
             self.scroll_offset = 0;
@@ -80,9 +133,39 @@ impl CommitPickerPane {
         self.render_cache_valid = false;
     }
 
+    /// Record whether the loaded commits stop short of the end of history
+    pub fn set_has_more(&mut self, has_more: bool) {
+        self.has_more = has_more;
+        self.request_more_if_near_end();
+    }
+
+    /// Add the next page of older commits below the loaded ones
+    pub fn append_commits(&mut self, commits: Vec<crate::git::CommitInfo>, has_more: bool) {
+        self.commits.extend(commits);
+        self.has_more = has_more;
+        self.load_more_requested = false;
+        self.render_cache_valid = false;
+    }
+
+    /// SHA of the last loaded commit, once the selection is close enough to it to need more
+    pub fn take_load_more_request(&mut self) -> Option<String> {
+        if !std::mem::take(&mut self.load_more_requested) {
+            return None;
+        }
+        self.commits.last().map(|commit| commit.sha.clone())
+    }
+
+    fn request_more_if_near_end(&mut self) {
+        if self.has_more && self.current_index + LOAD_MORE_THRESHOLD >= self.commits.len() {
+            self.load_more_requested = true;
+        }
+    }
+
     pub fn set_error(&mut self, error: String) {
         self.loading_state = CommitPickerLoadingState::Error;
         self.error_message = Some(error);
+        self.has_more = false;
+        self.load_more_requested = false;
         self.commits.clear();
         self.current_index = 0;
         self.scroll_offset = 0;
@@ -104,8 +187,14 @@ impl CommitPickerPane {
         if matches!(self.loading_state, CommitPickerLoadingState::Loaded)
             && !self.commits.is_empty()
         {
+            // Don't wrap to the top while older commits are still to be loaded
+            if self.has_more && self.current_index + 1 == self.commits.len() {
+                self.request_more_if_near_end();
+                return;
+            }
             self.current_index = (self.current_index + 1) % self.commits.len();
             self.update_scroll_offset(20); // Use reasonable default
+            self.request_more_if_near_end();
         }
     }
 
@@ -159,7 +248,13 @@ impl CommitPickerPane {
 
 impl Pane for CommitPickerPane {
     fn title(&self) -> String {
-        "Commit History".to_string()
+        if self.showing_reflog {
+            "Reflog (HEAD)".to_string()
+        } else if self.has_more {
+            format!("Commit History ({}+)", self.commits.len())
+        } else {
+            "Commit History".to_string()
+        }
     }
 
     fn render(
@@ -184,7 +279,7 @@ impl Pane for CommitPickerPane {
                 return Ok(());
             }
             CommitPickerLoadingState::Loading => {
-                let paragraph = Paragraph::new("⏳ Loading commit history...").block(
+                let paragraph = Paragraph::new(self.loading_text()).block(
                     Block::default()
                         .title(self.title())
                         .borders(Borders::ALL)
@@ -213,7 +308,12 @@ impl Pane for CommitPickerPane {
             }
             CommitPickerLoadingState::Loaded => {
                 if self.commits.is_empty() {
-                    let paragraph = Paragraph::new("📭 No commits found in this repository\n\nThis might be a new repository with no commits yet.")
+                    let message = if self.showing_reflog {
+                        "📭 No reflog entries for HEAD"
+                    } else {
+                        "📭 No commits found in this repository\n\nThis might be a new repository with no commits yet."
+                    };
+                    let paragraph = Paragraph::new(message)
                         .block(
                             Block::default()
                                 .title(self.title())
@@ -273,6 +373,11 @@ impl Pane for CommitPickerPane {
                         .add_modifier(Modifier::BOLD),
                 ));
 
+                spans.push(Span::styled(
+                    format!("{} ", crate::util::humanize_unix_time(commit.time)),
+                    Style::default().fg(theme.secondary_color()),
+                ));
+
                 // Add first line of commit message
                 let first_line = commit.message.lines().next().unwrap_or("").to_string();
                 spans.push(Span::styled(
@@ -417,12 +522,14 @@ mod tests {
                 sha: "abc123".to_string(),
                 short_sha: "abc123".to_string(),
                 message: "First commit".to_string(),
+                time: 0,
                 files_changed: vec![],
             },
             crate::git::CommitInfo {
                 sha: "def456".to_string(),
                 short_sha: "def456".to_string(),
                 message: "Second commit".to_string(),
+                time: 0,
                 files_changed: vec![],
             },
         ];
@@ -452,12 +559,14 @@ mod tests {
                 sha: "abc123".to_string(),
                 short_sha: "abc123".to_string(),
                 message: "First commit".to_string(),
+                time: 0,
                 files_changed: vec![],
             },
             crate::git::CommitInfo {
                 sha: "def456".to_string(),
                 short_sha: "def456".to_string(),
                 message: "Second commit".to_string(),
+                time: 0,
                 files_changed: vec![],
             },
         ];
@@ -483,4 +592,68 @@ mod tests {
         assert!(pane.handle_event(&t_event));
         assert_eq!(pane.current_index, 1); // Should navigate next
     }
+
+    fn test_commits(range: std::ops::Range<usize>) -> Vec<crate::git::CommitInfo> {
+        range
+            .map(|i| crate::git::CommitInfo {
+                sha: format!("sha{i}"),
+                short_sha: format!("sha{i}"),
+                message: format!("Commit {i}"),
+                time: 0,
+                files_changed: vec![],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_commit_picker_loads_more_near_end() {
+        let mut pane = CommitPickerPane::new();
+        pane.update_commits(test_commits(0..20));
+        pane.set_has_more(true);
+        assert_eq!(pane.take_load_more_request(), None);
+        assert_eq!(pane.title(), "Commit History (20+)");
+
+        for _ in 0..9 {
+            pane.navigate_next();
+        }
+        assert_eq!(pane.take_load_more_request(), None);
+        pane.navigate_next();
+        assert_eq!(pane.take_load_more_request(), Some("sha19".to_string()));
+        assert_eq!(pane.take_load_more_request(), None);
+
+        // The selection waits at the end instead of wrapping until the next page arrives
+        for _ in 0..20 {
+            pane.navigate_next();
+        }
+        assert_eq!(pane.current_index, 19);
+
+        pane.append_commits(test_commits(20..25), false);
+        pane.navigate_next();
+        assert_eq!(pane.current_index, 20);
+        assert_eq!(pane.get_current_commit().unwrap().sha, "sha20");
+        assert_eq!(pane.title(), "Commit History");
+
+        // With the full history loaded, navigation wraps as before
+        for _ in 0..5 {
+            pane.navigate_next();
+        }
+        assert_eq!(pane.current_index, 0);
+        assert_eq!(pane.take_load_more_request(), None);
+    }
+
+    #[test]
+    fn test_commit_picker_loading_progress() {
+        let mut pane = CommitPickerPane::new();
+        pane.set_loading();
+        assert!(pane.loading_text().ends_with(" Loading commit history..."));
+
+        pane.set_progress(0, 500);
+        assert!(pane.loading_text().ends_with(" Loading commit history..."));
+        pane.set_progress(120, 500);
+        assert!(pane.loading_text().ends_with(" Loaded 120/500 commits..."));
+
+        // A new load starts counting from scratch
+        pane.set_loading();
+        assert!(pane.loading_text().ends_with(" Loading commit history..."));
+    }
 }
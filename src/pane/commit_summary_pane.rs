@@ -1,4 +1,3 @@
-use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -6,9 +5,10 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
 };
+use std::cell::Cell;
 use std::sync::Arc;
 
-use super::{AppEvent, Pane};
+use super::{AppEvent, FileIcon, Pane, ScrollState};
 use crate::git::GitRepo;
 use crate::llm::LlmClient;
 use crate::shared_state::LlmSharedState;
@@ -23,7 +23,10 @@ pub enum CommitSummaryLoadingState {
 pub struct CommitSummaryPane {
     visible: bool,
     current_commit: Option<crate::git::CommitInfo>,
-    scroll_offset: usize,
+    scroll: ScrollState,
+    /// Rows of the file list as last rendered, so scrolling stops with the last file at the bottom;
+    /// zero until the list has been drawn
+    visible_height: Cell<usize>,
     llm_summary: Option<String>,
     llm_client: Option<LlmClient>,
     is_loading_summary: bool,
@@ -44,7 +47,8 @@ impl CommitSummaryPane {
         Self {
             visible: false,
             current_commit: None,
-            scroll_offset: 0,
+            scroll: ScrollState::default(),
+            visible_height: Cell::new(0),
             llm_summary: None,
             llm_client: None,
             is_loading_summary: false,
@@ -59,7 +63,8 @@ impl CommitSummaryPane {
         Self {
             visible: false,
             current_commit: None,
-            scroll_offset: 0,
+            scroll: ScrollState::default(),
+            visible_height: Cell::new(0),
             llm_summary: None,
             llm_client,
             is_loading_summary: false,
@@ -83,10 +88,9 @@ impl CommitSummaryPane {
         if commit_changed {
             // Reset state when commit changes
             self.llm_summary = None;
-            self.scroll_offset = 0;
+            self.scroll.offset = 0;
             self.is_loading_summary = false;
             self.pending_summary_sha = None;
-            self.clear_error();
             self.cache_callback = None;
 
             // Update loading state based on new commit
@@ -106,17 +110,26 @@ impl CommitSummaryPane {
     }
 
     pub fn clear_error(&mut self) {
-        if let Some(shared_state) = &self.llm_shared_state {
-            shared_state.clear_error("commit_summary");
+        if let (Some(shared_state), Some(commit)) = (&self.llm_shared_state, &self.current_commit) {
+            shared_state.clear_error(&crate::git::summary_error_key(&commit.sha));
         }
     }
 
-    fn request_llm_summary(&mut self) {
-        if let Some(_commit) = &self.current_commit {
-            self.loading_state = CommitSummaryLoadingState::Loaded;
-            self.llm_summary =
-                Some("LLM summary generation not yet implemented with shared state".to_string());
-        }
+    /// Why the current commit's summary failed, if it did
+    fn summary_error(&self) -> Option<String> {
+        let commit = self.current_commit.as_ref()?;
+        self.llm_shared_state
+            .as_ref()?
+            .get_error(&crate::git::summary_error_key(&commit.sha))
+    }
+
+    /// Whether the current commit's summary is being generated
+    fn is_generating_summary(&self) -> bool {
+        self.is_loading_summary
+            || matches!(
+                (&self.llm_shared_state, &self.current_commit),
+                (Some(shared_state), Some(commit)) if shared_state.is_summary_loading(&commit.sha)
+            )
     }
 
     /// Set a cached summary directly without generating a new one
@@ -142,11 +155,10 @@ impl CommitSummaryPane {
         }
     }
 
-    /// Force generation of a new summary (bypassing cache)
+    /// Drop the shown summary and any error, so the summary preloader generates it again
     pub fn force_generate_summary(&mut self) {
         self.llm_summary = None;
         self.clear_error();
-        self.request_llm_summary();
     }
 
     /// Get and clear any pending cache callback
@@ -216,12 +228,13 @@ impl Pane for CommitSummaryPane {
                     Style::default().fg(theme.foreground_color()),
                 )])));
             } else {
+                let visible_height = chunks[0].height.saturating_sub(2) as usize; // Account for borders
+                self.visible_height.set(visible_height);
                 for (index, file_change) in commit.files_changed.iter().enumerate() {
-                    if index < self.scroll_offset {
+                    if index < self.scroll.offset {
                         continue;
                     }
 
-                    let visible_height = chunks[0].height.saturating_sub(2) as usize; // Account for borders
                     if file_items.len() >= visible_height {
                         break;
                     }
@@ -229,20 +242,21 @@ impl Pane for CommitSummaryPane {
                     let mut spans = Vec::new();
 
                     // Status indicator with validation
-                    let status_char = match file_change.status {
-                        crate::git::FileChangeStatus::Added => "📄 ",
-                        crate::git::FileChangeStatus::Modified => "📝 ",
-                        crate::git::FileChangeStatus::Deleted => "🗑️  ",
-                        crate::git::FileChangeStatus::Renamed => "📋 ",
-                    };
+                    let status_char =
+                        FileIcon::for_change(&file_change.status).render(app.get_icons());
                     spans.push(Span::raw(status_char));
 
-                    // File path with length validation
-                    let file_path_str = file_change.path.to_string_lossy();
+                    // File path with length validation; renames show as `old -> new`
+                    let file_path_str = match &file_change.old_path {
+                        Some(old_path) => {
+                            format!("{} -> {}", old_path.display(), file_change.path.display())
+                        }
+                        None => file_change.path.to_string_lossy().to_string(),
+                    };
                     let display_path = if file_path_str.len() > 80 {
                         format!("...{}", &file_path_str[file_path_str.len() - 77..])
                     } else {
-                        file_path_str.to_string()
+                        file_path_str
                     };
 
                     spans.push(Span::styled(
@@ -293,21 +307,33 @@ impl Pane for CommitSummaryPane {
             f.render_widget(file_list, chunks[0]);
 
             // Render LLM summary section with enhanced error handling and loading states
-            let summary_content = if let Some(summary) = &self.llm_summary {
-                summary.clone()
-            } else if self.is_loading_summary {
-                "⏳ Generating summary...".to_string()
+            let summary_lines: Vec<Line> = if let Some(summary) = &self.llm_summary {
+                summary
+                    .lines()
+                    .map(|line| Line::from(line.to_string()))
+                    .collect()
+            } else if self.is_generating_summary() {
+                vec![Line::from(format!(
+                    "{} Generating summary...",
+                    app.spinner_frame()
+                ))]
+            } else if let Some(error) = self.summary_error() {
+                vec![
+                    Line::from(Span::styled(
+                        format!("❌ {error}"),
+                        Style::default().fg(theme.error_color()),
+                    )),
+                    Line::from(Span::styled(
+                        "Press r to retry",
+                        Style::default().fg(theme.secondary_color()),
+                    )),
+                ]
             } else if self.llm_client.is_none() {
-                "LLM client not available".to_string()
+                vec![Line::from("LLM client not available")]
             } else {
-                "📋 Checking cache...".to_string()
+                vec![Line::from("📋 Checking cache...")]
             };
 
-            let summary_lines: Vec<Line> = summary_content
-                .lines()
-                .map(|line| Line::from(line.to_string()))
-                .collect();
-
             let summary_paragraph = Paragraph::new(summary_lines)
                 .block(
                     Block::default()
@@ -335,49 +361,18 @@ impl Pane for CommitSummaryPane {
     fn handle_event(&mut self, event: &AppEvent) -> bool {
         match event {
             AppEvent::Key(key) => {
-                match key.code {
-                    KeyCode::Char('j') | KeyCode::Down => {
-                        if let Some(commit) = &self.current_commit {
-                            let max_scroll = commit.files_changed.len().saturating_sub(1);
-                            self.scroll_offset =
-                                std::cmp::min(self.scroll_offset.saturating_add(1), max_scroll);
-                        }
-                        true
-                    }
-                    KeyCode::Char('k') | KeyCode::Up => {
-                        self.scroll_offset = self.scroll_offset.saturating_sub(1);
-                        true
-                    }
-                    KeyCode::PageDown => {
-                        if let Some(commit) = &self.current_commit {
-                            let page_size = 10; // Approximate page size
-                            let max_scroll = commit.files_changed.len().saturating_sub(page_size);
-                            self.scroll_offset = std::cmp::min(
-                                self.scroll_offset.saturating_add(page_size),
-                                max_scroll,
-                            );
-                        }
-                        true
-                    }
-                    KeyCode::PageUp => {
-                        let page_size = 10; // Approximate page size
-                        self.scroll_offset = self.scroll_offset.saturating_sub(page_size);
-                        true
-                    }
-                    KeyCode::Char('g') => {
-                        // Go to top
-                        self.scroll_offset = 0;
-                        true
-                    }
-                    KeyCode::Char('G') if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                        // Go to bottom
-                        if let Some(commit) = &self.current_commit {
-                            self.scroll_offset = commit.files_changed.len().saturating_sub(1);
-                        }
-                        true
-                    }
-                    _ => false,
+                // Pages follow the list's rendered height; there is nothing to page before it is drawn
+                let page_size = self.visible_height.get();
+                if page_size == 0 {
+                    return false;
                 }
+                // Stop once the last file reaches the bottom of the list
+                let max_offset = self
+                    .current_commit
+                    .as_ref()
+                    .map(|commit| commit.files_changed.len().saturating_sub(page_size))
+                    .unwrap_or(0);
+                self.scroll.handle_key(key, max_offset, page_size)
             }
             _ => false,
         }
@@ -406,7 +401,7 @@ mod tests {
         let pane = CommitSummaryPane::new();
         assert!(!pane.visible());
         assert!(pane.current_commit.is_none());
-        assert_eq!(pane.scroll_offset, 0);
+        assert_eq!(pane.scroll.offset, 0);
         assert!(pane.llm_summary.is_none());
     }
 
@@ -418,8 +413,10 @@ mod tests {
             sha: "abc123".to_string(),
             short_sha: "abc123".to_string(),
             message: "Test commit".to_string(),
+            time: 0,
             files_changed: vec![crate::git::CommitFileChange {
                 path: std::path::PathBuf::from("test.rs"),
+                old_path: None,
                 status: crate::git::FileChangeStatus::Modified,
                 additions: 5,
                 deletions: 2,
@@ -429,10 +426,37 @@ mod tests {
         pane.update_commit(Some(commit.clone()));
         assert!(pane.current_commit.is_some());
         assert_eq!(pane.current_commit.as_ref().unwrap().sha, "abc123");
-        assert_eq!(pane.scroll_offset, 0);
+        assert_eq!(pane.scroll.offset, 0);
         assert!(pane.llm_summary.is_none());
     }
 
+    #[test]
+    fn test_commit_summary_error_cleared_on_retry() {
+        let shared_state = Arc::new(LlmSharedState::new());
+        let mut pane = CommitSummaryPane::new();
+        pane.set_shared_state(Arc::clone(&shared_state));
+        pane.update_commit(Some(crate::git::CommitInfo {
+            sha: "abc123".to_string(),
+            short_sha: "abc123".to_string(),
+            message: "Test commit".to_string(),
+            time: 0,
+            files_changed: vec![],
+        }));
+
+        shared_state.set_error(
+            crate::git::summary_error_key("abc123"),
+            "Failed to generate summary: rate limited".to_string(),
+        );
+        assert_eq!(
+            pane.summary_error().as_deref(),
+            Some("Failed to generate summary: rate limited")
+        );
+
+        pane.force_generate_summary();
+        assert!(pane.summary_error().is_none());
+        assert!(!shared_state.has_errors());
+    }
+
     #[test]
     fn test_commit_summary_pane_scrolling() {
         let mut pane = CommitSummaryPane::new();
@@ -441,9 +465,11 @@ mod tests {
             sha: "abc123".to_string(),
             short_sha: "abc123".to_string(),
             message: "Test commit".to_string(),
+            time: 0,
             files_changed: (0..20)
                 .map(|i| crate::git::CommitFileChange {
                     path: std::path::PathBuf::from(format!("file{}.rs", i)),
+                    old_path: None,
                     status: crate::git::FileChangeStatus::Modified,
                     additions: i,
                     deletions: i / 2,
@@ -453,35 +479,56 @@ mod tests {
 
         pane.update_commit(Some(commit));
 
-        // Test j key (scroll down)
+        // Nothing scrolls until the file list has been rendered
         let j_event = AppEvent::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        assert!(!pane.handle_event(&j_event));
+        assert_eq!(pane.scroll.offset, 0);
+        pane.visible_height.set(10);
+
+        // Test j key (scroll down)
         assert!(pane.handle_event(&j_event));
-        assert_eq!(pane.scroll_offset, 1);
+        assert_eq!(pane.scroll.offset, 1);
 
         // Test k key (scroll up)
         let k_event = AppEvent::Key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE));
         assert!(pane.handle_event(&k_event));
-        assert_eq!(pane.scroll_offset, 0);
+        assert_eq!(pane.scroll.offset, 0);
 
         // Test page down
         let page_down_event = AppEvent::Key(KeyEvent::from(KeyCode::PageDown));
         assert!(pane.handle_event(&page_down_event));
-        assert_eq!(pane.scroll_offset, 10);
+        assert_eq!(pane.scroll.offset, 10);
 
         // Test page up
         let page_up_event = AppEvent::Key(KeyEvent::from(KeyCode::PageUp));
         assert!(pane.handle_event(&page_up_event));
-        assert_eq!(pane.scroll_offset, 0);
+        assert_eq!(pane.scroll.offset, 0);
 
-        // Test go to bottom (Shift+G)
+        // Test go to bottom (Shift+G): the last ten files fill the list
         let bottom_event = AppEvent::Key(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT));
         assert!(pane.handle_event(&bottom_event));
-        assert_eq!(pane.scroll_offset, 19);
+        assert_eq!(pane.scroll.offset, 10);
+
+        // Scrolling further down stays put rather than scrolling into emptiness
+        assert!(pane.handle_event(&j_event));
+        assert!(pane.handle_event(&page_down_event));
+        assert_eq!(pane.scroll.offset, 10);
+
+        // Test half page up (u)
+        let half_up_event = AppEvent::Key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE));
+        assert!(pane.handle_event(&half_up_event));
+        assert_eq!(pane.scroll.offset, 5);
+
+        // A taller list, as last rendered, leaves less to scroll
+        pane.visible_height.set(16);
+        assert!(pane.handle_event(&bottom_event));
+        assert_eq!(pane.scroll.offset, 4);
 
-        // Test go to top (g)
+        // Test go to top (g g)
         let top_event = AppEvent::Key(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE));
         assert!(pane.handle_event(&top_event));
-        assert_eq!(pane.scroll_offset, 0);
+        assert!(pane.handle_event(&top_event));
+        assert_eq!(pane.scroll.offset, 0);
     }
 
     #[test]
@@ -504,7 +551,7 @@ mod tests {
 
         // Create a test LLM client
         let llm_config = LlmConfig {
-            api_key: Some("test_key".to_string()),
+            provider: Some(crate::config::LlmProvider::Mock),
             ..Default::default()
         };
         let llm_client = crate::llm::LlmClient::new(llm_config).ok();
@@ -532,15 +579,18 @@ mod tests {
             sha: "abc123".to_string(),
             short_sha: "abc123".to_string(),
             message: "Test commit".to_string(),
+            time: 0,
             files_changed: vec![
                 crate::git::CommitFileChange {
                     path: std::path::PathBuf::from("src/main.rs"),
+                    old_path: None,
                     status: crate::git::FileChangeStatus::Modified,
                     additions: 10,
                     deletions: 5,
                 },
                 crate::git::CommitFileChange {
                     path: std::path::PathBuf::from("src/lib.rs"),
+                    old_path: None,
                     status: crate::git::FileChangeStatus::Added,
                     additions: 20,
                     deletions: 0,
@@ -583,6 +633,7 @@ mod tests {
             sha: "abc123".to_string(),
             short_sha: "abc123".to_string(),
             message: "Test commit".to_string(),
+            time: 0,
             files_changed: vec![],
         };
 
@@ -0,0 +1,99 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::ui::{DiscardRequest, Theme};
+
+/// Something destructive that waits on a yes in the confirmation dialog
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmAction {
+    /// Throw away a file's working tree changes, or delete it when untracked
+    Discard(DiscardRequest),
+    /// Clear the advice chat and ask about the current diff again
+    ClearAdviceChat,
+}
+
+/// Modal yes/no question asked before a destructive action, which captures every key
+/// until it's answered
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfirmDialog {
+    pub title: String,
+    pub question: String,
+    /// What saying yes loses
+    pub warning: String,
+    /// Nothing can bring back what's lost, so the warning is in the error color
+    pub irreversible: bool,
+    pub action: ConfirmAction,
+}
+
+impl ConfirmDialog {
+    pub fn new(
+        title: impl Into<String>,
+        question: impl Into<String>,
+        warning: impl Into<String>,
+        action: ConfirmAction,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            question: question.into(),
+            warning: warning.into(),
+            irreversible: false,
+            action,
+        }
+    }
+
+    pub fn irreversible(mut self) -> Self {
+        self.irreversible = true;
+        self
+    }
+
+    /// `y` for yes, `n` or `Esc` for no, None for any other key, which leaves it open
+    pub fn answer(key: &KeyEvent) -> Option<bool> {
+        match key.code {
+            KeyCode::Char('y') => Some(true),
+            KeyCode::Char('n') | KeyCode::Esc => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Centered popup with the question, its warning and the keys that answer it
+    pub fn render(&self, f: &mut Frame, theme: &Theme) {
+        let text = vec![
+            Line::from(Span::styled(
+                self.question.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(
+                self.warning.clone(),
+                Style::default().fg(if self.irreversible {
+                    theme.error_color()
+                } else {
+                    theme.secondary_color()
+                }),
+            )),
+            Line::from(""),
+            Line::from("y: yes   n / Esc: no"),
+        ];
+
+        let popup = super::centered_area(f.area(), 70, 6);
+        let paragraph = Paragraph::new(text)
+            .style(
+                Style::default()
+                    .fg(theme.foreground_color())
+                    .bg(theme.background_color()),
+            )
+            .block(
+                Block::default()
+                    .title(self.title.clone())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.error_color())),
+            )
+            .wrap(Wrap { trim: true });
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
+}
@@ -2,19 +2,155 @@ use crossterm::event::KeyCode;
 use ratatui::{
     Frame,
     layout::Rect,
-    style::Style,
+    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
 use super::{AppEvent, Pane};
-use crate::git::GitRepo;
-use crate::ui::App;
+use crate::git::{GitRepo, STAGED_SECTION_LABEL, UNSTAGED_SECTION_LABEL};
+use crate::ui::{ActivePane, App, Theme, blend_colors};
 
 pub struct DiffPane {
     visible: bool,
 }
 
+/// A row of the inline diff: a line of `line_strings`, or unchanged lines folded away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffRow {
+    Line(usize),
+    Fold(usize),
+}
+
+/// Where a `@@ -start,count +start,count @@` hunk header's old side starts, and its length
+pub fn parse_hunk_header(line: &str) -> Option<(usize, usize)> {
+    let old = line.strip_prefix("@@ -")?.split_whitespace().next()?;
+    let (start, count) = old.split_once(',').unwrap_or((old, "1"));
+    Some((start.parse().ok()?, count.parse().ok()?))
+}
+
+/// Rows for `lines` with every run of unchanged lines folded into one marker, counting the
+/// context lines and the lines between hunks; headers, hunk headers and changes stay
+pub fn fold_unchanged_lines(lines: &[String]) -> Vec<DiffRow> {
+    let mut rows = Vec::new();
+    let mut unchanged = 0;
+    // Line after the last hunk on the old side, so the gap before the next can be counted
+    let mut next_old_line = 1;
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with(' ') {
+            unchanged += 1;
+            continue;
+        }
+        // Each file's hunks count from the top again, as in the staged and unstaged view
+        if line.starts_with("diff --git ") {
+            next_old_line = 1;
+        }
+        if let Some((start, count)) = parse_hunk_header(line) {
+            unchanged += start.saturating_sub(next_old_line);
+            next_old_line = start + count;
+        }
+        if unchanged > 0 {
+            rows.push(DiffRow::Fold(unchanged));
+            unchanged = 0;
+        }
+        rows.push(DiffRow::Line(i));
+    }
+    if unchanged > 0 {
+        rows.push(DiffRow::Fold(unchanged));
+    }
+    rows
+}
+
+/// How far an added or removed line's background moves toward its own color in the
+/// hottest hunk, so even there the text stays readable
+const HEATMAP_MAX_BLEND: f32 = 0.35;
+
+/// Change density of the hunk each line belongs to: the share of the hunk's lines that are
+/// added or removed, from 0.0 to 1.0, and 0.0 for lines outside any hunk
+pub fn hunk_change_density(lines: &[String]) -> Vec<f32> {
+    let mut density = vec![0.0; lines.len()];
+    let mut hunk_start = None;
+
+    let close_hunk = |start: usize, end: usize, density: &mut [f32]| {
+        let body = &lines[start + 1..end];
+        if body.is_empty() {
+            return;
+        }
+        let changed = body
+            .iter()
+            .filter(|line| line.starts_with('+') || line.starts_with('-'))
+            .count();
+        let hunk_density = changed as f32 / body.len() as f32;
+        density[start + 1..end].fill(hunk_density);
+    };
+
+    for (i, line) in lines.iter().enumerate() {
+        let is_hunk_header = parse_hunk_header(line).is_some();
+        let ends_hunk = is_hunk_header
+            || line.starts_with("diff --git ")
+            || line == STAGED_SECTION_LABEL
+            || line == UNSTAGED_SECTION_LABEL;
+        if ends_hunk && let Some(start) = hunk_start.take() {
+            close_hunk(start, i, &mut density);
+        }
+        if is_hunk_header {
+            hunk_start = Some(i);
+        }
+    }
+    if let Some(start) = hunk_start {
+        close_hunk(start, lines.len(), &mut density);
+    }
+    density
+}
+
+/// Changes in the stretch of the diff a minimap cell stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimapMark {
+    Unchanged,
+    Added,
+    Removed,
+    Both,
+}
+
+/// One minimap cell for each of `height` rows, shrinking `rows` of the diff proportionally:
+/// what changes its stretch holds, and whether any of it is in the viewport of
+/// `viewport_height` rows from `offset`
+pub fn minimap_cells(
+    lines: &[String],
+    rows: &[DiffRow],
+    height: usize,
+    offset: usize,
+    viewport_height: usize,
+) -> Vec<(MinimapMark, bool)> {
+    (0..height)
+        .map(|cell| {
+            let start = cell * rows.len() / height;
+            let end = ((cell + 1) * rows.len() / height)
+                .max(start + 1)
+                .min(rows.len());
+            if start >= end {
+                return (MinimapMark::Unchanged, false);
+            }
+
+            let (mut added, mut removed) = (false, false);
+            for row in &rows[start..end] {
+                if let DiffRow::Line(i) = row {
+                    added |= lines[*i].starts_with('+');
+                    removed |= lines[*i].starts_with('-');
+                }
+            }
+            let mark = match (added, removed) {
+                (false, false) => MinimapMark::Unchanged,
+                (true, false) => MinimapMark::Added,
+                (false, true) => MinimapMark::Removed,
+                (true, true) => MinimapMark::Both,
+            };
+            (mark, start < offset + viewport_height && offset < end)
+        })
+        .collect()
+}
+
 impl Default for DiffPane {
     fn default() -> Self {
         Self::new()
@@ -25,6 +161,52 @@ impl DiffPane {
     pub fn new() -> Self {
         Self { visible: true }
     }
+
+    /// One-column overview of the whole diff down `area`, marking where lines were added
+    /// and removed, with the part on screen shown reversed
+    fn render_minimap(f: &mut Frame, app: &App, area: Rect, lines: &[String], rows: &[DiffRow]) {
+        let theme = app.get_theme();
+        let cells = minimap_cells(
+            lines,
+            rows,
+            area.height as usize,
+            app.get_scroll_offset(),
+            app.current_diff_height,
+        );
+        let minimap: Vec<Line> = cells
+            .into_iter()
+            .map(|(mark, in_view)| {
+                let (symbol, color) = match mark {
+                    MinimapMark::Unchanged if in_view => (" ", theme.border_color()),
+                    MinimapMark::Unchanged => ("│", theme.border_color()),
+                    MinimapMark::Added => ("▐", theme.added_color()),
+                    MinimapMark::Removed => ("▐", theme.removed_color()),
+                    MinimapMark::Both => ("▐", theme.secondary_color()),
+                };
+                let style = Style::default().fg(color);
+                let style = if in_view {
+                    style.add_modifier(Modifier::REVERSED)
+                } else {
+                    style
+                };
+                Line::from(Span::styled(symbol, style))
+            })
+            .collect();
+        f.render_widget(Paragraph::new(minimap), area);
+    }
+}
+
+/// Style for an added or removed line, with a background warmed by its hunk's change
+/// density when the heatmap is on and the theme's colors can be blended
+pub fn heatmap_style(color: Color, theme: &Theme, heat: &Option<Vec<f32>>, i: usize) -> Style {
+    let style = Style::default().fg(color);
+    let Some(density) = heat.as_ref().and_then(|heat| heat.get(i)) else {
+        return style;
+    };
+    match blend_colors(theme.background_color(), color, density * HEATMAP_MAX_BLEND) {
+        Some(background) => style.bg(background),
+        None => style,
+    }
 }
 
 impl Pane for DiffPane {
@@ -41,51 +223,113 @@ impl Pane for DiffPane {
     ) -> Result<(), Box<dyn std::error::Error>> {
         let theme = app.get_theme();
         if let Some(file) = app.get_current_file() {
-            let file_path = file.path.to_string_lossy();
-            let title = format!("Diff: {file_path}");
+            let mut file_path = file.path.to_string_lossy().to_string();
+            if let Some(stats) = app.current_semantic_stats() {
+                file_path.push_str(&format!(" ({} touched)", stats.describe()));
+            }
+            let selection = app.diff_selection_range();
+            let title = if selection.is_some() {
+                format!("Diff: {file_path} -- VISUAL LINE -- (e explain, Esc cancel)")
+            } else if app.is_diff_folded() {
+                format!("Diff: {file_path} (unchanged lines folded, z to unfold)")
+            } else {
+                format!("Diff: {file_path}")
+            };
 
             let mut lines = Vec::new();
+            let heat = app
+                .is_diff_heatmap_enabled()
+                .then(|| hunk_change_density(&file.line_strings));
+            let rows = app.diff_rows();
 
-            for (i, line) in file.line_strings.iter().enumerate() {
-                if i < app.get_scroll_offset() {
-                    continue;
-                }
+            for row in rows
+                .iter()
+                .copied()
+                .skip(app.get_scroll_offset())
+                .take(app.current_diff_height)
+            {
+                let i = match row {
+                    DiffRow::Line(i) => i,
+                    DiffRow::Fold(count) => {
+                        let noun = if count == 1 { "line" } else { "lines" };
+                        lines.push(Line::from(Span::styled(
+                            format!("… {count} unchanged {noun} …"),
+                            Style::default()
+                                .fg(theme.unchanged_color())
+                                .add_modifier(Modifier::ITALIC),
+                        )));
+                        continue;
+                    }
+                };
+                let line = &file.line_strings[i];
 
-                if lines.len() >= app.current_diff_height {
-                    break;
+                let (mut style, line_text) =
+                    if line == STAGED_SECTION_LABEL || line == UNSTAGED_SECTION_LABEL {
+                        (
+                            Style::default()
+                                .fg(theme.primary_color())
+                                .add_modifier(Modifier::BOLD),
+                            line,
+                        )
+                    } else if line.starts_with('+') {
+                        (heatmap_style(theme.added_color(), theme, &heat, i), line)
+                    } else if line.starts_with('-') {
+                        (heatmap_style(theme.removed_color(), theme, &heat, i), line)
+                    } else if line.starts_with(' ') {
+                        (Style::default().fg(theme.unchanged_color()), line)
+                    } else {
+                        (Style::default().fg(theme.foreground_color()), line)
+                    };
+                if selection.is_some_and(|(start, end)| (start..=end).contains(&i)) {
+                    style = style.add_modifier(Modifier::REVERSED);
                 }
 
-                let (style, line_text) = if line.starts_with('+') {
-                    (Style::default().fg(theme.added_color()), line)
-                } else if line.starts_with('-') {
-                    (Style::default().fg(theme.removed_color()), line)
-                } else if line.starts_with(' ') {
-                    (Style::default().fg(theme.unchanged_color()), line)
+                // Themes that don't rely on color alone get a gutter marker and text style
+                let span = if theme.uses_diff_markers() {
+                    if let Some(rest) = line.strip_prefix('+') {
+                        Span::styled(format!("▌+{rest}"), style.add_modifier(Modifier::BOLD))
+                    } else if let Some(rest) = line.strip_prefix('-') {
+                        Span::styled(
+                            format!("▌-{rest}"),
+                            style.add_modifier(Modifier::UNDERLINED),
+                        )
+                    } else {
+                        Span::styled(format!(" {line_text}"), style)
+                    }
                 } else {
-                    (Style::default().fg(theme.foreground_color()), line)
+                    Span::styled(line_text.clone(), style)
                 };
-
-                let span = Span::styled(line_text.clone(), style);
                 lines.push(Line::from(span));
             }
 
-            let text = ratatui::text::Text::from(lines);
-            let paragraph = Paragraph::new(text)
-                .block(
-                    Block::default()
-                        .title(title)
-                        .borders(Borders::ALL)
-                        .border_style(Style::default().fg(theme.border_color())),
-                )
-                .wrap(Wrap { trim: false });
+            let block = Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(app.pane_border_style(ActivePane::Diff));
+            let inner = block.inner(area);
+            f.render_widget(block, area);
 
-            f.render_widget(paragraph, area);
+            // The minimap only earns its column once the diff doesn't fit on screen
+            let mut text_area = inner;
+            if rows.len() > app.current_diff_height && inner.width > 1 {
+                text_area.width -= 1;
+                let minimap_area = Rect {
+                    x: inner.right() - 1,
+                    width: 1,
+                    ..inner
+                };
+                Self::render_minimap(f, app, minimap_area, &file.line_strings, &rows);
+            }
+
+            let text = ratatui::text::Text::from(lines);
+            let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
+            f.render_widget(paragraph, text_area);
         } else {
             let paragraph = Paragraph::new("No changes detected").block(
                 Block::default()
                     .title(self.title())
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(theme.border_color())),
+                    .border_style(app.pane_border_style(ActivePane::Diff)),
             );
             f.render_widget(paragraph, area);
         }
@@ -96,10 +340,7 @@ impl Pane for DiffPane {
         match event {
             AppEvent::Key(key) => matches!(
                 key.code,
-                KeyCode::Char('j')
-                    | KeyCode::Char('k')
-                    | KeyCode::Left
-                    | KeyCode::Right
+                KeyCode::Char('j') | KeyCode::Char('k') | KeyCode::Left | KeyCode::Right
             ),
             _ => false,
         }
@@ -113,3 +354,125 @@ impl Pane for DiffPane {
         self.visible = visible;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_hunk_header() {
+        assert_eq!(
+            parse_hunk_header("@@ -12,7 +12,8 @@ fn main() {"),
+            Some((12, 7))
+        );
+        assert_eq!(parse_hunk_header("@@ -3 +3 @@"), Some((3, 1)));
+        assert_eq!(parse_hunk_header("@@ -0,0 +1,2 @@"), Some((0, 0)));
+        assert_eq!(parse_hunk_header("+@@ -1 +1 @@"), None);
+    }
+
+    #[test]
+    fn test_fold_unchanged_lines() {
+        let diff = lines(&[
+            "diff --git a/lib.rs b/lib.rs",
+            "@@ -4,3 +4,3 @@",
+            " a",
+            " b",
+            "-c",
+            "+C",
+            "@@ -20,3 +20,3 @@",
+            " d",
+            "-e",
+            "+E",
+            " f",
+        ]);
+
+        // Three lines above the first hunk, and the thirteen between the hunks
+        assert_eq!(
+            fold_unchanged_lines(&diff),
+            [
+                DiffRow::Line(0),
+                DiffRow::Fold(3),
+                DiffRow::Line(1),
+                DiffRow::Fold(2),
+                DiffRow::Line(4),
+                DiffRow::Line(5),
+                DiffRow::Fold(13),
+                DiffRow::Line(6),
+                DiffRow::Fold(1),
+                DiffRow::Line(8),
+                DiffRow::Line(9),
+                DiffRow::Fold(1),
+            ]
+        );
+
+        // A second diff of the same file, as in the staged and unstaged view, counts
+        // the lines above its first hunk from the top again
+        let diff = lines(&[
+            "diff --git a/lib.rs b/lib.rs",
+            "@@ -20,1 +20,1 @@",
+            "-e",
+            "+E",
+            "diff --git a/lib.rs b/lib.rs",
+            "@@ -4,1 +4,1 @@",
+            "-c",
+            "+C",
+        ]);
+        assert_eq!(fold_unchanged_lines(&diff)[6], DiffRow::Fold(3));
+    }
+
+    #[test]
+    fn test_hunk_change_density() {
+        let diff = lines(&[
+            "diff --git a/lib.rs b/lib.rs",
+            "@@ -4,3 +4,3 @@",
+            " a",
+            " b",
+            " c",
+            "-d",
+            "@@ -20,2 +20,2 @@",
+            "-e",
+            "+E",
+            "diff --git a/main.rs b/main.rs",
+            "@@ -1,1 +1,1 @@",
+            " f",
+        ]);
+
+        assert_eq!(
+            hunk_change_density(&diff),
+            [
+                0.0, 0.0, 0.25, 0.25, 0.25, 0.25, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0
+            ]
+        );
+    }
+
+    #[test]
+    fn test_minimap_cells() {
+        let diff = lines(&["@@ -1,6 +1,6 @@", " a", "-b", "+B", " c", " d", " e", "+f"]);
+        let rows: Vec<DiffRow> = (0..diff.len()).map(DiffRow::Line).collect();
+
+        // Each of the four cells stands for two rows; the viewport covers rows 2 and 3
+        assert_eq!(
+            minimap_cells(&diff, &rows, 4, 2, 2),
+            [
+                (MinimapMark::Unchanged, false),
+                (MinimapMark::Both, true),
+                (MinimapMark::Unchanged, false),
+                (MinimapMark::Added, false),
+            ]
+        );
+
+        // A diff shorter than the minimap stretches, a row standing for more than one cell
+        assert_eq!(
+            minimap_cells(&diff[..2], &rows[..2], 3, 0, 1),
+            [
+                (MinimapMark::Unchanged, true),
+                (MinimapMark::Unchanged, true),
+                (MinimapMark::Unchanged, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use super::{AppEvent, Pane};
+use crate::git::{FileDiff, GitRepo};
+use crate::semantic_stats::SemanticStats;
+use crate::ui::App;
+
+/// Hunk and line totals for one file or a whole change set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    pub hunks: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+impl DiffStats {
+    pub fn from_file(file: &FileDiff) -> Self {
+        Self {
+            hunks: file.hunk_count(),
+            additions: file.additions,
+            deletions: file.deletions,
+        }
+    }
+
+    pub fn total(files: &[FileDiff]) -> Self {
+        files.iter().fold(Self::default(), |total, file| {
+            let stats = Self::from_file(file);
+            Self {
+                hunks: total.hunks + stats.hunks,
+                additions: total.additions + stats.additions,
+                deletions: total.deletions + stats.deletions,
+            }
+        })
+    }
+}
+
+/// Transient popup with the size of the current file and of every changed file
+pub struct DiffStatsPane {
+    visible: bool,
+    current_file: Option<(String, DiffStats)>,
+    /// Functions the current file's diff touches, when it's been parsed
+    current_semantic_stats: Option<SemanticStats>,
+    file_count: usize,
+    total: DiffStats,
+}
+
+impl Default for DiffStatsPane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiffStatsPane {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            current_file: None,
+            current_semantic_stats: None,
+            file_count: 0,
+            total: DiffStats::default(),
+        }
+    }
+
+    /// Snapshot the stats; the popup does not follow later refreshes
+    pub fn set_stats(&mut self, current_file: Option<&FileDiff>, files: &[FileDiff]) {
+        self.current_file =
+            current_file.map(|file| (file.path.display().to_string(), DiffStats::from_file(file)));
+        self.file_count = files.len();
+        self.total = DiffStats::total(files);
+        self.current_semantic_stats = None;
+    }
+
+    pub fn set_semantic_stats(&mut self, stats: Option<SemanticStats>) {
+        self.current_semantic_stats = stats;
+    }
+
+    fn stats_lines(stats: &DiffStats) -> [Line<'static>; 3] {
+        [
+            Line::from(format!("  Hunks:   {}", stats.hunks)),
+            Line::from(format!("  Added:   +{}", stats.additions)),
+            Line::from(format!("  Removed: -{}", stats.deletions)),
+        ]
+    }
+}
+
+impl Pane for DiffStatsPane {
+    fn title(&self) -> String {
+        "Diff Stats (any key to close)".to_string()
+    }
+
+    fn render(
+        &self,
+        f: &mut Frame,
+        app: &App,
+        area: Rect,
+        _git_repo: &GitRepo,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = app.get_theme();
+        let popup = super::centered_area(area, 50, 12);
+        let heading_style = Style::default()
+            .fg(theme.secondary_color())
+            .add_modifier(Modifier::BOLD);
+
+        let mut text = Vec::new();
+        match &self.current_file {
+            Some((path, stats)) => {
+                text.push(Line::from(Span::styled(path.clone(), heading_style)));
+                text.extend(Self::stats_lines(stats));
+                if let Some(semantic) = &self.current_semantic_stats {
+                    text.push(Line::from(format!(
+                        "  Functions: {}",
+                        semantic.functions_touched
+                    )));
+                }
+            }
+            None => text.push(Line::from(Span::styled("No file selected", heading_style))),
+        }
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            format!("All changes ({} files)", self.file_count),
+            heading_style,
+        )));
+        text.extend(Self::stats_lines(&self.total));
+
+        let paragraph = Paragraph::new(text)
+            .style(
+                Style::default()
+                    .fg(theme.foreground_color())
+                    .bg(theme.background_color()),
+            )
+            .block(
+                Block::default()
+                    .title(self.title())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary_color())),
+            );
+
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &AppEvent) -> bool {
+        match event {
+            AppEvent::Key(_) => {
+                self.set_visible(false);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn as_diff_stats_pane_mut(&mut self) -> Option<&mut DiffStatsPane> {
+        Some(self)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn test_diff_stats_totals() {
+        let files = vec![
+            FileDiff::for_test("a.rs", &["@@ -1 +1 @@", "-a", "+b", "@@ -9 +9 @@", "+c"])
+                .with_counts(2, 1),
+            FileDiff::for_test("b.rs", &["@@ -0,0 +1 @@", "+new"]).with_counts(1, 0),
+        ];
+
+        let mut pane = DiffStatsPane::new();
+        pane.set_stats(files.first(), &files);
+        assert_eq!(
+            pane.current_file,
+            Some((
+                "a.rs".to_string(),
+                DiffStats {
+                    hunks: 2,
+                    additions: 2,
+                    deletions: 1
+                }
+            ))
+        );
+        assert_eq!(pane.file_count, 2);
+        assert_eq!(
+            pane.total,
+            DiffStats {
+                hunks: 3,
+                additions: 3,
+                deletions: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_any_key_dismisses_diff_stats() {
+        let mut pane = DiffStatsPane::new();
+        pane.set_visible(true);
+
+        let key = AppEvent::Key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        assert!(pane.handle_event(&key));
+        assert!(!pane.visible());
+    }
+}
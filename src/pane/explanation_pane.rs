@@ -0,0 +1,226 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+use tokio::sync::oneshot;
+
+use super::{AppEvent, Pane, PaneKeyUtils};
+use crate::git::GitRepo;
+use crate::llm::LlmClient;
+use crate::ui::App;
+
+/// What the explanation popup is showing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExplanationContent {
+    Loading,
+    Reply(String),
+    Error(String),
+}
+
+/// Popup with the LLM's explanation of lines selected in the diff
+pub struct ExplanationPane {
+    visible: bool,
+    /// None when no LLM is configured, so explaining only says how to set one up
+    llm_client: Option<LlmClient>,
+    /// File and line range the explanation is about, shown in the title
+    location: String,
+    content: ExplanationContent,
+    pending_reply: Option<oneshot::Receiver<Result<String, String>>>,
+    scroll_offset: usize,
+}
+
+impl ExplanationPane {
+    pub fn new(llm_client: Option<LlmClient>) -> Self {
+        Self {
+            visible: false,
+            llm_client,
+            location: String::new(),
+            content: ExplanationContent::Loading,
+            pending_reply: None,
+            scroll_offset: 0,
+        }
+    }
+
+    /// Ask the LLM to explain `lines`, showing the popup while it thinks
+    /// Any explanation still in flight is dropped
+    pub fn explain(&mut self, file_path: String, location: String, lines: Vec<String>) {
+        self.location = location;
+        self.scroll_offset = 0;
+        self.visible = true;
+        let Some(client) = self.llm_client.clone() else {
+            self.content = ExplanationContent::Error(crate::llm::NO_LLM_HINT.to_string());
+            self.pending_reply = None;
+            return;
+        };
+
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let result = client.explain_diff_lines(file_path, lines).await;
+            // The popup may have been closed, in which case nobody is waiting
+            let _ = sender.send(result);
+        });
+        self.content = ExplanationContent::Loading;
+        self.pending_reply = Some(receiver);
+    }
+
+    /// Pick up the reply once it arrives
+    pub fn check_pending_reply(&mut self) {
+        let Some(receiver) = self.pending_reply.as_mut() else {
+            return;
+        };
+        self.content = match receiver.try_recv() {
+            Ok(Ok(reply)) => ExplanationContent::Reply(reply),
+            Ok(Err(error)) => ExplanationContent::Error(error),
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                ExplanationContent::Error("The explanation request was dropped".to_string())
+            }
+        };
+        self.pending_reply = None;
+    }
+
+    fn content_line_count(&self) -> usize {
+        match &self.content {
+            ExplanationContent::Reply(reply) => reply.lines().count(),
+            _ => 1,
+        }
+    }
+}
+
+impl Pane for ExplanationPane {
+    fn title(&self) -> String {
+        format!("Explain {} (Esc to close)", self.location)
+    }
+
+    fn render(
+        &self,
+        f: &mut Frame,
+        app: &App,
+        area: Rect,
+        _git_repo: &GitRepo,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = app.get_theme();
+        let popup = super::large_popup_area(area);
+
+        let text: Vec<Line> = match &self.content {
+            ExplanationContent::Loading => vec![Line::from(Span::styled(
+                format!("{} Thinking...", app.spinner_frame()),
+                Style::default()
+                    .fg(theme.secondary_color())
+                    .add_modifier(Modifier::ITALIC),
+            ))],
+            ExplanationContent::Reply(reply) => reply
+                .lines()
+                .map(|line| Line::from(line.to_string()))
+                .collect(),
+            ExplanationContent::Error(error) => vec![Line::from(Span::styled(
+                format!("❌ {error}"),
+                Style::default().fg(theme.error_color()),
+            ))],
+        };
+
+        // Don't scroll past the last line of the reply
+        let visible_height = popup.height.saturating_sub(2) as usize;
+        let max_scroll = text.len().saturating_sub(visible_height);
+        let scroll = self.scroll_offset.min(max_scroll) as u16;
+
+        let paragraph = Paragraph::new(text)
+            .style(
+                Style::default()
+                    .fg(theme.foreground_color())
+                    .bg(theme.background_color()),
+            )
+            .block(
+                Block::default()
+                    .title(self.title())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary_color())),
+            )
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0));
+
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &AppEvent) -> bool {
+        match event {
+            AppEvent::Key(key) => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.pending_reply = None;
+                    self.set_visible(false);
+                    true
+                }
+                _ => {
+                    let line_count = self.content_line_count();
+                    PaneKeyUtils::handle_scroll_keys(&mut self.scroll_offset, key, line_count);
+                    self.scroll_offset = self.scroll_offset.min(line_count.saturating_sub(1));
+                    true
+                }
+            },
+            _ => false,
+        }
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn as_explanation_pane_mut(&mut self) -> Option<&mut ExplanationPane> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmBackend;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_explanation_reply_and_dismissal() {
+        let backend = MockLlmBackend::new(
+            vec![Ok("It renames a variable".to_string())],
+            Duration::ZERO,
+        );
+        let client =
+            LlmClient::with_backend(crate::config::LlmConfig::default(), Arc::new(backend));
+        let mut pane = ExplanationPane::new(Some(client));
+
+        pane.explain(
+            "src/lib.rs".to_string(),
+            "src/lib.rs lines 3-4".to_string(),
+            vec!["-let a = 1;".to_string(), "+let b = 1;".to_string()],
+        );
+        assert!(pane.visible());
+        assert_eq!(pane.content, ExplanationContent::Loading);
+        assert_eq!(pane.title(), "Explain src/lib.rs lines 3-4 (Esc to close)");
+
+        for _ in 0..100 {
+            pane.check_pending_reply();
+            if pane.pending_reply.is_none() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(
+            pane.content,
+            ExplanationContent::Reply("It renames a variable".to_string())
+        );
+
+        let esc = AppEvent::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(pane.handle_event(&esc));
+        assert!(!pane.visible());
+    }
+}
@@ -7,9 +7,62 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem},
 };
 
-use super::{AppEvent, Pane};
+use super::{AppEvent, FileIcon, Pane};
 use crate::git::GitRepo;
-use crate::ui::App;
+use crate::ui::{ActivePane, App, RecentChange, Theme, TreeDisplayNode};
+
+/// Cells in the churn bar drawn after each file
+const CHURN_BAR_WIDTH: usize = 6;
+
+/// Largest additions plus deletions among the files in the tree, which fills a whole bar
+pub fn max_churn(tree_nodes: &[(TreeDisplayNode, usize)]) -> usize {
+    tree_nodes
+        .iter()
+        .filter(|(node, _)| !node.is_dir)
+        .map(|(node, _)| node.additions + node.deletions)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Added and removed cells of a churn bar, scaled so `max_churn` fills all of it
+/// Any change gets at least one cell so small edits still show up
+fn churn_bar_cells(additions: usize, deletions: usize, max_churn: usize) -> (usize, usize) {
+    let churn = additions + deletions;
+    if churn == 0 || max_churn == 0 {
+        return (0, 0);
+    }
+    let filled = (churn * CHURN_BAR_WIDTH)
+        .div_ceil(max_churn)
+        .clamp(1, CHURN_BAR_WIDTH);
+    let added = (additions * filled + churn / 2) / churn;
+    (added, filled - added)
+}
+
+/// Bar like ` ████░░`, green for additions and red for deletions, showing how much a file
+/// changed next to the largest change in view
+pub fn churn_bar_spans(
+    additions: usize,
+    deletions: usize,
+    max_churn: usize,
+    theme: &Theme,
+) -> Vec<Span<'static>> {
+    let (added, removed) = churn_bar_cells(additions, deletions, max_churn);
+    if added + removed == 0 {
+        return Vec::new();
+    }
+    vec![
+        Span::raw(" "),
+        Span::styled("█".repeat(added), Style::default().fg(theme.added_color())),
+        Span::styled(
+            "█".repeat(removed),
+            Style::default().fg(theme.removed_color()),
+        ),
+        Span::styled(
+            "░".repeat(CHURN_BAR_WIDTH - added - removed),
+            Style::default().fg(theme.unchanged_color()),
+        ),
+    ]
+}
 
 pub struct FileTreePane {
     visible: bool,
@@ -44,6 +97,7 @@ impl Pane for FileTreePane {
         _git_repo: &GitRepo,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let theme = app.get_theme();
+        let max_churn = max_churn(app.get_tree_nodes());
         let tree_items: Vec<ListItem> = app
             .get_tree_nodes()
             .iter()
@@ -51,7 +105,39 @@ impl Pane for FileTreePane {
             .map(|(index, (node, depth))| {
                 let indent = "  ".repeat(*depth);
                 let name_spans = if node.is_dir {
-                    vec![Span::raw(format!("{}📁 {}", indent, node.name))]
+                    if let Some(count) = node.untracked_file_count {
+                        let mut spans = Vec::new();
+
+                        // Collapsed untracked directories are selectable in place of their files
+                        if index == app.get_current_tree_index() {
+                            spans.push(Span::styled(
+                                "-> ",
+                                Style::default()
+                                    .fg(theme.secondary_color())
+                                    .add_modifier(Modifier::BOLD),
+                            ));
+                        } else if node.collapsed {
+                            spans.push(Span::raw("   "));
+                        }
+
+                        spans.push(Span::raw(format!(
+                            "{indent}{}{}/",
+                            FileIcon::Directory.render(app.get_icons()),
+                            node.name
+                        )));
+                        let noun = if count == 1 { "file" } else { "files" };
+                        spans.push(Span::styled(
+                            format!(" ({count} new {noun})"),
+                            Style::default().fg(theme.added_color()),
+                        ));
+                        spans
+                    } else {
+                        vec![Span::raw(format!(
+                            "{indent}{}{}",
+                            FileIcon::Directory.render(app.get_icons()),
+                            node.name
+                        ))]
+                    }
                 } else {
                     let mut spans = Vec::new();
 
@@ -67,19 +153,8 @@ impl Pane for FileTreePane {
                         spans.push(Span::raw("   "));
                     }
 
-                    let status_char = if let Some(status) = node.status {
-                        if status.is_wt_new() {
-                            "📄 "
-                        } else if status.is_wt_modified() {
-                            "📝 "
-                        } else if status.is_wt_deleted() {
-                            "🗑️  "
-                        } else {
-                            "📄 "
-                        }
-                    } else {
-                        "📄 "
-                    };
+                    let status_char =
+                        FileIcon::for_status(node.status, node.submodule).render(app.get_icons());
 
                     spans.push(Span::raw(format!("{indent}{status_char}")));
                     spans.push(Span::raw(node.name.clone()));
@@ -100,6 +175,25 @@ impl Pane for FileTreePane {
                                 .add_modifier(Modifier::BOLD),
                         ));
                     }
+                    spans.extend(churn_bar_spans(
+                        node.additions,
+                        node.deletions,
+                        max_churn,
+                        theme,
+                    ));
+
+                    // Say when a highlighted file changed, for as long as it stays highlighted
+                    if let Some(age) = app
+                        .get_files()
+                        .iter()
+                        .position(|f| f.path == node.path)
+                        .and_then(|file_idx| app.get_recent_change_age(file_idx))
+                    {
+                        spans.push(Span::raw(format!(
+                            " · {} ago",
+                            crate::util::humanize_duration(age)
+                        )));
+                    }
 
                     spans
                 };
@@ -108,17 +202,22 @@ impl Pane for FileTreePane {
                     // Check if this file is recently changed by finding its index
                     if let Some(file_idx) = app.get_files().iter().position(|f| f.path == node.path)
                     {
-                        if file_idx < app.get_file_change_timestamps().len()
-                            && app.is_file_recently_changed(file_idx)
-                        {
-                            // Recently changed - highlight
-                            Style::default()
-                                .fg(theme.foreground_color())
-                                .bg(theme.highlight_color())
-                                .add_modifier(Modifier::BOLD)
-                        } else {
-                            // Not recently changed - normal
-                            Style::default().fg(theme.foreground_color())
+                        match app.get_recent_change(file_idx) {
+                            Some(RecentChange::Fresh) => {
+                                // Recently changed - highlight
+                                Style::default()
+                                    .fg(theme.foreground_color())
+                                    .bg(theme.highlight_color())
+                                    .add_modifier(Modifier::BOLD)
+                            }
+                            Some(RecentChange::Fading) => {
+                                // Highlight about to expire - dimmer style before returning to normal
+                                Style::default().fg(theme.highlight_color())
+                            }
+                            None => {
+                                // Not recently changed - normal
+                                Style::default().fg(theme.foreground_color())
+                            }
                         }
                     } else {
                         // File not found in files list - normal
@@ -141,7 +240,7 @@ impl Pane for FileTreePane {
                 Block::default()
                     .title(self.title())
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(theme.border_color())),
+                    .border_style(app.pane_border_style(ActivePane::FileTree)),
             )
             .highlight_style(
                 Style::default()
@@ -181,3 +280,24 @@ impl Pane for FileTreePane {
         self.visible = visible;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_churn_bar_scales_to_largest_file() {
+        // The largest file fills the bar, split by its add/remove ratio
+        assert_eq!(churn_bar_cells(30, 30, 60), (3, 3));
+        assert_eq!(churn_bar_cells(60, 0, 60), (6, 0));
+        // Half the churn gets half the bar
+        assert_eq!(churn_bar_cells(0, 30, 60), (0, 3));
+        // A one-line change next to a huge one still gets a cell
+        assert_eq!(churn_bar_cells(1, 0, 10_000), (1, 0));
+        assert_eq!(churn_bar_cells(0, 0, 60), (0, 0));
+
+        let spans = churn_bar_spans(20, 10, 60, &Theme::Dark);
+        let bar: String = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(bar, " ███░░░");
+    }
+}
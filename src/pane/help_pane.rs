@@ -1,4 +1,4 @@
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::{
     Frame,
     layout::Rect,
@@ -7,12 +7,226 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
-use super::{AppEvent, Pane};
+use super::{AppEvent, FileIcon, Pane};
+use crate::config::IconSet;
 use crate::git::GitRepo;
 use crate::ui::{ActivePane, App};
 
+const GENERAL: &str = "General";
+const PANE_MODES: &str = "Pane Modes";
+
+/// A single hotkey listed in the help pane
+#[derive(Debug, Clone, PartialEq)]
+pub struct HelpEntry {
+    pub keys: &'static str,
+    pub description: &'static str,
+    /// Section the hotkey is listed under, e.g. "Monitor" or "General"
+    pub context: &'static str,
+}
+
+impl HelpEntry {
+    fn new(keys: &'static str, description: &'static str, context: &'static str) -> Self {
+        Self {
+            keys,
+            description,
+            context,
+        }
+    }
+
+    /// Case-insensitive match against the keys, description, or context
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        [self.keys, self.description, self.context]
+            .iter()
+            .any(|field| field.to_lowercase().contains(&filter))
+    }
+}
+
+/// Hotkeys relevant to the current app state, in display order
+pub fn help_entries(app: &App) -> Vec<HelpEntry> {
+    let mut entries = if app.is_in_commit_picker_mode() {
+        let context = "Commit Picker";
+        vec![
+            HelpEntry::new("j / k / ↑ / ↓", "Navigate commits", context),
+            HelpEntry::new("g t", "Next commit", context),
+            HelpEntry::new("g T", "Previous commit", context),
+            HelpEntry::new("Enter", "Select commit", context),
+            HelpEntry::new("r", "Retry a failed summary", context),
+            HelpEntry::new("Esc", "Exit commit picker", context),
+            HelpEntry::new("Ctrl+P", "Enter commit picker mode", context),
+            HelpEntry::new("Ctrl+W", "Return to working directory", context),
+        ]
+    } else {
+        match app.get_last_active_pane() {
+            ActivePane::FileTree => {
+                let context = "File Tree";
+                vec![
+                    HelpEntry::new("g t / Right", "Next file", context),
+                    HelpEntry::new("g T / Left", "Previous file", context),
+                    HelpEntry::new(
+                        "j / k / ↓ / ↑",
+                        "Next/previous file (when focused)",
+                        context,
+                    ),
+                    HelpEntry::new("Enter", "Expand/collapse untracked directory", context),
+                    HelpEntry::new(
+                        "' <letters>",
+                        "Jump to a name starting with them (when focused)",
+                        context,
+                    ),
+                ]
+            }
+            ActivePane::Monitor => {
+                let context = "Monitor";
+                vec![
+                    HelpEntry::new("Alt+j / Alt+Down", "Scroll down", context),
+                    HelpEntry::new("Alt+k / Alt+Up", "Scroll up", context),
+                    HelpEntry::new("Alt+d / Alt+u", "Half page down/up", context),
+                    HelpEntry::new("Alt+f / Alt+b", "Page down/up", context),
+                    HelpEntry::new("Alt+g g", "Go to top", context),
+                    HelpEntry::new("Alt+G", "Go to bottom", context),
+                    HelpEntry::new(
+                        "j / k / d / u / f / b / G",
+                        "Scroll without Alt (when focused)",
+                        context,
+                    ),
+                ]
+            }
+            ActivePane::Diff | ActivePane::SideBySideDiff => {
+                let context = "Diff View";
+                vec![
+                    HelpEntry::new("j / Down / Ctrl+e", "Scroll down", context),
+                    HelpEntry::new("k / Up / Ctrl+y", "Scroll up", context),
+                    HelpEntry::new("Right", "Next file", context),
+                    HelpEntry::new("Left", "Previous file", context),
+                    HelpEntry::new("d / u", "Half page down/up", context),
+                    HelpEntry::new("f / PageDown", "Page down", context),
+                    HelpEntry::new("b / PageUp", "Page up", context),
+                    HelpEntry::new("g g", "Go to top", context),
+                    HelpEntry::new("Shift+G", "Go to bottom", context),
+                    HelpEntry::new("<n>j / <n>k", "Scroll n lines", context),
+                    HelpEntry::new("<n>G", "Go to line n", context),
+                    HelpEntry::new("] c / n", "Next hunk", context),
+                    HelpEntry::new("[ c / N", "Previous hunk", context),
+                    HelpEntry::new("s", "Stage the hunk at the top", context),
+                    HelpEntry::new("z", "Fold / show unchanged lines", context),
+                    HelpEntry::new("V", "Select lines (motions extend, e explains)", context),
+                    HelpEntry::new("Ctrl+L", "Open LLM advice panel", context),
+                ]
+            }
+        }
+    };
+
+    entries.extend([
+        HelpEntry::new("?", "Show/hide this help page", GENERAL),
+        HelpEntry::new("Tab / Shift+Tab", "Focus next/previous pane", GENERAL),
+        HelpEntry::new("/", "Search all changed files", GENERAL),
+        HelpEntry::new(
+            ": / Ctrl+Shift+P",
+            "Command palette: type to filter, Enter runs",
+            GENERAL,
+        ),
+        HelpEntry::new("i", "Show diff stats", GENERAL),
+        HelpEntry::new("$", "Show LLM token usage and estimated cost", GENERAL),
+        HelpEntry::new(
+            "v",
+            "Cycle working tree / staged / last commit / automatic view",
+            GENERAL,
+        ),
+        HelpEntry::new(
+            "S",
+            "Show only staged changes / back to automatic view",
+            GENERAL,
+        ),
+        HelpEntry::new(
+            "B",
+            "Show staged and unstaged changes together / back to automatic view",
+            GENERAL,
+        ),
+        HelpEntry::new("C", "Draft a commit message for staged changes", GENERAL),
+        HelpEntry::new("X", "Discard current file's working tree changes", GENERAL),
+        HelpEntry::new("p", "Copy current file's changes as a patch", GENERAL),
+        HelpEntry::new(
+            "x",
+            "View current file's diff in external_diff_command",
+            GENERAL,
+        ),
+        HelpEntry::new("P", "Save current file's changes as <file>.patch", GENERAL),
+        HelpEntry::new(
+            "e",
+            "Open current file in $EDITOR at the diff line",
+            GENERAL,
+        ),
+        HelpEntry::new("Esc", "Exit help page", GENERAL),
+        HelpEntry::new("Ctrl+h", "Toggle diff panel visibility", GENERAL),
+        HelpEntry::new("Ctrl+o", "Toggle monitor pane visibility", GENERAL),
+        HelpEntry::new("Ctrl+t", "Cycle through themes", GENERAL),
+        HelpEntry::new("Ctrl+g", "Show full commit message", GENERAL),
+        HelpEntry::new("Ctrl+Space", "Pause/resume git refresh", GENERAL),
+        HelpEntry::new("q / Ctrl+c", "Quit application", GENERAL),
+    ]);
+
+    // Add commit picker shortcut if not already in commit picker mode
+    if !app.is_in_commit_picker_mode() {
+        entries.push(HelpEntry::new(
+            "Ctrl+P",
+            "Enter commit picker mode",
+            GENERAL,
+        ));
+        entries.push(HelpEntry::new(
+            "Ctrl+R",
+            "Browse HEAD's reflog to recover lost commits",
+            GENERAL,
+        ));
+    }
+
+    // Add working directory shortcut if we have a selected commit
+    if app.get_selected_commit().is_some() {
+        entries.push(HelpEntry::new(
+            "Ctrl+W",
+            "Return to working directory",
+            GENERAL,
+        ));
+    }
+
+    entries.extend([
+        HelpEntry::new("Ctrl+d", "Switch to inline diff view", PANE_MODES),
+        HelpEntry::new("Ctrl+s", "Switch to side-by-side diff view", PANE_MODES),
+    ]);
+
+    entries
+}
+
+/// What each file tree and commit summary icon means in `icons`; icons the set draws
+/// alike are listed once, with their meanings joined
+pub fn icon_legend(icons: IconSet) -> Vec<(String, String)> {
+    let meanings = [
+        (FileIcon::Directory, "Directory"),
+        (FileIcon::Submodule, "Submodule"),
+        (FileIcon::Added, "New file"),
+        (FileIcon::Modified, "Modified file"),
+        (FileIcon::Deleted, "Deleted file"),
+        (FileIcon::Renamed, "Renamed file"),
+        (FileIcon::File, "File with only staged changes"),
+    ];
+    let mut legend: Vec<(String, String)> = Vec::new();
+    for (icon, meaning) in meanings {
+        let rendered = icon.render(icons);
+        match legend
+            .iter_mut()
+            .find(|(existing, _)| *existing == rendered)
+        {
+            Some((_, meanings)) => meanings.push_str(&format!(", or {}", meaning.to_lowercase())),
+            None => legend.push((rendered, meaning.to_string())),
+        }
+    }
+    legend
+}
+
 pub struct HelpPane {
     visible: bool,
+    filter: String,
+    filter_active: bool,
 }
 
 impl Default for HelpPane {
@@ -23,7 +237,16 @@ impl Default for HelpPane {
 
 impl HelpPane {
     pub fn new() -> Self {
-        Self { visible: false }
+        Self {
+            visible: false,
+            filter: String::new(),
+            filter_active: false,
+        }
+    }
+
+    fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.filter_active = false;
     }
 }
 
@@ -40,7 +263,9 @@ impl Pane for HelpPane {
         _git_repo: &GitRepo,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let theme = app.get_theme();
-        let last_active_pane = app.get_last_active_pane();
+        let heading_style = Style::default()
+            .fg(theme.primary_color())
+            .add_modifier(Modifier::BOLD);
 
         let mut help_text = vec![
             Line::from(Span::styled(
@@ -52,102 +277,77 @@ impl Pane for HelpPane {
             Line::from(""),
         ];
 
-        // Check if we're in commit picker mode and show commit picker shortcuts
-        let (pane_title, pane_hotkeys) = if app.is_in_commit_picker_mode() {
-            (
-                "Commit Picker",
-                vec![
-                    "  j / k / ↑ / ↓     - Navigate commits",
-                    "  g t               - Next commit",
-                    "  g T               - Previous commit",
-                    "  Enter             - Select commit",
-                    "  Esc               - Exit commit picker",
-                    "  Ctrl+P            - Enter commit picker mode",
-                    "  Ctrl+W            - Return to working directory",
-                ],
-            )
-        } else {
-            match last_active_pane {
-                ActivePane::FileTree => (
-                    "File Tree",
-                    vec![
-                        "  Tab / g t / Right - Next file",
-                        "  Shift+Tab / g T / Left - Previous file",
-                    ],
-                ),
-                ActivePane::Monitor => (
-                    "Monitor",
-                    vec![
-                        "  Alt+j / Alt+Down  - Scroll down",
-                        "  Alt+k / Alt+Up    - Scroll up",
-                    ],
-                ),
-                ActivePane::Diff | ActivePane::SideBySideDiff => (
-                    "Diff View",
-                    vec![
-                        "  j / Down / Ctrl+e - Scroll down",
-                        "  k / Up / Ctrl+y   - Scroll up",
-                        "  Right             - Next file",
-                        "  Left              - Previous file",
-                        "  PageDown          - Page down",
-                        "  PageUp            - Page up",
-                        "  g g               - Go to top",
-                        "  Shift+G           - Go to bottom",
-                        "  Ctrl+L            - Open LLM advice panel",
-                    ],
-                ),
-            }
-        };
+        if self.filter_active || !self.filter.is_empty() {
+            let cursor = if self.filter_active { "_" } else { "" };
+            help_text.push(Line::from(vec![
+                Span::styled("Filter: /", Style::default().fg(theme.secondary_color())),
+                Span::raw(format!("{}{cursor}", self.filter)),
+            ]));
+            help_text.push(Line::from(""));
+        }
 
-        help_text.push(Line::from(Span::styled(
-            format!("{pane_title} Hotkeys:"),
-            Style::default()
-                .fg(theme.primary_color())
-                .add_modifier(Modifier::BOLD),
-        )));
-        for hotkey in pane_hotkeys {
-            help_text.push(Line::from(hotkey));
+        let entries: Vec<HelpEntry> = help_entries(app)
+            .into_iter()
+            .filter(|entry| entry.matches(&self.filter))
+            .collect();
+
+        if entries.is_empty() {
+            help_text.push(Line::from("  No hotkeys match the filter"));
+            help_text.push(Line::from(""));
         }
-        help_text.push(Line::from(""));
 
-        help_text.extend(vec![
-            Line::from(Span::styled(
-                "General:",
-                Style::default()
-                    .fg(theme.primary_color())
-                    .add_modifier(Modifier::BOLD),
-            )),
-            Line::from("  ?             - Show/hide this help page"),
-            Line::from("  Esc           - Exit help page"),
-            Line::from("  Ctrl+h        - Toggle diff panel visibility"),
-            Line::from("  Ctrl+o        - Toggle monitor pane visibility"),
-            Line::from("  Ctrl+t        - Toggle light/dark theme"),
-            Line::from("  q / Ctrl+c    - Quit application"),
-        ]);
-
-        // Add commit picker shortcut if not already in commit picker mode
-        if !app.is_in_commit_picker_mode() {
-            help_text.push(Line::from("  Ctrl+P        - Enter commit picker mode"));
+        let mut contexts: Vec<&str> = Vec::new();
+        for entry in &entries {
+            if !contexts.contains(&entry.context) {
+                contexts.push(entry.context);
+            }
         }
 
-        // Add working directory shortcut if we have a selected commit
-        if app.get_selected_commit().is_some() {
-            help_text.push(Line::from("  Ctrl+W        - Return to working directory"));
+        for context in contexts {
+            // Pane-specific sections have longer key combinations, so they get a wider column
+            let (heading, key_width) = if context == GENERAL || context == PANE_MODES {
+                (format!("{context}:"), 13)
+            } else {
+                (format!("{context} Hotkeys:"), 17)
+            };
+
+            help_text.push(Line::from(Span::styled(heading, heading_style)));
+            for entry in entries.iter().filter(|entry| entry.context == context) {
+                help_text.push(Line::from(format!(
+                    "  {:<key_width$} - {}",
+                    entry.keys, entry.description
+                )));
+            }
+            help_text.push(Line::from(""));
         }
 
-        help_text.extend(vec![
-            Line::from(""),
-            Line::from(Span::styled(
-                "Pane Modes:",
-                Style::default()
-                    .fg(theme.primary_color())
-                    .add_modifier(Modifier::BOLD),
-            )),
-            Line::from("  Ctrl+d        - Switch to inline diff view"),
-            Line::from("  Ctrl+s        - Switch to side-by-side diff view"),
-            Line::from(""),
-            Line::from("Press ? or Esc to return to the previous pane"),
-        ]);
+        // The legend isn't hotkeys, so a filter hides it
+        if self.filter.is_empty() {
+            help_text.push(Line::from(Span::styled("Legend:", heading_style)));
+            for (icon, meaning) in icon_legend(app.get_icons()) {
+                help_text.push(Line::from(format!("  {icon}- {meaning}")));
+            }
+            for (marker, color, meaning) in [
+                ("+", theme.added_color(), "Added line"),
+                ("-", theme.removed_color(), "Removed line"),
+                (
+                    " ",
+                    theme.unchanged_color(),
+                    "Unchanged line around a change",
+                ),
+            ] {
+                help_text.push(Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled(format!("{marker}  "), Style::default().fg(color)),
+                    Span::raw(format!("- {meaning}")),
+                ]));
+            }
+            help_text.push(Line::from(""));
+        }
+
+        help_text.push(Line::from(
+            "Press / to filter, ? or Esc to return to the previous pane",
+        ));
 
         let text = ratatui::text::Text::from(help_text);
         let paragraph = Paragraph::new(text)
@@ -163,15 +363,39 @@ impl Pane for HelpPane {
         Ok(())
     }
 
+    /// Handles filter input; keys that close the help page are left to the app
     fn handle_event(&mut self, event: &AppEvent) -> bool {
         match event {
+            // Ctrl and Alt chords aren't typing, so Ctrl+C still quits
+            AppEvent::Key(key)
+                if self.filter_active
+                    && matches!(key.code, KeyCode::Char(_))
+                    && key
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                false
+            }
+            AppEvent::Key(key) if self.filter_active => {
+                match key.code {
+                    KeyCode::Esc => self.clear_filter(),
+                    KeyCode::Enter => self.filter_active = false,
+                    KeyCode::Backspace => {
+                        self.filter.pop();
+                    }
+                    KeyCode::Char(c) => self.filter.push(c),
+                    _ => {}
+                }
+                true
+            }
             AppEvent::Key(key) => match key.code {
-                KeyCode::Char('?') => {
-                    self.set_visible(false);
+                KeyCode::Char('/') => {
+                    self.filter.clear();
+                    self.filter_active = true;
                     true
                 }
-                KeyCode::Esc => {
-                    self.set_visible(false);
+                KeyCode::Esc if !self.filter.is_empty() => {
+                    self.clear_filter();
                     true
                 }
                 _ => false,
@@ -186,11 +410,16 @@ impl Pane for HelpPane {
 
     fn set_visible(&mut self, visible: bool) {
         self.visible = visible;
+        if !visible {
+            self.clear_filter();
+        }
     }
 }
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::ui::{App, Theme};
+    use crossterm::event::KeyEvent;
     use std::sync::Arc;
 
     fn create_test_llm_state() -> Arc<crate::shared_state::LlmSharedState> {
@@ -230,6 +459,7 @@ mod tests {
             sha: "abc123".to_string(),
             short_sha: "abc123".to_string(),
             message: "Test commit".to_string(),
+            time: 0,
             files_changed: vec![],
         };
         app.select_commit(test_commit);
@@ -241,4 +471,85 @@ mod tests {
         app.clear_selected_commit();
         assert!(app.get_selected_commit().is_none());
     }
+
+    #[test]
+    fn test_help_entries_filter() {
+        let app = create_test_app();
+        let entries = help_entries(&app);
+
+        let monitor: Vec<&str> = entries
+            .iter()
+            .filter(|entry| entry.matches("MONITOR"))
+            .map(|entry| entry.keys)
+            .collect();
+        assert_eq!(monitor, vec!["Ctrl+o"]);
+
+        // Context names match too
+        assert!(
+            entries
+                .iter()
+                .filter(|entry| entry.matches("pane modes"))
+                .all(|entry| entry.context == PANE_MODES)
+        );
+        assert!(entries.iter().all(|entry| entry.matches("")));
+    }
+
+    #[test]
+    fn test_icon_legend_follows_icon_set() {
+        let emoji = icon_legend(IconSet::Emoji);
+        assert_eq!(emoji[1], ("📦 ".to_string(), "Submodule".to_string()));
+        // New and staged-only files share an emoji
+        assert_eq!(
+            emoji[2],
+            (
+                "📄 ".to_string(),
+                "New file, or file with only staged changes".to_string()
+            )
+        );
+        assert_eq!(emoji.len(), 6);
+
+        let ascii = icon_legend(IconSet::Ascii);
+        assert_eq!(ascii[0], ("[D] ".to_string(), "Directory".to_string()));
+        assert_eq!(ascii[3], ("M  ".to_string(), "Modified file".to_string()));
+        assert_eq!(ascii.len(), 7);
+    }
+
+    #[test]
+    fn test_help_filter_input() {
+        let mut pane = HelpPane::new();
+        pane.set_visible(true);
+        let key = |code| AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+        // Keys that close the help page are left to the app when not filtering
+        assert!(!pane.handle_event(&key(KeyCode::Esc)));
+        assert!(!pane.handle_event(&key(KeyCode::Char('?'))));
+
+        assert!(pane.handle_event(&key(KeyCode::Char('/'))));
+        for c in "mon?".chars() {
+            assert!(pane.handle_event(&key(KeyCode::Char(c))));
+        }
+        assert!(pane.handle_event(&key(KeyCode::Backspace)));
+        assert_eq!(pane.filter, "mon");
+
+        // Chords are left to the app rather than typed
+        for modifiers in [KeyModifiers::CONTROL, KeyModifiers::ALT] {
+            let chord = AppEvent::Key(KeyEvent::new(KeyCode::Char('c'), modifiers));
+            assert!(!pane.handle_event(&chord));
+        }
+        assert_eq!(pane.filter, "mon");
+
+        // Enter keeps the filter applied, Esc then clears it
+        assert!(pane.handle_event(&key(KeyCode::Enter)));
+        assert!(!pane.filter_active);
+        assert_eq!(pane.filter, "mon");
+        assert!(pane.handle_event(&key(KeyCode::Esc)));
+        assert_eq!(pane.filter, "");
+
+        // Hiding the pane drops any filter
+        pane.handle_event(&key(KeyCode::Char('/')));
+        pane.handle_event(&key(KeyCode::Char('x')));
+        pane.set_visible(false);
+        assert_eq!(pane.filter, "");
+        assert!(!pane.filter_active);
+    }
 }
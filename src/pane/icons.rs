@@ -0,0 +1,150 @@
+use git2::Status;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::IconSet;
+use crate::git::FileChangeStatus;
+
+/// Terminal cells every icon takes up, the width of an emoji
+const ICON_WIDTH: usize = 2;
+
+/// What a file tree or commit summary row stands for, drawn with the configured icon set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileIcon {
+    Directory,
+    Submodule,
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    /// A file whose status has no icon of its own, such as one that is only staged
+    File,
+}
+
+impl FileIcon {
+    /// Icon for a file tree entry, from its working tree status
+    pub fn for_status(status: Option<Status>, submodule: bool) -> Self {
+        let Some(status) = status else {
+            return if submodule {
+                Self::Submodule
+            } else {
+                Self::File
+            };
+        };
+        if submodule {
+            Self::Submodule
+        } else if status.is_wt_new() {
+            Self::Added
+        } else if status.is_wt_renamed() || status.is_index_renamed() {
+            Self::Renamed
+        } else if status.is_wt_modified() {
+            Self::Modified
+        } else if status.is_wt_deleted() {
+            Self::Deleted
+        } else {
+            Self::File
+        }
+    }
+
+    /// Icon for a file changed by a commit
+    pub fn for_change(status: &FileChangeStatus) -> Self {
+        match status {
+            FileChangeStatus::Added => Self::Added,
+            FileChangeStatus::Modified => Self::Modified,
+            FileChangeStatus::Deleted => Self::Deleted,
+            FileChangeStatus::Renamed => Self::Renamed,
+        }
+    }
+
+    /// The bare glyph for this icon in `icons`
+    fn glyph(self, icons: IconSet) -> &'static str {
+        match icons {
+            IconSet::Emoji => match self {
+                Self::Directory => "📁",
+                Self::Submodule => "📦",
+                // A new file looks like any other file, the green stats say it's new
+                Self::Added | Self::File => "📄",
+                Self::Modified => "📝",
+                Self::Deleted => "🗑️",
+                Self::Renamed => "📋",
+            },
+            IconSet::NerdFont => match self {
+                Self::Directory => "\u{f07b}",
+                Self::Submodule => "\u{f487}",
+                Self::Added => "\u{f457}",
+                Self::Modified => "\u{f459}",
+                Self::Deleted => "\u{f458}",
+                Self::Renamed => "\u{f45a}",
+                Self::File => "\u{f15b}",
+            },
+            IconSet::Ascii => match self {
+                Self::Directory => "[D]",
+                Self::Submodule => "S",
+                Self::Added => "+",
+                Self::Modified => "M",
+                Self::Deleted => "-",
+                Self::Renamed => "R",
+                Self::File => "",
+            },
+        }
+    }
+
+    /// The icon in `icons`, padded to at least `ICON_WIDTH` cells and followed by the space
+    /// that separates it from the name, so names line up whichever icon is in front
+    pub fn render(self, icons: IconSet) -> String {
+        let glyph = self.glyph(icons);
+        let padding = ICON_WIDTH.saturating_sub(glyph.width());
+        format!("{glyph}{} ", " ".repeat(padding))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_sets() {
+        let modified = FileIcon::for_status(Some(Status::WT_MODIFIED), false);
+        assert_eq!(modified, FileIcon::Modified);
+        assert_eq!(modified.render(IconSet::Emoji), "📝 ");
+        assert_eq!(modified.render(IconSet::Ascii), "M  ");
+
+        assert_eq!(FileIcon::Directory.render(IconSet::Ascii), "[D] ");
+        assert_eq!(
+            FileIcon::for_change(&FileChangeStatus::Deleted).render(IconSet::Ascii),
+            "-  "
+        );
+        assert_eq!(
+            FileIcon::for_status(Some(Status::WT_NEW), false).render(IconSet::Ascii),
+            "+  "
+        );
+        // Submodules keep their icon whatever their status
+        assert_eq!(
+            FileIcon::for_status(Some(Status::WT_MODIFIED), true),
+            FileIcon::Submodule
+        );
+        assert_eq!(FileIcon::for_status(None, false), FileIcon::File);
+    }
+
+    #[test]
+    fn test_status_icons_have_equal_widths() {
+        let statuses = [
+            FileIcon::Submodule,
+            FileIcon::Added,
+            FileIcon::Modified,
+            FileIcon::Deleted,
+            FileIcon::Renamed,
+            FileIcon::File,
+        ];
+        for icons in [IconSet::Emoji, IconSet::NerdFont, IconSet::Ascii] {
+            for icon in statuses {
+                assert_eq!(
+                    icon.render(icons).width(),
+                    ICON_WIDTH + 1,
+                    "{icon:?} in {icons:?} is {:?}",
+                    icon.render(icons)
+                );
+            }
+        }
+    }
+}
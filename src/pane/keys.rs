@@ -1,8 +1,10 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use log::debug;
 
+use super::ScrollAction;
 use super::advice_panel::AdviceMode;
-use crate::ui::App;
+use crate::action::Action;
+use crate::ui::{ActivePane, App};
 
 /// Key handling result type
 pub enum KeyResult {
@@ -22,11 +24,165 @@ pub struct GlobalKeyHandler;
 impl GlobalKeyHandler {
     /// Handle global application key events
     pub fn handle_global_key(app: &mut App, key: &KeyEvent) -> KeyResult {
+        // The commit message popup sits above everything else until dismissed
+        if app.is_showing_commit_message() {
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                log::info!("User requested quit via Ctrl+C from commit message popup");
+                return KeyResult::Quit;
+            }
+            app.forward_key_to_commit_message(*key);
+            return KeyResult::Handled;
+        }
+
+        // The diff stats popup is transient: any key dismisses it
+        if app.is_showing_diff_stats() {
+            app.forward_key_to_diff_stats(*key);
+            return KeyResult::Handled;
+        }
+
+        // So is the token usage popup
+        if app.is_showing_token_usage() {
+            app.forward_key_to_token_usage(*key);
+            return KeyResult::Handled;
+        }
+
+        // The explanation popup scrolls until dismissed
+        if app.is_showing_explanation() {
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                log::info!("User requested quit via Ctrl+C from explanation popup");
+                return KeyResult::Quit;
+            }
+            app.forward_key_to_explanation(*key);
+            return KeyResult::Handled;
+        }
+
+        // The commit message draft is edited in place until dismissed
+        if app.is_showing_commit_draft() {
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                log::info!("User requested quit via Ctrl+C from commit message draft");
+                return KeyResult::Quit;
+            }
+            app.forward_key_to_commit_draft(*key);
+            return KeyResult::Handled;
+        }
+
+        // A destructive action waits on a yes or no before anything else happens
+        if app.is_confirming() {
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                log::info!("User requested quit via Ctrl+C from a confirmation");
+                return KeyResult::Quit;
+            }
+            if let Some(confirmed) = super::ConfirmDialog::answer(key) {
+                app.answer_confirmation(confirmed);
+            }
+            return KeyResult::Handled;
+        }
+
+        // The command palette takes typing as its filter until a command is picked
+        if app.is_showing_command_palette() {
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    log::info!("User requested quit via Ctrl+C from the command palette");
+                    return KeyResult::Quit;
+                }
+                KeyCode::Esc => app.close_command_palette(),
+                KeyCode::Enter => {
+                    if app.run_selected_palette_command() {
+                        log::info!("User requested quit from the command palette");
+                        return KeyResult::Quit;
+                    }
+                }
+                _ => {
+                    app.forward_key_to_command_palette(*key);
+                }
+            }
+            return KeyResult::Handled;
+        }
+
+        // The help page handles its own filter input
+        if app.is_showing_help() && app.forward_key_to_help(*key) {
+            return KeyResult::Handled;
+        }
+
+        // The search overlay captures typing until it is closed
+        if app.is_searching() {
+            return Self::handle_search_keys(app, key);
+        }
+
+        // Ctrl shortcuts that work from any mode, once no popup or dialog has the key
+        if Self::handle_ctrl_shortcut(app, key) {
+            return KeyResult::Handled;
+        }
+
+        // Digits build up a vim-style count for the next motion, e.g. 5j or 10G
+        // The advice panel owns its own input, so counts don't apply there
+        if !app.is_advice_panel_visible() {
+            if let KeyCode::Char(c @ '0'..='9') = key.code
+                && key.modifiers.is_empty()
+                && (c != '0' || app.pending_count().is_some())
+            {
+                app.push_count_digit(c.to_digit(10).unwrap_or(0) as usize);
+                return KeyResult::Handled;
+            }
+
+            if let Some(count) = app.take_pending_count() {
+                return Self::handle_counted_key(app, key, count);
+            }
+        }
+
+        Self::dispatch_key(app, key)
+    }
+
+    /// Ctrl+P, Ctrl+R, Ctrl+G, Ctrl+Space, Ctrl+O and Ctrl+W, which apply whatever pane has focus
+    fn handle_ctrl_shortcut(app: &mut App, key: &KeyEvent) -> bool {
+        if !key.modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+        let action = match key.code {
+            // Ctrl+Shift+P, where the terminal tells them apart, opens the command palette instead
+            KeyCode::Char('p') if !key.modifiers.contains(KeyModifiers::SHIFT) => {
+                Action::OpenCommitPicker
+            }
+            // The advice panel keeps Ctrl+R for asking again
+            KeyCode::Char('r') if !app.is_advice_panel_visible() => Action::OpenReflog,
+            KeyCode::Char('g') => Action::ShowCommitMessage,
+            KeyCode::Char(' ') => Action::ToggleRefreshPaused,
+            KeyCode::Char('o') => {
+                debug!("User pressed Ctrl+O - toggling monitor pane");
+                Action::ToggleMonitorPane
+            }
+            // Advice panel chat input keeps Ctrl+W
+            KeyCode::Char('w') if !app.is_advice_panel_chat_input_active() => {
+                debug!("User pressed Ctrl+W - returning to working directory view");
+                Action::ReturnToWorkingDirectory
+            }
+            _ => return false,
+        };
+        app.apply_action(action);
+        true
+    }
+
+    /// Route a key to the handler for the current mode
+    fn dispatch_key(app: &mut App, key: &KeyEvent) -> KeyResult {
         // Handle commit picker mode key events first
         if app.is_in_commit_picker_mode() {
             return Self::handle_commit_picker_keys(app, key);
         }
 
+        // Selecting diff lines is modal until they are explained or the selection is cancelled
+        if app.is_selecting_diff() {
+            return Self::handle_diff_selection_keys(app, key);
+        }
+
+        // Clearing the advice chat loses the conversation, so App confirms it first
+        if key.code == KeyCode::Char('r')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+            && app.is_advice_panel_visible()
+        {
+            app.apply_action(Action::ClearAdviceChat);
+            return KeyResult::Handled;
+        }
+
         // Let panes handle the key first
         let panes_handled = app.forward_key_to_panes(*key);
         if panes_handled {
@@ -37,6 +193,41 @@ impl GlobalKeyHandler {
         Self::handle_main_mode_keys(app, key)
     }
 
+    /// Apply a count prefix: `G` jumps to that line, motions repeat, anything else ignores it
+    fn handle_counted_key(app: &mut App, key: &KeyEvent, count: usize) -> KeyResult {
+        if key.code == KeyCode::Char('G') && !app.is_in_commit_picker_mode() {
+            debug!("User triggered '{count}G' - jumping to line {count}");
+            app.scroll_to_line(count.saturating_sub(1));
+            return KeyResult::Handled;
+        }
+
+        if !Self::is_repeatable_motion(key) {
+            return Self::dispatch_key(app, key);
+        }
+
+        debug!("Repeating {:?} {count} times", key.code);
+        for _ in 0..count {
+            if let KeyResult::NotHandled = Self::dispatch_key(app, key) {
+                return KeyResult::NotHandled;
+            }
+        }
+        KeyResult::Handled
+    }
+
+    /// Scrolling keys that a count prefix multiplies
+    fn is_repeatable_motion(key: &KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char('j' | 'k' | 'd' | 'u' | 'f' | 'b') => {
+                key.modifiers.is_empty() || key.modifiers.contains(KeyModifiers::ALT)
+            }
+            KeyCode::Char('e') | KeyCode::Char('y') => {
+                key.modifiers.contains(KeyModifiers::CONTROL)
+            }
+            KeyCode::Down | KeyCode::Up | KeyCode::PageDown | KeyCode::PageUp => true,
+            _ => false,
+        }
+    }
+
     /// Handle keys when in commit picker mode
     fn handle_commit_picker_keys(app: &mut App, key: &KeyEvent) -> KeyResult {
         match key.code {
@@ -58,6 +249,11 @@ impl GlobalKeyHandler {
                 app.exit_commit_picker_mode();
                 KeyResult::Handled
             }
+            KeyCode::Char('r') if key.modifiers.is_empty() => {
+                debug!("User pressed 'r' in commit picker mode, retrying the summary");
+                app.retry_commit_summary();
+                KeyResult::Handled
+            }
             _ => {
                 // Forward key events to commit picker pane with error handling
                 let picker_handled = app.forward_key_to_commit_picker(*key);
@@ -72,79 +268,112 @@ impl GlobalKeyHandler {
         }
     }
 
-    /// Handle keys when in main mode (not commit picker)
-    fn handle_main_mode_keys(app: &mut App, key: &KeyEvent) -> KeyResult {
+    /// Handle keys while selecting diff lines: motions extend the selection
+    fn handle_diff_selection_keys(app: &mut App, key: &KeyEvent) -> KeyResult {
+        if let Some(action) = ScrollAction::from_key(key) {
+            app.move_diff_selection(action);
+            return KeyResult::Handled;
+        }
+
         match key.code {
-            KeyCode::Char('q') => {
-                log::info!("User requested quit");
-                KeyResult::Quit
-            }
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                log::info!("User requested quit via Ctrl+C");
+                log::info!("User requested quit via Ctrl+C from line selection");
                 KeyResult::Quit
             }
-            KeyCode::Char('G') if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                // This should have been handled by forward_key_to_panes above
-                app.scroll_to_bottom(app.current_diff_height);
-                KeyResult::Handled
-            }
-            KeyCode::Char('j') if key.modifiers.is_empty() => {
-                // This should have been handled by forward_key_to_panes above
-                app.scroll_down(app.current_diff_height);
+            KeyCode::Char('e') | KeyCode::Enter => {
+                debug!("User asked for an explanation of the selected diff lines");
+                app.explain_diff_selection();
                 KeyResult::Handled
             }
-            KeyCode::Down => {
-                // This should have been handled by forward_key_to_panes above
-                app.scroll_down(app.current_diff_height);
+            KeyCode::Esc | KeyCode::Char('V') => {
+                debug!("User cancelled the diff line selection");
+                app.cancel_diff_selection();
                 KeyResult::Handled
             }
-            KeyCode::Char('k') if key.modifiers.is_empty() => {
-                // This should have been handled by forward_key_to_panes above
-                app.scroll_up();
-                KeyResult::Handled
+            // Other keys would move away from the selection, so they wait until it ends
+            _ => KeyResult::Handled,
+        }
+    }
+
+    /// Handle keys when the search overlay is open
+    fn handle_search_keys(app: &mut App, key: &KeyEvent) -> KeyResult {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                log::info!("User requested quit via Ctrl+C from search");
+                KeyResult::Quit
             }
-            KeyCode::Up => {
-                // This should have been handled by forward_key_to_panes above
-                app.scroll_up();
+            KeyCode::Esc => {
+                debug!("User pressed Escape in search, closing");
+                app.close_search();
                 KeyResult::Handled
             }
-            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                // This should have been handled by forward_key_to_panes above
-                app.scroll_down(app.current_diff_height);
+            KeyCode::Enter => {
+                debug!("User pressed Enter in search - jumping to match");
+                app.jump_to_search_result();
                 KeyResult::Handled
             }
-            KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                // This should have been handled by forward_key_to_panes above
-                app.scroll_up();
+            _ => {
+                app.forward_key_to_search(*key);
                 KeyResult::Handled
             }
-            KeyCode::Char('g') => {
-                if app.handle_g_press() {
-                    KeyResult::Handled
-                } else {
-                    // g was pressed, wait for next key
-                    KeyResult::Handled
-                }
-            }
-            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                debug!("User pressed Ctrl+T - toggling theme");
-                app.toggle_theme();
-                KeyResult::Handled
+        }
+    }
+
+    /// Handle keys when in main mode (not commit picker)
+    fn handle_main_mode_keys(app: &mut App, key: &KeyEvent) -> KeyResult {
+        // Alt plus any standard scroll key scrolls the monitor pane
+        if key.modifiers.contains(KeyModifiers::ALT) && app.scroll_monitor(key) {
+            return KeyResult::Handled;
+        }
+
+        // Scroll keys move whichever pane has focus; the diff handles them below
+        match app.get_focused_pane() {
+            ActivePane::Monitor
+                if !key.modifiers.contains(KeyModifiers::ALT)
+                    && ScrollAction::from_key(key).is_some() =>
+            {
+                app.scroll_monitor(key);
+                return KeyResult::Handled;
             }
-            KeyCode::Char('t') => {
-                // Check if g was pressed recently
-                if let Some(last_time) = app.last_g_press
-                    && std::time::Instant::now()
-                        .duration_since(last_time)
-                        .as_millis()
-                        < 500
+            ActivePane::FileTree => {
+                // `'` then a name jumps to it; keys matching no name keep their hotkeys
+                if let KeyCode::Char(c) = key.code
+                    && !key
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
                 {
-                    debug!("User triggered 'gt' key combination - next file");
-                    app.next_file();
+                    if c == '\'' {
+                        app.start_tree_jump();
+                        return KeyResult::Handled;
+                    }
+                    if (c.is_alphanumeric() || matches!(c, '.' | '_' | '-'))
+                        && app.handle_tree_jump_key(c)
+                    {
+                        return KeyResult::Handled;
+                    }
                 }
-                KeyResult::Handled
+                match ScrollAction::from_key(key) {
+                    Some(ScrollAction::LineDown) => {
+                        app.next_file();
+                        return KeyResult::Handled;
+                    }
+                    Some(ScrollAction::LineUp) => {
+                        app.prev_file();
+                        return KeyResult::Handled;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+
+        match key.code {
+            KeyCode::Char('g') if key.modifiers.is_empty() => {
+                // A second g within 500ms goes to the top; the first waits for it
+                app.handle_g_press();
+                return KeyResult::Handled;
             }
-            KeyCode::Char('T') => {
+            KeyCode::Char('t' | 'T') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // Check if g was pressed recently
                 if let Some(last_time) = app.last_g_press
                     && std::time::Instant::now()
@@ -152,114 +381,123 @@ impl GlobalKeyHandler {
                         .as_millis()
                         < 500
                 {
-                    debug!("User triggered 'gT' key combination - previous file");
-                    app.prev_file();
+                    debug!("User triggered a 'gt' / 'gT' key combination");
+                    app.apply_action(if key.code == KeyCode::Char('t') {
+                        Action::NextFile
+                    } else {
+                        Action::PrevFile
+                    });
                 }
-                KeyResult::Handled
-            }
-            KeyCode::PageDown => {
-                app.page_down(app.current_diff_height);
-                KeyResult::Handled
+                return KeyResult::Handled;
             }
-            KeyCode::PageUp => {
-                app.page_up(app.current_diff_height);
-                KeyResult::Handled
+            KeyCode::Char(bracket @ (']' | '[')) => {
+                app.last_bracket_press = Some((bracket, std::time::Instant::now()));
+                return KeyResult::Handled;
             }
-            KeyCode::Left => {
-                debug!("User pressed Left - previous file");
-                app.prev_file();
-                KeyResult::Handled
-            }
-            KeyCode::Right => {
-                debug!("User pressed Right - next file");
-                app.next_file();
-                KeyResult::Handled
-            }
-            KeyCode::Tab => {
-                debug!("User pressed Tab - next file");
-                app.next_file();
-                KeyResult::Handled
-            }
-            KeyCode::BackTab => {
-                debug!("User pressed Shift+Tab - previous file");
-                app.prev_file();
-                KeyResult::Handled
+            KeyCode::Char('c') if key.modifiers.is_empty() => {
+                if app.handle_hunk_jump_key() {
+                    debug!("User triggered a ]c / [c hunk jump");
+                }
+                return KeyResult::Handled;
             }
-            KeyCode::Char('?') => {
-                app.toggle_help();
-                KeyResult::Handled
+            KeyCode::Enter => {
+                debug!("User pressed Enter - toggle untracked directory");
+                return if app.toggle_untracked_dir() {
+                    KeyResult::Handled
+                } else {
+                    KeyResult::NotHandled
+                };
             }
             KeyCode::Esc => {
                 if app.is_showing_help() {
                     app.toggle_help();
                 } else if app.is_advice_panel_visible() {
                     debug!("User pressed Escape - hiding advice panel and showing diff pane");
-                    // Hide advice panel and show diff pane (same behavior as Ctrl+D)
-                    if let Err(e) = app.toggle_pane_visibility(&super::PaneId::Advice) {
-                        log::warn!("Failed to hide advice panel: {}", e);
-                    }
-                    if !app.is_diff_panel_visible()
-                        && let Err(e) = app.toggle_pane_visibility(&super::PaneId::Diff)
-                    {
-                        log::warn!("Failed to show diff pane: {}", e);
-                    }
+                    // Same as Ctrl+D from the advice panel
+                    app.apply_action(Action::SinglePaneDiff);
                 }
-                KeyResult::Handled
-            }
-            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                app.set_side_by_side_diff();
-                KeyResult::Handled
-            }
-            KeyCode::Char('h') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                app.toggle_diff_panel();
-                KeyResult::Handled
-            }
-            KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                app.toggle_changed_files_pane();
-                KeyResult::Handled
-            }
-            KeyCode::Char('j') if key.modifiers.contains(KeyModifiers::ALT) => {
-                app.scroll_monitor_down();
-                KeyResult::Handled
+                return KeyResult::Handled;
             }
-            KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::ALT) => {
-                app.scroll_monitor_up();
-                KeyResult::Handled
-            }
-            KeyCode::Char('m') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                app.toggle_monitor_pane();
-                KeyResult::Handled
-            }
-            KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                debug!("User pressed Ctrl+L - toggling advice panel");
-                if let Err(e) = app.toggle_pane_visibility(&super::PaneId::Advice) {
-                    log::warn!("Failed to toggle advice panel: {}", e);
-                }
-                KeyResult::Handled
-            }
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                debug!("User pressed Ctrl+D - switching to diff pane");
-                // Only handle Ctrl+D for advice panel navigation when advice panel is visible
-                if app.is_advice_panel_visible() {
-                    // Hide advice panel and show diff pane
-                    if let Err(e) = app.toggle_pane_visibility(&super::PaneId::Advice) {
-                        log::warn!("Failed to hide advice panel: {}", e);
-                    }
-                    if !app.is_diff_panel_visible()
-                        && let Err(e) = app.toggle_pane_visibility(&super::PaneId::Diff)
-                    {
-                        log::warn!("Failed to show diff pane: {}", e);
-                    }
-                    KeyResult::Handled
+            _ => {}
+        }
+
+        match Self::main_mode_action(key) {
+            Some(action) => {
+                debug!("User pressed {:?} - {}", key.code, action.description());
+                if app.apply_action(action) {
+                    log::info!("User requested quit");
+                    KeyResult::Quit
                 } else {
-                    // Fall through to default Ctrl+D behavior (single pane diff)
-                    app.set_single_pane_diff();
                     KeyResult::Handled
                 }
             }
-            _ => KeyResult::NotHandled,
+            None => KeyResult::NotHandled,
         }
     }
+
+    /// The action a key is bound to in main mode, leaving out multi-key sequences like `g g`
+    pub fn main_mode_action(key: &KeyEvent) -> Option<Action> {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let plain = key.modifiers.is_empty();
+        let action = match key.code {
+            KeyCode::Char('q') => Action::Quit,
+            KeyCode::Char('c') if control => Action::Quit,
+            KeyCode::Char('G') if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                Action::ScrollDiff(ScrollAction::Bottom)
+            }
+            KeyCode::Char('j') if plain => Action::ScrollDiff(ScrollAction::LineDown),
+            KeyCode::Char('e') if control => Action::ScrollDiff(ScrollAction::LineDown),
+            KeyCode::Down => Action::ScrollDiff(ScrollAction::LineDown),
+            KeyCode::Char('k') if plain => Action::ScrollDiff(ScrollAction::LineUp),
+            KeyCode::Char('y') if control => Action::ScrollDiff(ScrollAction::LineUp),
+            KeyCode::Up => Action::ScrollDiff(ScrollAction::LineUp),
+            KeyCode::Char('d') if plain => Action::ScrollDiff(ScrollAction::HalfPageDown),
+            KeyCode::Char('u') if plain => Action::ScrollDiff(ScrollAction::HalfPageUp),
+            KeyCode::Char('f') if plain => Action::ScrollDiff(ScrollAction::PageDown),
+            KeyCode::PageDown => Action::ScrollDiff(ScrollAction::PageDown),
+            KeyCode::Char('b') if plain => Action::ScrollDiff(ScrollAction::PageUp),
+            KeyCode::PageUp => Action::ScrollDiff(ScrollAction::PageUp),
+            KeyCode::Right => Action::NextFile,
+            KeyCode::Left => Action::PrevFile,
+            KeyCode::Tab => Action::FocusNextPane,
+            KeyCode::BackTab => Action::FocusPrevPane,
+            KeyCode::Char('p' | 'P')
+                if key
+                    .modifiers
+                    .contains(KeyModifiers::CONTROL | KeyModifiers::SHIFT) =>
+            {
+                Action::OpenCommandPalette
+            }
+            KeyCode::Char(':') => Action::OpenCommandPalette,
+            KeyCode::Char('/') => Action::OpenSearch,
+            KeyCode::Char('e') if plain => Action::OpenInEditor,
+            KeyCode::Char('x') if plain => Action::OpenExternalDiff,
+            KeyCode::Char('i') if plain => Action::ShowDiffStats,
+            KeyCode::Char('n') if plain => Action::NextHunk,
+            KeyCode::Char('N') => Action::PrevHunk,
+            KeyCode::Char('z') if plain => Action::ToggleFolding,
+            KeyCode::Char('V') => Action::SelectDiffLines,
+            KeyCode::Char('v') if plain => Action::CycleViewMode,
+            KeyCode::Char('S') => Action::ToggleStagedView,
+            KeyCode::Char('p') if plain => Action::CopyPatch,
+            KeyCode::Char('P') => Action::SavePatch,
+            KeyCode::Char('s') if plain => Action::StageHunk,
+            KeyCode::Char('X') => Action::DiscardFile,
+            KeyCode::Char('B') => Action::ToggleStagedAndUnstagedView,
+            KeyCode::Char('C') => Action::DraftCommitMessage,
+            KeyCode::Char('$') => Action::ShowTokenUsage,
+            KeyCode::Char('?') => Action::ToggleHelp,
+            KeyCode::Char('t') if control => Action::ToggleTheme,
+            KeyCode::Char('s') if control => Action::SideBySideDiff,
+            KeyCode::Char('h') if control => Action::ToggleDiffPanel,
+            KeyCode::Char('b') if control => Action::ToggleChangedFilesPane,
+            KeyCode::Char('m') if control => Action::ToggleMonitorPane,
+            KeyCode::Char('l') if control => Action::ToggleAdvicePanel,
+            KeyCode::Char('d') if control => Action::SinglePaneDiff,
+            _ => return None,
+        };
+        Some(action)
+    }
 }
 
 /// Key handling utilities for panes
@@ -295,7 +533,7 @@ impl PaneKeyUtils {
             }
             KeyCode::Char('G') if key.modifiers.contains(KeyModifiers::SHIFT) => {
                 let visible_lines = 20; // Approximate visible area height
-                *scroll_offset = content_line_count.saturating_sub(visible_lines).max(0);
+                *scroll_offset = content_line_count.saturating_sub(visible_lines);
                 true
             }
             _ => false,
@@ -316,10 +554,46 @@ impl AdvicePanelKeyHandler {
 
         match advice_panel.mode {
             AdviceMode::Chatting => Self::handle_chatting_mode_keys(advice_panel, key),
+            AdviceMode::Improvements => Self::handle_improvements_mode_keys(advice_panel, key),
             AdviceMode::Help => Self::handle_help_mode_keys(advice_panel, key),
         }
     }
 
+    /// Handle keys in the improvements view
+    fn handle_improvements_mode_keys(
+        advice_panel: &mut super::advice_panel::AdvicePanel,
+        key: &KeyEvent,
+    ) -> bool {
+        match key.code {
+            KeyCode::Tab => {
+                advice_panel.toggle_improvements_view();
+                true
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                advice_panel.move_improvement_selection(1);
+                true
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                advice_panel.move_improvement_selection(-1);
+                true
+            }
+            KeyCode::Enter => {
+                advice_panel.toggle_selected_improvement();
+                true
+            }
+            KeyCode::Char('y') => {
+                advice_panel.copy_selected_example();
+                true
+            }
+            KeyCode::Esc => {
+                // Leave the view, and let the parent close the panel as from the chat
+                advice_panel.toggle_improvements_view();
+                false
+            }
+            _ => false,
+        }
+    }
+
     /// Handle keys when in chat mode
     fn handle_chatting_mode_keys(
         advice_panel: &mut super::advice_panel::AdvicePanel,
@@ -380,6 +654,14 @@ impl AdvicePanelKeyHandler {
                     advice_panel.chat_input_active = true;
                     true
                 }
+                KeyCode::Tab => {
+                    advice_panel.toggle_improvements_view();
+                    true
+                }
+                KeyCode::Char('r') if advice_panel.can_retry() => {
+                    advice_panel.retry_failed_request();
+                    true
+                }
                 KeyCode::Char('?') => {
                     advice_panel.mode = AdviceMode::Help;
                     // Reset scroll offset when entering help mode
@@ -400,6 +682,13 @@ impl AdvicePanelKeyHandler {
                         "  /                  - Activate chat input",
                         "  Enter              - Send message (when input active)",
                         "  Esc                - Deactivate chat input",
+                        "  r                  - Retry a failed request",
+                        "",
+                        "Improvements View:",
+                        "  Tab                - Switch between chat and improvements",
+                        "  j / k / ↑ / ↓     - Select an improvement",
+                        "  Enter              - Show or hide its code examples",
+                        "  y                  - Copy a code example (again for the next one)",
                         "",
                         "Panel Controls:",
                         "  Ctrl+L             - Toggle advice panel",
@@ -435,7 +724,8 @@ impl AdvicePanelKeyHandler {
                             for msg in messages {
                                 // Skip user messages that contain the diff pattern
                                 if msg.role == super::advice_panel::MessageRole::User
-                                    && msg.content.contains("Please provide 3 actionable improvements for the following code changes:") {
+                                    && crate::llm::is_advice_prompt(&msg.content)
+                                {
                                     continue;
                                 }
                                 // Count header line
@@ -505,3 +795,338 @@ impl AdvicePanelKeyHandler {
         }
     }
 }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{FileDiff, TreeNode};
+    use crate::ui::Theme;
+    use std::sync::Arc;
+
+    fn create_test_app() -> App {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let llm_state = Arc::new(crate::shared_state::LlmSharedState::new());
+        let mut app = App::new_with_config(true, true, 0, themes, None, llm_state);
+        app.current_diff_height = 5;
+        app.update_files(vec![FileDiff {
+            path: std::path::PathBuf::from("a.rs"),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_MODIFIED,
+            line_strings: (0..50).map(|i| format!("+line {i}")).collect(),
+            additions: 50,
+            deletions: 0,
+        }]);
+        app
+    }
+
+    fn press(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            let modifiers = if c.is_ascii_uppercase() {
+                KeyModifiers::SHIFT
+            } else {
+                KeyModifiers::NONE
+            };
+            GlobalKeyHandler::handle_global_key(app, &KeyEvent::new(KeyCode::Char(c), modifiers));
+        }
+    }
+
+    #[test]
+    fn test_count_prefix_repeats_motion() {
+        let mut app = create_test_app();
+
+        press(&mut app, "5j");
+        assert_eq!(app.get_scroll_offset(), 5);
+        assert_eq!(app.pending_count(), None);
+
+        press(&mut app, "2k");
+        assert_eq!(app.get_scroll_offset(), 3);
+
+        // The count only applies to the next motion
+        press(&mut app, "j");
+        assert_eq!(app.get_scroll_offset(), 4);
+
+        press(&mut app, "10j");
+        assert_eq!(app.get_scroll_offset(), 14);
+    }
+
+    #[test]
+    fn test_hunk_jumps() {
+        let mut app = create_test_app();
+        let mut lines: Vec<String> = (0..30).map(|i| format!(" line {i}")).collect();
+        for hunk in [2, 12, 20] {
+            lines[hunk] = format!("@@ -{hunk},3 +{hunk},3 @@");
+        }
+        let file = FileDiff {
+            line_strings: lines,
+            ..app.get_files()[0].clone()
+        };
+        app.update_files(vec![file]);
+
+        press(&mut app, "]c");
+        assert_eq!(app.get_scroll_offset(), 2);
+        press(&mut app, "n");
+        assert_eq!(app.get_scroll_offset(), 12);
+        press(&mut app, "n");
+        assert_eq!(app.get_scroll_offset(), 20);
+        press(&mut app, "n");
+        assert_eq!(app.get_scroll_offset(), 20);
+        assert_eq!(app.get_status_message(), Some("No next hunk"));
+
+        press(&mut app, "[c");
+        assert_eq!(app.get_scroll_offset(), 12);
+        press(&mut app, "N");
+        assert_eq!(app.get_scroll_offset(), 2);
+
+        // A `c` on its own does nothing
+        press(&mut app, "c");
+        assert_eq!(app.get_scroll_offset(), 2);
+
+        // Folded, hunks are found among the rows left showing
+        app.current_diff_height = 2;
+        press(&mut app, "ggz");
+        assert_eq!(app.get_scroll_offset(), 0);
+        press(&mut app, "n");
+        assert_eq!(app.get_scroll_offset(), 1);
+        press(&mut app, "n");
+        assert_eq!(app.get_scroll_offset(), 3);
+    }
+
+    #[test]
+    fn test_scroll_keys_follow_focus() {
+        let mut app = create_test_app();
+        let mut files = app.get_files().to_vec();
+        files.push(FileDiff {
+            path: std::path::PathBuf::from("b.rs"),
+            ..files[0].clone()
+        });
+        let file_node = |file: &FileDiff| TreeNode {
+            name: file.path.display().to_string(),
+            path: file.path.clone(),
+            is_dir: false,
+            children: Vec::new(),
+            file_diff: Some(file.clone()),
+            untracked: false,
+        };
+        let root = TreeNode {
+            name: ".".to_string(),
+            path: std::path::PathBuf::from("."),
+            is_dir: true,
+            children: files.iter().map(file_node).collect(),
+            file_diff: None,
+            untracked: false,
+        };
+        app.update_files(files);
+        app.update_tree(&root);
+
+        // Tab from the diff wraps round to the file tree, where j picks the next file
+        GlobalKeyHandler::handle_global_key(
+            &mut app,
+            &KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE),
+        );
+        assert_eq!(app.get_focused_pane(), ActivePane::FileTree);
+        let first = app.get_current_file().map(|f| f.path.clone());
+        press(&mut app, "j");
+        assert_ne!(app.get_current_file().map(|f| f.path.clone()), first);
+        assert_eq!(app.get_scroll_offset(), 0);
+
+        // ' starts a name jump, and other letters keep their hotkeys
+        press(&mut app, "'a");
+        assert_eq!(app.get_current_file().map(|f| f.path.clone()), first);
+        press(&mut app, "G");
+        assert_eq!(app.get_scroll_offset(), 45);
+        press(&mut app, "gg");
+        assert_eq!(app.get_scroll_offset(), 0);
+
+        GlobalKeyHandler::handle_global_key(
+            &mut app,
+            &KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT),
+        );
+        assert_eq!(app.get_focused_pane(), ActivePane::Diff);
+        press(&mut app, "j");
+        assert_eq!(app.get_scroll_offset(), 1);
+    }
+
+    #[test]
+    fn test_count_prefix_jumps_to_line() {
+        let mut app = create_test_app();
+
+        press(&mut app, "10G");
+        assert_eq!(app.get_scroll_offset(), 9);
+
+        // Without a count, G still goes to the bottom
+        press(&mut app, "G");
+        assert_eq!(app.get_scroll_offset(), 45);
+
+        // A leading zero is not a count
+        press(&mut app, "0");
+        assert_eq!(app.pending_count(), None);
+    }
+
+    #[test]
+    fn test_count_prefix_reset_by_other_keys() {
+        let mut app = create_test_app();
+
+        press(&mut app, "3");
+        assert_eq!(app.pending_count(), Some(3));
+        press(&mut app, "x");
+        assert_eq!(app.pending_count(), None);
+
+        press(&mut app, "j");
+        assert_eq!(app.get_scroll_offset(), 1);
+    }
+
+    #[test]
+    fn test_page_scroll_keys() {
+        let mut app = create_test_app();
+
+        // Half and full pages are measured by the 5-line diff height
+        press(&mut app, "d");
+        assert_eq!(app.get_scroll_offset(), 2);
+        press(&mut app, "f");
+        assert_eq!(app.get_scroll_offset(), 7);
+        press(&mut app, "u");
+        assert_eq!(app.get_scroll_offset(), 5);
+        press(&mut app, "b");
+        assert_eq!(app.get_scroll_offset(), 0);
+
+        press(&mut app, "2f");
+        assert_eq!(app.get_scroll_offset(), 10);
+        press(&mut app, "gg");
+        assert_eq!(app.get_scroll_offset(), 0);
+    }
+
+    #[test]
+    fn test_any_key_dismisses_diff_stats() {
+        let mut app = create_test_app();
+
+        press(&mut app, "i");
+        assert!(app.is_showing_diff_stats());
+
+        // The dismissing key is not also treated as a motion
+        press(&mut app, "j");
+        assert!(!app.is_showing_diff_stats());
+        assert_eq!(app.get_scroll_offset(), 0);
+    }
+
+    #[test]
+    fn test_token_usage_popup() {
+        let mut app = create_test_app();
+
+        press(&mut app, "$");
+        assert!(app.is_showing_token_usage());
+
+        press(&mut app, "j");
+        assert!(!app.is_showing_token_usage());
+        assert_eq!(app.get_scroll_offset(), 0);
+    }
+
+    #[test]
+    fn test_open_in_editor_request() {
+        let mut app = create_test_app();
+
+        // The test file doesn't exist on disk, so there is nothing to edit
+        press(&mut app, "e");
+        assert_eq!(app.take_editor_request(), None);
+        assert_eq!(app.get_status_message(), Some("a.rs no longer exists"));
+
+        let manifest = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        app.update_files(vec![FileDiff {
+            path: manifest.clone(),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_MODIFIED,
+            line_strings: vec![],
+            additions: 0,
+            deletions: 0,
+        }]);
+        press(&mut app, "e");
+        assert_eq!(app.take_editor_request(), Some((manifest, Some(1))));
+        assert_eq!(app.take_editor_request(), None);
+    }
+
+    #[test]
+    fn test_discard_needs_confirmation() {
+        let mut app = create_test_app();
+
+        // Keys go to the popup, not the diff, until it's answered
+        press(&mut app, "X");
+        assert!(app.is_confirming());
+        press(&mut app, "jn");
+        assert!(!app.is_confirming());
+        assert_eq!(app.get_scroll_offset(), 0);
+        assert!(app.take_discard_request().is_none());
+
+        press(&mut app, "Xy");
+        assert_eq!(
+            app.take_discard_request(),
+            Some(crate::ui::DiscardRequest {
+                path: std::path::PathBuf::from("a.rs"),
+                old_path: None,
+                untracked: false,
+            })
+        );
+
+        // Staged changes aren't the working tree's to discard, and the dirty directory
+        // view's diffs against HEAD don't say which changes are
+        for view_mode in [
+            crate::git::ViewMode::Staged,
+            crate::git::ViewMode::DirtyDirectory,
+        ] {
+            app.set_view_mode(view_mode);
+            press(&mut app, "X");
+            assert!(!app.is_confirming());
+            assert_eq!(
+                app.get_status_message(),
+                Some("Only working tree changes can be discarded")
+            );
+        }
+    }
+
+    #[test]
+    fn test_ctrl_shortcuts_wait_for_confirmation() {
+        let mut app = create_test_app();
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+        press(&mut app, "X");
+        GlobalKeyHandler::handle_global_key(&mut app, &ctrl('o'));
+        GlobalKeyHandler::handle_global_key(&mut app, &ctrl('p'));
+        assert!(app.is_confirming());
+        assert!(!app.is_showing_monitor_pane());
+        assert_eq!(app.take_main_loop_action(), None);
+
+        press(&mut app, "n");
+        GlobalKeyHandler::handle_global_key(&mut app, &ctrl('o'));
+        GlobalKeyHandler::handle_global_key(&mut app, &ctrl('p'));
+        assert!(app.is_showing_monitor_pane());
+        assert_eq!(app.take_main_loop_action(), Some(Action::OpenCommitPicker));
+    }
+
+    #[test]
+    fn test_command_palette_runs_action() {
+        let mut app = create_test_app();
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        let theme = app.get_theme().clone();
+
+        press(&mut app, ":");
+        assert!(app.is_showing_command_palette());
+        // Typing filters instead of scrolling the diff
+        press(&mut app, "theme");
+        assert_eq!(app.get_scroll_offset(), 0);
+        GlobalKeyHandler::handle_global_key(&mut app, &enter);
+        assert!(!app.is_showing_command_palette());
+        assert_ne!(app.get_theme(), &theme);
+
+        // Actions needing the config or git state are left to the main loop
+        press(&mut app, ":reflog");
+        GlobalKeyHandler::handle_global_key(&mut app, &enter);
+        assert_eq!(app.take_main_loop_action(), Some(Action::OpenReflog));
+
+        press(&mut app, ":");
+        GlobalKeyHandler::handle_global_key(
+            &mut app,
+            &KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+        );
+        assert!(!app.is_showing_command_palette());
+        assert!(app.take_main_loop_action().is_none());
+    }
+}
@@ -11,27 +11,47 @@ use crate::ui::{App, Theme};
 
 // Module declarations
 mod advice_panel;
+mod command_palette_pane;
+mod commit_draft_pane;
+mod commit_message_pane;
 mod commit_picker_pane;
 mod commit_summary_pane;
+mod confirm_dialog;
 mod diff_pane;
+mod diff_stats_pane;
+mod explanation_pane;
 mod file_tree_pane;
 mod help_pane;
+mod icons;
 mod keys;
 mod monitor_pane;
+mod scroll_state;
+mod search_pane;
 mod side_by_side_diff_pane;
 mod status_bar_pane;
+mod token_usage_pane;
 
 // Re-exports to maintain public API
 pub use advice_panel::*;
+pub use command_palette_pane::*;
+pub use commit_draft_pane::*;
+pub use commit_message_pane::*;
 pub use commit_picker_pane::*;
 pub use commit_summary_pane::*;
+pub use confirm_dialog::*;
 pub use diff_pane::*;
+pub use diff_stats_pane::*;
+pub use explanation_pane::*;
 pub use file_tree_pane::*;
 pub use help_pane::*;
+pub use icons::*;
 pub use keys::*;
 pub use monitor_pane::*;
+pub use scroll_state::*;
+pub use search_pane::*;
 pub use side_by_side_diff_pane::*;
 pub use status_bar_pane::*;
+pub use token_usage_pane::*;
 
 // Core trait that all panes implement
 pub trait Pane {
@@ -61,6 +81,52 @@ pub trait Pane {
     fn as_advice_pane_mut(&mut self) -> Option<&mut AdvicePanel> {
         None
     }
+    fn as_search_pane(&self) -> Option<&SearchPane> {
+        None
+    }
+    fn as_search_pane_mut(&mut self) -> Option<&mut SearchPane> {
+        None
+    }
+    fn as_commit_message_pane_mut(&mut self) -> Option<&mut CommitMessagePane> {
+        None
+    }
+    fn as_diff_stats_pane_mut(&mut self) -> Option<&mut DiffStatsPane> {
+        None
+    }
+    fn as_token_usage_pane_mut(&mut self) -> Option<&mut TokenUsagePane> {
+        None
+    }
+    fn as_explanation_pane_mut(&mut self) -> Option<&mut ExplanationPane> {
+        None
+    }
+    fn as_commit_draft_pane_mut(&mut self) -> Option<&mut CommitDraftPane> {
+        None
+    }
+    fn as_command_palette_pane(&self) -> Option<&CommandPalettePane> {
+        None
+    }
+    fn as_command_palette_pane_mut(&mut self) -> Option<&mut CommandPalettePane> {
+        None
+    }
+}
+
+/// A `width` by `height` area centered in `area`, shrunk to fit it
+pub fn centered_area(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Centered area taking most of `area`, leaving the diff visible around the edges
+pub fn large_popup_area(area: Rect) -> Rect {
+    let width = (area.width * 4 / 5).max(area.width.min(40));
+    let height = (area.height * 3 / 5).max(area.height.min(8));
+    centered_area(area, width, height)
 }
 
 // Shared enums and types
@@ -75,12 +141,18 @@ pub enum PaneId {
     CommitPicker,
     CommitSummary,
     Advice,
+    Search,
+    CommitMessage,
+    DiffStats,
+    TokenUsage,
+    Explanation,
+    CommitDraft,
+    CommandPalette,
 }
 
 #[derive(Debug, Clone)]
 pub enum AppEvent {
     Key(KeyEvent),
-    DataUpdated((), String),
     ThemeChanged(()),
 }
 
@@ -100,7 +172,13 @@ impl std::fmt::Debug for PaneRegistry {
 }
 
 impl PaneRegistry {
-    pub fn new(theme: Theme, llm_client: LlmClient, llm_shared_state: Arc<LlmSharedState>) -> Self {
+    /// Register every pane; those that talk to the LLM say it isn't configured when
+    /// `llm_client` is None
+    pub fn new(
+        theme: Theme,
+        llm_client: Option<LlmClient>,
+        llm_shared_state: Arc<LlmSharedState>,
+    ) -> Self {
         let mut registry = Self {
             panes: HashMap::new(),
             theme,
@@ -112,7 +190,7 @@ impl PaneRegistry {
 
     fn register_default_panes(
         &mut self,
-        llm_client: LlmClient,
+        llm_client: Option<LlmClient>,
         llm_shared_state: Arc<LlmSharedState>,
     ) {
         self.register_pane(PaneId::FileTree, Box::new(FileTreePane::new()));
@@ -122,8 +200,20 @@ impl PaneRegistry {
         self.register_pane(PaneId::Help, Box::new(HelpPane::new()));
         self.register_pane(PaneId::StatusBar, Box::new(StatusBarPane::new()));
         self.register_pane(PaneId::CommitPicker, Box::new(CommitPickerPane::new()));
-        let mut commit_summary_pane =
-            CommitSummaryPane::new_with_llm_client(Some(llm_client.clone()));
+        self.register_pane(PaneId::Search, Box::new(SearchPane::new()));
+        self.register_pane(PaneId::CommitMessage, Box::new(CommitMessagePane::new()));
+        self.register_pane(PaneId::DiffStats, Box::new(DiffStatsPane::new()));
+        self.register_pane(PaneId::TokenUsage, Box::new(TokenUsagePane::new()));
+        self.register_pane(PaneId::CommandPalette, Box::new(CommandPalettePane::new()));
+        self.register_pane(
+            PaneId::Explanation,
+            Box::new(ExplanationPane::new(llm_client.clone())),
+        );
+        self.register_pane(
+            PaneId::CommitDraft,
+            Box::new(CommitDraftPane::new(llm_client.clone())),
+        );
+        let mut commit_summary_pane = CommitSummaryPane::new_with_llm_client(llm_client.clone());
         commit_summary_pane.set_shared_state(llm_shared_state.clone());
         self.register_pane(PaneId::CommitSummary, Box::new(commit_summary_pane));
 
@@ -131,13 +221,13 @@ impl PaneRegistry {
         let mut advice_panel = AdvicePanel::new().expect("Failed to create AdvicePanel");
         advice_panel.set_shared_state(llm_shared_state.clone());
 
-        // Extract max_tokens from LlmClient config and set it
-        let max_tokens = llm_client.get_max_tokens();
-        advice_panel.set_max_tokens(max_tokens);
-
-        advice_panel.set_llm_client(std::sync::Arc::new(tokio::sync::Mutex::new(
-            llm_client.clone(),
-        )));
+        // Advice diffs are truncated to the same limit as summaries
+        if let Some(llm_client) = llm_client {
+            advice_panel.set_max_diff_chars(llm_client.get_max_diff_chars());
+            advice_panel.set_advice_model(llm_client.get_advice_model());
+            advice_panel.set_advice_count(llm_client.get_advice_count());
+            advice_panel.set_llm_client(std::sync::Arc::new(tokio::sync::Mutex::new(llm_client)));
+        }
         self.register_pane(PaneId::Advice, Box::new(advice_panel));
     }
 
@@ -185,29 +275,35 @@ impl PaneRegistry {
 mod tests {
     use super::*;
     use crate::config::LlmConfig;
-    use std::env;
     use std::sync::Arc;
 
     fn create_test_pane_registry() -> PaneRegistry {
-        let mut llm_config = LlmConfig::default();
-        if env::var("OPENAI_API_KEY").is_err() {
-            llm_config.api_key = Some("dummy_key".to_string());
-        }
+        let llm_config = LlmConfig {
+            provider: Some(crate::config::LlmProvider::Mock),
+            ..Default::default()
+        };
         let llm_client = crate::llm::LlmClient::new(llm_config).unwrap();
         let llm_shared_state = Arc::new(crate::shared_state::LlmSharedState::new());
-        PaneRegistry::new(crate::ui::Theme::Dark, llm_client, llm_shared_state)
+        PaneRegistry::new(crate::ui::Theme::Dark, Some(llm_client), llm_shared_state)
     }
 
     #[test]
     fn test_pane_registry_creation() {
         let registry = create_test_pane_registry();
-        assert_eq!(registry.panes.len(), 9); // Default panes + commit picker + commit summary + advice pane
+        assert_eq!(registry.panes.len(), 16); // Default panes + commit picker + commit summary + advice + search + commit message + diff stats + token usage + explanation + commit draft + command palette pane
         assert!(registry.get_pane(&PaneId::FileTree).is_some());
         assert!(registry.get_pane(&PaneId::Monitor).is_some());
         assert!(registry.get_pane(&PaneId::Diff).is_some());
         assert!(registry.get_pane(&PaneId::CommitPicker).is_some());
         assert!(registry.get_pane(&PaneId::CommitSummary).is_some());
         assert!(registry.get_pane(&PaneId::Advice).is_some());
+        assert!(registry.get_pane(&PaneId::Search).is_some());
+        assert!(registry.get_pane(&PaneId::CommitMessage).is_some());
+        assert!(registry.get_pane(&PaneId::DiffStats).is_some());
+        assert!(registry.get_pane(&PaneId::TokenUsage).is_some());
+        assert!(registry.get_pane(&PaneId::Explanation).is_some());
+        assert!(registry.get_pane(&PaneId::CommitDraft).is_some());
+        assert!(registry.get_pane(&PaneId::CommandPalette).is_some());
     }
 
     #[test]
@@ -237,10 +333,10 @@ mod tests {
         use std::sync::Arc;
 
         // Create app using the same pattern as existing tests
-        let mut llm_config = crate::config::LlmConfig::default();
-        if std::env::var("OPENAI_API_KEY").is_err() {
-            llm_config.api_key = Some("dummy_key".to_string());
-        }
+        let llm_config = crate::config::LlmConfig {
+            provider: Some(crate::config::LlmProvider::Mock),
+            ..Default::default()
+        };
         let llm_client = crate::llm::LlmClient::new(llm_config).ok();
         let llm_state = Arc::new(crate::shared_state::LlmSharedState::new());
         let themes = vec![crate::ui::Theme::Dark, crate::ui::Theme::Light];
@@ -278,10 +374,10 @@ mod tests {
         use std::sync::Arc;
 
         // Create app with same pattern as tests
-        let mut llm_config = crate::config::LlmConfig::default();
-        if std::env::var("OPENAI_API_KEY").is_err() {
-            llm_config.api_key = Some("dummy_key".to_string());
-        }
+        let llm_config = crate::config::LlmConfig {
+            provider: Some(crate::config::LlmProvider::Mock),
+            ..Default::default()
+        };
         let llm_client = crate::llm::LlmClient::new(llm_config).ok();
         let llm_state = Arc::new(crate::shared_state::LlmSharedState::new());
         let themes = vec![crate::ui::Theme::Dark, crate::ui::Theme::Light];
@@ -1,4 +1,3 @@
-use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::{
     Frame,
     layout::Rect,
@@ -9,12 +8,11 @@ use ratatui::{
 
 use super::{AppEvent, Pane};
 use crate::git::GitRepo;
-use crate::ui::App;
+use crate::ui::{ActivePane, App};
 
+/// The monitor command's output, drawn from the app's bounded `MonitorBuffer`
 pub struct MonitorPane {
     visible: bool,
-    scroll_offset: usize,
-    output: String,
 }
 
 impl Default for MonitorPane {
@@ -25,15 +23,7 @@ impl Default for MonitorPane {
 
 impl MonitorPane {
     pub fn new() -> Self {
-        Self {
-            visible: false,
-            scroll_offset: 0,
-            output: String::new(),
-        }
-    }
-
-    pub fn update_output(&mut self, output: String) {
-        self.output = output;
+        Self { visible: false }
     }
 }
 
@@ -50,11 +40,12 @@ impl Pane for MonitorPane {
         _git_repo: &GitRepo,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let theme = app.get_theme();
-        let monitor_lines: Vec<_> = self.output.lines().skip(self.scroll_offset).collect();
         let visible_lines = area.height.saturating_sub(2) as usize;
 
-        let display_lines: Vec<Line> = monitor_lines
-            .iter()
+        let display_lines: Vec<Line> = app
+            .get_monitor_output()
+            .lines()
+            .skip(app.get_monitor_scroll_offset())
             .take(visible_lines)
             .map(|line| {
                 Line::from(Span::styled(
@@ -69,7 +60,7 @@ impl Pane for MonitorPane {
         } else if !app.get_monitor_has_run() {
             "Monitor ⏳ loading...".to_string()
         } else if let Some(elapsed) = app.get_monitor_elapsed_time() {
-            let time_str = app.format_elapsed_time(elapsed);
+            let time_str = crate::util::humanize_duration(elapsed);
             format!("Monitor ⏱️ {time_str} ago")
         } else {
             "Monitor Output".to_string()
@@ -81,7 +72,7 @@ impl Pane for MonitorPane {
                 Block::default()
                     .title(title)
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(theme.border_color())),
+                    .border_style(app.pane_border_style(ActivePane::Monitor)),
             )
             .wrap(Wrap { trim: false });
 
@@ -89,25 +80,8 @@ impl Pane for MonitorPane {
         Ok(())
     }
 
-    fn handle_event(&mut self, event: &AppEvent) -> bool {
-        match event {
-            AppEvent::Key(key) => match key.code {
-                KeyCode::Char('j') if key.modifiers.contains(KeyModifiers::ALT) => {
-                    self.scroll_offset = self.scroll_offset.saturating_add(1);
-                    true
-                }
-                KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::ALT) => {
-                    self.scroll_offset = self.scroll_offset.saturating_sub(1);
-                    true
-                }
-                _ => false,
-            },
-            AppEvent::DataUpdated(_, data) => {
-                self.update_output(data.clone());
-                true
-            }
-            _ => false,
-        }
+    fn handle_event(&mut self, _event: &AppEvent) -> bool {
+        false
     }
 
     fn visible(&self) -> bool {
@@ -0,0 +1,142 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A scroll movement shared by every scrollable pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollAction {
+    LineDown,
+    LineUp,
+    HalfPageDown,
+    HalfPageUp,
+    PageDown,
+    PageUp,
+    Top,
+    Bottom,
+}
+
+impl ScrollAction {
+    /// Standard bindings: j/k, d/u half page, f/b full page, G bottom
+    /// `gg` needs two presses, so it is tracked by `ScrollState::handle_key`
+    pub fn from_key(key: &KeyEvent) -> Option<Self> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => Some(Self::LineDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(Self::LineUp),
+            KeyCode::Char('d') => Some(Self::HalfPageDown),
+            KeyCode::Char('u') => Some(Self::HalfPageUp),
+            KeyCode::Char('f') | KeyCode::PageDown => Some(Self::PageDown),
+            KeyCode::Char('b') | KeyCode::PageUp => Some(Self::PageUp),
+            KeyCode::Char('G') => Some(Self::Bottom),
+            _ => None,
+        }
+    }
+}
+
+/// Scroll position of a pane, clamped so it never runs past the content
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScrollState {
+    pub offset: usize,
+    last_g_press: Option<std::time::Instant>,
+}
+
+impl ScrollState {
+    /// Move by `action`, where `page_size` is the number of visible lines
+    pub fn apply(&mut self, action: ScrollAction, max_offset: usize, page_size: usize) {
+        let page_size = page_size.max(1);
+        let half_page = (page_size / 2).max(1);
+
+        // Moving up never needs clamping, so a shrinking content length can't make it jump
+        self.offset = match action {
+            ScrollAction::LineDown => self.offset.saturating_add(1).min(max_offset),
+            ScrollAction::LineUp => self.offset.saturating_sub(1),
+            ScrollAction::HalfPageDown => self.offset.saturating_add(half_page).min(max_offset),
+            ScrollAction::HalfPageUp => self.offset.saturating_sub(half_page),
+            ScrollAction::PageDown => self.offset.saturating_add(page_size).min(max_offset),
+            ScrollAction::PageUp => self.offset.saturating_sub(page_size),
+            ScrollAction::Top => 0,
+            ScrollAction::Bottom => max_offset,
+        };
+    }
+
+    /// Apply the standard scroll bindings, including `gg` for top
+    /// Returns true if the key was a scroll key
+    pub fn handle_key(&mut self, key: &KeyEvent, max_offset: usize, page_size: usize) -> bool {
+        if key.code == KeyCode::Char('g') && !key.modifiers.contains(KeyModifiers::CONTROL) {
+            let now = std::time::Instant::now();
+            let is_double_press = self
+                .last_g_press
+                .is_some_and(|last| now.duration_since(last).as_millis() < 500);
+            if is_double_press {
+                self.apply(ScrollAction::Top, max_offset, page_size);
+                self.last_g_press = None;
+            } else {
+                self.last_g_press = Some(now);
+            }
+            return true;
+        }
+
+        match ScrollAction::from_key(key) {
+            Some(action) => {
+                self.apply(action, max_offset, page_size);
+                true
+            }
+            None => false,
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_actions_clamp_to_content() {
+        let mut scroll = ScrollState::default();
+
+        scroll.apply(ScrollAction::HalfPageDown, 30, 10);
+        assert_eq!(scroll.offset, 5);
+        scroll.apply(ScrollAction::PageDown, 30, 10);
+        assert_eq!(scroll.offset, 15);
+        scroll.apply(ScrollAction::PageDown, 30, 10);
+        scroll.apply(ScrollAction::PageDown, 30, 10);
+        assert_eq!(scroll.offset, 30);
+        scroll.apply(ScrollAction::LineDown, 30, 10);
+        assert_eq!(scroll.offset, 30);
+
+        scroll.apply(ScrollAction::HalfPageUp, 30, 10);
+        assert_eq!(scroll.offset, 25);
+        scroll.apply(ScrollAction::Top, 30, 10);
+        assert_eq!(scroll.offset, 0);
+        scroll.apply(ScrollAction::LineUp, 30, 10);
+        assert_eq!(scroll.offset, 0);
+        scroll.apply(ScrollAction::Bottom, 30, 10);
+        assert_eq!(scroll.offset, 30);
+    }
+
+    #[test]
+    fn test_scroll_key_bindings() {
+        let mut scroll = ScrollState::default();
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+
+        assert!(scroll.handle_key(&key('f'), 100, 20));
+        assert_eq!(scroll.offset, 20);
+        assert!(scroll.handle_key(&key('u'), 100, 20));
+        assert_eq!(scroll.offset, 10);
+
+        // A single g waits for the second one
+        assert!(scroll.handle_key(&key('g'), 100, 20));
+        assert_eq!(scroll.offset, 10);
+        assert!(scroll.handle_key(&key('g'), 100, 20));
+        assert_eq!(scroll.offset, 0);
+
+        let shift_g = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert!(scroll.handle_key(&shift_g, 100, 20));
+        assert_eq!(scroll.offset, 100);
+
+        // Ctrl combinations are left for other bindings
+        let ctrl_d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert!(!scroll.handle_key(&ctrl_d, 100, 20));
+        assert!(!scroll.handle_key(&key('x'), 100, 20));
+    }
+}
@@ -0,0 +1,327 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use super::{AppEvent, Pane};
+use crate::git::{FileDiff, GitRepo};
+use crate::ui::App;
+
+/// A line in one of the changed files that contains the search query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatch {
+    pub file_index: usize,
+    pub line_index: usize,
+    pub line: String,
+}
+
+pub struct SearchPane {
+    visible: bool,
+    query: String,
+    results: Vec<SearchMatch>,
+    current_index: usize,
+}
+
+impl Default for SearchPane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchPane {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            query: String::new(),
+            results: Vec::new(),
+            current_index: 0,
+        }
+    }
+
+    pub fn get_current_match(&self) -> Option<&SearchMatch> {
+        self.results.get(self.current_index)
+    }
+
+    /// Clear the query and results so the next search starts fresh
+    pub fn reset(&mut self) {
+        self.query.clear();
+        self.results.clear();
+        self.current_index = 0;
+    }
+
+    /// Rescan every file for the current query, case-insensitively
+    pub fn update_results(&mut self, files: &[FileDiff]) {
+        self.results.clear();
+
+        if !self.query.is_empty() {
+            let needle = self.query.to_lowercase();
+            for (file_index, file) in files.iter().enumerate() {
+                for (line_index, line) in file.line_strings.iter().enumerate() {
+                    if line.to_lowercase().contains(&needle) {
+                        self.results.push(SearchMatch {
+                            file_index,
+                            line_index,
+                            line: line.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        if self.current_index >= self.results.len() {
+            self.current_index = 0;
+        }
+    }
+
+    fn navigate_next(&mut self) {
+        if !self.results.is_empty() {
+            self.current_index = (self.current_index + 1) % self.results.len();
+        }
+    }
+
+    fn navigate_prev(&mut self) {
+        if !self.results.is_empty() {
+            self.current_index = if self.current_index == 0 {
+                self.results.len() - 1
+            } else {
+                self.current_index - 1
+            };
+        }
+    }
+
+    fn visible_start(&self, visible_height: usize) -> usize {
+        // Scroll just far enough to keep the current result on screen
+        (self.current_index + 1).saturating_sub(visible_height)
+    }
+}
+
+impl Pane for SearchPane {
+    fn title(&self) -> String {
+        "Search Changed Files".to_string()
+    }
+
+    fn render(
+        &self,
+        f: &mut Frame,
+        app: &App,
+        area: Rect,
+        _git_repo: &GitRepo,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = app.get_theme();
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled("/", Style::default().fg(theme.secondary_color())),
+            Span::styled(
+                self.query.as_str(),
+                Style::default().fg(theme.foreground_color()),
+            ),
+            Span::styled("_", Style::default().fg(theme.secondary_color())),
+        ]))
+        .block(
+            Block::default()
+                .title(self.title())
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border_color())),
+        );
+        f.render_widget(input, chunks[0]);
+
+        let results_title = format!("Results ({})", self.results.len());
+        if self.results.is_empty() {
+            let message = if self.query.is_empty() {
+                "Type to search every changed file"
+            } else {
+                "No matches"
+            };
+            let paragraph = Paragraph::new(message).block(
+                Block::default()
+                    .title(results_title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border_color())),
+            );
+            f.render_widget(paragraph, chunks[1]);
+            return Ok(());
+        }
+
+        let visible_height = chunks[1].height.saturating_sub(2) as usize;
+        let start_index = self.visible_start(visible_height);
+        let files = app.get_files();
+
+        let items: Vec<ListItem> = self
+            .results
+            .iter()
+            .enumerate()
+            .skip(start_index)
+            .take(visible_height)
+            .map(|(index, result)| {
+                let mut spans = Vec::new();
+
+                if index == self.current_index {
+                    spans.push(Span::styled(
+                        "-> ",
+                        Style::default()
+                            .fg(theme.secondary_color())
+                            .add_modifier(Modifier::BOLD),
+                    ));
+                } else {
+                    spans.push(Span::raw("   "));
+                }
+
+                let file_path = files
+                    .get(result.file_index)
+                    .map(|file| file.path.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                spans.push(Span::styled(
+                    format!("{}:{} ", file_path, result.line_index + 1),
+                    Style::default().fg(theme.directory_color()),
+                ));
+
+                let line_color = if result.line.starts_with('+') {
+                    theme.added_color()
+                } else if result.line.starts_with('-') {
+                    theme.removed_color()
+                } else {
+                    theme.unchanged_color()
+                };
+                spans.push(Span::styled(
+                    result.line.clone(),
+                    Style::default().fg(line_color),
+                ));
+
+                let line_style = if index == self.current_index {
+                    Style::default()
+                        .bg(theme.highlight_color())
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+
+                ListItem::new(Line::from(spans).style(line_style))
+            })
+            .collect();
+
+        let list = List::new(items).block(
+            Block::default()
+                .title(results_title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border_color())),
+        );
+        f.render_widget(list, chunks[1]);
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &AppEvent) -> bool {
+        match event {
+            AppEvent::Key(key) => match key.code {
+                KeyCode::Down => {
+                    self.navigate_next();
+                    true
+                }
+                KeyCode::Up => {
+                    self.navigate_prev();
+                    true
+                }
+                KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.query.clear();
+                    true
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    true
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.query.push(c);
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn as_search_pane(&self) -> Option<&SearchPane> {
+        Some(self)
+    }
+
+    fn as_search_pane_mut(&mut self) -> Option<&mut SearchPane> {
+        Some(self)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+
+    fn type_query(pane: &mut SearchPane, query: &str) {
+        for c in query.chars() {
+            let event = AppEvent::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+            assert!(pane.handle_event(&event));
+        }
+    }
+
+    #[test]
+    fn test_search_across_files() {
+        let files = vec![
+            FileDiff::for_test("a.rs", &["@@ -1 +1 @@", "-let foo = 1;", "+let Foo = 2;"]),
+            FileDiff::for_test("b.rs", &[" unrelated", "+foo();"]),
+        ];
+        let mut pane = SearchPane::new();
+
+        pane.update_results(&files);
+        assert!(pane.results.is_empty());
+
+        type_query(&mut pane, "foo");
+        pane.update_results(&files);
+        let locations: Vec<(usize, usize)> = pane
+            .results
+            .iter()
+            .map(|m| (m.file_index, m.line_index))
+            .collect();
+        assert_eq!(locations, vec![(0, 1), (0, 2), (1, 1)]);
+
+        // Narrowing the query updates the results
+        type_query(&mut pane, "(");
+        pane.update_results(&files);
+        assert_eq!(pane.results.len(), 1);
+        assert_eq!(pane.get_current_match().unwrap().file_index, 1);
+    }
+
+    #[test]
+    fn test_search_navigation_and_editing() {
+        let files = vec![FileDiff::for_test("a.rs", &["+one", "+two", "+three"])];
+        let mut pane = SearchPane::new();
+        type_query(&mut pane, "t");
+        pane.update_results(&files);
+        assert_eq!(pane.results.len(), 2);
+
+        let down = AppEvent::Key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        assert!(pane.handle_event(&down));
+        assert_eq!(pane.get_current_match().unwrap().line, "+three");
+        assert!(pane.handle_event(&down));
+        assert_eq!(pane.get_current_match().unwrap().line, "+two");
+
+        let backspace = AppEvent::Key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        assert!(pane.handle_event(&backspace));
+        assert_eq!(pane.query, "");
+
+        pane.reset();
+        assert!(pane.results.is_empty());
+        assert!(pane.get_current_match().is_none());
+    }
+}
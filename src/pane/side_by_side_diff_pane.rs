@@ -4,12 +4,33 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::Style,
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Paragraph},
 };
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
+use super::diff_pane::{heatmap_style, hunk_change_density};
 use super::{AppEvent, Pane};
 use crate::git::GitRepo;
-use crate::ui::App;
+use crate::ui::{ActivePane, App};
+
+/// One screen row of a side-by-side column
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ColumnRow {
+    /// Diff line the row is part of, None for padding below the end of the diff
+    line: Option<usize>,
+    text: String,
+}
+
+impl ColumnRow {
+    fn new(line: Option<usize>, text: String) -> Self {
+        Self { line, text }
+    }
+
+    fn padding() -> Self {
+        Self::new(None, String::new())
+    }
+}
 
 pub struct SideBySideDiffPane {
     visible: bool,
@@ -25,6 +46,89 @@ impl SideBySideDiffPane {
     pub fn new() -> Self {
         Self { visible: false }
     }
+
+    /// Hard-wrap a line into chunks at most `width` columns wide, preserving whitespace
+    ///
+    /// Wide graphemes such as CJK characters and emoji take two columns, and are moved
+    /// whole to the next chunk rather than split across the column edge.
+    fn wrap_line(content: &str, width: usize) -> Vec<String> {
+        if width == 0 || content.is_empty() {
+            return vec![content.to_string()];
+        }
+
+        let mut rows = Vec::new();
+        let mut row = String::new();
+        let mut row_width = 0;
+        for grapheme in content.graphemes(true) {
+            let grapheme_width = grapheme.width();
+            if row_width + grapheme_width > width && !row.is_empty() {
+                rows.push(std::mem::take(&mut row));
+                row_width = 0;
+            }
+            row.push_str(grapheme);
+            row_width += grapheme_width;
+        }
+        rows.push(row);
+        rows
+    }
+
+    /// What a diff line shows in the left (old) and right (new) column
+    fn split_line(line: &str) -> (&str, &str) {
+        if let Some(stripped) = line.strip_prefix('+') {
+            ("", stripped)
+        } else if let Some(stripped) = line.strip_prefix('-') {
+            (stripped, "")
+        } else if let Some(stripped) = line.strip_prefix(' ') {
+            (stripped, stripped)
+        } else {
+            (line, line)
+        }
+    }
+
+    /// Rows of the left and right columns for a viewport `height` rows tall, starting at
+    /// diff line `scroll_offset`
+    ///
+    /// Both columns go by the one scroll offset and are built a diff line at a time, so
+    /// they always hold the same lines at the same rows, whatever either side's wrapping
+    /// does; both are padded out to `height` when the diff ends first.
+    fn column_rows(
+        lines: &[String],
+        scroll_offset: usize,
+        height: usize,
+        wrap_width: usize,
+    ) -> (Vec<ColumnRow>, Vec<ColumnRow>) {
+        let mut left = Vec::with_capacity(height);
+        let mut right = Vec::with_capacity(height);
+        for (i, line) in lines.iter().enumerate().skip(scroll_offset) {
+            if left.len() >= height {
+                break;
+            }
+            let (left_content, right_content) = Self::split_line(line);
+            let (left_wrapped, right_wrapped) =
+                Self::wrap_row(left_content, right_content, wrap_width);
+            for (left_text, right_text) in left_wrapped.into_iter().zip(right_wrapped) {
+                if left.len() >= height {
+                    break;
+                }
+                left.push(ColumnRow::new(Some(i), left_text));
+                right.push(ColumnRow::new(Some(i), right_text));
+            }
+        }
+        left.resize(height, ColumnRow::padding());
+        right.resize(height, ColumnRow::padding());
+        (left, right)
+    }
+
+    /// Wrap both sides of a paired row and pad the shorter side with blank
+    /// lines so the two columns stay vertically aligned.
+    fn wrap_row(left: &str, right: &str, width: usize) -> (Vec<String>, Vec<String>) {
+        let mut left_rows = Self::wrap_line(left, width);
+        let mut right_rows = Self::wrap_line(right, width);
+        let height = left_rows.len().max(right_rows.len());
+        left_rows.resize(height, String::new());
+        right_rows.resize(height, String::new());
+        (left_rows, right_rows)
+    }
 }
 
 impl Pane for SideBySideDiffPane {
@@ -49,72 +153,73 @@ impl Pane for SideBySideDiffPane {
                 .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
                 .split(area);
 
-            let mut left_lines = Vec::new();
-            let mut right_lines = Vec::new();
-
-            let mut line_count = 0;
-            for (i, line) in file.line_strings.iter().enumerate() {
-                if i < app.get_scroll_offset() {
-                    continue;
-                }
-
-                if line_count >= app.current_diff_height {
-                    break;
-                }
-
-                let (left_content, right_content) = if let Some(stripped) = line.strip_prefix('+') {
-                    ("".to_string(), stripped.to_string())
-                } else if let Some(stripped) = line.strip_prefix('-') {
-                    (stripped.to_string(), "".to_string())
-                } else if let Some(stripped) = line.strip_prefix(' ') {
-                    let content = stripped.to_string();
-                    (content.clone(), content)
-                } else {
-                    (line.to_string(), line.to_string())
-                };
+            // Wrap manually rather than letting the Paragraph do it, so that a
+            // long line on one side doesn't push the other side out of alignment
+            let wrap_width = chunks[0].width.saturating_sub(2) as usize;
 
-                let left_style = if line.starts_with('-') {
-                    Style::default().fg(theme.removed_color())
+            let heat = app
+                .is_diff_heatmap_enabled()
+                .then(|| hunk_change_density(&file.line_strings));
+            let left_style = |i: usize| {
+                let line = &file.line_strings[i];
+                if line.starts_with('-') {
+                    heatmap_style(theme.removed_color(), theme, &heat, i)
                 } else if line.starts_with(' ') || line.starts_with('+') {
                     Style::default().fg(theme.unchanged_color())
                 } else {
                     Style::default().fg(theme.foreground_color())
-                };
-
-                let right_style = if line.starts_with('+') {
-                    Style::default().fg(theme.added_color())
+                }
+            };
+            let right_style = |i: usize| {
+                let line = &file.line_strings[i];
+                if line.starts_with('+') {
+                    heatmap_style(theme.added_color(), theme, &heat, i)
                 } else if line.starts_with(' ') || line.starts_with('-') {
                     Style::default().fg(theme.unchanged_color())
                 } else {
                     Style::default().fg(theme.foreground_color())
-                };
-
-                left_lines.push(Line::from(Span::styled(left_content, left_style)));
-                right_lines.push(Line::from(Span::styled(right_content, right_style)));
+                }
+            };
+            let styled = |row: ColumnRow, style: &dyn Fn(usize) -> Style| match row.line {
+                Some(i) => Line::from(Span::styled(row.text, style(i))),
+                None => Line::from(""),
+            };
 
-                line_count += 1;
-            }
+            let (left_rows, right_rows) = Self::column_rows(
+                &file.line_strings,
+                app.get_scroll_offset(),
+                app.current_diff_height,
+                wrap_width,
+            );
+            let left_lines: Vec<Line> = left_rows
+                .into_iter()
+                .map(|row| styled(row, &left_style))
+                .collect();
+            let right_lines: Vec<Line> = right_rows
+                .into_iter()
+                .map(|row| styled(row, &right_style))
+                .collect();
 
             let left_text = ratatui::text::Text::from(left_lines);
             let right_text = ratatui::text::Text::from(right_lines);
 
-            let left_paragraph = Paragraph::new(left_text)
-                .block(
-                    Block::default()
-                        .title("Original")
-                        .borders(Borders::ALL)
-                        .border_style(Style::default().fg(theme.border_color())),
-                )
-                .wrap(Wrap { trim: false });
-
-            let right_paragraph = Paragraph::new(right_text)
-                .block(
-                    Block::default()
-                        .title("Modified")
-                        .borders(Borders::ALL)
-                        .border_style(Style::default().fg(theme.border_color())),
-                )
-                .wrap(Wrap { trim: false });
+            let left_paragraph = Paragraph::new(left_text).block(
+                Block::default()
+                    .title("Original")
+                    .borders(Borders::ALL)
+                    .border_style(app.pane_border_style(ActivePane::SideBySideDiff)),
+            );
+
+            let right_title = match app.current_semantic_stats() {
+                Some(stats) => format!("Modified ({} touched)", stats.describe()),
+                None => "Modified".to_string(),
+            };
+            let right_paragraph = Paragraph::new(right_text).block(
+                Block::default()
+                    .title(right_title)
+                    .borders(Borders::ALL)
+                    .border_style(app.pane_border_style(ActivePane::SideBySideDiff)),
+            );
 
             f.render_widget(left_paragraph, chunks[0]);
             f.render_widget(right_paragraph, chunks[1]);
@@ -123,7 +228,7 @@ impl Pane for SideBySideDiffPane {
                 Block::default()
                     .title(self.title())
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(theme.border_color())),
+                    .border_style(app.pane_border_style(ActivePane::SideBySideDiff)),
             );
             f.render_widget(paragraph, area);
         }
@@ -134,10 +239,7 @@ impl Pane for SideBySideDiffPane {
         match event {
             AppEvent::Key(key) => matches!(
                 key.code,
-                KeyCode::Char('j')
-                    | KeyCode::Char('k')
-                    | KeyCode::Left
-                    | KeyCode::Right
+                KeyCode::Char('j') | KeyCode::Char('k') | KeyCode::Left | KeyCode::Right
             ),
             _ => false,
         }
@@ -151,3 +253,80 @@ impl Pane for SideBySideDiffPane {
         self.visible = visible;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_line_preserves_whitespace() {
+        assert_eq!(
+            SideBySideDiffPane::wrap_line("    let x = 1;", 6),
+            vec!["    le", "t x = ", "1;"]
+        );
+        assert_eq!(SideBySideDiffPane::wrap_line("", 6), vec![""]);
+        assert_eq!(SideBySideDiffPane::wrap_line("short", 0), vec!["short"]);
+    }
+
+    #[test]
+    fn test_wrap_line_counts_display_width() {
+        // Each CJK character and emoji fills two columns
+        assert_eq!(
+            SideBySideDiffPane::wrap_line("日本語のテキスト", 6),
+            vec!["日本語", "のテキ", "スト"]
+        );
+        assert_eq!(
+            SideBySideDiffPane::wrap_line("a🎉b🎉", 3),
+            vec!["a🎉", "b🎉"]
+        );
+        // A wide grapheme never splits at an odd column edge
+        assert_eq!(SideBySideDiffPane::wrap_line("ab日", 3), vec!["ab", "日"]);
+        for row in SideBySideDiffPane::wrap_line("混合 mixed テキスト text", 5) {
+            assert!(row.width() <= 5, "{row:?} overflows the column");
+        }
+    }
+
+    #[test]
+    fn test_wrap_row_pads_shorter_side() {
+        let (left, right) = SideBySideDiffPane::wrap_row("abcdefghij", "xy", 4);
+        assert_eq!(left, vec!["abcd", "efgh", "ij"]);
+        assert_eq!(right, vec!["xy", "", ""]);
+
+        let (left, right) = SideBySideDiffPane::wrap_row("", "0123456789", 5);
+        assert_eq!(left.len(), right.len());
+        assert_eq!(left, vec!["", ""]);
+    }
+
+    #[test]
+    fn test_column_rows_stay_in_step() {
+        let lines: Vec<String> = [
+            "@@ -1,3 +1,3 @@",
+            " fn main() {",
+            "-    short();",
+            "+    a_much_longer_call(with, several, arguments);",
+            " }",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+
+        for (scroll_offset, height) in [(0, 3), (0, 20), (2, 4), (3, 1), (10, 5)] {
+            let (left, right) = SideBySideDiffPane::column_rows(&lines, scroll_offset, height, 12);
+            assert_eq!(left.len(), height);
+            assert_eq!(right.len(), height);
+            for (left_row, right_row) in left.iter().zip(&right) {
+                assert_eq!(left_row.line, right_row.line);
+            }
+        }
+
+        // The added line wraps to five rows; the left column pads beside it
+        let (left, right) = SideBySideDiffPane::column_rows(&lines, 3, 7, 12);
+        let lines_shown: Vec<Option<usize>> = right.iter().map(|row| row.line).collect();
+        assert_eq!(
+            lines_shown,
+            [Some(3), Some(3), Some(3), Some(3), Some(3), Some(4), None]
+        );
+        assert_eq!(left[1], ColumnRow::new(Some(3), String::new()));
+        assert_eq!(left[6], ColumnRow::padding());
+    }
+}
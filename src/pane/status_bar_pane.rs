@@ -9,6 +9,152 @@ use super::{AppEvent, Pane};
 use crate::git::GitRepo;
 use crate::ui::App;
 
+/// Terminals narrower than this get the compact status bar unless configured otherwise
+pub const COMPACT_STATUS_BAR_WIDTH: u16 = 100;
+
+/// Markers after the branch name: ahead/behind the upstream, unpushed commits and stashes
+fn branch_indicators(app: &App, git_repo: &GitRepo) -> Vec<String> {
+    let mut indicators = Vec::new();
+    // Ahead/behind the upstream follows the branch name, hidden when there's no upstream
+    if let (Some(ahead), Some(behind)) = (git_repo.ahead, git_repo.behind) {
+        indicators.push(format!("↑{ahead}"));
+        indicators.push(format!("↓{behind}"));
+    }
+    // Without an upstream there's no ahead count to say the branch needs pushing
+    if app.shows_unpushed() && git_repo.has_unpushed && git_repo.ahead.is_none() {
+        indicators.push("⇡".to_string());
+    }
+    if app.shows_stash_count() && git_repo.stash_count > 0 {
+        indicators.push(format!("⚑{}", git_repo.stash_count));
+    }
+    indicators
+}
+
+/// Files, additions and deletions of the selected commit, or of the current view
+fn change_totals(app: &App, git_repo: &GitRepo) -> (usize, usize, usize) {
+    match app.get_selected_commit() {
+        Some(commit) => (
+            commit.files_changed.len(),
+            commit.files_changed.iter().map(|f| f.additions).sum(),
+            commit.files_changed.iter().map(|f| f.deletions).sum(),
+        ),
+        None => git_repo.total_stats,
+    }
+}
+
+fn full_status_text(app: &App, git_repo: &GitRepo) -> String {
+    let repo_name = &git_repo.repo_name;
+    let branch = std::iter::once(git_repo.branch_name.clone())
+        .chain(branch_indicators(app, git_repo))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let (commit_sha, commit_summary) = &git_repo.commit_info;
+    let (total_files, total_additions, total_deletions) = change_totals(app, git_repo);
+
+    let view_mode_text = if let Some(selected_commit) = app.get_selected_commit() {
+        format!("🔍 Selected Commit: {}", selected_commit.short_sha)
+    } else {
+        match git_repo.current_view_mode {
+            crate::git::ViewMode::WorkingTree => "💼 Working Tree".to_string(),
+            crate::git::ViewMode::Staged => "📋 Staged Files".to_string(),
+            crate::git::ViewMode::DirtyDirectory => "🗂️ Dirty Directory".to_string(),
+            crate::git::ViewMode::LastCommit => "📜 Last Commit".to_string(),
+            crate::git::ViewMode::StagedAndUnstaged => "🧩 Staged + Unstaged".to_string(),
+            crate::git::ViewMode::Base => format!(
+                "🔀 Base: {}",
+                git_repo.diff_base.as_deref().unwrap_or("unknown")
+            ),
+        }
+    };
+
+    // A view picked by hand stays until cleared, so say it isn't the automatic one
+    let view_mode_text =
+        if app.get_selected_commit().is_none() && app.get_forced_view_mode().is_some() {
+            format!("{view_mode_text} (pinned)")
+        } else {
+            view_mode_text
+        };
+
+    let mut status_text = format!("📂 {repo_name} | 🌿 {branch}");
+    let view_text = if let Some(selected_commit) = app.get_selected_commit() {
+        format!(
+            "{view_mode_text} | 🎯 {} > {} | 📊 {total_files} files (+{total_additions}/-{total_deletions})",
+            selected_commit.short_sha,
+            selected_commit.message.lines().next().unwrap_or(""),
+        )
+    } else {
+        format!(
+            "{view_mode_text} | 🎯 {commit_sha} > {commit_summary} | 📊 {total_files} files (+{total_additions}/-{total_deletions})"
+        )
+    };
+
+    if let Some(theme_name) = app.get_theme_change_notice() {
+        status_text = format!("🎨 Theme: {theme_name} | {status_text}");
+    }
+
+    if let Some(message) = app.get_status_message() {
+        status_text = format!("⚠️ {message} | {status_text}");
+    }
+
+    // Plain text: the pause emoji is measured one column wide but drawn two, which
+    // shifts the rest of the bar
+    if app.is_git_refresh_paused() {
+        status_text = format!("PAUSED | {status_text}");
+    }
+
+    // A two-line bar keeps the repository on top and what's being viewed below
+    let separator = if app.get_status_bar_lines() > 1 {
+        "\n"
+    } else {
+        " | "
+    };
+    format!("{status_text}{separator}{view_text}")
+}
+
+/// One short line without emoji, like `repo·main↑1·WT·3f+10-2`
+fn compact_status_text(app: &App, git_repo: &GitRepo) -> String {
+    let branch = std::iter::once(git_repo.branch_name.clone())
+        .chain(branch_indicators(app, git_repo))
+        .collect::<String>();
+
+    let view = if let Some(selected_commit) = app.get_selected_commit() {
+        selected_commit.short_sha.clone()
+    } else {
+        let view = match git_repo.current_view_mode {
+            crate::git::ViewMode::WorkingTree => "WT".to_string(),
+            crate::git::ViewMode::Staged => "ST".to_string(),
+            crate::git::ViewMode::DirtyDirectory => "DD".to_string(),
+            crate::git::ViewMode::LastCommit => "LC".to_string(),
+            crate::git::ViewMode::StagedAndUnstaged => "SU".to_string(),
+            crate::git::ViewMode::Base => {
+                format!("B:{}", git_repo.diff_base.as_deref().unwrap_or("unknown"))
+            }
+        };
+        // Pinned views are marked the way the full bar says "(pinned)"
+        if app.get_forced_view_mode().is_some() {
+            format!("{view}*")
+        } else {
+            view
+        }
+    };
+
+    let (files, additions, deletions) = change_totals(app, git_repo);
+    let mut parts = Vec::new();
+    if app.is_git_refresh_paused() {
+        parts.push("PAUSED".to_string());
+    }
+    if let Some(message) = app.get_status_message() {
+        parts.push(message.to_string());
+    }
+    parts.extend([
+        git_repo.repo_name.clone(),
+        branch,
+        view,
+        format!("{files}f+{additions}-{deletions}"),
+    ]);
+    parts.join("·")
+}
+
 pub struct StatusBarPane {
     visible: bool,
 }
@@ -38,44 +184,10 @@ impl Pane for StatusBarPane {
         git_repo: &GitRepo,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let theme = app.get_theme();
-        let repo_name = &git_repo.repo_name;
-        let branch = &git_repo.branch_name;
-        let (commit_sha, commit_summary) = &git_repo.commit_info;
-        let (total_files, total_additions, total_deletions) = git_repo.total_stats;
-        let view_mode = git_repo.current_view_mode;
-
-        let view_mode_text = if let Some(selected_commit) = app.get_selected_commit() {
-            format!("🔍 Selected Commit: {}", selected_commit.short_sha)
+        let status_text = if app.is_status_bar_compact(area.width) {
+            compact_status_text(app, git_repo)
         } else {
-            match view_mode {
-                crate::git::ViewMode::WorkingTree => "💼 Working Tree".to_string(),
-                crate::git::ViewMode::Staged => "📋 Staged Files".to_string(),
-                crate::git::ViewMode::DirtyDirectory => "🗂️ Dirty Directory".to_string(),
-                crate::git::ViewMode::LastCommit => "📜 Last Commit".to_string(),
-            }
-        };
-
-        let status_text = if let Some(selected_commit) = app.get_selected_commit() {
-            format!(
-                "📂 {repo_name} | 🌿 {branch} | {view_mode_text} | 🎯 {} > {} | 📊 {} files (+{}/-{})",
-                selected_commit.short_sha,
-                selected_commit.message.lines().next().unwrap_or(""),
-                selected_commit.files_changed.len(),
-                selected_commit
-                    .files_changed
-                    .iter()
-                    .map(|f| f.additions)
-                    .sum::<usize>(),
-                selected_commit
-                    .files_changed
-                    .iter()
-                    .map(|f| f.deletions)
-                    .sum::<usize>()
-            )
-        } else {
-            format!(
-                "📂 {repo_name} | 🌿 {branch} | {view_mode_text} | 🎯 {commit_sha} > {commit_summary} | 📊 {total_files} files (+{total_additions}/-{total_deletions})"
-            )
+            full_status_text(app, git_repo)
         };
 
         let paragraph = Paragraph::new(status_text)
@@ -104,3 +216,84 @@ impl Pane for StatusBarPane {
         self.visible = visible;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::ViewMode;
+    use crate::ui::Theme;
+    use std::sync::Arc;
+
+    fn test_repo() -> GitRepo {
+        GitRepo {
+            path: std::path::PathBuf::from("/repo"),
+            changed_files: Vec::new(),
+            staged_files: Vec::new(),
+            dirty_directory_files: Vec::new(),
+            untracked_dirs: Vec::new(),
+            last_commit_files: Vec::new(),
+            last_commit_id: None,
+            base_files: Vec::new(),
+            diff_base: None,
+            current_view_mode: ViewMode::WorkingTree,
+            repo_name: "grw".to_string(),
+            branch_name: "main".to_string(),
+            ahead: Some(1),
+            behind: Some(0),
+            stash_count: 2,
+            has_unpushed: true,
+            commit_info: ("abc1234".to_string(), "Fix things".to_string()),
+            total_stats: (3, 10, 2),
+        }
+    }
+
+    #[test]
+    fn test_compact_status_text() {
+        let llm_state = Arc::new(crate::shared_state::LlmSharedState::new());
+        let mut app = App::new_with_config(true, true, 0, vec![Theme::Dark], None, llm_state);
+        let repo = test_repo();
+
+        assert_eq!(
+            compact_status_text(&app, &repo),
+            "grw·main↑1↓0⚑2·WT·3f+10-2"
+        );
+        assert!(full_status_text(&app, &repo).contains("🌿 main ↑1 ↓0 ⚑2"));
+
+        // Only narrow terminals get the compact bar unless it's configured
+        assert!(app.is_status_bar_compact(COMPACT_STATUS_BAR_WIDTH - 1));
+        assert!(!app.is_status_bar_compact(COMPACT_STATUS_BAR_WIDTH));
+        app.set_status_bar_style(crate::config::StatusBarStyle::Compact);
+        assert!(app.is_status_bar_compact(200));
+        app.set_status_bar_style(crate::config::StatusBarStyle::Full);
+        assert!(!app.is_status_bar_compact(40));
+    }
+
+    #[test]
+    fn test_two_line_status_text() {
+        let llm_state = Arc::new(crate::shared_state::LlmSharedState::new());
+        let mut app = App::new_with_config(true, true, 0, vec![Theme::Dark], None, llm_state);
+        let repo = test_repo();
+
+        let one_line = full_status_text(&app, &repo);
+        assert_eq!(one_line.lines().count(), 1);
+        assert!(one_line.contains("⚑2 | 💼 Working Tree"));
+
+        app.set_status_bar_lines(2);
+        app.set_status_message("Saved".to_string());
+        let text = full_status_text(&app, &repo);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "⚠️ Saved | 📂 grw | 🌿 main ↑1 ↓0 ⚑2",
+                "💼 Working Tree | 🎯 abc1234 > Fix things | 📊 3 files (+10/-2)",
+            ]
+        );
+
+        app.set_git_refresh_paused(true);
+        assert!(
+            full_status_text(&app, &repo).starts_with("PAUSED | ⚠️ Saved | 📂 grw"),
+            "the pause marker is plain text"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use super::{AppEvent, Pane};
+use crate::git::GitRepo;
+use crate::llm::UsageSummary;
+use crate::ui::App;
+
+/// Transient popup with the LLM tokens used this session and what they cost
+pub struct TokenUsagePane {
+    visible: bool,
+    summary: Option<UsageSummary>,
+}
+
+impl Default for TokenUsagePane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenUsagePane {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            summary: None,
+        }
+    }
+
+    /// Snapshot the usage; None when no LLM client is configured
+    pub fn set_summary(&mut self, summary: Option<UsageSummary>) {
+        self.summary = summary;
+    }
+
+    fn summary_lines(summary: &UsageSummary) -> Vec<Line<'static>> {
+        let mut lines = vec![
+            Line::from(format!("  Requests:   {}", summary.requests)),
+            Line::from(format!("  Prompt:     {}", summary.usage.prompt_tokens)),
+            Line::from(format!("  Completion: {}", summary.usage.completion_tokens)),
+            Line::from(format!("  Total:      {}", summary.usage.total())),
+        ];
+        if let Some(budget) = summary.budget {
+            let remaining = budget.saturating_sub(summary.usage.total());
+            lines.push(Line::from(format!(
+                "  Budget:     {budget} ({remaining} left)"
+            )));
+        }
+        lines.push(Line::from(match summary.estimated_cost {
+            Some(cost) => format!("  Est. cost:  ${cost:.4}"),
+            None => "  Est. cost:  set price_per_1k_tokens".to_string(),
+        }));
+        lines
+    }
+}
+
+impl Pane for TokenUsagePane {
+    fn title(&self) -> String {
+        "LLM Token Usage (any key to close)".to_string()
+    }
+
+    fn render(
+        &self,
+        f: &mut Frame,
+        app: &App,
+        area: Rect,
+        _git_repo: &GitRepo,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = app.get_theme();
+        let popup = super::centered_area(area, 50, 10);
+        let heading_style = Style::default()
+            .fg(theme.secondary_color())
+            .add_modifier(Modifier::BOLD);
+
+        let mut text = Vec::new();
+        match &self.summary {
+            Some(summary) => {
+                text.push(Line::from(Span::styled("This session", heading_style)));
+                text.extend(Self::summary_lines(summary));
+            }
+            None => text.push(Line::from(Span::styled(
+                "LLM not configured",
+                heading_style,
+            ))),
+        }
+
+        let paragraph = Paragraph::new(text)
+            .style(
+                Style::default()
+                    .fg(theme.foreground_color())
+                    .bg(theme.background_color()),
+            )
+            .block(
+                Block::default()
+                    .title(self.title())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.primary_color())),
+            );
+
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &AppEvent) -> bool {
+        match event {
+            AppEvent::Key(_) => {
+                self.set_visible(false);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn as_token_usage_pane_mut(&mut self) -> Option<&mut TokenUsagePane> {
+        Some(self)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::TokenUsage;
+
+    #[test]
+    fn test_token_usage_lines() {
+        let summary = UsageSummary {
+            usage: TokenUsage {
+                prompt_tokens: 1200,
+                completion_tokens: 300,
+            },
+            requests: 2,
+            budget: Some(2000),
+            estimated_cost: Some(0.003),
+        };
+
+        let lines: Vec<String> = TokenUsagePane::summary_lines(&summary)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert!(lines.contains(&"  Total:      1500".to_string()));
+        assert!(lines.contains(&"  Budget:     2000 (500 left)".to_string()));
+        assert!(lines.contains(&"  Est. cost:  $0.0030".to_string()));
+    }
+}
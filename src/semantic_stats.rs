@@ -0,0 +1,205 @@
+use crate::git::FileDiff;
+
+/// What a file's diff touches beyond its line counts, found by parsing the file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SemanticStats {
+    /// Functions and methods with an added or removed line inside them
+    pub functions_touched: usize,
+}
+
+impl SemanticStats {
+    /// Parse `source`, the new version of `file`, and count what its diff touches
+    ///
+    /// None when grw was built without the `semantic-stats` feature, the file's language
+    /// isn't supported or it doesn't parse.
+    #[cfg(feature = "semantic-stats")]
+    pub fn compute(file: &FileDiff, source: &str) -> Option<Self> {
+        let language = language_for(&file.path)?;
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&language.grammar()).ok()?;
+        let tree = parser.parse(source, None)?;
+        let root = tree.root_node();
+
+        let line_count = source.lines().count();
+        let mut functions = std::collections::HashSet::new();
+        for line in touched_new_lines(file) {
+            if line == 0 || line > line_count {
+                continue;
+            }
+            let point = tree_sitter::Point::new(line - 1, 0);
+            let mut node = root.descendant_for_point_range(point, point);
+            while let Some(current) = node {
+                if language.function_kinds().contains(&current.kind()) {
+                    functions.insert(current.id());
+                    break;
+                }
+                node = current.parent();
+            }
+        }
+        Some(Self {
+            functions_touched: functions.len(),
+        })
+    }
+
+    #[cfg(not(feature = "semantic-stats"))]
+    pub fn compute(_file: &FileDiff, _source: &str) -> Option<Self> {
+        None
+    }
+
+    /// Shown next to the file name, e.g. "2 functions"
+    pub fn describe(&self) -> String {
+        match self.functions_touched {
+            1 => "1 function".to_string(),
+            count => format!("{count} functions"),
+        }
+    }
+}
+
+/// Lines in the new version of the file the diff touches: those it adds, and those
+/// removed lines used to sit just before
+#[cfg(feature = "semantic-stats")]
+fn touched_new_lines(file: &FileDiff) -> Vec<usize> {
+    let mut touched = Vec::new();
+    let mut next_line = None;
+    for line in &file.line_strings {
+        if line.starts_with("@@") {
+            next_line = crate::git::types::hunk_new_start(line);
+        } else if line.starts_with('+') {
+            touched.extend(next_line);
+            next_line = next_line.map(|n| n + 1);
+        } else if line.starts_with('-') {
+            touched.extend(next_line);
+        } else if line.starts_with(' ') {
+            next_line = next_line.map(|n| n + 1);
+        }
+    }
+    touched.dedup();
+    touched
+}
+
+/// Languages whose functions grw can find, picked by file extension
+#[cfg(feature = "semantic-stats")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    Go,
+    JavaScript,
+}
+
+#[cfg(feature = "semantic-stats")]
+pub fn language_for(path: &std::path::Path) -> Option<Language> {
+    match path.extension()?.to_str()? {
+        "rs" => Some(Language::Rust),
+        "py" | "pyi" => Some(Language::Python),
+        "go" => Some(Language::Go),
+        "js" | "mjs" | "cjs" | "jsx" => Some(Language::JavaScript),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "semantic-stats")]
+impl Language {
+    fn grammar(&self) -> tree_sitter::Language {
+        match self {
+            Language::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Language::Python => tree_sitter_python::LANGUAGE.into(),
+            Language::Go => tree_sitter_go::LANGUAGE.into(),
+            Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+        }
+    }
+
+    /// Node kinds of named functions; closures and lambdas count toward the function
+    /// they're written in
+    fn function_kinds(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &["function_item"],
+            Language::Python => &["function_definition"],
+            Language::Go => &["function_declaration", "method_declaration"],
+            Language::JavaScript => &[
+                "function_declaration",
+                "generator_function_declaration",
+                "method_definition",
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "semantic-stats")]
+    #[test]
+    fn test_touched_new_lines() {
+        let file = FileDiff::for_test(
+            "src/lib.rs",
+            &[
+                "@@ -1,3 +1,3 @@",
+                " fn a() {",
+                "-    old();",
+                "+    new();",
+                " }",
+                "@@ -10,2 +10,1 @@",
+                " fn b() {}",
+                "-fn c() {}",
+            ],
+        );
+        assert_eq!(touched_new_lines(&file), vec![2, 11]);
+    }
+
+    #[cfg(feature = "semantic-stats")]
+    #[test]
+    fn test_language_for() {
+        use std::path::Path;
+
+        assert_eq!(language_for(Path::new("src/main.rs")), Some(Language::Rust));
+        assert_eq!(
+            language_for(Path::new("app.mjs")),
+            Some(Language::JavaScript)
+        );
+        assert_eq!(language_for(Path::new("README.md")), None);
+        assert_eq!(language_for(Path::new("Makefile")), None);
+    }
+
+    #[cfg(feature = "semantic-stats")]
+    #[test]
+    fn test_compute_counts_functions_touched() {
+        let source =
+            "fn a() {\n    new();\n}\n\nfn b() {\n    let f = || 1;\n}\n\nconst X: u8 = 1;\n";
+        let file = FileDiff::for_test(
+            "src/lib.rs",
+            &[
+                "@@ -1,9 +1,9 @@",
+                " fn a() {",
+                "-    old();",
+                "+    new();",
+                " }",
+                " ",
+                " fn b() {",
+                "-    let f = || 0;",
+                "+    let f = || 1;",
+                " }",
+                " ",
+                "-const X: u8 = 0;",
+                "+const X: u8 = 1;",
+            ],
+        );
+        assert_eq!(
+            SemanticStats::compute(&file, source),
+            Some(SemanticStats {
+                functions_touched: 2
+            })
+        );
+
+        let unsupported = FileDiff::for_test("notes.txt", &["@@ -1 +1 @@", "-a", "+b"]);
+        assert_eq!(SemanticStats::compute(&unsupported, "b\n"), None);
+    }
+
+    #[cfg(not(feature = "semantic-stats"))]
+    #[test]
+    fn test_compute_without_feature() {
+        let file = FileDiff::for_test("src/lib.rs", &["@@ -1 +1 @@", "-fn a() {}", "+fn b() {}"]);
+        assert_eq!(SemanticStats::compute(&file, "fn b() {}\n"), None);
+    }
+}
@@ -1,9 +1,25 @@
 use scc::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, AtomicUsize, Ordering};
 
 use crate::git::{CommitInfo, FileDiff, GitRepo};
 
+/// Commits loaded by a background commit history task, or why loading failed
+pub type CommitHistoryResult = Result<Vec<CommitInfo>, String>;
+
+/// Where the reflog listing is posted among the pages of commit history
+const REFLOG_RESULT_KEY: &str = "HEAD@{reflog}";
+
+/// Advice chats kept on disk; the ones touched longest ago are dropped first
+const MAX_SAVED_ADVICE_SESSIONS: usize = 100;
+
+/// On-disk form of the advice cache
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct AdviceCacheFile {
+    sessions: std::collections::BTreeMap<String, Vec<crate::pane::ChatMessageData>>,
+}
+
 /// Shared state for git operations using lock-free data structures
 pub struct GitSharedState {
     /// Current repository state
@@ -18,6 +34,21 @@ pub struct GitSharedState {
     /// Current view mode
     view_mode: AtomicU8, // Encoded ViewMode
 
+    /// Whether the background refresh loop is paused
+    paused: AtomicBool,
+
+    /// Set to make the background refresh loop run now instead of waiting out its interval
+    refresh_requested: AtomicBool,
+
+    /// Milliseconds the background refresh loop waits between updates
+    refresh_interval_ms: AtomicU64,
+
+    /// Commits processed so far by the commit history load in progress
+    commit_history_progress: AtomicUsize,
+
+    /// Commit history pages loaded in the background, keyed by the SHA they resume after
+    commit_history_results: HashMap<String, CommitHistoryResult>,
+
     /// Error state
     error_state: HashMap<String, String>,
 }
@@ -35,6 +66,11 @@ impl GitSharedState {
             commit_cache: HashMap::new(),
             file_diff_cache: HashMap::new(),
             view_mode: AtomicU8::new(0),
+            paused: AtomicBool::new(false),
+            refresh_requested: AtomicBool::new(false),
+            refresh_interval_ms: AtomicU64::new(crate::config::DEFAULT_REFRESH_INTERVAL_MS),
+            commit_history_progress: AtomicUsize::new(0),
+            commit_history_results: HashMap::new(),
             error_state: HashMap::new(),
         }
     }
@@ -100,6 +136,71 @@ impl GitSharedState {
     pub fn set_view_mode(&self, mode: u8) {
         self.view_mode.store(mode, Ordering::Relaxed);
     }
+
+    /// Check whether background git refreshes are paused
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Flip the paused flag, returning the new state
+    pub fn toggle_paused(&self) -> bool {
+        !self.paused.fetch_xor(true, Ordering::Relaxed)
+    }
+
+    /// Ask the background refresh loop to run as soon as possible
+    pub fn request_refresh(&self) {
+        self.refresh_requested.store(true, Ordering::Relaxed);
+    }
+
+    /// Check for and clear a pending refresh request
+    pub fn take_refresh_request(&self) -> bool {
+        self.refresh_requested.swap(false, Ordering::Relaxed)
+    }
+
+    /// Change how long the background refresh loop waits between updates, from its next wait
+    pub fn set_refresh_interval_ms(&self, interval_ms: u64) {
+        self.refresh_interval_ms
+            .store(interval_ms, Ordering::Relaxed);
+    }
+
+    pub fn refresh_interval_ms(&self) -> u64 {
+        self.refresh_interval_ms.load(Ordering::Relaxed)
+    }
+
+    /// Record how many commits the history load in progress has processed
+    pub fn set_commit_history_progress(&self, loaded: usize) {
+        self.commit_history_progress
+            .store(loaded, Ordering::Relaxed);
+    }
+
+    pub fn get_commit_history_progress(&self) -> usize {
+        self.commit_history_progress.load(Ordering::Relaxed)
+    }
+
+    /// Post a page of commit history for the UI, `after` being the SHA it resumed after
+    pub fn store_commit_history_result(&self, after: Option<&str>, result: CommitHistoryResult) {
+        self.commit_history_results
+            .upsert(after.unwrap_or("HEAD").to_string(), result);
+    }
+
+    /// Post the reflog listing for the UI, alongside pages of history under a key no SHA can take
+    pub fn store_reflog_result(&self, result: CommitHistoryResult) {
+        self.commit_history_results
+            .upsert(REFLOG_RESULT_KEY.to_string(), result);
+    }
+
+    pub fn take_reflog_result(&self) -> Option<CommitHistoryResult> {
+        self.commit_history_results
+            .remove(REFLOG_RESULT_KEY)
+            .map(|(_, result)| result)
+    }
+
+    /// Take a posted page of commit history, leaving nothing behind for the next load
+    pub fn take_commit_history_result(&self, after: Option<&str>) -> Option<CommitHistoryResult> {
+        self.commit_history_results
+            .remove(after.unwrap_or("HEAD"))
+            .map(|(_, result)| result)
+    }
 }
 
 /// Shared state for LLM operations using lock-free data structures
@@ -123,6 +224,9 @@ pub struct LlmSharedState {
 
     /// Pending chat responses for async task results
     pending_chat_responses: HashMap<String, crate::pane::ChatMessageData>, // message_id -> pending AI response
+
+    /// Finished advice chats, saved across runs
+    advice_sessions: HashMap<String, Vec<crate::pane::ChatMessageData>>, // model:diff_hash -> chat
 }
 
 impl Default for LlmSharedState {
@@ -141,6 +245,7 @@ impl LlmSharedState {
             advice_error_state: HashMap::new(),
             current_advice_results: HashMap::new(),
             pending_chat_responses: HashMap::new(),
+            advice_sessions: HashMap::new(),
         }
     }
 
@@ -183,6 +288,11 @@ impl LlmSharedState {
         self.error_state.remove(key).is_some()
     }
 
+    /// Get error state for a specific operation
+    pub fn get_error(&self, key: &str) -> Option<String> {
+        self.error_state.read(key, |_, v| v.clone())
+    }
+
     /// Get all current errors
     pub fn get_all_errors(&self) -> Vec<(String, String)> {
         let mut errors = Vec::new();
@@ -263,6 +373,63 @@ impl LlmSharedState {
     pub fn remove_pending_chat_response(&self, message_id: &str) -> bool {
         self.pending_chat_responses.remove(message_id).is_some()
     }
+
+    /// Cache the advice chat for a model and diff, replacing any earlier one
+    pub fn cache_advice_session(&self, key: String, messages: Vec<crate::pane::ChatMessageData>) {
+        self.advice_sessions.upsert(key, messages);
+    }
+
+    /// Get the cached advice chat for a model and diff
+    pub fn get_cached_advice_session(
+        &self,
+        key: &str,
+    ) -> Option<Vec<crate::pane::ChatMessageData>> {
+        self.advice_sessions.read(key, |_, v| v.clone())
+    }
+
+    /// Forget the cached advice chat for a model and diff
+    pub fn remove_advice_session(&self, key: &str) -> bool {
+        self.advice_sessions.remove(key).is_some()
+    }
+
+    /// Write the advice cache as JSON, keeping only the most recently used chats
+    ///
+    /// Nothing is written without any chats, so a run that never opened the advice
+    /// panel leaves the cache from an earlier one in place.
+    pub fn save_advice_cache(&self, path: &Path) -> std::io::Result<()> {
+        let mut sessions = Vec::new();
+        self.advice_sessions
+            .scan(|k, v| sessions.push((k.clone(), v.clone())));
+        if sessions.is_empty() {
+            return Ok(());
+        }
+        sessions.sort_by_key(|(_, messages)| {
+            std::cmp::Reverse(messages.last().map(|message| message.timestamp))
+        });
+        sessions.truncate(MAX_SAVED_ADVICE_SESSIONS);
+
+        let file = AdviceCacheFile {
+            sessions: sessions.into_iter().collect(),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(&file)?)
+    }
+
+    /// Read an advice cache written by `save_advice_cache`; a missing file is not an error
+    pub fn load_advice_cache(&self, path: &Path) -> std::io::Result<()> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        let file: AdviceCacheFile = serde_json::from_str(&contents)?;
+        for (key, messages) in file.sessions {
+            self.advice_sessions.upsert(key, messages);
+        }
+        Ok(())
+    }
 }
 
 /// Central manager for all shared state components
@@ -290,6 +457,12 @@ impl SharedStateManager {
         &self.llm_state
     }
 
+    /// Where advice chats are kept between runs; None without a cache directory, rather than
+    /// writing into the repository being watched
+    fn advice_cache_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("grw").join("advice_cache.json"))
+    }
+
     /// Initialize all shared state components
     pub fn initialize(&self) -> Result<(), Box<dyn std::error::Error>> {
         // Initialize git state with default view mode
@@ -299,17 +472,33 @@ impl SharedStateManager {
         self.git_state.clear_all_errors();
         self.llm_state.clear_all_errors();
 
+        // A corrupt cache only costs regenerating advice
+        if let Some(path) = Self::advice_cache_path()
+            && let Err(e) = self.llm_state.load_advice_cache(&path)
+        {
+            log::warn!("Could not load the advice cache: {e}");
+        }
+
         Ok(())
     }
 
     /// Shutdown all shared state components and perform final cleanup
     pub fn shutdown(&self) -> Result<(), Box<dyn std::error::Error>> {
+        // Failing to save the cache shouldn't stop the rest of the cleanup
+        if let Some(path) = Self::advice_cache_path()
+            && let Err(e) = self.llm_state.save_advice_cache(&path)
+        {
+            log::warn!("Could not save the advice cache: {e}");
+        }
+
         // Clear all cached data to free memory
         self.git_state.commit_cache.clear();
+        self.git_state.commit_history_results.clear();
         self.git_state.file_diff_cache.clear();
         self.git_state.repo_data.clear();
 
         self.llm_state.summary_cache.clear();
+        self.llm_state.advice_sessions.clear();
 
         Ok(())
     }
@@ -342,6 +531,65 @@ mod tests {
         assert!(all_errors_after_clear.is_empty());
     }
 
+    #[test]
+    fn test_git_shared_state_pause_toggle() {
+        let git_state = GitSharedState::new();
+        assert!(!git_state.is_paused());
+
+        assert!(git_state.toggle_paused());
+        assert!(git_state.is_paused());
+
+        assert!(!git_state.toggle_paused());
+        assert!(!git_state.is_paused());
+    }
+
+    #[test]
+    fn test_git_shared_state_refresh_request() {
+        let git_state = GitSharedState::new();
+        assert!(!git_state.take_refresh_request());
+
+        git_state.request_refresh();
+        assert!(git_state.take_refresh_request());
+        assert!(!git_state.take_refresh_request());
+    }
+
+    #[test]
+    fn test_git_shared_state_refresh_interval() {
+        let git_state = GitSharedState::new();
+        assert_eq!(
+            git_state.refresh_interval_ms(),
+            crate::config::DEFAULT_REFRESH_INTERVAL_MS
+        );
+
+        git_state.set_refresh_interval_ms(2000);
+        assert_eq!(git_state.refresh_interval_ms(), 2000);
+    }
+
+    #[test]
+    fn test_git_shared_state_commit_history_results() {
+        let git_state = GitSharedState::new();
+        assert!(git_state.take_commit_history_result(None).is_none());
+
+        git_state.store_commit_history_result(None, Ok(Vec::new()));
+        git_state.store_commit_history_result(Some("abc123"), Err("boom".to_string()));
+
+        assert!(matches!(
+            git_state.take_commit_history_result(Some("abc123")),
+            Some(Err(e)) if e == "boom"
+        ));
+        assert!(matches!(
+            git_state.take_commit_history_result(None),
+            Some(Ok(commits)) if commits.is_empty()
+        ));
+        assert!(git_state.take_commit_history_result(None).is_none());
+
+        // The reflog never collides with the first page of history
+        git_state.store_reflog_result(Ok(Vec::new()));
+        assert!(git_state.take_commit_history_result(None).is_none());
+        assert!(matches!(git_state.take_reflog_result(), Some(Ok(_))));
+        assert!(git_state.take_reflog_result().is_none());
+    }
+
     #[test]
     fn test_git_shared_state_concurrent_access() {
         use std::sync::Arc;
@@ -358,6 +606,7 @@ mod tests {
                     sha: format!("commit_{}", i),
                     short_sha: format!("commit_{}", i),
                     message: format!("Test commit {}", i),
+                    time: 0,
                     files_changed: vec![],
                 };
                 state.cache_commit(format!("commit_{}", i), commit);
@@ -429,4 +678,42 @@ mod tests {
         let all_errors_after_clear = llm_state.get_all_errors();
         assert!(all_errors_after_clear.is_empty());
     }
+
+    #[test]
+    fn test_advice_cache_round_trip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("grw").join("advice_cache.json");
+
+        let llm_state = LlmSharedState::new();
+        // A missing file just means nothing was cached yet
+        llm_state.load_advice_cache(&path).unwrap();
+        // and with no chats there's nothing to write
+        llm_state.save_advice_cache(&path).unwrap();
+        assert!(!path.exists());
+
+        let message = crate::pane::ChatMessageData {
+            id: "1".to_string(),
+            role: crate::pane::MessageRole::Assistant,
+            content: "Handle the error".to_string(),
+            timestamp: std::time::SystemTime::now(),
+        };
+        llm_state.cache_advice_session("gpt-4o:abc".to_string(), vec![message]);
+        llm_state.save_advice_cache(&path).unwrap();
+
+        let reloaded = LlmSharedState::new();
+        reloaded.load_advice_cache(&path).unwrap();
+        let session = reloaded.get_cached_advice_session("gpt-4o:abc").unwrap();
+        assert_eq!(session[0].content, "Handle the error");
+        assert!(
+            reloaded
+                .get_cached_advice_session("gpt-4o-mini:abc")
+                .is_none()
+        );
+
+        // A later run without any chats leaves the saved ones alone
+        LlmSharedState::new().save_advice_cache(&path).unwrap();
+        let reloaded = LlmSharedState::new();
+        reloaded.load_advice_cache(&path).unwrap();
+        assert!(reloaded.get_cached_advice_session("gpt-4o:abc").is_some());
+    }
 }
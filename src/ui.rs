@@ -1,15 +1,22 @@
-use crate::git::{CommitInfo, FileDiff, GitRepo, PreloadConfig, SummaryPreloader, TreeNode};
+use crate::action::Action;
+use crate::git::{
+    CommitInfo, FileDiff, GitRepo, PreloadConfig, SummaryPreloader, TreeNode, ViewMode,
+};
 use crate::llm::LlmClient;
-use git2::Status;
-use crate::pane::{PaneId, PaneRegistry};
+use crate::pane::{
+    ConfirmAction, ConfirmDialog, DiffRow, FileIcon, PaneId, PaneRegistry, ScrollAction,
+    ScrollState,
+};
+use crate::shared_state::{CommitHistoryResult, GitSharedState};
 use crossterm::event::KeyEvent;
+use git2::Status;
 use ratatui::{
     Frame,
     backend::Backend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
 };
 use std::sync::Arc;
 
@@ -32,6 +39,8 @@ pub struct ColorPalette {
     pub added: Color,
     pub removed: Color,
     pub unchanged: Color,
+    /// Mark diff lines with symbols and text styles, not just color
+    pub diff_markers: bool,
 }
 
 impl ColorPalette {
@@ -48,6 +57,7 @@ impl ColorPalette {
             added: Color::Green,
             removed: Color::Red,
             unchanged: Color::White,
+            diff_markers: false,
         }
     }
 
@@ -64,15 +74,119 @@ impl ColorPalette {
             added: Color::Green,
             removed: Color::LightRed,
             unchanged: Color::Black,
+            diff_markers: false,
+        }
+    }
+
+    /// Maximum-contrast palette that avoids relying on subtle hues
+    pub fn high_contrast() -> Self {
+        Self {
+            background: Color::Black,
+            foreground: Color::White,
+            primary: Color::LightYellow,
+            secondary: Color::LightCyan,
+            error: Color::LightRed,
+            highlight: Color::LightMagenta,
+            border: Color::White,
+            directory: Color::LightYellow,
+            added: Color::LightCyan,
+            removed: Color::LightYellow,
+            unchanged: Color::White,
+            diff_markers: true,
+        }
+    }
+
+    /// Palette that leaves every color at the terminal default
+    pub fn monochrome() -> Self {
+        Self {
+            background: Color::Reset,
+            foreground: Color::Reset,
+            primary: Color::Reset,
+            secondary: Color::Reset,
+            error: Color::Reset,
+            highlight: Color::Reset,
+            border: Color::Reset,
+            directory: Color::Reset,
+            added: Color::Reset,
+            removed: Color::Reset,
+            unchanged: Color::Reset,
+            diff_markers: false,
         }
     }
+
+    /// Replace RGB colors with their nearest ANSI equivalent for 16-color terminals
+    pub fn to_ansi16(&self) -> Self {
+        self.map_colors(nearest_ansi_color)
+    }
+
+    /// Replace RGB colors with their nearest xterm equivalent for 256-color terminals
+    pub fn to_ansi256(&self) -> Self {
+        self.map_colors(nearest_256_color)
+    }
+
+    fn map_colors(&self, map: fn(Color) -> Color) -> Self {
+        Self {
+            background: map(self.background),
+            foreground: map(self.foreground),
+            primary: map(self.primary),
+            secondary: map(self.secondary),
+            error: map(self.error),
+            highlight: map(self.highlight),
+            border: map(self.border),
+            directory: map(self.directory),
+            added: map(self.added),
+            removed: map(self.removed),
+            unchanged: map(self.unchanged),
+            diff_markers: self.diff_markers,
+        }
+    }
+
+    /// Build a palette by applying hex color overrides on top of the dark palette
+    /// Returns None if none of the colors could be parsed
+    pub fn from_custom_theme(custom_theme: &crate::config::CustomTheme) -> Option<Self> {
+        let mut palette = Self::dark();
+        let mut any_color_parsed = false;
+
+        macro_rules! apply_color {
+            ($field:ident) => {
+                if let Some(hex) = &custom_theme.$field {
+                    match parse_hex_color(hex) {
+                        Ok(color) => {
+                            palette.$field = color;
+                            any_color_parsed = true;
+                        }
+                        Err(e) => log::warn!(
+                            "Failed to parse custom theme color for '{}': {}",
+                            stringify!($field),
+                            e
+                        ),
+                    }
+                }
+            };
+        }
+
+        apply_color!(background);
+        apply_color!(foreground);
+        apply_color!(primary);
+        apply_color!(secondary);
+        apply_color!(error);
+        apply_color!(highlight);
+        apply_color!(border);
+        apply_color!(directory);
+        apply_color!(added);
+        apply_color!(removed);
+        apply_color!(unchanged);
+
+        any_color_parsed.then_some(palette)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Theme {
     Dark,
     Light,
-    Custom(Arc<ColorPalette>),
+    HighContrast,
+    Custom(String, Arc<ColorPalette>),
 }
 
 impl Theme {
@@ -80,10 +194,45 @@ impl Theme {
         match self {
             Theme::Dark => Arc::new(ColorPalette::dark()),
             Theme::Light => Arc::new(ColorPalette::light()),
-            Theme::Custom(palette) => Arc::clone(palette),
+            Theme::HighContrast => Arc::new(ColorPalette::high_contrast()),
+            Theme::Custom(_, palette) => Arc::clone(palette),
         }
     }
 
+    pub fn name(&self) -> &str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::HighContrast => "high-contrast",
+            Theme::Custom(name, _) => name,
+        }
+    }
+
+    /// Adapt the theme to what the terminal can display
+    pub fn for_color_support(self, support: ColorSupport) -> Theme {
+        match (support, self) {
+            (ColorSupport::Ansi16, Theme::Custom(name, palette)) => {
+                Theme::Custom(name, Arc::new(palette.to_ansi16()))
+            }
+            (ColorSupport::Ansi256, Theme::Custom(name, palette)) => {
+                Theme::Custom(name, Arc::new(palette.to_ansi256()))
+            }
+            (ColorSupport::NoColor, theme) => Theme::Custom(
+                theme.name().to_string(),
+                Arc::new(ColorPalette {
+                    diff_markers: theme.uses_diff_markers(),
+                    ..ColorPalette::monochrome()
+                }),
+            ),
+            (_, theme) => theme,
+        }
+    }
+
+    /// Whether diff lines should be marked with symbols and text styles, not just color
+    pub fn uses_diff_markers(&self) -> bool {
+        self.get_palette().diff_markers
+    }
+
     pub fn background_color(&self) -> Color {
         self.get_palette().background
     }
@@ -167,6 +316,204 @@ pub fn parse_hex_color(hex_str: &str) -> Result<Color, String> {
     Ok(Color::Rgb(r, g, b))
 }
 
+/// What the terminal can render
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    NoColor,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Resolve color support from the configured mode and the environment
+    pub fn detect(mode: crate::config::ColorMode) -> Self {
+        Self::from_env_values(
+            mode,
+            std::env::var("NO_COLOR").ok().as_deref(),
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    fn from_env_values(
+        mode: crate::config::ColorMode,
+        no_color: Option<&str>,
+        colorterm: Option<&str>,
+        term: Option<&str>,
+    ) -> Self {
+        use crate::config::ColorMode;
+
+        match mode {
+            ColorMode::Always => return ColorSupport::TrueColor,
+            ColorMode::Never => return ColorSupport::NoColor,
+            ColorMode::Auto => {}
+        }
+
+        // https://no-color.org: any non-empty value disables color
+        if no_color.is_some_and(|v| !v.is_empty()) {
+            return ColorSupport::NoColor;
+        }
+
+        if colorterm
+            .is_some_and(|v| v.eq_ignore_ascii_case("truecolor") || v.eq_ignore_ascii_case("24bit"))
+        {
+            return ColorSupport::TrueColor;
+        }
+
+        match term {
+            Some("dumb") => ColorSupport::NoColor,
+            Some(term) if term.contains("direct") => ColorSupport::TrueColor,
+            Some(term) if term.contains("256color") => ColorSupport::Ansi256,
+            _ => ColorSupport::Ansi16,
+        }
+    }
+}
+
+/// The 16 standard ANSI colors with the RGB xterm renders them as
+const ANSI_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Map an RGB color to the closest of the 16 standard ANSI colors
+pub fn nearest_ansi_color(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    let (r, g, b) = (r as i32, g as i32, b as i32);
+
+    ANSI_COLORS
+        .iter()
+        .min_by_key(|(_, (ar, ag, ab))| {
+            (r - *ar as i32).pow(2) + (g - *ag as i32).pow(2) + (b - *ab as i32).pow(2)
+        })
+        .map(|(ansi, _)| *ansi)
+        .unwrap_or(color)
+}
+
+/// Levels of each channel in the xterm 256-color cube, colors 16 to 231
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Map an RGB color to the nearest of the xterm 256-color cube and gray ramp
+pub fn nearest_256_color(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    let distance = |(ar, ag, ab): (u8, u8, u8)| {
+        (r as i32 - ar as i32).pow(2)
+            + (g as i32 - ag as i32).pow(2)
+            + (b as i32 - ab as i32).pow(2)
+    };
+    let nearest_level = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - channel as i32).abs())
+            .unwrap_or(0)
+    };
+
+    let (ri, gi, bi) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+
+    // Grays 232 to 255 run from 8 to 238 in steps of 10
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * gray_step;
+    let gray = (gray_level, gray_level, gray_level);
+
+    if distance(gray) < distance(cube) {
+        Color::Indexed(232 + gray_step)
+    } else {
+        Color::Indexed(cube_index as u8)
+    }
+}
+
+/// RGB of an RGB or standard ANSI color, None for the terminal's own colors like `Reset`
+pub fn color_to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        color => ANSI_COLORS
+            .iter()
+            .find(|(ansi, _)| *ansi == color)
+            .map(|(_, rgb)| *rgb),
+    }
+}
+
+/// `from` moved `amount` (0.0 to 1.0) of the way toward `to`, or None when either has no RGB
+pub fn blend_colors(from: Color, to: Color, amount: f32) -> Option<Color> {
+    let (from, to) = (color_to_rgb(from)?, color_to_rgb(to)?);
+    let amount = amount.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount).round() as u8;
+    Some(Color::Rgb(
+        mix(from.0, to.0),
+        mix(from.1, to.1),
+        mix(from.2, to.2),
+    ))
+}
+
+/// Load a theme file into a palette
+/// Theme files are TOML with the same keys as the `custom_theme` config section
+pub fn load_theme_file(path: &std::path::Path) -> Result<ColorPalette, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read theme file {}: {}", path.display(), e))?;
+    let custom_theme: crate::config::CustomTheme = toml::from_str(&content)
+        .map_err(|e| format!("Failed to parse theme file {}: {}", path.display(), e))?;
+
+    ColorPalette::from_custom_theme(&custom_theme)
+        .ok_or_else(|| format!("Theme file {} has no valid colors", path.display()))
+}
+
+/// Load every `*.toml` theme in a directory as named custom themes, sorted by name
+pub fn load_themes_dir(dir: &std::path::Path) -> Vec<Theme> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        log::debug!("No themes directory at {}", dir.display());
+        return Vec::new();
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            match load_theme_file(&path) {
+                Ok(palette) => Some(Theme::Custom(name, Arc::new(palette))),
+                Err(e) => {
+                    log::warn!("{e}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// How long a typed count prefix waits for its motion before being discarded
+const COUNT_PREFIX_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Upper bound on count prefixes so a mistyped count can't stall the UI
+const MAX_COUNT_PREFIX: usize = 9999;
+
+/// How long a status bar message stays visible
+const STATUS_MESSAGE_DURATION: std::time::Duration = std::time::Duration::from_secs(4);
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FileBrowserPane {
     FileTree,
@@ -195,6 +542,13 @@ pub enum ActivePane {
     SideBySideDiff,
 }
 
+/// How strongly a recently changed file should be highlighted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecentChange {
+    Fresh,
+    Fading,
+}
+
 #[derive(Debug, Clone)]
 pub struct TreeDisplayNode {
     pub name: String,
@@ -203,24 +557,71 @@ pub struct TreeDisplayNode {
     pub status: Option<Status>,
     pub additions: usize,
     pub deletions: usize,
+    /// Number of new files below a wholly untracked directory
+    pub untracked_file_count: Option<usize>,
+    pub collapsed: bool,
+    pub submodule: bool,
+}
+
+/// Diff lines picked with `V`, vim visual-line style
+#[derive(Debug, Clone)]
+struct DiffSelection {
+    /// File the selection was made in; it lapses when another file is shown
+    path: std::path::PathBuf,
+    anchor: usize,
+    cursor: usize,
+}
+
+/// A file whose working tree changes are to be thrown away with `X`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscardRequest {
+    pub path: std::path::PathBuf,
+    /// Path before the file was renamed, restored in its place
+    pub old_path: Option<std::path::PathBuf>,
+    /// Deleted from disk rather than restored, since git has no copy of it
+    pub untracked: bool,
 }
 
 #[derive(Debug)]
 pub struct App {
     files: Vec<FileDiff>,
     current_file_index: usize,
-    scroll_offset: usize,
+    scroll: ScrollState,
     tree_nodes: Vec<(TreeDisplayNode, usize)>,
     current_tree_index: usize,
     file_indices_in_tree: Vec<usize>,
+    expanded_untracked_dirs: std::collections::HashSet<std::path::PathBuf>,
     pub last_g_press: Option<std::time::Instant>,
+    /// `]` or `[` and when it was pressed, waiting for the `c` of a hunk jump
+    pub last_bracket_press: Option<(char, std::time::Instant)>,
+    /// Start of a file name typed in the file tree, and when its last letter was typed
+    tree_jump: Option<(String, std::time::Instant)>,
     pub current_diff_height: usize,
     side_by_side_diff: bool,
     show_diff_panel: bool,
     show_changed_files_pane: bool,
     file_change_timestamps: Vec<std::time::Instant>,
-    monitor_output: String,
-    monitor_scroll_offset: usize,
+    recent_highlight_duration: std::time::Duration,
+    icons: crate::config::IconSet,
+    status_bar_style: crate::config::StatusBarStyle,
+    status_bar_lines: u16,
+    /// Directory levels the file tree nests before flattening deeper paths, None for no limit
+    max_tree_depth: Option<usize>,
+    /// Whether added and removed lines get a background by their hunk's change density
+    diff_heatmap: bool,
+    /// Rows of context kept above and below a line jumped or moved to in the diff
+    scroll_margin: usize,
+    /// Whether the diff title counts the functions the current file's changes touch
+    semantic_stats: bool,
+    /// Semantic stats computed so far, by file, dropped when the file's diff changes
+    semantic_stats_cache:
+        std::collections::HashMap<std::path::PathBuf, Option<crate::semantic_stats::SemanticStats>>,
+    spinner: crate::util::Spinner,
+    show_stash_count: bool,
+    show_unpushed: bool,
+    /// Latest lines from the monitor command, capped at `monitor_max_lines`
+    monitor_output: crate::monitor::MonitorBuffer,
+    monitor_scroll: ScrollState,
     show_monitor_pane: bool,
     monitor_visible_height: usize,
     monitor_command_configured: bool,
@@ -232,10 +633,72 @@ pub struct App {
     current_theme_index: usize,
     pane_registry: PaneRegistry,
     last_active_pane: ActivePane,
+    /// Pane moved to with Tab, if it's still on screen; otherwise focus follows the layout
+    focused_pane: Option<ActivePane>,
     app_mode: AppMode,
     selected_commit: Option<CommitInfo>,
     summary_preloader: SummaryPreloader,
     last_branch_name: Option<String>,
+    git_refresh_paused: bool,
+    theme_changed_at: Option<std::time::Instant>,
+    pending_count: Option<(usize, std::time::Instant)>,
+    status_message: Option<(String, std::time::Instant)>,
+    /// File to open in $EDITOR and, from the diff pane, the line to open it at
+    /// Picked up by the main loop, which owns the terminal
+    editor_request: Option<(std::path::PathBuf, Option<usize>)>,
+    /// Diff to pipe through `external_diff_command`, picked up by the main loop like
+    /// `editor_request`
+    external_diff_request: Option<String>,
+    /// Patch of one hunk to apply to the index, picked up by the main loop, which
+    /// refreshes once it's staged
+    hunk_stage_request: Option<String>,
+    /// Destructive action waiting on a yes or no, asked in a popup that takes every key
+    confirm_dialog: Option<ConfirmDialog>,
+    /// Confirmed discard, picked up by the main loop, which refreshes once it's done
+    discard_request: Option<DiscardRequest>,
+    /// Action needing the config or shared git state, run by the main loop
+    main_loop_action: Option<Action>,
+    commit_history_task: Option<CommitHistoryTask>,
+    /// Repository root, from the root of the last file tree
+    repo_root: std::path::PathBuf,
+    /// Token counts shared with the LLM client, None when no client is configured
+    llm_usage: Option<Arc<crate::llm::SessionUsage>>,
+    diff_selection: Option<DiffSelection>,
+    /// What the git worker is showing, mirrored by the main loop
+    view_mode: ViewMode,
+    /// View mode chosen by the user, shown instead of the automatic one until cleared
+    forced_view_mode: Option<ViewMode>,
+    /// The inline diff folds unchanged lines away, showing only the changes
+    fold_unchanged: bool,
+}
+
+/// Commit history loading on a blocking task, which posts its result to shared state
+#[derive(Debug)]
+pub struct CommitHistoryTask {
+    handle: tokio::task::JoinHandle<()>,
+    /// SHA the page resumes after, or None for the first page
+    after: Option<String>,
+    /// Loading HEAD's reflog rather than a page of history
+    reflog: bool,
+}
+
+impl CommitHistoryTask {
+    pub fn new(handle: tokio::task::JoinHandle<()>, after: Option<String>) -> Self {
+        Self {
+            handle,
+            after,
+            reflog: false,
+        }
+    }
+
+    /// A task loading HEAD's reflog, which comes in a single page
+    pub fn reflog(handle: tokio::task::JoinHandle<()>) -> Self {
+        Self {
+            handle,
+            after: None,
+            reflog: true,
+        }
+    }
 }
 
 impl App {
@@ -251,33 +714,42 @@ impl App {
             .get(initial_theme_index)
             .cloned()
             .unwrap_or(Theme::Dark);
-        let pane_registry = if let Some(ref llm_client) = llm_client {
-            PaneRegistry::new(theme.clone(), llm_client.clone(), llm_state.clone())
-        } else {
-            // Provide a dummy or default LlmClient for the registry when none is available.
-            let mut dummy_llm_config = crate::config::LlmConfig::default();
-            if std::env::var("OPENAI_API_KEY").is_err() {
-                dummy_llm_config.api_key = Some("dummy_key".to_string());
-            }
-            let dummy_llm_client = LlmClient::new(dummy_llm_config).unwrap();
-            PaneRegistry::new(theme.clone(), dummy_llm_client, llm_state.clone())
-        };
+        let pane_registry = PaneRegistry::new(theme.clone(), llm_client.clone(), llm_state.clone());
 
         Self {
             files: Vec::new(),
             current_file_index: 0,
-            scroll_offset: 0,
+            scroll: ScrollState::default(),
             tree_nodes: Vec::new(),
             current_tree_index: 0,
             file_indices_in_tree: Vec::new(),
+            expanded_untracked_dirs: std::collections::HashSet::new(),
             last_g_press: None,
+            last_bracket_press: None,
+            tree_jump: None,
             current_diff_height: 20,
             side_by_side_diff: false,
             show_diff_panel,
             show_changed_files_pane,
             file_change_timestamps: Vec::new(),
-            monitor_output: String::new(),
-            monitor_scroll_offset: 0,
+            recent_highlight_duration: std::time::Duration::from_millis(
+                crate::config::DEFAULT_RECENT_HIGHLIGHT_MS,
+            ),
+            icons: crate::config::IconSet::default(),
+            status_bar_style: crate::config::StatusBarStyle::default(),
+            status_bar_lines: 1,
+            max_tree_depth: None,
+            diff_heatmap: false,
+            scroll_margin: 0,
+            semantic_stats: false,
+            semantic_stats_cache: std::collections::HashMap::new(),
+            spinner: crate::util::Spinner::new(),
+            show_stash_count: true,
+            show_unpushed: true,
+            monitor_output: crate::monitor::MonitorBuffer::new(
+                crate::config::DEFAULT_MONITOR_MAX_LINES,
+            ),
+            monitor_scroll: ScrollState::default(),
             show_monitor_pane: false,
             monitor_visible_height: 10, // Default value
             monitor_command_configured: false,
@@ -289,10 +761,28 @@ impl App {
             current_theme_index: initial_theme_index,
             pane_registry,
             last_active_pane: ActivePane::default(),
+            focused_pane: None,
             app_mode: AppMode::Normal,
             selected_commit: None,
             summary_preloader: SummaryPreloader::new(llm_client.clone(), Arc::clone(&llm_state)),
             last_branch_name: None,
+            git_refresh_paused: false,
+            theme_changed_at: None,
+            pending_count: None,
+            status_message: None,
+            editor_request: None,
+            external_diff_request: None,
+            hunk_stage_request: None,
+            confirm_dialog: None,
+            discard_request: None,
+            main_loop_action: None,
+            commit_history_task: None,
+            repo_root: std::path::PathBuf::new(),
+            llm_usage: llm_client.as_ref().map(LlmClient::session_usage),
+            diff_selection: None,
+            view_mode: ViewMode::WorkingTree,
+            forced_view_mode: None,
+            fold_unchanged: false,
         }
     }
 
@@ -347,29 +837,60 @@ impl App {
 
         self.file_change_timestamps = new_timestamps;
 
+        // Semantic stats are for one diff, so they go when it changes
+        if !self.semantic_stats_cache.is_empty() {
+            let files = &self.files;
+            self.semantic_stats_cache.retain(|path, _| {
+                let old = old_files.iter().find(|file| file.path == *path);
+                let new = files.iter().find(|file| file.path == *path);
+                matches!((old, new), (Some(old), Some(new)) if old.line_strings == new.line_strings)
+            });
+        }
+
         // Try to preserve the current file selection by finding the same file path
         if let Some(ref path) = current_file_path {
             if let Some(new_index) = self.files.iter().position(|f| f.path == *path) {
                 // Found the same file in the new list, update the index
                 self.current_file_index = new_index;
-                // Don't reset scroll_offset since we're staying on the same file
+                // Don't reset the scroll offset since we're staying on the same file
             } else if self.current_file_index >= self.files.len() {
                 // Current file no longer exists, reset to first file
                 self.current_file_index = 0;
-                self.scroll_offset = 0;
+                self.scroll.offset = 0;
             }
         } else if self.current_file_index >= self.files.len() {
             // No current file was selected or index is out of bounds
             self.current_file_index = 0;
-            self.scroll_offset = 0;
+            self.scroll.offset = 0;
+        }
+
+        // Another file now sits at the selected index, or the same one got shorter; either
+        // way the old offset could leave the diff scrolled past its end
+        let selected_path = self.files.get(self.current_file_index).map(|f| &f.path);
+        if selected_path != current_file_path.as_ref() {
+            self.scroll.offset = 0;
+        } else {
+            self.clamp_scroll_offset();
+        }
+
+        // Keep search results in step with the files being displayed
+        if self.is_searching() {
+            self.refresh_search_results();
         }
     }
 
     pub fn update_tree(&mut self, tree: &TreeNode) {
+        self.repo_root = tree.path.clone();
         self.tree_nodes = Vec::new();
         self.current_tree_index = 0;
         self.file_indices_in_tree = Vec::new();
 
+        // Expand/collapse choices carry over the rebuild; directories that are gone drop out
+        let mut untracked_dirs = std::collections::HashSet::new();
+        collect_untracked_dirs(tree, &mut untracked_dirs);
+        self.expanded_untracked_dirs
+            .retain(|dir| untracked_dirs.contains(dir));
+
         for node in &tree.children {
             self.add_tree_node_recursive(node, 1, &mut Vec::new());
         }
@@ -381,14 +902,35 @@ impl App {
     fn add_tree_node_recursive(&mut self, node: &TreeNode, depth: usize, path: &mut Vec<String>) {
         path.push(node.name.clone());
 
-        if node.file_diff.is_some() || !node.children.is_empty() {
+        // Wholly untracked directories are collapsed into a single node unless expanded
+        let collapsed = node.untracked && !self.expanded_untracked_dirs.contains(&node.path);
+
+        // Past the depth limit, directories are folded into the names of what's below them,
+        // listed one level under the last directory shown
+        let flatten_from = self.max_tree_depth.filter(|&max_depth| depth > max_depth);
+        let shown = match flatten_from {
+            Some(_) => node.file_diff.is_some() || collapsed,
+            None => node.file_diff.is_some() || !node.children.is_empty(),
+        };
+
+        if shown {
+            let (name, depth) = match flatten_from {
+                Some(max_depth) => (path[max_depth..].join("/"), max_depth + 1),
+                None => (node.name.clone(), depth),
+            };
             let display_node = TreeDisplayNode {
-                name: node.name.clone(),
+                name,
                 path: node.path.clone(),
                 is_dir: node.is_dir,
                 status: node.file_diff.as_ref().map(|d| d.status),
                 additions: node.file_diff.as_ref().map(|d| d.additions).unwrap_or(0),
                 deletions: node.file_diff.as_ref().map(|d| d.deletions).unwrap_or(0),
+                untracked_file_count: node.untracked.then(|| node.file_count()),
+                collapsed,
+                submodule: node
+                    .file_diff
+                    .as_ref()
+                    .is_some_and(|d| d.submodule.is_some()),
             };
             self.tree_nodes.push((display_node, depth));
 
@@ -398,18 +940,65 @@ impl App {
                 } else {
                     self.file_indices_in_tree.push(usize::MAX);
                 }
+            } else if collapsed {
+                // A collapsed directory stands in for its files, so navigation can land on it
+                let current_inside = self
+                    .files
+                    .get(self.current_file_index)
+                    .is_some_and(|f| f.path.starts_with(&node.path));
+                let file_index = if current_inside {
+                    Some(self.current_file_index)
+                } else {
+                    self.files
+                        .iter()
+                        .position(|f| f.path.starts_with(&node.path))
+                };
+                self.file_indices_in_tree
+                    .push(file_index.unwrap_or(usize::MAX));
             } else {
                 self.file_indices_in_tree.push(usize::MAX);
             }
         }
 
-        for child in &node.children {
-            self.add_tree_node_recursive(child, depth + 1, path);
+        if !collapsed {
+            for child in &node.children {
+                self.add_tree_node_recursive(child, depth + 1, path);
+            }
         }
 
         path.pop();
     }
 
+    /// Expand or collapse the untracked directory under the cursor
+    /// Takes effect the next time the tree is rebuilt
+    pub fn toggle_untracked_dir(&mut self) -> bool {
+        let Some((node, _)) = self.tree_nodes.get(self.current_tree_index) else {
+            return false;
+        };
+
+        if node.is_dir && node.untracked_file_count.is_some() {
+            if !self.expanded_untracked_dirs.remove(&node.path) {
+                self.expanded_untracked_dirs.insert(node.path.clone());
+            }
+            return true;
+        }
+
+        // Collapse the expanded untracked directory containing the current file
+        let current_path = node.path.clone();
+        let containing_dir = self
+            .expanded_untracked_dirs
+            .iter()
+            .filter(|dir| current_path.starts_with(dir))
+            .max_by_key(|dir| dir.components().count())
+            .cloned();
+        if let Some(dir) = containing_dir {
+            self.expanded_untracked_dirs.remove(&dir);
+            return true;
+        }
+
+        false
+    }
+
     fn sync_tree_index_with_file_index(&mut self) {
         if let Some(tree_index) = self
             .file_indices_in_tree
@@ -421,6 +1010,9 @@ impl App {
             // Find the first valid file index
             for (i, &file_idx) in self.file_indices_in_tree.iter().enumerate() {
                 if file_idx != usize::MAX {
+                    if file_idx != self.current_file_index {
+                        self.scroll.offset = 0;
+                    }
                     self.current_file_index = file_idx;
                     self.current_tree_index = i;
                     break;
@@ -429,111 +1021,518 @@ impl App {
         }
     }
 
-    pub fn scroll_down(&mut self, max_lines: usize) {
-        if self.current_file_index < self.files.len() {
-            let current_file = &self.files[self.current_file_index];
-            if self.scroll_offset + max_lines < current_file.line_strings.len() {
-                self.scroll_offset += 1;
-            }
+    /// Furthest the current file can scroll while still filling `max_lines`
+    fn max_scroll_offset(&self, max_lines: usize) -> usize {
+        self.diff_row_count().saturating_sub(max_lines)
+    }
+
+    /// Whether the inline diff is showing with unchanged lines folded away
+    pub fn is_diff_folded(&self) -> bool {
+        self.fold_unchanged && self.current_information_pane == InformationPane::Diff
+    }
+
+    /// Rows of the current file's inline diff, one per line unless unchanged lines are folded
+    pub fn diff_rows(&self) -> Vec<DiffRow> {
+        let Some(file) = self.get_current_file() else {
+            return Vec::new();
+        };
+        if self.is_diff_folded() {
+            crate::pane::fold_unchanged_lines(&file.line_strings)
+        } else {
+            (0..file.line_strings.len()).map(DiffRow::Line).collect()
         }
     }
 
-    pub fn page_down(&mut self, max_lines: usize) {
-        if self.current_file_index < self.files.len() {
-            let current_file = &self.files[self.current_file_index];
-            let total_lines = current_file.line_strings.len();
-            if total_lines > max_lines {
-                self.scroll_offset = (self.scroll_offset + max_lines).min(total_lines - max_lines);
-            }
+    fn diff_row_count(&self) -> usize {
+        if self.is_diff_folded() {
+            self.diff_rows().len()
+        } else {
+            self.get_current_file()
+                .map_or(0, |file| file.line_strings.len())
         }
     }
 
-    pub fn page_up(&mut self, max_lines: usize) {
-        self.scroll_offset = self.scroll_offset.saturating_sub(max_lines);
+    /// Row of the inline diff showing `line`, or the fold marker it's hidden behind
+    fn row_for_line(&self, line: usize) -> usize {
+        if !self.is_diff_folded() {
+            return line;
+        }
+        let rows = self.diff_rows();
+        match rows
+            .iter()
+            .position(|row| matches!(row, DiffRow::Line(i) if *i >= line))
+        {
+            Some(row) if rows[row] == DiffRow::Line(line) => row,
+            Some(row) if row > 0 && matches!(rows[row - 1], DiffRow::Fold(_)) => row - 1,
+            Some(row) => row,
+            None => rows.len().saturating_sub(1),
+        }
     }
 
-    pub fn scroll_up(&mut self) {
-        if self.scroll_offset > 0 {
-            self.scroll_offset -= 1;
+    /// Line of the diff at `row` of the inline diff, or the next line shown after a fold
+    fn line_for_row(&self, row: usize) -> usize {
+        if !self.is_diff_folded() {
+            return row;
         }
+        let rows = self.diff_rows();
+        rows.iter()
+            .skip(row)
+            .chain(rows.iter().rev())
+            .find_map(|row| match row {
+                DiffRow::Line(i) => Some(*i),
+                DiffRow::Fold(_) => None,
+            })
+            .unwrap_or(0)
     }
 
-    pub fn scroll_to_bottom(&mut self, max_lines: usize) {
-        if let Some(file) = self.get_current_file() {
-            let total_lines = file.line_strings.len();
-            if total_lines > max_lines {
-                self.scroll_offset = total_lines - max_lines;
-            } else {
-                self.scroll_offset = 0;
+    /// Fold unchanged lines out of the inline diff, or show them again, keeping the top
+    /// line in view
+    pub fn toggle_diff_folding(&mut self) {
+        let top_line = self.line_for_row(self.scroll.offset);
+        self.fold_unchanged = !self.fold_unchanged;
+        self.diff_selection = None;
+        self.scroll.offset = self
+            .row_for_line(top_line)
+            .min(self.max_scroll_offset(self.current_diff_height));
+
+        let message = if self.fold_unchanged {
+            "Folding unchanged lines (z to show them)"
+        } else {
+            "Showing unchanged lines"
+        };
+        self.set_status_message(message.to_string());
+    }
+
+    /// Pull the diff back from past the end of the current file
+    fn clamp_scroll_offset(&mut self) {
+        self.scroll.offset = self
+            .scroll
+            .offset
+            .min(self.max_scroll_offset(self.current_diff_height));
+    }
+
+    /// Pull the diff and monitor back within their content after the panes change size
+    pub fn clamp_scroll_offsets(&mut self) {
+        self.clamp_scroll_offset();
+        self.monitor_scroll.offset = self
+            .monitor_scroll
+            .offset
+            .min(self.max_monitor_scroll_offset());
+    }
+
+    /// Apply a standard scroll movement to the diff
+    pub fn scroll_diff(&mut self, action: ScrollAction, max_lines: usize) {
+        let max_offset = self.max_scroll_offset(max_lines);
+        self.scroll.apply(action, max_offset, max_lines);
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll.offset = 0;
+    }
+
+    /// Rows of context kept between a line jumped or moved to and the diff's top or bottom
+    /// edge, at most half the diff's height so the line can always sit between them
+    fn effective_scroll_margin(&self) -> usize {
+        self.scroll_margin
+            .min(self.current_diff_height.saturating_sub(1) / 2)
+    }
+
+    /// Row a jump lands on and `s` stages from: the top row, or the scroll margin below it
+    fn focus_row(&self) -> usize {
+        (self.scroll.offset + self.effective_scroll_margin())
+            .min(self.diff_row_count().saturating_sub(1))
+    }
+
+    /// Scroll so `line` is at the top of the diff, below the scroll margin, without
+    /// scrolling past the end
+    pub fn scroll_to_line(&mut self, line: usize) {
+        self.scroll.offset = self
+            .row_for_line(line)
+            .saturating_sub(self.effective_scroll_margin())
+            .min(self.max_scroll_offset(self.current_diff_height));
+    }
+
+    /// Start selecting diff lines at the top visible line, or the scroll margin below it,
+    /// like vim's `V`
+    pub fn start_diff_selection(&mut self) {
+        if !(self.show_diff_panel && self.current_information_pane == InformationPane::Diff) {
+            self.set_status_message("Line selection needs the inline diff view".to_string());
+            return;
+        }
+        if self.is_diff_folded() {
+            self.set_status_message("Line selection needs unchanged lines shown (z)".to_string());
+            return;
+        }
+        match self.get_current_file() {
+            Some(file) if !file.line_strings.is_empty() => {
+                let line = self.focus_row().min(file.line_strings.len() - 1);
+                self.diff_selection = Some(DiffSelection {
+                    path: file.path.clone(),
+                    anchor: line,
+                    cursor: line,
+                });
             }
+            _ => self.set_status_message("No diff lines to select".to_string()),
         }
     }
 
-    pub fn scroll_to_top(&mut self) {
-        self.scroll_offset = 0;
+    pub fn is_selecting_diff(&self) -> bool {
+        self.diff_selection_range().is_some()
     }
 
-    pub fn handle_g_press(&mut self) -> bool {
-        let now = std::time::Instant::now();
-        let is_double_press = if let Some(last_time) = self.last_g_press {
-            now.duration_since(last_time).as_millis() < 500
-        } else {
-            false
+    /// First and last selected line of the current file's diff, if any are selected
+    pub fn diff_selection_range(&self) -> Option<(usize, usize)> {
+        let selection = self.diff_selection.as_ref()?;
+        let file = self
+            .get_current_file()
+            .filter(|file| file.path == selection.path)?;
+        let last = file.line_strings.len().checked_sub(1)?;
+        let start = selection.anchor.min(selection.cursor).min(last);
+        let end = selection.anchor.max(selection.cursor).min(last);
+        Some((start, end))
+    }
+
+    /// Extend the selection with a scroll motion, scrolling to keep its end in view
+    pub fn move_diff_selection(&mut self, action: ScrollAction) {
+        let last = self
+            .get_current_file()
+            .map(|file| file.line_strings.len().saturating_sub(1))
+            .unwrap_or(0);
+        let page_size = self.current_diff_height.max(1);
+        let margin = self.effective_scroll_margin();
+        let max_offset = self.max_scroll_offset(self.current_diff_height);
+        let Some(selection) = self.diff_selection.as_mut() else {
+            return;
         };
 
-        self.last_g_press = Some(now);
+        let mut cursor = ScrollState::default();
+        cursor.offset = selection.cursor.min(last);
+        cursor.apply(action, last, page_size);
+        selection.cursor = cursor.offset;
 
-        if is_double_press {
-            self.scroll_to_top();
-            true
-        } else {
-            false
+        if cursor.offset < self.scroll.offset + margin {
+            self.scroll.offset = cursor.offset.saturating_sub(margin);
+        } else if cursor.offset + margin >= self.scroll.offset + page_size {
+            self.scroll.offset = (cursor.offset + margin + 1 - page_size).min(max_offset);
         }
     }
 
-    pub fn toggle_help(&mut self) {
-        let help_pane_visible = if let Some(help_pane) = self.pane_registry.get_pane(&PaneId::Help)
-        {
-            help_pane.visible()
-        } else {
-            false
+    pub fn cancel_diff_selection(&mut self) {
+        self.diff_selection = None;
+    }
+
+    /// Ask the LLM to explain the selected lines, showing its reply in a popup
+    pub fn explain_diff_selection(&mut self) {
+        let Some((start, end)) = self.diff_selection_range() else {
+            return;
+        };
+        self.diff_selection = None;
+        if self.llm_usage.is_none() {
+            self.set_status_message("Explaining lines needs an LLM to be configured".to_string());
+            return;
+        }
+        let Some(file) = self.get_current_file() else {
+            return;
         };
 
-        if help_pane_visible {
-            // Hide help pane
-            self.pane_registry
-                .with_pane_mut(&PaneId::Help, |help_pane| {
-                    help_pane.set_visible(false);
-                });
-            // Hide help pane and restore the last active pane
-            match self.last_active_pane {
-                ActivePane::FileTree => {
-                    // This case implies the information pane was not shown.
-                    // We can't restore this state perfectly without more info,
-                    // so we'll default to the standard diff view.
-                    self.set_single_pane_diff();
-                    self.current_information_pane = InformationPane::Diff;
-                }
-                ActivePane::Monitor => {
-                    // Same as above, monitor is on the left. Default to diff view on right.
-                    self.set_single_pane_diff();
-                    self.current_information_pane = InformationPane::Diff;
-                }
-                ActivePane::Diff => {
-                    self.set_single_pane_diff();
-                    self.current_information_pane = InformationPane::Diff;
-                }
-                ActivePane::SideBySideDiff => {
-                    self.set_side_by_side_diff();
-                    self.current_information_pane = InformationPane::SideBySideDiff;
+        let path = file
+            .path
+            .strip_prefix(&self.repo_root)
+            .unwrap_or(&file.path)
+            .display()
+            .to_string();
+        // Describe the range by new-file line numbers where the selection has any
+        let new_lines: Vec<usize> = (start..=end)
+            .filter_map(|index| file.new_file_line(index))
+            .collect();
+        let location = match (new_lines.first(), new_lines.last()) {
+            (Some(first), Some(last)) if first == last => format!("{path} line {first}"),
+            (Some(first), Some(last)) => format!("{path} lines {first}-{last}"),
+            _ => path.clone(),
+        };
+        let lines = file.excerpt(start, end);
+
+        log::debug!("Explaining {} diff lines of {location}", lines.len());
+        self.pane_registry
+            .with_pane_mut(&PaneId::Explanation, |pane| {
+                if let Some(explanation) = pane.as_explanation_pane_mut() {
+                    explanation.explain(path, location, lines);
                 }
-            }
-        } else {
-            // Determine which pane is active before showing help
-            if self
-                .pane_registry
-                .get_pane(&PaneId::SideBySideDiff)
-                .is_some_and(|p| p.visible())
+            });
+    }
+
+    pub fn set_view_mode(&mut self, view_mode: ViewMode) {
+        if view_mode != self.view_mode {
+            self.semantic_stats_cache.clear();
+        }
+        self.view_mode = view_mode;
+    }
+
+    pub fn get_forced_view_mode(&self) -> Option<ViewMode> {
+        self.forced_view_mode
+    }
+
+    /// Pin the staged view so it shows even with unstaged changes around, or go back to
+    /// picking the view automatically
+    pub fn toggle_staged_view(&mut self) {
+        self.forced_view_mode = match self.forced_view_mode {
+            Some(ViewMode::Staged) => None,
+            _ => Some(ViewMode::Staged),
+        };
+        let message = match self.forced_view_mode {
+            Some(_) => "Showing staged changes only (S to go back)",
+            None => "Choosing the view automatically again",
+        };
+        self.set_status_message(message.to_string());
+    }
+
+    /// Pin the view showing each file's staged changes above its unstaged ones, or go
+    /// back to picking the view automatically
+    pub fn toggle_staged_and_unstaged_view(&mut self) {
+        self.forced_view_mode = match self.forced_view_mode {
+            Some(ViewMode::StagedAndUnstaged) => None,
+            _ => Some(ViewMode::StagedAndUnstaged),
+        };
+        let message = match self.forced_view_mode {
+            Some(_) => "Showing staged and unstaged changes together (B to go back)",
+            None => "Choosing the view automatically again",
+        };
+        self.set_status_message(message.to_string());
+    }
+
+    /// Step the view through working tree, staged and last commit, then back to automatic
+    pub fn cycle_view_mode(&mut self) {
+        self.forced_view_mode = match self.forced_view_mode {
+            None => Some(ViewMode::WorkingTree),
+            Some(ViewMode::WorkingTree) => Some(ViewMode::Staged),
+            Some(ViewMode::Staged) => Some(ViewMode::LastCommit),
+            Some(_) => None,
+        };
+        let message = match self.forced_view_mode {
+            Some(ViewMode::WorkingTree) => "Showing the working tree (v for the next view)",
+            Some(ViewMode::Staged) => "Showing staged changes (v for the next view)",
+            Some(_) => "Showing the last commit (v to go back to automatic)",
+            None => "Choosing the view automatically again",
+        };
+        self.set_status_message(message.to_string());
+    }
+
+    /// Ask the LLM to draft a commit message for the staged changes, shown in an editable popup
+    pub fn draft_commit_message(&mut self) {
+        if self.view_mode != ViewMode::Staged || self.selected_commit.is_some() {
+            self.set_status_message("Commit messages are drafted from the staged view".to_string());
+            return;
+        }
+        if self.llm_usage.is_none() {
+            self.set_status_message(
+                "Drafting commit messages needs an LLM to be configured".to_string(),
+            );
+            return;
+        }
+
+        let diff: String = self
+            .files
+            .iter()
+            .map(|file| file.unified_diff(&self.repo_root))
+            .collect();
+        if diff.trim().is_empty() {
+            self.set_status_message("No staged changes to describe".to_string());
+            return;
+        }
+        // COMMIT_EDITMSG lives in the git directory, which isn't always `.git` under the root
+        let git_dir = crate::git::operations::discover_repository()
+            .ok()
+            .map(|(repo, _)| repo.path().to_path_buf());
+
+        log::debug!(
+            "Drafting a commit message for {} staged files",
+            self.files.len()
+        );
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommitDraft, |pane| {
+                if let Some(commit_draft) = pane.as_commit_draft_pane_mut() {
+                    commit_draft.draft(diff, git_dir);
+                }
+            });
+    }
+
+    /// Copy the current file's changes to the clipboard as a patch for `git apply`
+    pub fn copy_current_patch(&mut self) {
+        let message = match self.current_patch() {
+            Ok(patch) => match crate::clipboard::copy(&patch) {
+                Ok(()) => "Copied the patch to the clipboard".to_string(),
+                Err(e) => format!("Copy failed: {e}"),
+            },
+            Err(e) => e,
+        };
+        self.set_status_message(message);
+    }
+
+    /// Write the current file's changes as `<file name>.patch` in the working directory,
+    /// where `git format-patch` would put it too
+    pub fn save_current_patch(&mut self) {
+        let message = match std::env::current_dir() {
+            Ok(dir) => match self.write_current_patch(&dir) {
+                Ok(path) => format!("Saved the patch to {}", path.display()),
+                Err(e) => e,
+            },
+            Err(e) => format!("Save failed: {e}"),
+        };
+        self.set_status_message(message);
+    }
+
+    fn current_patch(&self) -> Result<String, String> {
+        let file = self
+            .get_current_file()
+            .ok_or_else(|| "No file selected".to_string())?;
+        file.patch(&self.repo_root)
+    }
+
+    fn write_current_patch(&self, dir: &std::path::Path) -> Result<std::path::PathBuf, String> {
+        let patch = self.current_patch()?;
+        let file_name = self
+            .get_current_file()
+            .and_then(|file| file.path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let path = dir.join(format!("{file_name}.patch"));
+        std::fs::write(&path, patch).map_err(|e| format!("Save failed: {e}"))?;
+        Ok(path)
+    }
+
+    /// Append a digit to the vim-style count prefix for the next motion
+    pub fn push_count_digit(&mut self, digit: usize) {
+        let count = self.pending_count().unwrap_or(0);
+        let count = count
+            .saturating_mul(10)
+            .saturating_add(digit)
+            .min(MAX_COUNT_PREFIX);
+        self.pending_count = Some((count, std::time::Instant::now()));
+    }
+
+    /// The count prefix typed so far, unless it has timed out
+    pub fn pending_count(&self) -> Option<usize> {
+        self.pending_count
+            .filter(|(_, typed_at)| typed_at.elapsed() < COUNT_PREFIX_TIMEOUT)
+            .map(|(count, _)| count)
+    }
+
+    /// Consume the count prefix so it only applies to a single command
+    pub fn take_pending_count(&mut self) -> Option<usize> {
+        let count = self.pending_count();
+        self.pending_count = None;
+        count
+    }
+
+    pub fn handle_g_press(&mut self) -> bool {
+        let now = std::time::Instant::now();
+        let is_double_press = if let Some(last_time) = self.last_g_press {
+            now.duration_since(last_time).as_millis() < 500
+        } else {
+            false
+        };
+
+        self.last_g_press = Some(now);
+
+        if is_double_press {
+            self.scroll_to_top();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Finish a `]c` / `[c` hunk jump if `]` or `[` was pressed just before the `c`
+    pub fn handle_hunk_jump_key(&mut self) -> bool {
+        let Some((bracket, pressed_at)) = self.last_bracket_press.take() else {
+            return false;
+        };
+        if pressed_at.elapsed().as_millis() >= 500 {
+            return false;
+        }
+        self.jump_to_hunk(bracket == ']');
+        true
+    }
+
+    /// Rows of the inline diff holding a `@@` hunk header
+    fn hunk_rows(&self) -> Vec<usize> {
+        let Some(file) = self.get_current_file() else {
+            return Vec::new();
+        };
+        self.diff_rows()
+            .iter()
+            .enumerate()
+            .filter_map(|(row, diff_row)| match diff_row {
+                DiffRow::Line(i) if file.line_strings[*i].starts_with("@@") => Some(row),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Scroll the next or previous hunk header to the top of the diff, below the scroll margin
+    pub fn jump_to_hunk(&mut self, forward: bool) {
+        let margin = self.effective_scroll_margin();
+        let position = self.focus_row();
+        let hunks = self.hunk_rows();
+        let target = if forward {
+            hunks.into_iter().find(|&row| row > position)
+        } else {
+            hunks.into_iter().rev().find(|&row| row < position)
+        };
+        match target {
+            Some(row) => {
+                self.scroll.offset = row
+                    .saturating_sub(margin)
+                    .min(self.max_scroll_offset(self.current_diff_height))
+            }
+            None if forward => self.set_status_message("No next hunk".to_string()),
+            None => self.set_status_message("No previous hunk".to_string()),
+        }
+    }
+
+    pub fn toggle_help(&mut self) {
+        let help_pane_visible = if let Some(help_pane) = self.pane_registry.get_pane(&PaneId::Help)
+        {
+            help_pane.visible()
+        } else {
+            false
+        };
+
+        if help_pane_visible {
+            // Hide help pane
+            self.pane_registry
+                .with_pane_mut(&PaneId::Help, |help_pane| {
+                    help_pane.set_visible(false);
+                });
+            // Hide help pane and restore the last active pane
+            match self.last_active_pane {
+                ActivePane::FileTree => {
+                    // This case implies the information pane was not shown.
+                    // We can't restore this state perfectly without more info,
+                    // so we'll default to the standard diff view.
+                    self.set_single_pane_diff();
+                    self.current_information_pane = InformationPane::Diff;
+                }
+                ActivePane::Monitor => {
+                    // Same as above, monitor is on the left. Default to diff view on right.
+                    self.set_single_pane_diff();
+                    self.current_information_pane = InformationPane::Diff;
+                }
+                ActivePane::Diff => {
+                    self.set_single_pane_diff();
+                    self.current_information_pane = InformationPane::Diff;
+                }
+                ActivePane::SideBySideDiff => {
+                    self.set_side_by_side_diff();
+                    self.current_information_pane = InformationPane::SideBySideDiff;
+                }
+            }
+        } else {
+            // Determine which pane is active before showing help
+            if self
+                .pane_registry
+                .get_pane(&PaneId::SideBySideDiff)
+                .is_some_and(|p| p.visible())
             {
                 self.last_active_pane = ActivePane::SideBySideDiff;
             } else if self
@@ -567,6 +1566,15 @@ impl App {
         }
     }
 
+    /// Forward a key to the help pane, which consumes it while filtering
+    pub fn forward_key_to_help(&mut self, key: KeyEvent) -> bool {
+        self.pane_registry
+            .with_pane_mut(&PaneId::Help, |pane| {
+                pane.handle_event(&crate::pane::AppEvent::Key(key))
+            })
+            .unwrap_or(false)
+    }
+
     pub fn is_showing_help(&self) -> bool {
         if let Some(help_pane) = self.pane_registry.get_pane(&PaneId::Help) {
             help_pane.visible()
@@ -634,7 +1642,7 @@ impl App {
                 {
                     self.current_file_index = file_idx;
                     self.current_tree_index = next_tree_index;
-                    self.scroll_offset = 0;
+                    self.scroll.offset = 0;
                     return;
                 }
                 next_tree_index = (next_tree_index + 1) % self.tree_nodes.len();
@@ -643,7 +1651,7 @@ impl App {
             // If we couldn't find another file, just cycle through files directly
             self.current_file_index = (self.current_file_index + 1) % self.files.len();
             self.sync_tree_index_with_file_index();
-            self.scroll_offset = 0;
+            self.scroll.offset = 0;
         }
     }
 
@@ -664,7 +1672,7 @@ impl App {
                 {
                     self.current_file_index = file_idx;
                     self.current_tree_index = prev_tree_index;
-                    self.scroll_offset = 0;
+                    self.scroll.offset = 0;
                     return;
                 }
                 prev_tree_index = if prev_tree_index == 0 {
@@ -681,39 +1689,111 @@ impl App {
                 self.current_file_index - 1
             };
             self.sync_tree_index_with_file_index();
-            self.scroll_offset = 0;
+            self.scroll.offset = 0;
         }
     }
 
-    pub fn get_current_file(&self) -> Option<&FileDiff> {
-        self.files.get(self.current_file_index)
+    /// Start typing a file name to jump to in the file tree
+    pub fn start_tree_jump(&mut self) {
+        self.tree_jump = Some((String::new(), std::time::Instant::now()));
     }
 
-    pub fn update_monitor_output(&mut self, output: String) {
-        self.monitor_output = output.clone();
-        // Update the pane registry as well
-        self.pane_registry
-            .with_pane_mut(&PaneId::Monitor, |monitor_pane| {
-                let _ = monitor_pane.handle_event(&crate::pane::AppEvent::DataUpdated((), output));
-            });
-        // Don't reset scroll offset - preserve user's current scroll position
+    /// Type `c` as part of a file name to jump to in the file tree; false when no jump was
+    /// started or nothing matches, so it can act as a hotkey instead
+    pub fn handle_tree_jump_key(&mut self, c: char) -> bool {
+        self.type_to_jump(c, std::time::Instant::now())
     }
 
-    pub fn scroll_monitor_down(&mut self) {
-        let lines: Vec<&str> = self.monitor_output.lines().collect();
-        if !lines.is_empty() {
-            // Only scroll if there's more content below the current view
-            let max_scroll = lines.len().saturating_sub(self.monitor_visible_height);
-            if self.monitor_scroll_offset < max_scroll {
-                self.monitor_scroll_offset += 1;
+    /// After `start_tree_jump`, letters typed within `TREE_JUMP_TIMEOUT_MS` of each other build
+    /// up a name prefix, and the cursor moves to the next node whose name starts with it; typing
+    /// the same single letter again cycles through the nodes starting with it
+    fn type_to_jump(&mut self, c: char, now: std::time::Instant) -> bool {
+        let prefix = match self.tree_jump.take() {
+            Some((prefix, typed_at))
+                if now.duration_since(typed_at).as_millis() < TREE_JUMP_TIMEOUT_MS =>
+            {
+                prefix
             }
+            _ => return false,
+        };
+        let lowered: String = c.to_lowercase().collect();
+        let cycling = prefix == lowered;
+        let prefix = if cycling { prefix } else { prefix + &lowered };
+
+        // A longer prefix can still match the current node; cycling moves past it
+        let count = self.tree_nodes.len();
+        let start = if cycling { 1 } else { 0 };
+        let found = (start..count + start)
+            .map(|offset| (self.current_tree_index + offset) % count)
+            .find(|&index| {
+                self.file_indices_in_tree
+                    .get(index)
+                    .is_some_and(|&file_index| file_index != usize::MAX)
+                    && self.tree_nodes[index]
+                        .0
+                        .name
+                        .to_lowercase()
+                        .starts_with(&prefix)
+            });
+        // Nothing matching ends the jump and leaves the key to its hotkey
+        let Some(index) = found else {
+            return false;
+        };
+        let file_index = self.file_indices_in_tree[index];
+        if file_index != self.current_file_index {
+            self.scroll.offset = 0;
         }
+        self.current_tree_index = index;
+        self.current_file_index = file_index;
+
+        self.tree_jump = Some((prefix, now));
+        true
     }
 
-    pub fn scroll_monitor_up(&mut self) {
-        if self.monitor_scroll_offset > 0 {
-            self.monitor_scroll_offset -= 1;
+    pub fn get_current_file(&self) -> Option<&FileDiff> {
+        self.files.get(self.current_file_index)
+    }
+
+    /// Apply what the monitor command streamed: a new run's lines replace the last run's
+    /// once it prints or finishes, and each line it prints is appended
+    pub fn update_monitor_output(&mut self, output: crate::monitor::MonitorOutput) {
+        use crate::monitor::MonitorOutput;
+        match output {
+            MonitorOutput::Started => self.monitor_output.start_run(),
+            MonitorOutput::Line(line) => self.monitor_output.push_line(line),
+            MonitorOutput::Finished(timestamp) => {
+                self.monitor_output.finish_run();
+                self.update_monitor_timing(Some(timestamp.elapsed()), true)
+            }
         }
+        // Don't reset scroll offset - preserve user's current scroll position
+    }
+
+    pub fn get_monitor_output(&self) -> &crate::monitor::MonitorBuffer {
+        &self.monitor_output
+    }
+
+    pub fn set_monitor_max_lines(&mut self, max_lines: usize) {
+        self.monitor_output.set_max_lines(max_lines);
+    }
+
+    fn max_monitor_scroll_offset(&self) -> usize {
+        self.monitor_output
+            .lines()
+            .len()
+            .saturating_sub(self.monitor_visible_height)
+    }
+
+    /// Scroll the monitor pane with the standard scroll bindings
+    /// Returns true if the key was a scroll key
+    pub fn scroll_monitor(&mut self, key: &KeyEvent) -> bool {
+        let max_offset = self.max_monitor_scroll_offset();
+        self.monitor_scroll
+            .handle_key(key, max_offset, self.monitor_visible_height)
+    }
+
+    pub fn get_monitor_scroll_offset(&self) -> usize {
+        self.monitor_scroll.offset
     }
 
     pub fn toggle_monitor_pane(&mut self) {
@@ -826,89 +1906,506 @@ impl App {
         handled
     }
 
+    /// Never below one line, so paging a monitor squashed by a tiny terminal still moves
     pub fn set_monitor_visible_height(&mut self, height: usize) {
-        self.monitor_visible_height = height;
+        self.monitor_visible_height = height.max(1);
     }
 
     pub fn set_monitor_command_configured(&mut self, configured: bool) {
         self.monitor_command_configured = configured;
     }
 
-    pub fn update_monitor_timing(&mut self, elapsed: Option<std::time::Duration>, has_run: bool) {
-        self.monitor_elapsed_time = elapsed;
-        self.monitor_has_run = has_run;
+    pub fn set_git_refresh_paused(&mut self, paused: bool) {
+        self.git_refresh_paused = paused;
+    }
+
+    pub fn is_git_refresh_paused(&self) -> bool {
+        self.git_refresh_paused
+    }
+
+    pub fn update_monitor_timing(&mut self, elapsed: Option<std::time::Duration>, has_run: bool) {
+        self.monitor_elapsed_time = elapsed;
+        self.monitor_has_run = has_run;
+    }
+
+    pub fn get_theme(&self) -> &Theme {
+        &self.themes[self.current_theme_index]
+    }
+
+    /// Replace the themes that can be cycled through, showing the one at `index`
+    pub fn set_themes(&mut self, themes: Vec<Theme>, index: usize) {
+        if themes.is_empty() {
+            return;
+        }
+        self.current_theme_index = index.min(themes.len() - 1);
+        self.themes = themes;
+        self.pane_registry.set_theme(self.get_theme().clone());
+    }
+
+    /// Cycle to the next theme in `themes`, wrapping around
+    pub fn toggle_theme(&mut self) {
+        if self.themes.is_empty() {
+            return;
+        }
+        self.current_theme_index = (self.current_theme_index + 1) % self.themes.len();
+        self.pane_registry.set_theme(self.get_theme().clone());
+        self.theme_changed_at = Some(std::time::Instant::now());
+    }
+
+    /// Name of the active theme while it should still be announced in the status bar
+    pub fn get_theme_change_notice(&self) -> Option<&str> {
+        let changed_at = self.theme_changed_at?;
+        if changed_at.elapsed() < std::time::Duration::from_secs(2) {
+            Some(self.get_theme().name())
+        } else {
+            None
+        }
+    }
+
+    /// Show `message` in the status bar for a few seconds
+    pub fn set_status_message(&mut self, message: String) {
+        self.status_message = Some((message, std::time::Instant::now()));
+    }
+
+    pub fn get_status_message(&self) -> Option<&str> {
+        let (message, set_at) = self.status_message.as_ref()?;
+        (set_at.elapsed() < STATUS_MESSAGE_DURATION).then_some(message.as_str())
+    }
+
+    /// Ask the main loop to suspend the TUI and open the current file in $EDITOR
+    /// From the diff pane the editor opens at the top visible line, or line 1 if
+    /// that line was removed and has no place in the new file
+    pub fn request_open_in_editor(&mut self) {
+        let in_diff_pane =
+            self.show_diff_panel && self.current_information_pane == InformationPane::Diff;
+        match self.get_current_file() {
+            Some(file) if file.path.exists() => {
+                let top_line = self.line_for_row(self.scroll.offset);
+                let line = in_diff_pane.then(|| file.new_file_line(top_line).unwrap_or(1));
+                self.editor_request = Some((file.path.clone(), line));
+            }
+            Some(file) => {
+                self.set_status_message(format!("{} no longer exists", file.path.display()))
+            }
+            None => self.set_status_message("No file selected".to_string()),
+        }
+    }
+
+    pub fn take_editor_request(&mut self) -> Option<(std::path::PathBuf, Option<usize>)> {
+        self.editor_request.take()
+    }
+
+    /// Ask the main loop to suspend the TUI and show the current file's diff in the
+    /// configured external diff viewer
+    pub fn request_external_diff(&mut self) {
+        match self.get_current_file() {
+            Some(file) => {
+                self.external_diff_request = Some(file.unified_diff(&self.repo_root));
+            }
+            None => self.set_status_message("No file selected".to_string()),
+        }
+    }
+
+    pub fn take_external_diff_request(&mut self) -> Option<String> {
+        self.external_diff_request.take()
+    }
+
+    /// Ask the main loop to stage the hunk at the top of the inline diff, like `git add -p`
+    /// Only unstaged changes can be staged, so the staged view and commits are refused
+    pub fn stage_current_hunk(&mut self) {
+        let stageable = match self.view_mode {
+            ViewMode::WorkingTree | ViewMode::DirtyDirectory => true,
+            // Only below the unstaged label
+            ViewMode::StagedAndUnstaged => self.get_current_file().is_some_and(|file| {
+                file.line_strings
+                    .iter()
+                    .take(self.line_for_row(self.focus_row()) + 1)
+                    .any(|line| line == crate::git::UNSTAGED_SECTION_LABEL)
+            }),
+            ViewMode::Staged | ViewMode::LastCommit | ViewMode::Base => false,
+        };
+        if !stageable || self.selected_commit.is_some() {
+            self.set_status_message("Only unstaged hunks can be staged".to_string());
+            return;
+        }
+
+        let top_line = self.line_for_row(self.focus_row());
+        let patch = match self.get_current_file() {
+            Some(file) => file.hunk_patch(&self.repo_root, top_line),
+            None => Err("No file selected".to_string()),
+        };
+        match patch {
+            Ok(patch) => self.hunk_stage_request = Some(patch),
+            Err(e) => self.set_status_message(e),
+        }
+    }
+
+    pub fn take_hunk_stage_request(&mut self) -> Option<String> {
+        self.hunk_stage_request.take()
+    }
+
+    /// Ask for confirmation to throw away the current file's working tree changes
+    ///
+    /// The dirty directory view is left out: its diffs run against HEAD and carry a
+    /// synthetic status, so they don't say what a working tree discard would drop.
+    pub fn request_discard_current_file(&mut self) {
+        let discardable = matches!(
+            self.view_mode,
+            ViewMode::WorkingTree | ViewMode::StagedAndUnstaged
+        );
+        if !discardable || self.selected_commit.is_some() {
+            self.set_status_message("Only working tree changes can be discarded".to_string());
+            return;
+        }
+        let Some(file) = self.get_current_file() else {
+            self.set_status_message("No file selected".to_string());
+            return;
+        };
+        if !file.status.intersects(
+            git2::Status::WT_NEW
+                | git2::Status::WT_MODIFIED
+                | git2::Status::WT_DELETED
+                | git2::Status::WT_RENAMED
+                | git2::Status::WT_TYPECHANGE,
+        ) {
+            self.set_status_message("No working tree changes to discard".to_string());
+            return;
+        }
+
+        let request = DiscardRequest {
+            path: file.path.clone(),
+            old_path: file.old_path.clone(),
+            // A file staged as new has a copy in the index to go back to
+            untracked: file.status.is_wt_new() && !file.status.is_index_new(),
+        };
+        let path = file
+            .path
+            .strip_prefix(&self.repo_root)
+            .unwrap_or(&file.path)
+            .display()
+            .to_string();
+        let dialog = if request.untracked {
+            ConfirmDialog::new(
+                "Delete untracked file",
+                format!("Delete {path} from disk?"),
+                "git has no copy of it, so this can't be undone",
+                ConfirmAction::Discard(request),
+            )
+            .irreversible()
+        } else {
+            ConfirmDialog::new(
+                "Discard changes",
+                format!("Discard the working tree changes to {path}?"),
+                "The staged version is kept; unstaged edits are lost",
+                ConfirmAction::Discard(request),
+            )
+        };
+        self.ask_confirmation(dialog);
+    }
+
+    /// Clear the advice chat and ask again, after confirmation when there's a chat to lose
+    pub fn request_clear_advice_chat(&mut self) {
+        let has_chat = self
+            .pane_registry
+            .with_pane_mut(&PaneId::Advice, |pane| {
+                pane.as_advice_pane_mut()
+                    .is_some_and(|advice_panel| !advice_panel.get_chat_history().is_empty())
+            })
+            .unwrap_or(false);
+        if has_chat {
+            self.ask_confirmation(ConfirmDialog::new(
+                "Clear chat",
+                "Clear the advice chat and ask about the current diff again?",
+                "The conversation so far is lost",
+                ConfirmAction::ClearAdviceChat,
+            ));
+        } else {
+            self.run_confirmed_action(ConfirmAction::ClearAdviceChat);
+        }
+    }
+
+    /// Hold `dialog`'s action until it's answered
+    pub fn ask_confirmation(&mut self, dialog: ConfirmDialog) {
+        self.confirm_dialog = Some(dialog);
+    }
+
+    pub fn is_confirming(&self) -> bool {
+        self.confirm_dialog.is_some()
+    }
+
+    /// Carry out the action waiting on confirmation, or drop it
+    pub fn answer_confirmation(&mut self, confirmed: bool) {
+        let Some(dialog) = self.confirm_dialog.take() else {
+            return;
+        };
+        if confirmed {
+            self.run_confirmed_action(dialog.action);
+        } else {
+            self.set_status_message("Cancelled".to_string());
+        }
+    }
+
+    fn run_confirmed_action(&mut self, action: ConfirmAction) {
+        match action {
+            ConfirmAction::Discard(request) => self.discard_request = Some(request),
+            ConfirmAction::ClearAdviceChat => {
+                self.pane_registry.with_pane_mut(&PaneId::Advice, |pane| {
+                    if let Some(advice_panel) = pane.as_advice_pane_mut() {
+                        advice_panel.refresh_chat_with_new_diff();
+                    }
+                });
+            }
+        }
+    }
+
+    pub fn take_discard_request(&mut self) -> Option<DiscardRequest> {
+        self.discard_request.take()
+    }
+
+    // Public getters for private fields needed by panes
+    pub fn get_tree_nodes(&self) -> &Vec<(TreeDisplayNode, usize)> {
+        &self.tree_nodes
+    }
+
+    pub fn get_current_tree_index(&self) -> usize {
+        self.current_tree_index
+    }
+
+    pub fn get_files(&self) -> &Vec<FileDiff> {
+        &self.files
+    }
+
+    pub fn get_monitor_command_configured(&self) -> bool {
+        self.monitor_command_configured
+    }
+
+    pub fn get_monitor_has_run(&self) -> bool {
+        self.monitor_has_run
+    }
+
+    pub fn get_monitor_elapsed_time(&self) -> Option<std::time::Duration> {
+        self.monitor_elapsed_time
+    }
+
+    pub fn get_scroll_offset(&self) -> usize {
+        self.scroll.offset
+    }
+
+    pub fn set_recent_highlight_duration(&mut self, duration: std::time::Duration) {
+        self.recent_highlight_duration = duration;
+    }
+
+    pub fn set_icons(&mut self, icons: crate::config::IconSet) {
+        self.icons = icons;
+    }
+
+    pub fn get_icons(&self) -> crate::config::IconSet {
+        self.icons
+    }
+
+    pub fn set_status_bar_style(&mut self, style: crate::config::StatusBarStyle) {
+        self.status_bar_style = style;
+    }
+
+    /// Rows the full status bar takes, 1 or 2
+    pub fn set_status_bar_lines(&mut self, lines: u16) {
+        self.status_bar_lines = lines;
+    }
+
+    pub fn get_status_bar_lines(&self) -> u16 {
+        self.status_bar_lines
+    }
+
+    /// Takes effect the next time the tree is rebuilt
+    pub fn set_max_tree_depth(&mut self, depth: Option<usize>) {
+        self.max_tree_depth = depth;
+    }
+
+    pub fn set_diff_heatmap(&mut self, enabled: bool) {
+        self.diff_heatmap = enabled;
+    }
+
+    pub fn is_diff_heatmap_enabled(&self) -> bool {
+        self.diff_heatmap
+    }
+
+    pub fn set_scroll_margin(&mut self, margin: usize) {
+        self.scroll_margin = margin;
+    }
+
+    pub fn set_semantic_stats(&mut self, enabled: bool) {
+        self.semantic_stats = enabled;
+        if !enabled {
+            self.semantic_stats_cache.clear();
+        }
+    }
+
+    /// Parse the current file for its semantic stats unless they're cached
+    ///
+    /// A picked commit's files may have changed since, so it gets none.
+    pub fn update_semantic_stats(&mut self) {
+        if !self.semantic_stats || self.selected_commit.is_some() {
+            return;
+        }
+        let Some(file) = self.get_current_file() else {
+            return;
+        };
+        if self.semantic_stats_cache.contains_key(&file.path) {
+            return;
+        }
+
+        let stats = self
+            .read_new_side(file)
+            .and_then(|source| crate::semantic_stats::SemanticStats::compute(file, &source));
+        self.semantic_stats_cache.insert(file.path.clone(), stats);
+    }
+
+    /// The content the view's hunk line numbers count in: the working tree, the index for
+    /// staged changes or HEAD for the last commit; None for the view mixing staged and
+    /// unstaged hunks
+    fn read_new_side(&self, file: &FileDiff) -> Option<String> {
+        let from_repo =
+            |read: fn(&git2::Repository, &std::path::Path) -> color_eyre::Result<String>| {
+                let repo = git2::Repository::open(&self.repo_root).ok()?;
+                let path = file
+                    .path
+                    .strip_prefix(&self.repo_root)
+                    .unwrap_or(&file.path);
+                read(&repo, path).ok()
+            };
+        match self.view_mode {
+            ViewMode::WorkingTree | ViewMode::DirtyDirectory | ViewMode::Base => {
+                std::fs::read_to_string(self.repo_root.join(&file.path)).ok()
+            }
+            ViewMode::Staged => from_repo(crate::git::operations::read_index_file),
+            ViewMode::LastCommit => from_repo(crate::git::operations::read_head_file),
+            ViewMode::StagedAndUnstaged => None,
+        }
     }
 
-    pub fn format_elapsed_time(&self, elapsed: std::time::Duration) -> String {
-        let secs = elapsed.as_secs();
-        if secs < 60 {
-            format!("{secs}s")
-        } else if secs < 3600 {
-            let mins = secs / 60;
-            let remaining_secs = secs % 60;
-            format!("{mins}m{remaining_secs}s")
-        } else {
-            let hours = secs / 3600;
-            let remaining_mins = (secs % 3600) / 60;
-            format!("{hours}h{remaining_mins}m")
+    /// Semantic stats for the current file, None until they're computed or when the file
+    /// can't be parsed
+    pub fn current_semantic_stats(&self) -> Option<crate::semantic_stats::SemanticStats> {
+        if !self.semantic_stats || self.selected_commit.is_some() {
+            return None;
         }
+        let file = self.get_current_file()?;
+        self.semantic_stats_cache.get(&file.path).copied().flatten()
     }
 
-    pub fn get_theme(&self) -> &Theme {
-        &self.themes[self.current_theme_index]
+    /// Current frame of the spinner that loading panes show while the LLM works
+    pub fn spinner_frame(&self) -> &'static str {
+        self.spinner.frame()
     }
 
-    pub fn toggle_theme(&mut self) {
-        if self.themes.is_empty() {
-            return;
+    /// Whether the status bar is drawn compact on a terminal `width` columns wide
+    pub fn is_status_bar_compact(&self, width: u16) -> bool {
+        use crate::config::StatusBarStyle;
+        match self.status_bar_style {
+            StatusBarStyle::Auto => width < crate::pane::COMPACT_STATUS_BAR_WIDTH,
+            StatusBarStyle::Compact => true,
+            StatusBarStyle::Full => false,
         }
-        self.current_theme_index = (self.current_theme_index + 1) % self.themes.len();
-        self.pane_registry.set_theme(self.get_theme().clone());
     }
 
-    // Public getters for private fields needed by panes
-    pub fn get_tree_nodes(&self) -> &Vec<(TreeDisplayNode, usize)> {
-        &self.tree_nodes
+    /// Choose which situational indicators the status bar shows
+    pub fn set_status_bar_indicators(&mut self, show_stash_count: bool, show_unpushed: bool) {
+        self.show_stash_count = show_stash_count;
+        self.show_unpushed = show_unpushed;
     }
 
-    pub fn get_current_tree_index(&self) -> usize {
-        self.current_tree_index
+    pub fn shows_stash_count(&self) -> bool {
+        self.show_stash_count
     }
 
-    pub fn get_files(&self) -> &Vec<FileDiff> {
-        &self.files
+    pub fn shows_unpushed(&self) -> bool {
+        self.show_unpushed
     }
 
-    pub fn get_file_change_timestamps(&self) -> &Vec<std::time::Instant> {
-        &self.file_change_timestamps
+    /// How long ago a file last changed, while it is still highlighted as recently changed
+    pub fn get_recent_change_age(&self, file_index: usize) -> Option<std::time::Duration> {
+        self.get_recent_change(file_index)?;
+        Some(self.file_change_timestamps.get(file_index)?.elapsed())
     }
 
-    pub fn get_monitor_command_configured(&self) -> bool {
-        self.monitor_command_configured
+    /// Highlight phase for a file, fading out over the last second of the highlight window
+    pub fn get_recent_change(&self, file_index: usize) -> Option<RecentChange> {
+        let elapsed = self.file_change_timestamps.get(file_index)?.elapsed();
+        if elapsed >= self.recent_highlight_duration {
+            return None;
+        }
+
+        let fade_window = self
+            .recent_highlight_duration
+            .min(std::time::Duration::from_secs(1));
+        if self.recent_highlight_duration - elapsed <= fade_window {
+            Some(RecentChange::Fading)
+        } else {
+            Some(RecentChange::Fresh)
+        }
     }
 
-    pub fn get_monitor_has_run(&self) -> bool {
-        self.monitor_has_run
+    pub fn get_last_active_pane(&self) -> ActivePane {
+        self.last_active_pane
     }
 
-    pub fn get_monitor_elapsed_time(&self) -> Option<std::time::Duration> {
-        self.monitor_elapsed_time
+    /// Panes on screen that can take focus, in the order Tab moves through them
+    fn focusable_panes(&self) -> Vec<ActivePane> {
+        let mut panes = Vec::new();
+        if self.show_changed_files_pane {
+            panes.push(ActivePane::FileTree);
+            if self.show_monitor_pane {
+                panes.push(ActivePane::Monitor);
+            }
+        }
+        if self.show_diff_panel {
+            panes.push(if self.side_by_side_diff {
+                ActivePane::SideBySideDiff
+            } else {
+                ActivePane::Diff
+            });
+        }
+        panes
     }
 
-    pub fn get_scroll_offset(&self) -> usize {
-        self.scroll_offset
+    /// The pane the scroll keys act on: the one picked with Tab while it's still showing,
+    /// otherwise the diff when it's showing, otherwise the file tree
+    pub fn get_focused_pane(&self) -> ActivePane {
+        let panes = self.focusable_panes();
+        match self.focused_pane {
+            Some(pane) if panes.contains(&pane) => pane,
+            _ if !self.show_diff_panel => ActivePane::FileTree,
+            _ if self.side_by_side_diff => ActivePane::SideBySideDiff,
+            _ => ActivePane::Diff,
+        }
     }
 
-    pub fn is_file_recently_changed(&self, file_index: usize) -> bool {
-        if let Some(timestamp) = self.file_change_timestamps.get(file_index) {
-            timestamp.elapsed().as_secs() < 3
-        } else {
-            false
+    /// Move focus to the next visible pane, or the previous one when `forward` is false
+    pub fn cycle_focus(&mut self, forward: bool) {
+        let panes = self.focusable_panes();
+        if panes.is_empty() {
+            return;
         }
+        let current = self.get_focused_pane();
+        let index = panes.iter().position(|&p| p == current).unwrap_or(0);
+        let next = if forward {
+            (index + 1) % panes.len()
+        } else {
+            (index + panes.len() - 1) % panes.len()
+        };
+        self.focused_pane = Some(panes[next]);
     }
 
-    pub fn get_last_active_pane(&self) -> ActivePane {
-        self.last_active_pane
+    /// Border style for `pane`, picked out in the primary color while it has focus
+    pub fn pane_border_style(&self, pane: ActivePane) -> Style {
+        let theme = self.get_theme();
+        if self.get_focused_pane() == pane {
+            Style::default()
+                .fg(theme.primary_color())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.border_color())
+        }
     }
 
     // Commit picker mode state management methods
@@ -1054,6 +2551,23 @@ impl App {
             });
     }
 
+    /// Have the commit picker list HEAD's reflog, or commit history again
+    pub fn set_commit_picker_reflog(&mut self, showing_reflog: bool) {
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommitPicker, |pane| {
+                if let Some(commit_picker) = pane.as_commit_picker_pane_mut() {
+                    commit_picker.set_showing_reflog(showing_reflog);
+                }
+            });
+    }
+
+    pub fn is_commit_picker_showing_reflog(&self) -> bool {
+        self.pane_registry
+            .get_pane(&PaneId::CommitPicker)
+            .and_then(|pane| pane.as_commit_picker_pane())
+            .is_some_and(|commit_picker| commit_picker.is_showing_reflog())
+    }
+
     pub fn set_commit_picker_error(&mut self, error: String) {
         self.pane_registry
             .with_pane_mut(&PaneId::CommitPicker, |pane| {
@@ -1072,6 +2586,75 @@ impl App {
             });
     }
 
+    pub fn set_commit_picker_has_more(&mut self, has_more: bool) {
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommitPicker, |pane| {
+                if let Some(commit_picker) = pane.as_commit_picker_pane_mut() {
+                    commit_picker.set_has_more(has_more);
+                }
+            });
+    }
+
+    pub fn append_commit_picker_commits(&mut self, commits: Vec<CommitInfo>, has_more: bool) {
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommitPicker, |pane| {
+                if let Some(commit_picker) = pane.as_commit_picker_pane_mut() {
+                    commit_picker.append_commits(commits, has_more);
+                }
+            });
+    }
+
+    /// SHA to resume the commit history after, when the picker has scrolled near its end
+    pub fn take_commit_picker_load_more_request(&mut self) -> Option<String> {
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommitPicker, |pane| {
+                pane.as_commit_picker_pane_mut()
+                    .and_then(|commit_picker| commit_picker.take_load_more_request())
+            })
+            .flatten()
+    }
+
+    /// Track a commit history load, replacing any earlier one whose result is no longer wanted
+    pub fn start_commit_history_task(&mut self, task: CommitHistoryTask) {
+        self.commit_history_task = Some(task);
+    }
+
+    pub fn is_loading_commit_history(&self) -> bool {
+        self.commit_history_task.is_some()
+    }
+
+    /// The result of the commit history load once its task is done, with the SHA it resumed after
+    pub fn take_finished_commit_history(
+        &mut self,
+        git_state: &GitSharedState,
+    ) -> Option<(Option<String>, CommitHistoryResult)> {
+        if !self
+            .commit_history_task
+            .as_ref()
+            .is_some_and(|task| task.handle.is_finished())
+        {
+            return None;
+        }
+
+        let task = self.commit_history_task.take()?;
+        let result = if task.reflog {
+            git_state.take_reflog_result()
+        } else {
+            git_state.take_commit_history_result(task.after.as_deref())
+        }
+        .unwrap_or_else(|| Err("Commit history loading stopped unexpectedly".to_string()));
+        Some((task.after, result))
+    }
+
+    pub fn set_commit_picker_progress(&mut self, loaded: usize, total: usize) {
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommitPicker, |pane| {
+                if let Some(commit_picker) = pane.as_commit_picker_pane_mut() {
+                    commit_picker.set_progress(loaded, total);
+                }
+            });
+    }
+
     pub fn is_commit_picker_enter_pressed(&self) -> bool {
         if let Some(pane) = self.pane_registry.get_pane(&PaneId::CommitPicker)
             && let Some(commit_picker) = pane.as_commit_picker_pane()
@@ -1213,11 +2796,12 @@ impl App {
     pub fn check_advice_panel_tasks(&mut self) {
         // Get files for initialization
         let files = self.get_files().clone();
+        let repo_root = self.repo_root.clone();
 
         self.pane_registry.with_pane_mut(&PaneId::Advice, |pane| {
             if let Some(advice_panel) = pane.as_advice_pane_mut() {
                 // Initialize with current diff if needed
-                advice_panel.initialize_with_current_diff(&files);
+                advice_panel.initialize_with_current_diff(&files, &repo_root);
                 // Check for pending async tasks
                 advice_panel.check_pending_tasks();
             }
@@ -1241,12 +2825,341 @@ impl App {
             .unwrap_or(false)
     }
 
-    /// Check if diff panel is currently visible
-    pub fn is_diff_panel_visible(&self) -> bool {
-        self.pane_registry
-            .get_pane(&PaneId::Diff)
-            .map(|pane| pane.visible())
-            .unwrap_or(false)
+    /// Check if diff panel is currently visible
+    pub fn is_diff_panel_visible(&self) -> bool {
+        self.pane_registry
+            .get_pane(&PaneId::Diff)
+            .map(|pane| pane.visible())
+            .unwrap_or(false)
+    }
+
+    /// Show the full message of `commit_sha` in a popup
+    pub fn show_commit_message(&mut self, commit_sha: &str) {
+        let message = match crate::git::operations::discover_repository() {
+            Ok((repo, _)) => crate::git::operations::get_commit_message(&repo, commit_sha)
+                .unwrap_or_else(|e| format!("Error: Could not read commit message: {e}")),
+            Err(e) => format!("Error: Could not open git repository: {e}"),
+        };
+
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommitMessage, |pane| {
+                if let Some(commit_message) = pane.as_commit_message_pane_mut() {
+                    commit_message.set_message(commit_sha, message);
+                }
+                pane.set_visible(true);
+            });
+    }
+
+    pub fn is_showing_commit_message(&self) -> bool {
+        self.pane_registry
+            .get_pane(&PaneId::CommitMessage)
+            .map(|pane| pane.visible())
+            .unwrap_or(false)
+    }
+
+    pub fn forward_key_to_commit_message(&mut self, key: KeyEvent) -> bool {
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommitMessage, |pane| {
+                pane.handle_event(&crate::pane::AppEvent::Key(key))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Show hunk and line totals for the current file and all changed files
+    pub fn show_diff_stats(&mut self) {
+        let current_file = self.files.get(self.current_file_index);
+        let files = &self.files;
+        let semantic_stats = self.current_semantic_stats();
+        self.pane_registry
+            .with_pane_mut(&PaneId::DiffStats, |pane| {
+                if let Some(diff_stats) = pane.as_diff_stats_pane_mut() {
+                    diff_stats.set_stats(current_file, files);
+                    diff_stats.set_semantic_stats(semantic_stats);
+                }
+                pane.set_visible(true);
+            });
+    }
+
+    pub fn is_showing_diff_stats(&self) -> bool {
+        self.pane_registry
+            .get_pane(&PaneId::DiffStats)
+            .map(|pane| pane.visible())
+            .unwrap_or(false)
+    }
+
+    pub fn forward_key_to_diff_stats(&mut self, key: KeyEvent) -> bool {
+        self.pane_registry
+            .with_pane_mut(&PaneId::DiffStats, |pane| {
+                pane.handle_event(&crate::pane::AppEvent::Key(key))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Show the LLM tokens used this session and their estimated cost
+    pub fn show_token_usage(&mut self) {
+        let summary = self.llm_usage.as_ref().map(|usage| usage.summary());
+        self.pane_registry
+            .with_pane_mut(&PaneId::TokenUsage, |pane| {
+                if let Some(token_usage) = pane.as_token_usage_pane_mut() {
+                    token_usage.set_summary(summary);
+                }
+                pane.set_visible(true);
+            });
+    }
+
+    pub fn is_showing_token_usage(&self) -> bool {
+        self.pane_registry
+            .get_pane(&PaneId::TokenUsage)
+            .map(|pane| pane.visible())
+            .unwrap_or(false)
+    }
+
+    pub fn forward_key_to_token_usage(&mut self, key: KeyEvent) -> bool {
+        self.pane_registry
+            .with_pane_mut(&PaneId::TokenUsage, |pane| {
+                pane.handle_event(&crate::pane::AppEvent::Key(key))
+            })
+            .unwrap_or(false)
+    }
+
+    pub fn is_showing_explanation(&self) -> bool {
+        self.pane_registry
+            .get_pane(&PaneId::Explanation)
+            .map(|pane| pane.visible())
+            .unwrap_or(false)
+    }
+
+    pub fn forward_key_to_explanation(&mut self, key: KeyEvent) -> bool {
+        self.pane_registry
+            .with_pane_mut(&PaneId::Explanation, |pane| {
+                pane.handle_event(&crate::pane::AppEvent::Key(key))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Show the explanation of selected diff lines once the LLM replies
+    pub fn check_explanation_task(&mut self) {
+        self.pane_registry
+            .with_pane_mut(&PaneId::Explanation, |pane| {
+                if let Some(explanation) = pane.as_explanation_pane_mut() {
+                    explanation.check_pending_reply();
+                }
+            });
+    }
+
+    pub fn is_showing_commit_draft(&self) -> bool {
+        self.pane_registry
+            .get_pane(&PaneId::CommitDraft)
+            .map(|pane| pane.visible())
+            .unwrap_or(false)
+    }
+
+    pub fn forward_key_to_commit_draft(&mut self, key: KeyEvent) -> bool {
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommitDraft, |pane| {
+                pane.handle_event(&crate::pane::AppEvent::Key(key))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Show the drafted commit message once the LLM replies
+    pub fn check_commit_draft_task(&mut self) {
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommitDraft, |pane| {
+                if let Some(commit_draft) = pane.as_commit_draft_pane_mut() {
+                    commit_draft.check_pending_reply();
+                }
+            });
+    }
+
+    /// Open the command palette on the full list of commands
+    pub fn open_command_palette(&mut self) {
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommandPalette, |pane| {
+                if let Some(palette) = pane.as_command_palette_pane_mut() {
+                    palette.reset();
+                }
+                pane.set_visible(true);
+            });
+    }
+
+    pub fn close_command_palette(&mut self) {
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommandPalette, |pane| {
+                pane.set_visible(false);
+            });
+    }
+
+    pub fn is_showing_command_palette(&self) -> bool {
+        self.pane_registry
+            .get_pane(&PaneId::CommandPalette)
+            .map(|pane| pane.visible())
+            .unwrap_or(false)
+    }
+
+    pub fn forward_key_to_command_palette(&mut self, key: KeyEvent) -> bool {
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommandPalette, |pane| {
+                pane.handle_event(&crate::pane::AppEvent::Key(key))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Close the palette and run the selected command; true when it asks to quit
+    pub fn run_selected_palette_command(&mut self) -> bool {
+        let Some(command) = self
+            .pane_registry
+            .get_pane(&PaneId::CommandPalette)
+            .and_then(|pane| pane.as_command_palette_pane())
+            .and_then(|palette| palette.get_selected_command())
+        else {
+            return false;
+        };
+
+        log::debug!("Running '{}' from the command palette", command.name());
+        self.close_command_palette();
+        self.apply_action(command.action)
+    }
+
+    /// Carry out `action`, returning true when it asks to quit
+    ///
+    /// Actions that need the config or shared git state are left for the main loop to pick
+    /// up with `take_main_loop_action`.
+    pub fn apply_action(&mut self, action: Action) -> bool {
+        if action.needs_main_loop() {
+            self.main_loop_action = Some(action);
+            return false;
+        }
+
+        match action {
+            Action::Quit => return true,
+            Action::ScrollDiff(scroll) => self.scroll_diff(scroll, self.current_diff_height),
+            Action::NextFile => self.next_file(),
+            Action::PrevFile => self.prev_file(),
+            Action::NextHunk => self.jump_to_hunk(true),
+            Action::PrevHunk => self.jump_to_hunk(false),
+            Action::FocusNextPane => self.cycle_focus(true),
+            Action::FocusPrevPane => self.cycle_focus(false),
+            Action::OpenSearch => self.open_search(),
+            Action::OpenCommandPalette => self.open_command_palette(),
+            Action::ToggleFolding => self.toggle_diff_folding(),
+            Action::SelectDiffLines => self.start_diff_selection(),
+            Action::CycleViewMode => self.cycle_view_mode(),
+            Action::ToggleStagedView => self.toggle_staged_view(),
+            Action::ToggleStagedAndUnstagedView => self.toggle_staged_and_unstaged_view(),
+            Action::StageHunk => self.stage_current_hunk(),
+            Action::DiscardFile => self.request_discard_current_file(),
+            Action::CopyPatch => self.copy_current_patch(),
+            Action::SavePatch => self.save_current_patch(),
+            Action::OpenInEditor => self.request_open_in_editor(),
+            Action::OpenExternalDiff => self.request_external_diff(),
+            Action::ShowDiffStats => self.show_diff_stats(),
+            Action::ShowTokenUsage => self.show_token_usage(),
+            Action::DraftCommitMessage => self.draft_commit_message(),
+            Action::ToggleAdvicePanel => {
+                if let Err(e) = self.toggle_pane_visibility(&PaneId::Advice) {
+                    log::warn!("Failed to toggle advice panel: {}", e);
+                }
+            }
+            Action::ClearAdviceChat => self.request_clear_advice_chat(),
+            Action::ToggleHelp => self.toggle_help(),
+            Action::ToggleTheme => self.toggle_theme(),
+            Action::SinglePaneDiff => {
+                // Leaving the advice panel goes back to the diff as it was
+                if self.is_advice_panel_visible() {
+                    if let Err(e) = self.toggle_pane_visibility(&PaneId::Advice) {
+                        log::warn!("Failed to hide advice panel: {}", e);
+                    }
+                    if !self.is_diff_panel_visible()
+                        && let Err(e) = self.toggle_pane_visibility(&PaneId::Diff)
+                    {
+                        log::warn!("Failed to show diff pane: {}", e);
+                    }
+                } else {
+                    self.set_single_pane_diff();
+                }
+            }
+            Action::SideBySideDiff => self.set_side_by_side_diff(),
+            Action::ToggleDiffPanel => self.toggle_diff_panel(),
+            Action::ToggleChangedFilesPane => self.toggle_changed_files_pane(),
+            Action::ToggleMonitorPane => self.toggle_monitor_pane(),
+            Action::ReturnToWorkingDirectory => self.clear_selected_commit(),
+            Action::OpenCommitPicker
+            | Action::OpenReflog
+            | Action::ShowCommitMessage
+            | Action::ToggleRefreshPaused => {}
+        }
+        false
+    }
+
+    pub fn take_main_loop_action(&mut self) -> Option<Action> {
+        self.main_loop_action.take()
+    }
+
+    /// Open the search overlay with an empty query
+    pub fn open_search(&mut self) {
+        self.pane_registry.with_pane_mut(&PaneId::Search, |pane| {
+            if let Some(search) = pane.as_search_pane_mut() {
+                search.reset();
+            }
+            pane.set_visible(true);
+        });
+    }
+
+    pub fn close_search(&mut self) {
+        self.pane_registry.with_pane_mut(&PaneId::Search, |pane| {
+            pane.set_visible(false);
+        });
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.pane_registry
+            .get_pane(&PaneId::Search)
+            .map(|pane| pane.visible())
+            .unwrap_or(false)
+    }
+
+    /// Forward a key to the search overlay and rescan the files for the updated query
+    pub fn forward_key_to_search(&mut self, key: KeyEvent) -> bool {
+        let handled = self
+            .pane_registry
+            .with_pane_mut(&PaneId::Search, |pane| {
+                pane.handle_event(&crate::pane::AppEvent::Key(key))
+            })
+            .unwrap_or(false);
+        self.refresh_search_results();
+        handled
+    }
+
+    fn refresh_search_results(&mut self) {
+        let files = &self.files;
+        self.pane_registry.with_pane_mut(&PaneId::Search, |pane| {
+            if let Some(search) = pane.as_search_pane_mut() {
+                search.update_results(files);
+            }
+        });
+    }
+
+    /// Close the search overlay and show the selected match in the diff
+    pub fn jump_to_search_result(&mut self) {
+        let Some((file_index, line_index)) = self
+            .pane_registry
+            .get_pane(&PaneId::Search)
+            .and_then(|pane| pane.as_search_pane())
+            .and_then(|search| search.get_current_match())
+            .map(|m| (m.file_index, m.line_index))
+        else {
+            return;
+        };
+
+        self.close_search();
+        if file_index >= self.files.len() {
+            return;
+        }
+
+        self.current_file_index = file_index;
+        self.sync_tree_index_with_file_index();
+        self.scroll_to_line(line_index);
     }
 
     pub fn load_commit_files(&mut self, commit: &CommitInfo) {
@@ -1272,7 +3185,11 @@ impl App {
         for file_change in &commit.files_changed {
             // Create a FileDiff from CommitFileChange
             // We'll need to get the actual diff content using git commands
-            let diff_content = self.get_commit_diff_content(&commit.sha, &file_change.path);
+            let diff_content = self.get_commit_diff_content(
+                &commit.sha,
+                &file_change.path,
+                file_change.old_path.as_deref(),
+            );
 
             // Convert FileChangeStatus to git2::Status
             let status = match file_change.status {
@@ -1284,6 +3201,8 @@ impl App {
 
             commit_files.push(FileDiff {
                 path: file_change.path.clone(),
+                old_path: file_change.old_path.clone(),
+                submodule: None,
                 status,
                 line_strings: diff_content,
                 additions: file_change.additions,
@@ -1316,6 +3235,7 @@ impl App {
                 is_dir: true,
                 children: Vec::new(),
                 file_diff: None,
+                untracked: false,
             };
 
             for file_diff in &self.files {
@@ -1330,17 +3250,21 @@ impl App {
         &self,
         commit_sha: &str,
         file_path: &std::path::Path,
+        old_path: Option<&std::path::Path>,
     ) -> Vec<String> {
         // Use git2-based repository discovery and path handling
         match crate::git::operations::discover_repository() {
             Ok((repo, _repo_path)) => {
                 // Convert absolute path to relative path for git_operations
                 let relative_path = crate::git::operations::to_repo_relative_path(&repo, file_path);
+                let relative_old_path = old_path
+                    .map(|old_path| crate::git::operations::to_repo_relative_path(&repo, old_path));
 
                 match crate::git::operations::get_commit_file_diff(
                     &repo,
                     commit_sha,
                     &relative_path,
+                    relative_old_path.as_deref(),
                 ) {
                     Ok(lines) => lines,
                     Err(e) => {
@@ -1379,11 +3303,12 @@ impl App {
             if i == components.len() - 1 {
                 // This is the file itself
                 current_node.children.push(crate::git::TreeNode {
-                    name: component_str.clone(),
+                    name: file_diff.tree_name(repo_path),
                     path: file_diff.path.clone(),
                     is_dir: false,
                     children: Vec::new(),
                     file_diff: Some(file_diff.clone()),
+                    untracked: false,
                 });
             } else {
                 // This is a directory
@@ -1401,6 +3326,7 @@ impl App {
                         is_dir: true,
                         children: Vec::new(),
                         file_diff: None,
+                        untracked: false,
                     };
                     current_node.children.push(new_child);
                     let new_len = current_node.children.len();
@@ -1421,6 +3347,20 @@ impl App {
             .preload_around_index(commits, current_index);
     }
 
+    /// Generate the selected commit's summary again after it failed
+    pub fn retry_commit_summary(&mut self) {
+        let Some(commit) = self.get_current_selected_commit_from_picker() else {
+            return;
+        };
+        self.pane_registry
+            .with_pane_mut(&PaneId::CommitSummary, |pane| {
+                if let Some(commit_summary) = pane.as_commit_summary_pane_mut() {
+                    commit_summary.force_generate_summary();
+                }
+            });
+        self.summary_preloader.retry_summary(&commit.sha);
+    }
+
     pub fn set_preload_config(&mut self, config: PreloadConfig) {
         self.summary_preloader.set_config(config);
     }
@@ -1441,20 +3381,130 @@ impl App {
 
 #[allow(clippy::extra_unused_type_parameters)]
 pub fn render<B: Backend>(f: &mut Frame, app: &App, git_repo: &GitRepo) {
-    let size = f.area();
+    render_panes(f, app, git_repo);
+
+    // Popups float above whatever is underneath
+    if app.is_showing_commit_message() {
+        app.pane_registry
+            .render(f, app, f.area(), PaneId::CommitMessage, git_repo);
+    }
+    if app.is_showing_diff_stats() {
+        app.pane_registry
+            .render(f, app, f.area(), PaneId::DiffStats, git_repo);
+    }
+    if app.is_showing_token_usage() {
+        app.pane_registry
+            .render(f, app, f.area(), PaneId::TokenUsage, git_repo);
+    }
+    if app.is_showing_explanation() {
+        app.pane_registry
+            .render(f, app, f.area(), PaneId::Explanation, git_repo);
+    }
+    if app.is_showing_commit_draft() {
+        app.pane_registry
+            .render(f, app, f.area(), PaneId::CommitDraft, git_repo);
+    }
+    if app.is_showing_command_palette() {
+        app.pane_registry
+            .render(f, app, f.area(), PaneId::CommandPalette, git_repo);
+    }
+    if let Some(dialog) = &app.confirm_dialog {
+        dialog.render(f, app.get_theme());
+    }
+}
+
+/// Paths of the wholly untracked directories at or below `node`
+fn collect_untracked_dirs(
+    node: &TreeNode,
+    dirs: &mut std::collections::HashSet<std::path::PathBuf>,
+) {
+    if node.is_dir && node.untracked {
+        dirs.insert(node.path.clone());
+    }
+    for child in &node.children {
+        collect_untracked_dirs(child, dirs);
+    }
+}
+
+/// Longest pause between letters typed in the file tree for them to make up one name
+const TREE_JUMP_TIMEOUT_MS: u128 = 1000;
+
+/// Fewest rows the panes can be laid out in without splitting down to nothing
+pub const MIN_TERMINAL_HEIGHT: u16 = 10;
+/// Fewest columns, enough for the file tree and diff borders to leave room for text
+pub const MIN_TERMINAL_WIDTH: u16 = 20;
+
+/// Whether `area` is too small to lay the panes out in
+pub fn is_terminal_too_small(area: Rect) -> bool {
+    area.height < MIN_TERMINAL_HEIGHT || area.width < MIN_TERMINAL_WIDTH
+}
+
+/// Shown in place of the UI while the terminal is too small to lay it out
+pub fn render_terminal_too_small(f: &mut Frame) {
+    let area = f.area();
+    let paragraph = Paragraph::new(format!(
+        "Terminal too small ({}x{}), needs {MIN_TERMINAL_WIDTH}x{MIN_TERMINAL_HEIGHT}",
+        area.width, area.height
+    ))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+    f.render_widget(paragraph, area);
+}
+
+/// Full-screen notice shown instead of the UI when there is no repository to watch
+pub fn render_startup_error(f: &mut Frame, headline: &str, detail: &str) {
+    let area = f.area();
+    let text = vec![
+        Line::from(Span::styled(
+            headline.to_string(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(detail.to_string()),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Press q to quit",
+            Style::default().add_modifier(Modifier::DIM),
+        )),
+    ];
+
+    // Vertically centred, as far as the wrapped text allows
+    let top = area.height.saturating_sub(text.len() as u16 + 2) / 2;
+    let message_area = Rect {
+        y: area.y + top,
+        height: area.height - top,
+        ..area
+    };
+    let paragraph = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().title("grw"));
+    f.render_widget(paragraph, message_area);
+}
 
-    // Allow header to wrap to multiple lines (up to 3 lines)
-    let header_constraints = if size.width > 120 {
+/// Height of the status bar at the top of a terminal `width` columns wide
+/// Shared with the main loop, which sizes the diff and monitor panes the same way
+pub fn header_constraint(app: &App, width: u16) -> Constraint {
+    if app.is_status_bar_compact(width) {
+        // The compact status bar always fits on one line
+        Constraint::Length(1)
+    } else if app.get_status_bar_lines() > 1 {
+        Constraint::Length(app.get_status_bar_lines())
+    } else if width > 120 {
         // Wide screens: try to fit on one line
-        [Constraint::Length(1), Constraint::Min(0)]
+        Constraint::Length(1)
     } else {
-        // Narrow screens: allow up to 3 lines for header
-        [Constraint::Max(3), Constraint::Min(0)]
-    };
+        // Narrow screens: allow header to wrap to up to 3 lines
+        Constraint::Max(3)
+    }
+}
+
+fn render_panes(f: &mut Frame, app: &App, git_repo: &GitRepo) {
+    let size = f.area();
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(header_constraints)
+        .constraints([header_constraint(app, size.width), Constraint::Min(0)])
         .split(size);
 
     // Render status bar using new pane system
@@ -1515,6 +3565,13 @@ pub fn render<B: Backend>(f: &mut Frame, app: &App, git_repo: &GitRepo) {
         return;
     }
 
+    // Search results span every changed file, so the overlay takes the whole area
+    if app.is_searching() {
+        app.pane_registry
+            .render(f, app, chunks[1], PaneId::Search, git_repo);
+        return;
+    }
+
     // Handle the information pane (right side) for normal mode
     let file_browser_visible = app.is_showing_changed_files_pane();
     let info_pane_visible = app.is_showing_diff_panel();
@@ -1630,6 +3687,7 @@ fn render_information_pane(
 
 fn render_file_tree_content(f: &mut Frame, app: &App, area: Rect, _git_repo: &GitRepo) {
     let theme = app.get_theme();
+    let max_churn = crate::pane::max_churn(&app.tree_nodes);
     let tree_items: Vec<ListItem> = app
         .tree_nodes
         .iter()
@@ -1637,7 +3695,39 @@ fn render_file_tree_content(f: &mut Frame, app: &App, area: Rect, _git_repo: &Gi
         .map(|(index, (node, depth))| {
             let indent = "  ".repeat(*depth);
             let name_spans = if node.is_dir {
-                vec![Span::raw(format!("{}📁 {}", indent, node.name))]
+                if let Some(count) = node.untracked_file_count {
+                    let mut spans = Vec::new();
+
+                    // Collapsed untracked directories are selectable in place of their files
+                    if index == app.current_tree_index {
+                        spans.push(Span::styled(
+                            "-> ",
+                            Style::default()
+                                .fg(theme.secondary_color())
+                                .add_modifier(Modifier::BOLD),
+                        ));
+                    } else if node.collapsed {
+                        spans.push(Span::raw("   "));
+                    }
+
+                    spans.push(Span::raw(format!(
+                        "{indent}{}{}/",
+                        FileIcon::Directory.render(app.icons),
+                        node.name
+                    )));
+                    let noun = if count == 1 { "file" } else { "files" };
+                    spans.push(Span::styled(
+                        format!(" ({count} new {noun})"),
+                        Style::default().fg(theme.added_color()),
+                    ));
+                    spans
+                } else {
+                    vec![Span::raw(format!(
+                        "{indent}{}{}",
+                        FileIcon::Directory.render(app.icons),
+                        node.name
+                    ))]
+                }
             } else {
                 let mut spans = Vec::new();
 
@@ -1653,19 +3743,8 @@ fn render_file_tree_content(f: &mut Frame, app: &App, area: Rect, _git_repo: &Gi
                     spans.push(Span::raw("   "));
                 }
 
-                let status_char = if let Some(status) = node.status {
-                    if status.is_wt_new() {
-                        "📄 "
-                    } else if status.is_wt_modified() {
-                        "📝 "
-                    } else if status.is_wt_deleted() {
-                        "🗑️  "
-                    } else {
-                        "📄 "
-                    }
-                } else {
-                    "📄 "
-                };
+                let status_char =
+                    FileIcon::for_status(node.status, node.submodule).render(app.icons);
 
                 spans.push(Span::raw(format!("{indent}{status_char}")));
                 spans.push(Span::raw(node.name.clone()));
@@ -1686,6 +3765,25 @@ fn render_file_tree_content(f: &mut Frame, app: &App, area: Rect, _git_repo: &Gi
                             .add_modifier(Modifier::BOLD),
                     ));
                 }
+                spans.extend(crate::pane::churn_bar_spans(
+                    node.additions,
+                    node.deletions,
+                    max_churn,
+                    theme,
+                ));
+
+                // Say when a highlighted file changed, for as long as it stays highlighted
+                if let Some(age) = app
+                    .files
+                    .iter()
+                    .position(|f| f.path == node.path)
+                    .and_then(|file_idx| app.get_recent_change_age(file_idx))
+                {
+                    spans.push(Span::raw(format!(
+                        " · {} ago",
+                        crate::util::humanize_duration(age)
+                    )));
+                }
 
                 spans
             };
@@ -1693,17 +3791,22 @@ fn render_file_tree_content(f: &mut Frame, app: &App, area: Rect, _git_repo: &Gi
             let line_style = if !node.is_dir {
                 // Check if this file is recently changed by finding its index
                 if let Some(file_idx) = app.files.iter().position(|f| f.path == node.path) {
-                    if file_idx < app.file_change_timestamps.len()
-                        && app.is_file_recently_changed(file_idx)
-                    {
-                        // Recently changed - highlight
-                        Style::default()
-                            .fg(theme.foreground_color())
-                            .bg(theme.highlight_color())
-                            .add_modifier(Modifier::BOLD)
-                    } else {
-                        // Not recently changed - normal
-                        Style::default().fg(theme.foreground_color())
+                    match app.get_recent_change(file_idx) {
+                        Some(RecentChange::Fresh) => {
+                            // Recently changed - highlight
+                            Style::default()
+                                .fg(theme.foreground_color())
+                                .bg(theme.highlight_color())
+                                .add_modifier(Modifier::BOLD)
+                        }
+                        Some(RecentChange::Fading) => {
+                            // Highlight about to expire - dimmer style before returning to normal
+                            Style::default().fg(theme.highlight_color())
+                        }
+                        None => {
+                            // Not recently changed - normal
+                            Style::default().fg(theme.foreground_color())
+                        }
                     }
                 } else {
                     // File not found in files list - normal
@@ -1727,7 +3830,7 @@ fn render_file_tree_content(f: &mut Frame, app: &App, area: Rect, _git_repo: &Gi
             Block::default()
                 .title("Changed Files")
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(theme.border_color())),
+                .border_style(app.pane_border_style(ActivePane::FileTree)),
         )
         .highlight_style(
             Style::default()
@@ -1742,7 +3845,6 @@ fn render_file_tree_content(f: &mut Frame, app: &App, area: Rect, _git_repo: &Gi
 mod tests {
     use super::*;
     use crate::config::LlmConfig;
-    use log::debug;
     use std::env;
 
     fn create_test_app(
@@ -1751,92 +3853,600 @@ mod tests {
         initial_theme_index: usize,
         themes: Vec<Theme>,
     ) -> App {
-        let mut llm_config = LlmConfig::default();
-        if env::var("OPENAI_API_KEY").is_err() {
-            llm_config.api_key = Some("dummy_key".to_string());
+        let llm_config = LlmConfig {
+            provider: Some(crate::config::LlmProvider::Mock),
+            ..Default::default()
+        };
+        let llm_client = LlmClient::new(llm_config).ok();
+        let llm_state = Arc::new(crate::shared_state::LlmSharedState::new());
+        App::new_with_config(
+            show_diff_panel,
+            show_changed_files_pane,
+            initial_theme_index,
+            themes,
+            llm_client,
+            llm_state,
+        )
+    }
+
+    #[test]
+    fn test_app_creation() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let app = create_test_app(true, true, 0, themes);
+        assert_eq!(app.files.len(), 0);
+        assert_eq!(app.current_file_index, 0);
+        assert_eq!(app.scroll.offset, 0);
+        assert_eq!(app.current_diff_height, 20);
+        assert!(!app.is_showing_help());
+        assert!(app.show_diff_panel);
+        assert!(app.show_changed_files_pane);
+        assert_eq!(app.current_file_browser_pane, FileBrowserPane::FileTree);
+        assert_eq!(app.current_information_pane, InformationPane::Diff);
+        assert!(!app.monitor_command_configured);
+        assert!(app.monitor_elapsed_time.is_none());
+        assert!(!app.monitor_has_run);
+        assert_eq!(*app.get_theme(), Theme::Dark);
+        assert_eq!(app.last_active_pane, ActivePane::default());
+    }
+
+    #[test]
+    fn test_app_creation_no_diff() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let app = create_test_app(false, true, 0, themes);
+        assert!(!app.show_diff_panel);
+    }
+
+    #[test]
+    fn test_open_in_editor_at_diff_line() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        let manifest = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let lines = [
+            "diff --git a/Cargo.toml b/Cargo.toml",
+            "--- a/Cargo.toml",
+            "+++ b/Cargo.toml",
+            "@@ -10,3 +12,3 @@ [package]",
+            " kept",
+            "-removed",
+            "+added",
+            "@@ -40 +42,2 @@",
+            " context",
+        ];
+        app.update_files(vec![FileDiff {
+            path: manifest.clone(),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_MODIFIED,
+            line_strings: lines.iter().map(|l| l.to_string()).collect(),
+            additions: 1,
+            deletions: 1,
+        }]);
+
+        // The top visible line decides where the editor opens
+        for (offset, line) in [(4, 12), (6, 13), (8, 42)] {
+            app.scroll.offset = offset;
+            app.request_open_in_editor();
+            assert_eq!(
+                app.take_editor_request(),
+                Some((manifest.clone(), Some(line)))
+            );
+        }
+
+        // Removed lines and headers have no new-file line
+        for offset in [2, 5] {
+            app.scroll.offset = offset;
+            app.request_open_in_editor();
+            assert_eq!(app.take_editor_request(), Some((manifest.clone(), Some(1))));
+        }
+
+        // Outside the diff pane the file opens without a line
+        app.current_information_pane = InformationPane::SideBySideDiff;
+        app.request_open_in_editor();
+        assert_eq!(app.take_editor_request(), Some((manifest, None)));
+    }
+
+    #[test]
+    fn test_toggle_diff_folding_keeps_top_line() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.current_diff_height = 2;
+        let mut lines = vec!["@@ -1,12 +1,12 @@".to_string()];
+        lines.extend((0..10).map(|i| format!(" same {i}")));
+        lines.extend(["-old".to_string(), "+new".to_string(), " tail".to_string()]);
+        app.update_files(vec![FileDiff {
+            path: std::path::PathBuf::from("lib.rs"),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_MODIFIED,
+            line_strings: lines,
+            additions: 1,
+            deletions: 1,
+        }]);
+
+        // Scrolled to the removed line, which stays on top once the context is folded
+        app.scroll_to_line(11);
+        app.toggle_diff_folding();
+        assert!(app.is_diff_folded());
+        assert_eq!(
+            app.diff_rows(),
+            [
+                DiffRow::Line(0),
+                DiffRow::Fold(10),
+                DiffRow::Line(11),
+                DiffRow::Line(12),
+                DiffRow::Fold(1),
+            ]
+        );
+        assert_eq!(app.get_scroll_offset(), 2);
+
+        // Line selection works on lines, so it waits for the diff to be unfolded
+        app.start_diff_selection();
+        assert!(!app.is_selecting_diff());
+
+        app.toggle_diff_folding();
+        assert_eq!(app.diff_rows().len(), 14);
+        assert_eq!(app.get_scroll_offset(), 11);
+
+        // The side-by-side view is never folded
+        app.toggle_diff_folding();
+        app.current_information_pane = InformationPane::SideBySideDiff;
+        assert!(!app.is_diff_folded());
+    }
+
+    #[test]
+    fn test_write_current_patch_fills_in_missing_headers() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.update_files(vec![FileDiff {
+            path: std::path::PathBuf::from("src/new.rs"),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_NEW,
+            line_strings: vec![
+                "@@ -0,0 +1,2 @@".to_string(),
+                "+a".to_string(),
+                "+b".to_string(),
+            ],
+            additions: 2,
+            deletions: 0,
+        }]);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = app.write_current_patch(dir.path()).unwrap();
+        assert_eq!(path, dir.path().join("new.rs.patch"));
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "diff --git a/src/new.rs b/src/new.rs\nnew file mode 100644\n\
+             --- /dev/null\n+++ b/src/new.rs\n@@ -0,0 +1,2 @@\n+a\n+b\n"
+        );
+
+        app.update_files(Vec::new());
+        assert_eq!(
+            app.write_current_patch(dir.path()),
+            Err("No file selected".to_string())
+        );
+    }
+
+    #[test]
+    fn test_apply_action() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+
+        assert!(!app.apply_action(Action::ToggleTheme));
+        assert_eq!(app.get_theme(), &Theme::Light);
+        app.apply_action(Action::ToggleChangedFilesPane);
+        assert!(!app.is_showing_changed_files_pane());
+
+        app.apply_action(Action::OpenSearch);
+        assert!(app.is_searching());
+
+        assert!(app.take_main_loop_action().is_none());
+        app.apply_action(Action::OpenCommitPicker);
+        assert!(!app.is_in_commit_picker_mode());
+        assert_eq!(app.take_main_loop_action(), Some(Action::OpenCommitPicker));
+
+        assert!(app.apply_action(Action::Quit));
+    }
+
+    #[test]
+    fn test_semantic_stats_cache_follows_the_diff() {
+        use crate::semantic_stats::SemanticStats;
+
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.set_semantic_stats(true);
+        let file = FileDiff {
+            path: std::path::PathBuf::from("a.rs"),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_MODIFIED,
+            line_strings: vec!["@@ -1 +1 @@".to_string(), "+fn a() {}".to_string()],
+            additions: 1,
+            deletions: 0,
+        };
+        let stats = Some(SemanticStats {
+            functions_touched: 1,
+        });
+        app.update_files(vec![file.clone()]);
+        app.semantic_stats_cache.insert(file.path.clone(), stats);
+
+        // The same diff keeps its stats, a changed one drops them
+        app.update_files(vec![file.clone()]);
+        assert_eq!(app.current_semantic_stats(), stats);
+        let mut changed = file.clone();
+        changed.line_strings.push("+fn b() {}".to_string());
+        app.update_files(vec![changed]);
+        assert_eq!(app.current_semantic_stats(), None);
+
+        // Another view's line numbers count in other content
+        app.semantic_stats_cache.insert(file.path.clone(), stats);
+        app.set_view_mode(ViewMode::Staged);
+        assert_eq!(app.current_semantic_stats(), None);
+    }
+
+    #[test]
+    fn test_advice_panel_without_llm_shows_setup_hint() {
+        let llm_state = Arc::new(crate::shared_state::LlmSharedState::new());
+        let mut app = App::new_with_config(true, true, 0, vec![Theme::Dark], None, llm_state);
+
+        app.toggle_pane_visibility(&PaneId::Advice).unwrap();
+        app.check_advice_panel_tasks();
+
+        let pane = app.pane_registry.get_pane(&PaneId::Advice).unwrap();
+        assert_eq!(pane.title(), "Chat (AI disabled)");
+        let advice_panel = pane.as_advice_pane().unwrap();
+        assert!(advice_panel.get_chat_history().is_empty());
+        assert!(!advice_panel.needs_initialization);
+    }
+
+    #[test]
+    fn test_clear_advice_chat_needs_confirmation() {
+        use crate::pane::{AdviceContent, ChatMessageData, MessageRole};
+
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        let chat_len = |app: &mut App| {
+            app.pane_registry
+                .with_pane_mut(&PaneId::Advice, |pane| {
+                    pane.as_advice_pane_mut().unwrap().get_chat_history().len()
+                })
+                .unwrap()
+        };
+
+        // Nothing to lose yet, so it clears straight away
+        app.request_clear_advice_chat();
+        assert!(!app.is_confirming());
+
+        app.pane_registry.with_pane_mut(&PaneId::Advice, |pane| {
+            pane.as_advice_pane_mut().unwrap().content =
+                AdviceContent::Chat(vec![ChatMessageData {
+                    id: "1".to_string(),
+                    role: MessageRole::Assistant,
+                    content: "Looks good".to_string(),
+                    timestamp: std::time::SystemTime::now(),
+                }]);
+        });
+        app.request_clear_advice_chat();
+        assert!(app.is_confirming());
+        app.answer_confirmation(false);
+        assert!(!app.is_confirming());
+        assert_eq!(chat_len(&mut app), 1);
+
+        app.request_clear_advice_chat();
+        app.answer_confirmation(true);
+        assert_eq!(chat_len(&mut app), 0);
+    }
+
+    #[test]
+    fn test_scroll_offset_after_selected_file_changes() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.current_diff_height = 10;
+        let file = |name: &str, lines: usize| FileDiff {
+            path: std::path::PathBuf::from(name),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_MODIFIED,
+            line_strings: (0..lines).map(|i| format!("+line {i}")).collect(),
+            additions: lines,
+            deletions: 0,
+        };
+
+        app.update_files(vec![file("long.rs", 100), file("short.rs", 5)]);
+        app.scroll_to_line(80);
+        assert_eq!(app.get_scroll_offset(), 80);
+
+        // The long file goes away and the shorter one takes its place: back to the top
+        app.update_files(vec![file("short.rs", 5)]);
+        assert_eq!(
+            app.get_current_file().unwrap().path,
+            std::path::PathBuf::from("short.rs")
+        );
+        assert_eq!(app.get_scroll_offset(), 0);
+
+        // The same file shrinking keeps as much of the position as still fits
+        app.update_files(vec![file("long.rs", 100)]);
+        app.scroll_to_line(80);
+        app.update_files(vec![file("long.rs", 50)]);
+        assert_eq!(app.get_scroll_offset(), 40);
+    }
+
+    #[test]
+    fn test_stage_current_hunk() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.current_diff_height = 2;
+        let lines = [
+            "diff --git a/lib.rs b/lib.rs",
+            "--- a/lib.rs",
+            "+++ b/lib.rs",
+            "@@ -1,2 +1,2 @@",
+            "-a",
+            "+A",
+            " b",
+            "@@ -10,1 +10,1 @@",
+            "-c",
+            "+C",
+        ];
+        app.update_files(vec![FileDiff {
+            path: std::path::PathBuf::from("lib.rs"),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_MODIFIED,
+            line_strings: lines.iter().map(|line| line.to_string()).collect(),
+            additions: 2,
+            deletions: 2,
+        }]);
+
+        // The hunk at the top of the view is the one staged
+        app.scroll_to_line(8);
+        app.stage_current_hunk();
+        assert_eq!(
+            app.take_hunk_stage_request().as_deref(),
+            Some(
+                "diff --git a/lib.rs b/lib.rs\n--- a/lib.rs\n+++ b/lib.rs\n@@ -10,1 +10,1 @@\n-c\n+C\n"
+            )
+        );
+
+        // From the headers, it's the first hunk
+        app.scroll_to_line(0);
+        app.stage_current_hunk();
+        assert!(app.take_hunk_stage_request().unwrap().contains("+A\n b\n"));
+
+        app.set_view_mode(ViewMode::Staged);
+        app.stage_current_hunk();
+        assert!(app.take_hunk_stage_request().is_none());
+        assert_eq!(
+            app.get_status_message(),
+            Some("Only unstaged hunks can be staged")
+        );
+    }
+
+    #[test]
+    fn test_staged_and_unstaged_view() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.toggle_staged_and_unstaged_view();
+        assert_eq!(
+            app.get_forced_view_mode(),
+            Some(ViewMode::StagedAndUnstaged)
+        );
+        app.toggle_staged_and_unstaged_view();
+        assert_eq!(app.get_forced_view_mode(), None);
+
+        let file = |name: &str, status, lines: &[&str]| FileDiff {
+            path: std::path::PathBuf::from(format!("/repo/{name}")),
+            old_path: None,
+            submodule: None,
+            status,
+            line_strings: lines.iter().map(|line| line.to_string()).collect(),
+            additions: 1,
+            deletions: 0,
+        };
+        let staged = vec![
+            file("both.rs", git2::Status::INDEX_MODIFIED, &["+staged"]),
+            file("staged.rs", git2::Status::INDEX_NEW, &["+new"]),
+        ];
+        let unstaged = vec![
+            file("a.rs", git2::Status::WT_MODIFIED, &["+a"]),
+            file("both.rs", git2::Status::WT_MODIFIED, &["+unstaged"]),
+        ];
+
+        let combined = crate::git::combine_staged_and_unstaged(&staged, &unstaged);
+        let names: Vec<_> = combined
+            .iter()
+            .map(|file| file.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, ["a.rs", "both.rs", "staged.rs"]);
+
+        // A file with both kinds of change shows the staged diff first, each labelled
+        let both = &combined[1];
+        assert_eq!(
+            both.line_strings,
+            [
+                crate::git::STAGED_SECTION_LABEL,
+                "+staged",
+                crate::git::UNSTAGED_SECTION_LABEL,
+                "+unstaged",
+            ]
+        );
+        assert_eq!(
+            both.status,
+            git2::Status::INDEX_MODIFIED | git2::Status::WT_MODIFIED
+        );
+        assert_eq!(both.additions, 2);
+        assert_eq!(
+            combined[0].line_strings,
+            [crate::git::UNSTAGED_SECTION_LABEL, "+a"]
+        );
+    }
+
+    #[test]
+    fn test_toggle_staged_view() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        assert_eq!(app.get_forced_view_mode(), None);
+
+        app.toggle_staged_view();
+        assert_eq!(app.get_forced_view_mode(), Some(ViewMode::Staged));
+        app.toggle_staged_view();
+        assert_eq!(app.get_forced_view_mode(), None);
+
+        // The forced view decides what the repository snapshot shows
+        let staged = FileDiff {
+            path: std::path::PathBuf::from("/repo/staged.rs"),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::INDEX_MODIFIED,
+            line_strings: vec!["+a".to_string(), "+b".to_string()],
+            additions: 2,
+            deletions: 0,
+        };
+        let mut repo = GitRepo {
+            path: std::path::PathBuf::from("/repo"),
+            changed_files: Vec::new(),
+            staged_files: vec![staged],
+            dirty_directory_files: Vec::new(),
+            untracked_dirs: Vec::new(),
+            last_commit_files: Vec::new(),
+            last_commit_id: None,
+            base_files: Vec::new(),
+            diff_base: None,
+            current_view_mode: ViewMode::WorkingTree,
+            repo_name: "repo".to_string(),
+            branch_name: "main".to_string(),
+            ahead: None,
+            behind: None,
+            stash_count: 0,
+            has_unpushed: false,
+            commit_info: (String::new(), String::new()),
+            total_stats: (0, 0, 0),
+        };
+        repo.force_view_mode(ViewMode::Staged);
+        assert_eq!(repo.get_display_files().len(), 1);
+        assert_eq!(repo.total_stats, (1, 2, 0));
+    }
+
+    #[test]
+    fn test_cycle_view_mode() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            app.cycle_view_mode();
+            seen.push(app.get_forced_view_mode());
         }
-        let llm_client = LlmClient::new(llm_config).ok();
-        let llm_state = Arc::new(crate::shared_state::LlmSharedState::new());
-        App::new_with_config(
-            show_diff_panel,
-            show_changed_files_pane,
-            initial_theme_index,
-            themes,
-            llm_client,
-            llm_state,
-        )
+        assert_eq!(
+            seen,
+            vec![
+                Some(ViewMode::WorkingTree),
+                Some(ViewMode::Staged),
+                Some(ViewMode::LastCommit),
+                None,
+            ]
+        );
+
+        // Cycling carries on from a view pinned with `S`
+        app.toggle_staged_view();
+        app.cycle_view_mode();
+        assert_eq!(app.get_forced_view_mode(), Some(ViewMode::LastCommit));
     }
 
     #[test]
-    fn test_app_creation() {
+    fn test_focused_pane_border() {
         let themes = vec![Theme::Dark, Theme::Light];
-        let app = create_test_app(true, true, 0, themes);
-        assert_eq!(app.files.len(), 0);
-        assert_eq!(app.current_file_index, 0);
-        assert_eq!(app.scroll_offset, 0);
-        assert_eq!(app.current_diff_height, 20);
-        assert!(!app.is_showing_help());
-        assert!(app.show_diff_panel);
-        assert!(app.show_changed_files_pane);
-        assert_eq!(app.current_file_browser_pane, FileBrowserPane::FileTree);
-        assert_eq!(app.current_information_pane, InformationPane::Diff);
-        assert!(!app.monitor_command_configured);
-        assert!(app.monitor_elapsed_time.is_none());
-        assert!(!app.monitor_has_run);
-        assert_eq!(*app.get_theme(), Theme::Dark);
-        assert_eq!(app.last_active_pane, ActivePane::default());
+        let mut app = create_test_app(true, true, 0, themes);
+        let focused = Style::default()
+            .fg(app.get_theme().primary_color())
+            .add_modifier(Modifier::BOLD);
+        let unfocused = Style::default().fg(app.get_theme().border_color());
+
+        assert_eq!(app.get_focused_pane(), ActivePane::Diff);
+        assert_eq!(app.pane_border_style(ActivePane::Diff), focused);
+        assert_eq!(app.pane_border_style(ActivePane::FileTree), unfocused);
+
+        app.set_side_by_side_diff();
+        assert_eq!(app.get_focused_pane(), ActivePane::SideBySideDiff);
+
+        // With the diff hidden the file tree is all that's left to scroll
+        app.toggle_diff_panel();
+        assert_eq!(app.get_focused_pane(), ActivePane::FileTree);
+        assert_eq!(app.pane_border_style(ActivePane::FileTree), focused);
+        assert_eq!(app.pane_border_style(ActivePane::Monitor), unfocused);
     }
 
     #[test]
-    fn test_app_creation_no_diff() {
+    fn test_cycle_focus_through_visible_panes() {
         let themes = vec![Theme::Dark, Theme::Light];
-        let app = create_test_app(false, true, 0, themes);
-        assert!(!app.show_diff_panel);
+        let mut app = create_test_app(true, true, 0, themes);
+        assert_eq!(app.get_focused_pane(), ActivePane::Diff);
+
+        app.cycle_focus(true);
+        assert_eq!(app.get_focused_pane(), ActivePane::FileTree);
+        app.cycle_focus(false);
+        assert_eq!(app.get_focused_pane(), ActivePane::Diff);
+
+        app.toggle_monitor_pane();
+        app.cycle_focus(true);
+        app.cycle_focus(true);
+        assert_eq!(app.get_focused_pane(), ActivePane::Monitor);
+
+        // Hiding the focused pane hands focus back to the layout's default
+        app.toggle_monitor_pane();
+        assert_eq!(app.get_focused_pane(), ActivePane::Diff);
+        app.set_side_by_side_diff();
+        app.cycle_focus(false);
+        assert_eq!(app.get_focused_pane(), ActivePane::FileTree);
+        app.cycle_focus(false);
+        assert_eq!(app.get_focused_pane(), ActivePane::SideBySideDiff);
     }
 
     #[test]
     fn test_scroll_up() {
         let themes = vec![Theme::Dark, Theme::Light];
         let mut app = create_test_app(true, true, 0, themes);
-        app.scroll_offset = 5;
-        app.scroll_up();
-        assert_eq!(app.scroll_offset, 4);
+        app.scroll.offset = 5;
+        app.apply_action(Action::ScrollDiff(ScrollAction::LineUp));
+        assert_eq!(app.scroll.offset, 4);
     }
 
     #[test]
     fn test_scroll_up_at_zero() {
         let themes = vec![Theme::Dark, Theme::Light];
         let mut app = create_test_app(true, true, 0, themes);
-        app.scroll_offset = 0;
-        app.scroll_up();
-        assert_eq!(app.scroll_offset, 0);
+        app.scroll.offset = 0;
+        app.apply_action(Action::ScrollDiff(ScrollAction::LineUp));
+        assert_eq!(app.scroll.offset, 0);
     }
 
     #[test]
     fn test_page_up() {
         let themes = vec![Theme::Dark, Theme::Light];
         let mut app = create_test_app(true, true, 0, themes);
-        app.scroll_offset = 25;
-        app.page_up(10);
-        assert_eq!(app.scroll_offset, 15);
+        app.scroll.offset = 25;
+        app.current_diff_height = 10;
+        app.apply_action(Action::ScrollDiff(ScrollAction::PageUp));
+        assert_eq!(app.scroll.offset, 15);
     }
 
     #[test]
     fn test_page_up_underflow() {
         let themes = vec![Theme::Dark, Theme::Light];
         let mut app = create_test_app(true, true, 0, themes);
-        app.scroll_offset = 5;
-        app.page_up(10);
-        assert_eq!(app.scroll_offset, 0);
+        app.scroll.offset = 5;
+        app.current_diff_height = 10;
+        app.apply_action(Action::ScrollDiff(ScrollAction::PageUp));
+        assert_eq!(app.scroll.offset, 0);
     }
 
     #[test]
     fn test_scroll_to_top() {
         let themes = vec![Theme::Dark, Theme::Light];
         let mut app = create_test_app(true, true, 0, themes);
-        app.scroll_offset = 100;
+        app.scroll.offset = 100;
         app.scroll_to_top();
-        assert_eq!(app.scroll_offset, 0);
+        assert_eq!(app.scroll.offset, 0);
     }
 
     #[test]
@@ -1881,21 +4491,35 @@ mod tests {
 
     #[test]
     fn test_monitor_output_update() {
+        use crate::monitor::MonitorOutput;
+
         let themes = vec![Theme::Dark, Theme::Light];
         let mut app = create_test_app(true, true, 0, themes);
-        assert_eq!(app.monitor_output, "");
-        assert_eq!(app.monitor_scroll_offset, 0);
+        assert_eq!(app.monitor_output.lines().len(), 0);
+        assert_eq!(app.monitor_scroll.offset, 0);
 
         // Set scroll offset to test that it's preserved
-        app.monitor_scroll_offset = 5;
+        app.monitor_scroll.offset = 5;
+
+        app.update_monitor_output(MonitorOutput::Line("old run".to_string()));
+        app.update_monitor_output(MonitorOutput::Started);
+        assert_eq!(app.monitor_output.lines().collect::<Vec<_>>(), ["old run"]);
+        app.update_monitor_output(MonitorOutput::Line("test output".to_string()));
+        assert_eq!(
+            app.monitor_output.lines().collect::<Vec<_>>(),
+            ["test output"]
+        );
+        assert_eq!(app.monitor_scroll.offset, 5); // Should preserve scroll offset
+        assert!(!app.get_monitor_has_run());
 
-        app.update_monitor_output("test output".to_string());
-        assert_eq!(app.monitor_output, "test output");
-        assert_eq!(app.monitor_scroll_offset, 5); // Should preserve scroll offset
+        app.update_monitor_output(MonitorOutput::Finished(std::time::Instant::now()));
+        assert!(app.get_monitor_has_run());
     }
 
     #[test]
     fn test_monitor_scroll() {
+        use crate::monitor::MonitorOutput;
+
         let themes = vec![Theme::Dark, Theme::Light];
         let mut app = create_test_app(true, true, 0, themes);
 
@@ -1903,30 +4527,50 @@ mod tests {
         app.monitor_visible_height = 3;
 
         // Create a long output with multiple lines
-        let long_output = "Line 1\nLine 2\nLine 3\nLine 4\nLine 5";
-        app.update_monitor_output(long_output.to_string());
+        for i in 1..=5 {
+            app.update_monitor_output(MonitorOutput::Line(format!("Line {i}")));
+        }
+
+        let alt = |c| {
+            KeyEvent::new(
+                crossterm::event::KeyCode::Char(c),
+                crossterm::event::KeyModifiers::ALT,
+            )
+        };
 
         // Test scrolling down
-        app.scroll_monitor_down();
-        assert_eq!(app.monitor_scroll_offset, 1);
+        assert!(app.scroll_monitor(&alt('j')));
+        assert_eq!(app.monitor_scroll.offset, 1);
 
-        app.scroll_monitor_down();
-        assert_eq!(app.monitor_scroll_offset, 2);
+        app.scroll_monitor(&alt('j'));
+        assert_eq!(app.monitor_scroll.offset, 2);
 
         // Try to scroll past content - should stop at max scroll (5 lines - 3 visible = 2 max scroll)
-        app.scroll_monitor_down();
-        assert_eq!(app.monitor_scroll_offset, 2); // Should not increase beyond max
+        app.scroll_monitor(&alt('j'));
+        assert_eq!(app.monitor_scroll.offset, 2); // Should not increase beyond max
 
         // Test scrolling up
-        app.scroll_monitor_up();
-        assert_eq!(app.monitor_scroll_offset, 1);
+        app.scroll_monitor(&alt('k'));
+        assert_eq!(app.monitor_scroll.offset, 1);
 
-        app.scroll_monitor_up();
-        assert_eq!(app.monitor_scroll_offset, 0);
+        app.scroll_monitor(&alt('k'));
+        assert_eq!(app.monitor_scroll.offset, 0);
 
         // Test scrolling up when already at top
-        app.scroll_monitor_up();
-        assert_eq!(app.monitor_scroll_offset, 0);
+        app.scroll_monitor(&alt('k'));
+        assert_eq!(app.monitor_scroll.offset, 0);
+
+        // Page and jump bindings share the diff view's keys
+        app.scroll_monitor(&alt('G'));
+        assert_eq!(app.monitor_scroll.offset, 2);
+        app.scroll_monitor(&alt('u'));
+        assert_eq!(app.monitor_scroll.offset, 1);
+        app.scroll_monitor(&alt('g'));
+        app.scroll_monitor(&alt('g'));
+        assert_eq!(app.monitor_scroll.offset, 0);
+        app.scroll_monitor(&alt('f'));
+        assert_eq!(app.monitor_scroll.offset, 2);
+        assert!(!app.scroll_monitor(&alt('x')));
     }
 
     #[test]
@@ -1983,24 +4627,6 @@ mod tests {
         assert!(app.monitor_has_run);
     }
 
-    #[test]
-    fn test_format_elapsed_time() {
-        let themes = vec![Theme::Dark, Theme::Light];
-        let app = create_test_app(true, true, 0, themes);
-
-        // Test seconds
-        let secs = std::time::Duration::from_secs(45);
-        assert_eq!(app.format_elapsed_time(secs), "45s");
-
-        // Test minutes and seconds
-        let mins_secs = std::time::Duration::from_secs(125);
-        assert_eq!(app.format_elapsed_time(mins_secs), "2m5s");
-
-        // Test hours and minutes
-        let hours_mins = std::time::Duration::from_secs(3665);
-        assert_eq!(app.format_elapsed_time(hours_mins), "1h1m");
-    }
-
     #[test]
     fn test_diff_mode_switching() {
         let themes = vec![Theme::Dark, Theme::Light];
@@ -2146,6 +4772,136 @@ mod tests {
         assert_eq!(*app.get_theme(), Theme::Dark);
     }
 
+    #[test]
+    fn test_theme_toggle_cycles_all_themes() {
+        let custom = |name: &str| Theme::Custom(name.to_string(), Arc::new(ColorPalette::dark()));
+        let themes = vec![
+            Theme::Dark,
+            Theme::Light,
+            custom("custom"),
+            custom("dracula"),
+        ];
+        let mut app = create_test_app(true, true, 1, themes);
+        assert_eq!(app.get_theme_change_notice(), None);
+
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            app.toggle_theme();
+            seen.push(app.get_theme().name().to_string());
+        }
+        assert_eq!(seen, vec!["custom", "dracula", "dark", "light"]);
+        assert_eq!(app.get_theme_change_notice(), Some("light"));
+    }
+
+    #[test]
+    fn test_color_support_detection() {
+        use crate::config::ColorMode;
+
+        let detect = |mode, no_color, colorterm, term| {
+            ColorSupport::from_env_values(mode, no_color, colorterm, term)
+        };
+
+        assert_eq!(
+            detect(ColorMode::Auto, Some("1"), Some("truecolor"), None),
+            ColorSupport::NoColor
+        );
+        assert_eq!(
+            detect(ColorMode::Auto, Some(""), Some("truecolor"), None),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(ColorMode::Auto, None, None, Some("xterm-256color")),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            detect(ColorMode::Auto, None, None, Some("xterm-direct")),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(ColorMode::Auto, None, None, Some("xterm")),
+            ColorSupport::Ansi16
+        );
+        assert_eq!(
+            detect(ColorMode::Auto, None, None, Some("dumb")),
+            ColorSupport::NoColor
+        );
+
+        // Explicit modes override the environment
+        assert_eq!(
+            detect(ColorMode::Always, Some("1"), None, Some("dumb")),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(ColorMode::Never, None, Some("truecolor"), None),
+            ColorSupport::NoColor
+        );
+    }
+
+    #[test]
+    fn test_theme_color_downgrade() {
+        assert_eq!(
+            nearest_ansi_color(Color::Rgb(0x28, 0x2a, 0x36)),
+            Color::Black
+        );
+        assert_eq!(nearest_ansi_color(Color::Rgb(250, 10, 10)), Color::LightRed);
+        assert_eq!(nearest_ansi_color(Color::Cyan), Color::Cyan);
+
+        let custom = Theme::Custom(
+            "custom".to_string(),
+            Arc::new(ColorPalette {
+                added: Color::Rgb(0x20, 0xf0, 0x30),
+                ..ColorPalette::dark()
+            }),
+        );
+        let downgraded = custom.clone().for_color_support(ColorSupport::Ansi16);
+        assert_eq!(downgraded.added_color(), Color::LightGreen);
+
+        let indexed = custom.for_color_support(ColorSupport::Ansi256);
+        assert_eq!(indexed.added_color(), Color::Indexed(47));
+        assert_eq!(
+            nearest_256_color(Color::Rgb(0x28, 0x2a, 0x36)),
+            Color::Indexed(236)
+        );
+
+        let mono = Theme::Dark.for_color_support(ColorSupport::NoColor);
+        assert_eq!(mono.name(), "dark");
+        assert_eq!(mono.added_color(), Color::Reset);
+        assert!(!mono.uses_diff_markers());
+
+        // High contrast keeps its diff markers when it loses its colors
+        let mono = Theme::HighContrast.for_color_support(ColorSupport::NoColor);
+        assert_eq!(mono.added_color(), Color::Reset);
+        assert!(mono.uses_diff_markers());
+
+        assert_eq!(
+            Theme::Light.for_color_support(ColorSupport::TrueColor),
+            Theme::Light
+        );
+    }
+
+    #[test]
+    fn test_blend_colors() {
+        assert_eq!(
+            blend_colors(Color::Black, Color::Green, 0.5),
+            Some(Color::Rgb(0, 103, 0))
+        );
+        assert_eq!(
+            blend_colors(Color::Rgb(10, 20, 30), Color::White, 0.0),
+            Some(Color::Rgb(10, 20, 30))
+        );
+        assert_eq!(blend_colors(Color::Reset, Color::Green, 0.5), None);
+    }
+
+    #[test]
+    fn test_high_contrast_theme() {
+        let theme = Theme::HighContrast;
+        assert_eq!(theme.name(), "high-contrast");
+        assert!(theme.uses_diff_markers());
+        assert!(!Theme::Dark.uses_diff_markers());
+        assert_eq!(theme.background_color(), Color::Black);
+        assert_ne!(theme.added_color(), theme.removed_color());
+    }
+
     #[test]
     fn test_theme_colors() {
         // Test dark theme colors
@@ -2171,6 +4927,8 @@ mod tests {
         // Create initial files
         let file1 = FileDiff {
             path: std::path::PathBuf::from("zebra.txt"),
+            old_path: None,
+            submodule: None,
             status: git2::Status::INDEX_MODIFIED,
             line_strings: vec!["line 1".to_string()],
             additions: 1,
@@ -2178,6 +4936,8 @@ mod tests {
         };
         let file2 = FileDiff {
             path: std::path::PathBuf::from("apple.txt"),
+            old_path: None,
+            submodule: None,
             status: git2::Status::INDEX_MODIFIED,
             line_strings: vec!["line 1".to_string()],
             additions: 1,
@@ -2199,6 +4959,8 @@ mod tests {
         // Now add a new file that comes first alphabetically
         let file3 = FileDiff {
             path: std::path::PathBuf::from("aardvark.txt"),
+            old_path: None,
+            submodule: None,
             status: git2::Status::INDEX_NEW,
             line_strings: vec!["new line".to_string()],
             additions: 1,
@@ -2231,6 +4993,8 @@ mod tests {
         // Test preserving same file when file content changes
         let modified_file1 = FileDiff {
             path: std::path::PathBuf::from("aardvark.txt"),
+            old_path: None,
+            submodule: None,
             status: git2::Status::INDEX_MODIFIED,
             line_strings: vec!["modified line".to_string()],
             additions: 2,
@@ -2260,6 +5024,7 @@ mod tests {
             sha: "abc123".to_string(),
             short_sha: "abc123".to_string(),
             message: "Test commit".to_string(),
+            time: 0,
             files_changed: vec![],
         };
         app.select_commit(commit.clone());
@@ -2292,6 +5057,575 @@ mod tests {
         assert!(app.get_selected_commit().is_none()); // Should be cleared again
     }
 
+    #[test]
+    fn test_untracked_directory_collapses_into_single_node() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+
+        let new_file = |name: &str| FileDiff {
+            path: std::path::PathBuf::from(format!("newdir/{name}")),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_NEW,
+            line_strings: vec![format!("+{name}")],
+            additions: 1,
+            deletions: 0,
+        };
+        let file_node = |diff: FileDiff| TreeNode {
+            name: diff.path.file_name().unwrap().to_string_lossy().to_string(),
+            path: diff.path.clone(),
+            is_dir: false,
+            children: Vec::new(),
+            file_diff: Some(diff),
+            untracked: false,
+        };
+
+        let files = vec![new_file("a.txt"), new_file("b.txt")];
+        app.update_files(files.clone());
+
+        let root = TreeNode {
+            name: ".".to_string(),
+            path: std::path::PathBuf::new(),
+            is_dir: true,
+            children: vec![TreeNode {
+                name: "newdir".to_string(),
+                path: std::path::PathBuf::from("newdir"),
+                is_dir: true,
+                children: files.into_iter().map(file_node).collect(),
+                file_diff: None,
+                untracked: true,
+            }],
+            file_diff: None,
+            untracked: false,
+        };
+
+        // Collapsed by default: one node standing in for both files
+        app.update_tree(&root);
+        assert_eq!(app.tree_nodes.len(), 1);
+        assert_eq!(app.tree_nodes[0].0.untracked_file_count, Some(2));
+        assert!(app.tree_nodes[0].0.collapsed);
+        assert_eq!(app.file_indices_in_tree, vec![0]);
+
+        // Expanding shows the individual files
+        assert!(app.toggle_untracked_dir());
+        app.update_tree(&root);
+        assert_eq!(app.tree_nodes.len(), 3);
+        assert!(!app.tree_nodes[0].0.collapsed);
+
+        // Toggling from a file inside collapses the directory again
+        app.next_file();
+        assert!(app.toggle_untracked_dir());
+        app.update_tree(&root);
+        assert_eq!(app.tree_nodes.len(), 1);
+    }
+
+    #[test]
+    fn test_collapse_state_survives_tree_rebuild() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        let new_file = |path: &str| FileDiff {
+            path: std::path::PathBuf::from(path),
+            old_path: None,
+            submodule: None,
+            status: git2::Status::WT_NEW,
+            line_strings: vec!["+a".to_string()],
+            additions: 1,
+            deletions: 0,
+        };
+        let untracked_dir = |name: &str, files: &[FileDiff]| TreeNode {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(name),
+            is_dir: true,
+            children: files
+                .iter()
+                .map(|diff| TreeNode {
+                    name: diff.path.file_name().unwrap().to_string_lossy().to_string(),
+                    path: diff.path.clone(),
+                    is_dir: false,
+                    children: Vec::new(),
+                    file_diff: Some(diff.clone()),
+                    untracked: true,
+                })
+                .collect(),
+            file_diff: None,
+            untracked: true,
+        };
+        let root = |dirs: Vec<TreeNode>| TreeNode {
+            name: ".".to_string(),
+            path: std::path::PathBuf::new(),
+            is_dir: true,
+            children: dirs,
+            file_diff: None,
+            untracked: false,
+        };
+        let a = [new_file("a/1.txt"), new_file("a/2.txt")];
+        let b = [new_file("b/1.txt")];
+        app.update_files(a.iter().chain(&b).cloned().collect());
+
+        app.update_tree(&root(vec![untracked_dir("a", &a), untracked_dir("b", &b)]));
+        assert!(app.toggle_untracked_dir());
+
+        // A refresh rebuilding the same tree keeps "a" expanded
+        app.update_tree(&root(vec![untracked_dir("a", &a), untracked_dir("b", &b)]));
+        assert_eq!(app.tree_nodes.len(), 4);
+        assert!(!app.tree_nodes[0].0.collapsed);
+        assert!(app.tree_nodes[3].0.collapsed);
+
+        // Once "a" is gone it's forgotten, so coming back it starts collapsed again
+        app.update_files(b.to_vec());
+        app.update_tree(&root(vec![untracked_dir("b", &b)]));
+        app.update_files(a.iter().chain(&b).cloned().collect());
+        app.update_tree(&root(vec![untracked_dir("a", &a), untracked_dir("b", &b)]));
+        assert_eq!(app.tree_nodes.len(), 2);
+        assert!(app.tree_nodes[0].0.collapsed);
+    }
+
+    #[test]
+    fn test_max_tree_depth_flattens_deep_paths() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        let file = |path: &str| TreeNode {
+            name: path.rsplit('/').next().unwrap().to_string(),
+            path: std::path::PathBuf::from(path),
+            is_dir: false,
+            children: Vec::new(),
+            file_diff: Some(FileDiff {
+                path: std::path::PathBuf::from(path),
+                old_path: None,
+                submodule: None,
+                status: git2::Status::WT_MODIFIED,
+                line_strings: vec!["+a".to_string()],
+                additions: 1,
+                deletions: 0,
+            }),
+            untracked: false,
+        };
+        let dir = |path: &str, children: Vec<TreeNode>, untracked: bool| TreeNode {
+            name: path.rsplit('/').next().unwrap().to_string(),
+            path: std::path::PathBuf::from(path),
+            is_dir: true,
+            children,
+            file_diff: None,
+            untracked,
+        };
+        let root = dir(
+            "",
+            vec![
+                dir(
+                    "src",
+                    vec![
+                        dir(
+                            "src/a",
+                            vec![dir("src/a/b", vec![file("src/a/b/c.rs")], false)],
+                            false,
+                        ),
+                        dir("src/new", vec![file("src/new/n.rs")], true),
+                        file("src/d.rs"),
+                    ],
+                    false,
+                ),
+                file("top.rs"),
+            ],
+            false,
+        );
+        let names = |app: &App| {
+            app.tree_nodes
+                .iter()
+                .map(|(node, depth)| (node.name.clone(), *depth))
+                .collect::<Vec<_>>()
+        };
+
+        app.update_tree(&root);
+        assert_eq!(app.tree_nodes.len(), 7);
+
+        // Only src is nested; what's deeper sits one level under it with its path in the name
+        app.set_max_tree_depth(Some(1));
+        app.update_tree(&root);
+        assert_eq!(
+            names(&app),
+            vec![
+                ("src".to_string(), 1),
+                ("a/b/c.rs".to_string(), 2),
+                ("new".to_string(), 2),
+                ("d.rs".to_string(), 2),
+                ("top.rs".to_string(), 1),
+            ]
+        );
+        // The collapsed untracked directory still counts its files
+        assert_eq!(app.tree_nodes[2].0.untracked_file_count, Some(1));
+        assert_eq!(
+            app.tree_nodes[1].0.path,
+            std::path::PathBuf::from("src/a/b/c.rs")
+        );
+    }
+
+    #[test]
+    fn test_type_to_jump_in_file_tree() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        let files: Vec<FileDiff> = ["alpha.rs", "beta.rs", "bravo.rs", "Cargo.toml"]
+            .iter()
+            .map(|name| FileDiff {
+                path: std::path::PathBuf::from(name),
+                old_path: None,
+                submodule: None,
+                status: git2::Status::WT_MODIFIED,
+                line_strings: vec!["+a".to_string()],
+                additions: 1,
+                deletions: 0,
+            })
+            .collect();
+        app.update_files(files.clone());
+        app.update_tree(&TreeNode {
+            name: ".".to_string(),
+            path: std::path::PathBuf::new(),
+            is_dir: true,
+            children: files
+                .into_iter()
+                .map(|diff| TreeNode {
+                    name: diff.path.to_string_lossy().to_string(),
+                    path: diff.path.clone(),
+                    is_dir: false,
+                    children: Vec::new(),
+                    file_diff: Some(diff),
+                    untracked: false,
+                })
+                .collect(),
+            file_diff: None,
+            untracked: false,
+        });
+        let current = |app: &App| app.files[app.current_file_index].path.clone();
+        let start = std::time::Instant::now();
+        let later = |ms: u64| start + std::time::Duration::from_millis(ms);
+
+        // Letters aren't taken as a name until a jump is started
+        assert!(!app.type_to_jump('c', later(0)));
+        assert_eq!(current(&app), std::path::PathBuf::from("alpha.rs"));
+
+        // Case doesn't matter, and the same letter again cycles through its matches
+        app.tree_jump = Some((String::new(), later(0)));
+        assert!(app.type_to_jump('c', later(100)));
+        assert_eq!(current(&app), std::path::PathBuf::from("Cargo.toml"));
+        app.tree_jump = Some((String::new(), later(2000)));
+        assert!(app.type_to_jump('b', later(2000)));
+        assert_eq!(current(&app), std::path::PathBuf::from("beta.rs"));
+        assert!(app.type_to_jump('b', later(2100)));
+        assert_eq!(current(&app), std::path::PathBuf::from("bravo.rs"));
+        assert!(app.type_to_jump('b', later(2200)));
+        assert_eq!(current(&app), std::path::PathBuf::from("beta.rs"));
+
+        // Further letters narrow it down
+        assert!(app.type_to_jump('r', later(2300)));
+        assert_eq!(current(&app), std::path::PathBuf::from("bravo.rs"));
+
+        // A letter matching nothing ends the jump and is left to its hotkey
+        assert!(!app.type_to_jump('j', later(2400)));
+        assert!(!app.type_to_jump('a', later(2500)));
+        assert_eq!(current(&app), std::path::PathBuf::from("bravo.rs"));
+
+        // A pause ends it too
+        app.tree_jump = Some((String::new(), later(4000)));
+        assert!(!app.type_to_jump('a', later(6000)));
+        assert_eq!(current(&app), std::path::PathBuf::from("bravo.rs"));
+    }
+
+    #[test]
+    fn test_recent_change_highlight_decays() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.set_recent_highlight_duration(std::time::Duration::from_millis(3000));
+
+        let now = std::time::Instant::now();
+        app.file_change_timestamps = vec![
+            now,
+            now - std::time::Duration::from_millis(2500),
+            now - std::time::Duration::from_millis(3500),
+        ];
+
+        assert_eq!(app.get_recent_change(0), Some(RecentChange::Fresh));
+        assert_eq!(app.get_recent_change(1), Some(RecentChange::Fading));
+        assert_eq!(app.get_recent_change(2), None);
+        assert_eq!(app.get_recent_change(3), None);
+
+        // Shorter windows fade over their whole duration
+        app.set_recent_highlight_duration(std::time::Duration::from_millis(500));
+        assert_eq!(app.get_recent_change(0), Some(RecentChange::Fading));
+        assert_eq!(app.get_recent_change(1), None);
+    }
+
+    #[test]
+    fn test_search_jumps_to_match() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.current_diff_height = 2;
+
+        let file = |name: &str, lines: &[&str]| FileDiff {
+            path: std::path::PathBuf::from(name),
+            old_path: None,
+            submodule: None,
+            status: Status::WT_MODIFIED,
+            line_strings: lines.iter().map(|l| l.to_string()).collect(),
+            additions: 0,
+            deletions: 0,
+        };
+        app.update_files(vec![
+            file("a.rs", &["+alpha"]),
+            file(
+                "b.rs",
+                &[" one", " two", " three", "+needle", " five", " six"],
+            ),
+        ]);
+
+        app.open_search();
+        assert!(app.is_searching());
+        for c in "NEEDLE".chars() {
+            app.forward_key_to_search(KeyEvent::new(
+                crossterm::event::KeyCode::Char(c),
+                crossterm::event::KeyModifiers::NONE,
+            ));
+        }
+
+        app.jump_to_search_result();
+        assert!(!app.is_searching());
+        assert_eq!(app.get_current_file().unwrap().path.to_str(), Some("b.rs"));
+        assert_eq!(app.get_scroll_offset(), 3);
+
+        // Reopening starts a fresh search, and Enter without a match stays open
+        app.open_search();
+        app.jump_to_search_result();
+        assert!(app.is_searching());
+        app.close_search();
+        assert!(!app.is_searching());
+    }
+
+    #[tokio::test]
+    async fn test_diff_line_selection() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.current_diff_height = 2;
+
+        let file = |name: &str, lines: &[&str]| FileDiff {
+            path: std::path::PathBuf::from(name),
+            old_path: None,
+            submodule: None,
+            status: Status::WT_MODIFIED,
+            line_strings: lines.iter().map(|l| l.to_string()).collect(),
+            additions: 0,
+            deletions: 0,
+        };
+        app.update_files(vec![
+            file("a.rs", &["@@ -1,3 +1,3 @@", " one", "-two", "+2", " three"]),
+            file("b.rs", &["+alpha"]),
+        ]);
+
+        app.scroll_to_line(1);
+        app.start_diff_selection();
+        app.move_diff_selection(ScrollAction::LineDown);
+        app.move_diff_selection(ScrollAction::LineDown);
+        assert_eq!(app.diff_selection_range(), Some((1, 3)));
+        assert_eq!(app.get_scroll_offset(), 2); // Follows the end of the selection
+
+        // The excerpt keeps the hunk header so the LLM knows where the lines are
+        let excerpt = app.get_current_file().unwrap().excerpt(2, 3);
+        assert_eq!(excerpt, vec!["@@ -1,3 +1,3 @@", "-two", "+2"]);
+
+        // Showing another file drops the selection
+        app.current_file_index = 1;
+        assert!(!app.is_selecting_diff());
+        app.current_file_index = 0;
+
+        app.start_diff_selection();
+        assert!(app.is_selecting_diff());
+        app.explain_diff_selection();
+        assert!(!app.is_selecting_diff());
+        assert!(app.is_showing_explanation());
+    }
+
+    #[test]
+    fn test_scroll_margin() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.current_diff_height = 10;
+        app.set_scroll_margin(3);
+        let mut lines: Vec<String> = (0..40).map(|i| format!(" line {i}")).collect();
+        for hunk in [2, 12, 20] {
+            lines[hunk] = format!("@@ -{hunk},3 +{hunk},3 @@");
+        }
+        app.update_files(vec![FileDiff {
+            path: std::path::PathBuf::from("lib.rs"),
+            old_path: None,
+            submodule: None,
+            status: Status::WT_MODIFIED,
+            line_strings: lines,
+            additions: 0,
+            deletions: 0,
+        }]);
+
+        // Jumps leave three rows of context above their target
+        app.scroll_to_line(15);
+        assert_eq!(app.get_scroll_offset(), 12);
+        app.jump_to_hunk(true);
+        assert_eq!(app.get_scroll_offset(), 17);
+        app.jump_to_hunk(false);
+        assert_eq!(app.get_scroll_offset(), 9);
+        app.jump_to_hunk(false);
+        assert_eq!(app.get_scroll_offset(), 0);
+        app.jump_to_hunk(true);
+        assert_eq!(app.get_scroll_offset(), 9);
+
+        // The selection cursor scrolls the diff three rows before reaching either edge
+        app.start_diff_selection();
+        assert_eq!(app.diff_selection_range(), Some((12, 12)));
+        for _ in 0..4 {
+            app.move_diff_selection(ScrollAction::LineDown);
+        }
+        assert_eq!(app.get_scroll_offset(), 10);
+        app.move_diff_selection(ScrollAction::Bottom);
+        assert_eq!(app.get_scroll_offset(), 30);
+        for _ in 0..9 {
+            app.move_diff_selection(ScrollAction::LineUp);
+        }
+        assert_eq!(app.get_scroll_offset(), 27);
+
+        // A margin too big for the diff is capped so the target still shows
+        app.current_diff_height = 4;
+        app.set_scroll_margin(10);
+        app.scroll_to_line(15);
+        assert_eq!(app.get_scroll_offset(), 14);
+    }
+
+    #[tokio::test]
+    async fn test_draft_commit_message_needs_staged_view() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.update_files(vec![FileDiff {
+            path: std::path::PathBuf::from("a.rs"),
+            old_path: None,
+            submodule: None,
+            status: Status::INDEX_MODIFIED,
+            line_strings: vec![
+                "@@ -1 +1 @@".to_string(),
+                "-a".to_string(),
+                "+b".to_string(),
+            ],
+            additions: 1,
+            deletions: 1,
+        }]);
+
+        app.draft_commit_message();
+        assert!(!app.is_showing_commit_draft());
+        assert_eq!(
+            app.get_status_message(),
+            Some("Commit messages are drafted from the staged view")
+        );
+
+        app.set_view_mode(ViewMode::Staged);
+        app.draft_commit_message();
+        assert!(app.is_showing_commit_draft());
+    }
+
+    #[test]
+    fn test_render_startup_error() {
+        let backend = ratatui::backend::TestBackend::new(60, 12);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| {
+                render_startup_error(
+                    f,
+                    "Not a git repository — open grw inside one",
+                    "could not find repository at '/tmp'",
+                )
+            })
+            .unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Not a git repository"));
+        assert!(screen.contains("could not find repository"));
+        assert!(screen.contains("Press q to quit"));
+    }
+
+    #[test]
+    fn test_render_terminal_too_small() {
+        let backend = ratatui::backend::TestBackend::new(40, MIN_TERMINAL_HEIGHT - 1);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(render_terminal_too_small).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Terminal too small (40x9)"));
+
+        assert!(is_terminal_too_small(Rect::new(0, 0, 80, 2)));
+        assert!(is_terminal_too_small(Rect::new(0, 0, 5, 40)));
+        assert!(!is_terminal_too_small(Rect::new(
+            0,
+            0,
+            MIN_TERMINAL_WIDTH,
+            MIN_TERMINAL_HEIGHT
+        )));
+
+        // A monitor squashed to nothing still pages by at least a line
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.set_monitor_visible_height(0);
+        assert_eq!(app.monitor_visible_height, 1);
+    }
+
+    #[test]
+    fn test_clamp_scroll_offsets_after_resize() {
+        let themes = vec![Theme::Dark, Theme::Light];
+        let mut app = create_test_app(true, true, 0, themes);
+        app.update_files(vec![FileDiff {
+            path: std::path::PathBuf::from("a.rs"),
+            old_path: None,
+            submodule: None,
+            status: Status::WT_MODIFIED,
+            line_strings: (0..50).map(|i| format!("+line {i}")).collect(),
+            additions: 50,
+            deletions: 0,
+        }]);
+        app.current_diff_height = 10;
+        app.apply_action(Action::ScrollDiff(ScrollAction::Bottom));
+        assert_eq!(app.get_scroll_offset(), 40);
+
+        // A taller diff pane has room for more of the file, so the bottom moves up
+        app.current_diff_height = 30;
+        app.clamp_scroll_offsets();
+        assert_eq!(app.get_scroll_offset(), 20);
+    }
+
+    #[test]
+    fn test_load_themes_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("dracula.toml"),
+            "background = \"#282a36\"\nadded = \"#50fa7b\"\n",
+        )
+        .unwrap();
+        std::fs::write(temp_dir.path().join("broken.toml"), "background = [").unwrap();
+        std::fs::write(temp_dir.path().join("notes.txt"), "not a theme").unwrap();
+
+        let themes = load_themes_dir(temp_dir.path());
+        assert_eq!(themes.len(), 1);
+        assert_eq!(themes[0].name(), "dracula");
+        assert_eq!(themes[0].background_color(), Color::Rgb(0x28, 0x2a, 0x36));
+        assert_eq!(themes[0].added_color(), Color::Rgb(0x50, 0xfa, 0x7b));
+        // Unspecified colors fall back to the dark palette
+        assert_eq!(themes[0].border_color(), ColorPalette::dark().border);
+
+        assert!(load_themes_dir(&temp_dir.path().join("missing")).is_empty());
+    }
+
     #[test]
     fn test_performance_add_tree_node_recursive() {
         let themes = vec![Theme::Dark, Theme::Light];
@@ -2309,11 +5643,14 @@ mod tests {
                 children: Vec::new(),
                 file_diff: Some(FileDiff {
                     path: std::path::PathBuf::from(format!("file_{}.txt", i)),
+                    old_path: None,
+                    submodule: None,
                     status: git2::Status::INDEX_MODIFIED,
                     line_strings: large_line_strings,
                     additions: 10,
                     deletions: 5,
                 }),
+                untracked: false,
             };
             children.push(node);
         }
@@ -2324,6 +5661,7 @@ mod tests {
             is_dir: true,
             children,
             file_diff: None,
+            untracked: false,
         };
 
         let start = std::time::Instant::now();
@@ -0,0 +1,114 @@
+//! Small formatting helpers shared across panes
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Frames of the spinner drawn while waiting on the LLM
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+/// How long each spinner frame shows
+const SPINNER_FRAME_TIME: Duration = Duration::from_millis(100);
+
+/// Clock for loading animations, shared so every pane's spinner turns in step without
+/// timers of its own
+#[derive(Debug, Clone, Copy)]
+pub struct Spinner {
+    started: Instant,
+}
+
+impl Default for Spinner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Spinner {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+        }
+    }
+
+    /// The frame to draw now
+    pub fn frame(&self) -> &'static str {
+        Self::frame_at(self.started.elapsed())
+    }
+
+    /// The frame to draw `elapsed` after the spinner started
+    fn frame_at(elapsed: Duration) -> &'static str {
+        let step = elapsed.as_millis() / SPINNER_FRAME_TIME.as_millis();
+        SPINNER_FRAMES[step as usize % SPINNER_FRAMES.len()]
+    }
+}
+
+/// Coarse, human-friendly length of `duration` in its largest unit: "45s", "3m", "2h", "5d"
+pub fn humanize_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 60 * 60 {
+        format!("{}m", secs / 60)
+    } else if secs < 24 * 60 * 60 {
+        format!("{}h", secs / (60 * 60))
+    } else {
+        format!("{}d", secs / (24 * 60 * 60))
+    }
+}
+
+/// How long ago `time` was, e.g. "3m ago"; times in the future read as "0s ago"
+pub fn humanize_timestamp(time: SystemTime) -> String {
+    let elapsed = SystemTime::now().duration_since(time).unwrap_or_default();
+    format!("{} ago", humanize_duration(elapsed))
+}
+
+/// `humanize_timestamp` for a git time in seconds since the Unix epoch
+pub fn humanize_unix_time(seconds: i64) -> String {
+    let time = if seconds >= 0 {
+        UNIX_EPOCH + Duration::from_secs(seconds as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+    };
+    humanize_timestamp(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_humanize_duration_boundaries() {
+        let secs = Duration::from_secs;
+        assert_eq!(humanize_duration(secs(0)), "0s");
+        assert_eq!(humanize_duration(secs(59)), "59s");
+        assert_eq!(humanize_duration(secs(60)), "1m");
+        assert_eq!(humanize_duration(secs(3599)), "59m");
+        assert_eq!(humanize_duration(secs(3600)), "1h");
+        assert_eq!(humanize_duration(secs(86399)), "23h");
+        assert_eq!(humanize_duration(secs(86400)), "1d");
+        assert_eq!(humanize_duration(secs(400 * 86400)), "400d");
+        // Sub-second remainders are dropped rather than rounded up
+        assert_eq!(humanize_duration(Duration::from_millis(59_999)), "59s");
+    }
+
+    #[test]
+    fn test_spinner_frames_follow_elapsed_time() {
+        let at = |millis| Spinner::frame_at(Duration::from_millis(millis));
+        assert_eq!(at(0), "⠋");
+        assert_eq!(at(99), "⠋");
+        assert_eq!(at(100), "⠙");
+        // Wraps back round after the last frame
+        assert_eq!(at(1000), "⠋");
+        assert_eq!(at(950), "⠏");
+    }
+
+    #[test]
+    fn test_humanize_timestamp() {
+        let now = SystemTime::now();
+        assert_eq!(
+            humanize_timestamp(now - Duration::from_secs(3 * 60 + 5)),
+            "3m ago"
+        );
+        assert_eq!(humanize_timestamp(now + Duration::from_secs(30)), "0s ago");
+
+        let unix_now = now.duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+        assert_eq!(humanize_unix_time(unix_now - 2 * 86400 - 60), "2d ago");
+    }
+}
@@ -1,149 +0,0 @@
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::pane::{AdvicePanel, Pane, PaneId};
-    use crate::config::Config;
-
-    #[test]
-    fn test_panel_initial_visibility() {
-        // Test that advice panel starts with correct visibility state
-        let config = Config::default();
-        let advice_config = AdviceConfig::default();
-
-        // This should fail until AdvicePanel is implemented
-        let panel = AdvicePanel::new(config, advice_config).unwrap();
-
-        // Panel should be hidden after creation (not default pane)
-        assert!(!panel.is_visible(), "AdvicePanel should be hidden after creation");
-
-        // Test that we can get visibility state
-        let visibility = panel.get_visibility();
-        assert!(!visibility, "AdvicePanel should report correct visibility state (hidden)");
-    }
-
-    #[test]
-    fn test_panel_toggle_visibility() {
-        // Test that advice panel visibility can be toggled
-        let config = Config::default();
-        let advice_config = AdviceConfig::default();
-
-        // This should fail until AdvicePanel is implemented
-        let mut panel = AdvicePanel::new(config, advice_config).unwrap();
-
-        // Initially hidden
-        assert!(!panel.is_visible(), "Panel should start hidden");
-
-        // Toggle to show
-        panel.toggle_visibility();
-        assert!(panel.is_visible(), "Panel should be visible after first toggle");
-
-        // Toggle to hide
-        panel.toggle_visibility();
-        assert!(!panel.is_visible(), "Panel should be hidden after second toggle");
-    }
-
-    #[test]
-    fn test_panel_set_visibility() {
-        // Test that advice panel visibility can be set explicitly
-        let config = Config::default();
-        let advice_config = AdviceConfig::default();
-
-        // This should fail until AdvicePanel is implemented
-        let mut panel = AdvicePanel::new(config, advice_config).unwrap();
-
-        // Set to hidden
-        panel.set_visibility(false);
-        assert!(!panel.is_visible(), "Panel should be hidden when set to false");
-
-        // Set to visible
-        panel.set_visibility(true);
-        assert!(panel.is_visible(), "Panel should be visible when set to true");
-    }
-
-    #[test]
-    fn test_panel_visibility_with_config() {
-        // Test that advice panel respects visibility settings from config
-        let mut config = Config::default();
-
-        // Test with advice disabled in config
-        config.advice = Some(AdviceConfig {
-            enabled: Some(false),
-            ..Default::default()
-        });
-
-        let advice_config = config.advice.as_ref().unwrap().clone();
-
-        // This should fail until AdvicePanel is implemented
-        let panel = AdvicePanel::new(config, advice_config).unwrap();
-
-        // Even when disabled, panel should be creatable but visibility might be affected
-        // This tests the contract - actual behavior may vary based on implementation
-        let can_set_visibility = std::panic::catch_unwind(|| {
-            panel.set_visibility(true);
-            panel.is_visible()
-        });
-
-        // Should not panic, even with disabled config
-        assert!(can_set_visibility.is_ok(), "Visibility operations should not panic with disabled config");
-    }
-
-    #[test]
-    fn test_panel_visibility_edge_cases() {
-        // Test edge cases for panel visibility
-        let config = Config::default();
-        let advice_config = AdviceConfig::default();
-
-        // This should fail until AdvicePanel is implemented
-        let mut panel = AdvicePanel::new(config, advice_config).unwrap();
-
-        // Test multiple toggles in sequence
-        for i in 0..10 {
-            panel.toggle_visibility();
-            let expected_visibility = i % 2 == 1; // Odd numbers should be hidden
-            assert_eq!(panel.is_visible(), !expected_visibility,
-                "Panel visibility should alternate with each toggle (iteration {})", i);
-        }
-
-        // Test setting same visibility multiple times
-        panel.set_visibility(true);
-        assert!(panel.is_visible(), "Panel should be visible");
-        panel.set_visibility(true);
-        assert!(panel.is_visible(), "Panel should remain visible when set to true again");
-
-        panel.set_visibility(false);
-        assert!(!panel.is_visible(), "Panel should be hidden");
-        panel.set_visibility(false);
-        assert!(!panel.is_visible(), "Panel should remain hidden when set to false again");
-    }
-
-    #[test]
-    fn test_panel_visibility_interaction_with_other_states() {
-        // Test that visibility changes interact properly with other panel states
-        let config = Config::default();
-        let advice_config = AdviceConfig::default();
-
-        // This should fail until AdvicePanel is implemented
-        let mut panel = AdvicePanel::new(config, advice_config).unwrap();
-
-        // Test visibility changes while in different modes
-        // This will test the contract - implementation may handle this differently
-
-        // Hide panel while in viewing mode
-        panel.set_visibility(false);
-        assert!(!panel.is_visible(), "Panel should be hidden");
-
-        // Show panel while in viewing mode
-        panel.set_visibility(true);
-        assert!(panel.is_visible(), "Panel should be visible");
-
-        // This test establishes the contract - visibility should work
-        // regardless of other panel state or mode
-        let visibility_contract = std::panic::catch_unwind(|| {
-            // These operations should not panic regardless of panel mode
-            panel.toggle_visibility();
-            panel.is_visible()
-        });
-
-        assert!(visibility_contract.is_ok(), "Visibility operations should not panic regardless of panel mode");
-    }
-}
\ No newline at end of file
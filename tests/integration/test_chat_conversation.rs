@@ -1,298 +0,0 @@
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::pane::{AdvicePanel, Pane, PaneId};
-    use crate::config::Config;
-    use crate::shared_state::SharedState;
-    use crate::pane::{ChatMessageData, MessageRole};
-
-    #[test]
-    fn test_complete_chat_conversation_flow() {
-        // Test the complete flow of a chat conversation from start to finish
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        // Step 1: Open advice panel and generate initial advice
-        let sample_diff = r#"diff --git a/src/main.rs b/src/main.rs
-index abc123..def456 100644
---- a/src/main.rs
-+++ b/src/main.rs
-@@ -1,5 +1,5 @@
- fn main() {
--    println!("Hello, World!");
-+    println!("Hello, Rust!");
- }"#;
-
-        // This will fail until advice generation is implemented
-        let result = std::panic::catch_unwind(|| {
-            start_chat_conversation_with_diff(sample_diff, &shared_state)
-        });
-
-        assert!(result.is_ok(), "Starting chat conversation should not panic");
-
-        // Step 2: Enter chat mode with '/' key
-        let slash_key = crossterm::event::KeyEvent::new(
-            crossterm::event::KeyCode::Char('/'),
-            crossterm::event::KeyModifiers::NONE,
-        );
-
-        let enter_chat_result = std::panic::catch_unwind(|| {
-            enter_chat_mode_with_key(slash_key, &shared_state)
-        });
-
-        assert!(enter_chat_result.is_ok(), "Entering chat mode should not panic");
-        assert!(enter_chat_result.unwrap().is_ok(), "Should successfully enter chat mode");
-
-        // Step 3: Send a chat message
-        let chat_result = send_chat_message_in_conversation(
-            "Can you explain the first improvement in more detail?",
-            &shared_state
-        );
-
-        assert!(chat_result.is_ok(), "Sending chat message should succeed");
-
-        // Step 4: Verify conversation flow
-        let conversation = get_chat_conversation(&shared_state);
-        assert!(conversation.len() >= 2, "Conversation should have at least 2 messages");
-
-        // Verify message roles and content
-        let user_messages: Vec<_> = conversation.iter()
-            .filter(|m| m.role == MessageRole::User)
-            .collect();
-        assert_eq!(user_messages.len(), 1, "Should have exactly one user message");
-        assert_eq!(user_messages[0].content, "Can you explain the first improvement in more detail?");
-
-        // Step 5: Send follow-up message
-        let followup_result = send_chat_message_in_conversation(
-            "What are the performance implications?",
-            &shared_state
-        );
-
-        assert!(followup_result.is_ok(), "Follow-up message should succeed");
-
-        // Step 6: Verify conversation context is maintained
-        let updated_conversation = get_chat_conversation(&shared_state);
-        assert!(updated_conversation.len() >= 3, "Conversation should grow with follow-up messages");
-    }
-
-    #[test]
-    fn test_chat_mode_enter_exit() {
-        // Test entering and exiting chat mode
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        // Initially not in chat mode
-        assert!(!is_in_chat_mode(&shared_state), "Should start outside chat mode");
-
-        // Enter chat mode with '/'
-        let slash_key = crossterm::event::KeyEvent::new(
-            crossterm::event::KeyCode::Char('/'),
-            crossterm::event::KeyModifiers::NONE,
-        );
-
-        let enter_result = enter_chat_mode_with_key(slash_key, &shared_state);
-        assert!(enter_result.is_ok(), "Entering chat mode should succeed");
-        assert!(is_in_chat_mode(&shared_state), "Should be in chat mode after '/'");
-
-        // Exit chat mode with Esc
-        let esc_key = crossterm::event::KeyEvent::new(
-            crossterm::event::KeyCode::Esc,
-            crossterm::event::KeyModifiers::NONE,
-        );
-
-        let exit_result = exit_chat_mode_with_key(esc_key, &shared_state);
-        assert!(exit_result.is_ok(), "Exiting chat mode should succeed");
-        assert!(!is_in_chat_mode(&shared_state), "Should exit chat mode after Esc");
-    }
-
-    #[test]
-    fn test_chat_input_display() {
-        // Test that chat input is displayed correctly when in chat mode
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        // Enter chat mode
-        let slash_key = crossterm::event::KeyEvent::new(
-            crossterm::event::KeyCode::Char('/'),
-            crossterm::event::KeyModifiers::NONE,
-        );
-
-        enter_chat_mode_with_key(slash_key, &shared_state).unwrap();
-
-        // Test that chat input is displayed
-        let input_display = get_chat_input_display(&shared_state);
-        assert!(input_display.contains("/"), "Chat input should display '/' prompt");
-        assert!(input_display.contains(">"), "Chat input should show prompt character");
-
-        // Simulate typing a message
-        simulate_typing_in_chat("Hello AI", &shared_state);
-
-        let updated_display = get_chat_input_display(&shared_state);
-        assert!(updated_display.contains("Hello AI"), "Chat input should show typed text");
-    }
-
-    #[test]
-    fn test_chat_conversation_context_preservation() {
-        // Test that chat conversation preserves context from advice generation
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        // Generate initial advice with specific diff
-        let sample_diff = r#"diff --git a/src/performance.rs b/src/performance.rs
-index old..new 100644
---- a/src/performance.rs
-+++ b/src/performance.rs
-@@ -1,7 +1,6 @@
- fn process_data(data: Vec<i32>) -> Vec<i32> {
--    let mut result = Vec::new();
--    for item in data {
--        result.push(item * 2);
--    }
--    result
-+    data.into_iter().map(|x| x * 2).collect()
- }"#;
-
-        start_chat_conversation_with_diff(sample_diff, &shared_state).unwrap();
-
-        // Enter chat mode and send context-specific question
-        enter_chat_mode_with_key(
-            crossterm::event::KeyEvent::new(
-                crossterm::event::KeyCode::Char('/'),
-                crossterm::event::KeyModifiers::NONE,
-            ),
-            &shared_state
-        ).unwrap();
-
-        send_chat_message_in_conversation(
-            "How does the iterator-based approach improve performance?",
-            &shared_state
-        ).unwrap();
-
-        // Verify that the AI response considers the original diff context
-        let conversation = get_chat_conversation(&shared_state);
-
-        // Look for evidence that context was preserved
-        let context_preserved = conversation.iter().any(|msg| {
-            msg.content.to_lowercase().contains("iterator") ||
-            msg.content.to_lowercase().contains("performance") ||
-            msg.content.to_lowercase().contains("collect")
-        });
-
-        assert!(context_preserved, "AI response should consider original diff context");
-    }
-
-    #[test]
-    fn test_chat_conversation_error_recovery() {
-        // Test error recovery in chat conversations
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        // Start conversation
-        start_chat_conversation_with_diff("sample diff", &shared_state).unwrap();
-
-        // Enter chat mode
-        enter_chat_mode_with_key(
-            crossterm::event::KeyEvent::new(
-                crossterm::event::KeyCode::Char('/'),
-                crossterm::event::KeyModifiers::NONE,
-            ),
-            &shared_state
-        ).unwrap();
-
-        // Send a message that might cause an error
-        let error_result = send_chat_message_in_conversation("", &shared_state);
-        // Should handle empty message gracefully
-        assert!(error_result.is_ok(), "Should handle empty message without error");
-
-        // Send another message to verify conversation continues
-        let recovery_result = send_chat_message_in_conversation(
-            "Let's try a proper question",
-            &shared_state
-        );
-        assert!(recovery_result.is_ok(), "Should recover and continue conversation");
-
-        // Verify conversation is still functional
-        let conversation = get_chat_conversation(&shared_state);
-        assert!(conversation.len() >= 1, "Conversation should continue after error recovery");
-    }
-
-    #[test]
-    fn test_chat_conversation_performance() {
-        // Test performance of chat conversation operations
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        let start_time = std::time::Instant::now();
-
-        // Start conversation
-        start_chat_conversation_with_diff("sample diff", &shared_state).unwrap();
-
-        let setup_time = start_time.elapsed();
-        assert!(setup_time.as_millis() < 50, "Conversation setup should be fast");
-
-        // Enter chat mode and send multiple messages
-        enter_chat_mode_with_key(
-            crossterm::event::KeyEvent::new(
-                crossterm::event::KeyCode::Char('/'),
-                crossterm::event::KeyModifiers::NONE,
-            ),
-            &shared_state
-        ).unwrap();
-
-        let messages = vec![
-            "Question 1",
-            "Question 2",
-            "Question 3",
-        ];
-
-        for message in messages {
-            let msg_start = std::time::Instant::now();
-            send_chat_message_in_conversation(message, &shared_state).unwrap();
-            let msg_time = msg_start.elapsed();
-            assert!(msg_time.as_millis() < 100, "Each message should be processed quickly");
-        }
-
-        let total_time = start_time.elapsed();
-        assert!(total_time.as_secs() < 5, "Entire conversation should complete within 5 seconds");
-    }
-
-    // Helper functions that represent the implementation contracts
-    fn start_chat_conversation_with_diff(_diff: &str, _state: &SharedState) -> Result<(), String> {
-        // This should be implemented to start chat conversation with diff context
-        Err("Not implemented yet".to_string())
-    }
-
-    fn enter_chat_mode_with_key(_key: crossterm::event::KeyEvent, _state: &SharedState) -> Result<(), String> {
-        // This should be implemented to handle entering chat mode
-        Err("Not implemented yet".to_string())
-    }
-
-    fn exit_chat_mode_with_key(_key: crossterm::event::KeyEvent, _state: &SharedState) -> Result<(), String> {
-        // This should be implemented to handle exiting chat mode
-        Err("Not implemented yet".to_string())
-    }
-
-    fn send_chat_message_in_conversation(_message: &str, _state: &SharedState) -> Result<(), String> {
-        // This should be implemented to send chat messages
-        Err("Not implemented yet".to_string())
-    }
-
-    fn get_chat_conversation(_state: &SharedState) -> Vec<ChatMessageData> {
-        // This should be implemented to get the conversation history
-        Vec::new()
-    }
-
-    fn is_in_chat_mode(_state: &SharedState) -> bool {
-        // This should be implemented to check if currently in chat mode
-        false
-    }
-
-    fn get_chat_input_display(_state: &SharedState) -> String {
-        // This should be implemented to get the current chat input display
-        String::new()
-    }
-
-    fn simulate_typing_in_chat(_text: &str, _state: &SharedState) {
-        // This should be implemented to simulate typing in chat
-    }
-}
\ No newline at end of file
@@ -1,327 +0,0 @@
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::pane::{AdvicePanel, Pane, PaneId};
-    use crate::config::Config;
-    use crate::shared_state::SharedState;
-
-    #[test]
-    fn test_help_system_activation() {
-        // Test that the help system can be activated with '?' key
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        // Initially not in help mode
-        assert!(!is_help_visible(&shared_state), "Help should not be visible initially");
-
-        // Activate help with '?' key
-        let question_key = crossterm::event::KeyEvent::new(
-            crossterm::event::KeyCode::Char('?'),
-            crossterm::event::KeyModifiers::NONE,
-        );
-
-        let result = std::panic::catch_unwind(|| {
-            activate_help_system(question_key, &shared_state)
-        });
-
-        assert!(result.is_ok(), "Help system activation should not panic");
-
-        // Verify help is now visible
-        assert!(is_help_visible(&shared_state), "Help should be visible after '?' key");
-    }
-
-    #[test]
-    fn test_help_content_display() {
-        // Test that help content displays correctly
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        // Activate help system
-        activate_help_system(
-            crossterm::event::KeyEvent::new(
-                crossterm::event::KeyCode::Char('?'),
-                crossterm::event::KeyModifiers::NONE,
-            ),
-            &shared_state
-        ).unwrap();
-
-        // Get help content
-        let help_content = get_help_content(&shared_state);
-
-        // Verify help content contains expected sections
-        assert!(help_content.contains("Navigation"), "Help should contain navigation section");
-        assert!(help_content.contains("Ctrl+L"), "Help should mention Ctrl+L for advice panel");
-        assert!(help_content.contains("/"), "Help should mention / for chat");
-        assert!(help_content.contains("?"), "Help should mention ? for help");
-        assert!(help_content.contains("Esc"), "Help should mention Esc for exiting");
-
-        // Verify key bindings are documented
-        assert!(help_content.contains("j"), "Help should document j key");
-        assert!(help_content.contains("k"), "Help should document k key");
-        assert!(help_content.contains("g"), "Help should document g key");
-        assert!(help_content.contains("G"), "Help should document G key");
-    }
-
-    #[test]
-    fn test_help_system_exit() {
-        // Test that help system can be exited
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        // Activate help
-        activate_help_system(
-            crossterm::event::KeyEvent::new(
-                crossterm::event::KeyCode::Char('?'),
-                crossterm::event::KeyModifiers::NONE,
-            ),
-            &shared_state
-        ).unwrap();
-
-        assert!(is_help_visible(&shared_state), "Help should be visible");
-
-        // Exit help with Esc key
-        let esc_key = crossterm::event::KeyEvent::new(
-            crossterm::event::KeyCode::Esc,
-            crossterm::event::KeyModifiers::NONE,
-        );
-
-        let exit_result = exit_help_system(esc_key, &shared_state);
-        assert!(exit_result.is_ok(), "Exiting help should succeed");
-
-        assert!(!is_help_visible(&shared_state), "Help should not be visible after exit");
-    }
-
-    #[test]
-    fn test_help_system_navigation() {
-        // Test navigation within help content
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        // Activate help system
-        activate_help_system(
-            crossterm::event::KeyEvent::new(
-                crossterm::event::KeyCode::Char('?'),
-                crossterm::event::KeyModifiers::NONE,
-            ),
-            &shared_state
-        ).unwrap();
-
-        // Test navigation keys work in help mode
-        let nav_keys = vec![
-            crossterm::event::KeyEvent::new(crossterm::event::KeyCode::Char('j'), crossterm::event::KeyModifiers::NONE),
-            crossterm::event::KeyEvent::new(crossterm::event::KeyCode::Char('k'), crossterm::event::KeyModifiers::NONE),
-            crossterm::event::KeyEvent::new(crossterm::event::KeyCode::PageDown, crossterm::event::KeyModifiers::NONE),
-            crossterm::event::KeyEvent::new(crossterm::event::KeyCode::PageUp, crossterm::event::KeyModifiers::NONE),
-        ];
-
-        for key in nav_keys {
-            let result = std::panic::catch_unwind(|| {
-                navigate_help_content(key, &shared_state)
-            });
-
-            assert!(result.is_ok(), "Help navigation should not panic for {:?}", key);
-        }
-    }
-
-    #[test]
-    fn test_help_content_completeness() {
-        // Test that help content covers all major features
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        activate_help_system(
-            crossterm::event::KeyEvent::new(
-                crossterm::event::KeyCode::Char('?'),
-                crossterm::event::KeyModifiers::NONE,
-            ),
-            &shared_state
-        ).unwrap();
-
-        let help_content = get_help_content(&shared_state);
-
-        // Check for all major feature coverage
-        let expected_topics = vec![
-            "advice panel",
-            "chat",
-            "navigation",
-            "keyboard shortcuts",
-            "git diff",
-            "improvements",
-            "modes",
-            "configuration"
-        ];
-
-        for topic in expected_topics {
-            assert!(
-                help_content.to_lowercase().contains(&topic.to_lowercase()),
-                "Help should cover topic: {}", topic
-            );
-        }
-    }
-
-    #[test]
-    fn test_help_system_with_different_modes() {
-        // Test help system behavior in different panel modes
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        // Test help accessibility in different states
-        let modes_to_test = vec!["viewing", "chatting", "help"];
-
-        for mode in modes_to_test {
-            // Set up the panel in different modes (this is a contract test)
-            setup_panel_mode(mode, &shared_state);
-
-            // Help should be accessible from any mode
-            let help_result = std::panic::catch_unwind(|| {
-                activate_help_system(
-                    crossterm::event::KeyEvent::new(
-                        crossterm::event::KeyCode::Char('?'),
-                        crossterm::event::KeyModifiers::NONE,
-                    ),
-                    &shared_state
-                )
-            });
-
-            assert!(help_result.is_ok(), "Help should be accessible from {} mode", mode);
-        }
-    }
-
-    #[test]
-    fn test_help_system_performance() {
-        // Test help system performance
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        let start_time = std::time::Instant::now();
-
-        // Activate help system
-        activate_help_system(
-            crossterm::event::KeyEvent::new(
-                crossterm::event::KeyCode::Char('?'),
-                crossterm::event::KeyModifiers::NONE,
-            ),
-            &shared_state
-        ).unwrap();
-
-        let activation_time = start_time.elapsed();
-        assert!(activation_time.as_millis() < 50, "Help activation should be fast");
-
-        // Navigate through help content
-        let nav_start = std::time::Instant::now();
-        for _ in 0..10 {
-            navigate_help_content(
-                crossterm::event::KeyEvent::new(crossterm::event::KeyCode::Char('j'), crossterm::event::KeyModifiers::NONE),
-                &shared_state
-            ).unwrap();
-        }
-        let nav_time = nav_start.elapsed();
-        assert!(nav_time.as_millis() < 100, "Help navigation should be responsive");
-
-        // Exit help
-        let exit_start = std::time::Instant::now();
-        exit_help_system(
-            crossterm::event::KeyEvent::new(crossterm::event::KeyCode::Esc, crossterm::event::KeyModifiers::NONE),
-            &shared_state
-        ).unwrap();
-        let exit_time = exit_start.elapsed();
-        assert!(exit_time.as_millis() < 50, "Help exit should be fast");
-    }
-
-    #[test]
-    fn test_help_system_error_handling() {
-        // Test help system error handling
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        // Test help activation when system is in various states
-        let error_states = vec![
-            "no_panel_active",
-            "panel_loading",
-            "panel_error",
-            "chat_active",
-        ];
-
-        for state in error_states {
-            setup_system_state(state, &shared_state);
-
-            let help_result = std::panic::catch_unwind(|| {
-                activate_help_system(
-                    crossterm::event::KeyEvent::new(
-                        crossterm::event::KeyCode::Char('?'),
-                        crossterm::event::KeyModifiers::NONE,
-                    ),
-                    &shared_state
-                )
-            });
-
-            assert!(help_result.is_ok(), "Help should handle {} state gracefully", state);
-        }
-    }
-
-    #[test]
-    fn test_help_system_accessibility() {
-        // Test that help system is accessible and user-friendly
-        let config = Config::default();
-        let shared_state = SharedState::new(config.clone());
-
-        activate_help_system(
-            crossterm::event::KeyEvent::new(
-                crossterm::event::KeyCode::Char('?'),
-                crossterm::event::KeyModifiers::NONE,
-            ),
-            &shared_state
-        ).unwrap();
-
-        let help_content = get_help_content(&shared_state);
-
-        // Check for accessibility features
-        assert!(help_content.len() > 100, "Help content should be substantial");
-        assert!(help_content.lines().count() > 5, "Help should be well-formatted with multiple lines");
-
-        // Check that help is organized and readable
-        let lines: Vec<&str> = help_content.lines().collect();
-        let has_headers = lines.iter().any(|line| line.to_uppercase() == line.trim() && !line.is_empty());
-        assert!(has_headers, "Help should have section headers for organization");
-
-        // Check that key descriptions are clear
-        let key_descriptions = lines.iter()
-            .filter(|line| line.contains('-') && (line.contains('j') || line.contains('k') || line.contains("Ctrl")))
-            .count();
-        assert!(key_descriptions > 0, "Help should have clear key descriptions");
-    }
-
-    // Helper functions that represent the implementation contracts
-    fn activate_help_system(_key: crossterm::event::KeyEvent, _state: &SharedState) -> Result<(), String> {
-        // This should be implemented to activate the help system
-        Err("Not implemented yet".to_string())
-    }
-
-    fn exit_help_system(_key: crossterm::event::KeyEvent, _state: &SharedState) -> Result<(), String> {
-        // This should be implemented to exit the help system
-        Err("Not implemented yet".to_string())
-    }
-
-    fn is_help_visible(_state: &SharedState) -> bool {
-        // This should be implemented to check if help is visible
-        false
-    }
-
-    fn get_help_content(_state: &SharedState) -> String {
-        // This should be implemented to get help content
-        String::new()
-    }
-
-    fn navigate_help_content(_key: crossterm::event::KeyEvent, _state: &SharedState) -> Result<(), String> {
-        // This should be implemented to navigate help content
-        Err("Not implemented yet".to_string())
-    }
-
-    fn setup_panel_mode(_mode: &str, _state: &SharedState) {
-        // This should be implemented to set up panel in different modes
-    }
-
-    fn setup_system_state(_state: &str, _shared_state: &SharedState) {
-        // This should be implemented to set up different system states
-    }
-}
\ No newline at end of file